@@ -5,11 +5,20 @@ use std::{env, path::Path};
 const WIDTH: u32 = 800;
 const HEIGHT: u32 = 600;
 
-struct ImageDemo(Image);
+struct ImageDemo {
+    original: Image,
+    image: Image,
+    error: Option<String>,
+}
 
 impl ImageDemo {
     fn new<P: AsRef<Path>>(png: P) -> PixResult<Self> {
-        Ok(Self(Image::from_file(png)?))
+        let original = Image::from_file(png)?;
+        Ok(Self {
+            image: original.clone(),
+            original,
+            error: None,
+        })
     }
 }
 
@@ -22,13 +31,24 @@ impl PixEngine for ImageDemo {
 
     fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
         s.clear()?;
-        s.image(&self.0, [s.width()? as i32 / 2, s.height()? as i32 / 2])?;
+        s.image(&self.image, [s.width()? as i32 / 2, s.height()? as i32 / 2])?;
         s.text("<Esc>: Disable Tint")?;
         s.text("<Return>: Random Tint")?;
         s.text("<Left>: Disable Blend Mode")?;
         s.text("<Right>: Alpha Blend Mode")?;
         s.text("<Up>: Additive Blend Mode")?;
         s.text("<Down>: Modulated Blend Mode")?;
+        s.text("<1>: Flip  <2>: Rotate 90  <3>: Rotate 15  <4>: Scale Up")?;
+        s.text("<5>: Blur  <6>: Sharpen  <7>: Edge Detect")?;
+        s.text("<8>: Invert  <9>: Grayscale  <0>: Posterize")?;
+        s.text("<Backspace>: Reset Image")?;
+        s.text("Drop an image file to open it")?;
+        if let Some(error) = &self.error {
+            s.push();
+            s.fill(Color::RED);
+            s.text(error)?;
+            s.pop();
+        }
         Ok(())
     }
 
@@ -40,10 +60,39 @@ impl PixEngine for ImageDemo {
             Key::Right => s.blend_mode(BlendMode::Blend),
             Key::Up => s.blend_mode(BlendMode::Add),
             Key::Down => s.blend_mode(BlendMode::Mod),
+            Key::Num1 => self.image = self.image.flip(Flipped::Horizontal),
+            Key::Num2 => self.image = self.image.rotate90(),
+            Key::Num3 => self.image = self.image.rotate(15.0, ResizeFilter::Bilinear),
+            Key::Num4 => {
+                let (width, height) = self.image.dimensions();
+                self.image =
+                    self.image
+                        .resize(width * 5 / 4, height * 5 / 4, ResizeFilter::Bicubic);
+            }
+            Key::Num5 => self.image = self.image.blur(2),
+            Key::Num6 => self.image = self.image.sharpen(),
+            Key::Num7 => self.image = self.image.edge_detect(),
+            Key::Num8 => self.image = self.image.invert(),
+            Key::Num9 => self.image = self.image.grayscale(),
+            Key::Num0 => self.image = self.image.posterize(4),
+            Key::Backspace => self.image = self.original.clone(),
             _ => (),
         }
         Ok(false)
     }
+
+    fn on_file_dropped(
+        &mut self,
+        _s: &mut PixState,
+        _window_id: WindowId,
+        path: &Path,
+    ) -> PixResult<bool> {
+        match Self::new(path) {
+            Ok(demo) => *self = demo,
+            Err(err) => self.error = Some(format!("Failed to open dropped file: {err}")),
+        }
+        Ok(true)
+    }
 }
 
 fn main() -> PixResult<()> {
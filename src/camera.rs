@@ -0,0 +1,519 @@
+//! [`Camera2D`] functions for viewing a scrolling, zoomable world.
+//!
+//! A `Camera2D` maps world coordinates to the screen by centering its position in the viewport,
+//! then applying zoom and rotation. Drawing between [`PixState::begin_camera`] and
+//! [`PixState::end_camera`] applies the camera as the current [Transform], so shapes, images,
+//! sprites and [`Tilemap`]s can be drawn at their world positions. Text and UI widgets are best
+//! drawn after [`PixState::end_camera`] in screen coordinates.
+//!
+//! Cameras can smoothly follow a target, stay within world bounds and shake. Call
+//! [`Camera2D::update`] once per frame to advance them.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::begin_camera`]: Draw subsequent shapes and images through a [`Camera2D`].
+//! - [`PixState::end_camera`]: Restore drawing settings from before
+//!   [`PixState::begin_camera`].
+//! - [`PixState::world_mouse_pos`]: Returns the world position under the mouse.
+//!
+//! # Example
+//!
+//! ```
+//! # use pix_engine::prelude::*;
+//! # use std::time::Duration;
+//! # struct App { camera: Camera2D, player: Point<f64>, map: Tilemap };
+//! # impl PixEngine for App {
+//! fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     self.camera.follow(self.player);
+//!     self.camera.update(s.delta_time());
+//!
+//!     s.background(Color::BLACK);
+//!     s.begin_camera(&self.camera);
+//!     s.tilemap(&self.map, self.camera.view())?;
+//!     s.circle([self.player.x() as i32, self.player.y() as i32, 8])?;
+//!     s.end_camera();
+//!
+//!     s.text(format!("Mouse: {:?}", s.world_mouse_pos(&self.camera)))?;
+//!     Ok(())
+//! }
+//!
+//! fn on_key_pressed(&mut self, s: &mut PixState, event: KeyEvent) -> PixResult<bool> {
+//!     if event.key == Key::Space {
+//!         self.camera.shake(8.0, Duration::from_millis(300));
+//!     }
+//!     Ok(false)
+//! }
+//! # }
+//! ```
+
+use crate::prelude::*;
+use std::time::Duration;
+
+/// Default rate at which a [`Camera2D`] catches up with its follow target.
+const DEFAULT_FOLLOW_SPEED: f64 = 8.0;
+/// Smallest allowed [`Camera2D`] zoom.
+const MIN_ZOOM: f64 = 1e-3;
+
+/// Camera screen shake in progress.
+#[derive(Default, Debug, Copy, Clone, PartialEq)]
+struct Shake {
+    intensity: f64,
+    duration: Duration,
+    remaining: Duration,
+    offset: Point<f64>,
+}
+
+/// A 2D camera with position, zoom, rotation, bounds clamping, smooth follow and screen shake.
+/// See the [module-level documentation](self) for more details.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[must_use]
+pub struct Camera2D {
+    viewport: (u32, u32),
+    position: Point<f64>,
+    zoom: f64,
+    rotation: f64,
+    bounds: Option<Rect<f64>>,
+    target: Option<Point<f64>>,
+    follow_speed: f64,
+    shake: Shake,
+}
+
+impl Default for Camera2D {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+impl Camera2D {
+    /// Constructs a `Camera2D` for a viewport of `width` by `height` pixels, usually the size of
+    /// the canvas. The camera starts centered on the viewport, so world and screen coordinates
+    /// match.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let camera = Camera2D::new(800, 600);
+    /// assert_eq!(camera.position(), point!(400.0, 300.0));
+    /// assert_eq!(camera.world_to_screen([10.0, 20.0]), point!(10.0, 20.0));
+    /// ```
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            viewport: (width, height),
+            position: point!(f64::from(width) / 2.0, f64::from(height) / 2.0),
+            zoom: 1.0,
+            rotation: 0.0,
+            bounds: None,
+            target: None,
+            follow_speed: DEFAULT_FOLLOW_SPEED,
+            shake: Shake::default(),
+        }
+    }
+
+    /// Returns the viewport size in pixels as `(width, height)`.
+    #[inline]
+    #[must_use]
+    pub const fn viewport(&self) -> (u32, u32) {
+        self.viewport
+    }
+
+    /// Set the viewport size in pixels, e.g. when the window is resized.
+    #[inline]
+    pub fn set_viewport(&mut self, width: u32, height: u32) {
+        self.viewport = (width, height);
+        self.clamp();
+    }
+
+    /// Returns the world position at the center of the viewport.
+    #[inline]
+    pub const fn position(&self) -> Point<f64> {
+        self.position
+    }
+
+    /// Center the viewport on a world `position`, kept within the [bounds](Camera2D::set_bounds).
+    #[inline]
+    pub fn set_position<P: Into<Point<f64>>>(&mut self, position: P) {
+        self.position = position.into();
+        self.clamp();
+    }
+
+    /// Move the camera by `(x, y)` in world coordinates.
+    #[inline]
+    pub fn translate(&mut self, x: f64, y: f64) {
+        self.set_position(self.position + point!(x, y));
+    }
+
+    /// Returns the zoom, where `2.0` draws the world at twice its size. Defaults to `1.0`.
+    #[inline]
+    #[must_use]
+    pub const fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// Set the zoom, where `2.0` draws the world at twice its size.
+    #[inline]
+    pub fn set_zoom(&mut self, zoom: f64) {
+        self.zoom = zoom.max(MIN_ZOOM);
+        self.clamp();
+    }
+
+    /// Returns the clockwise rotation of the camera in radians. The world appears rotated
+    /// counter-clockwise.
+    #[inline]
+    #[must_use]
+    pub const fn rotation(&self) -> f64 {
+        self.rotation
+    }
+
+    /// Set the clockwise rotation of the camera in radians.
+    #[inline]
+    pub fn set_rotation(&mut self, rotation: f64) {
+        self.rotation = rotation;
+    }
+
+    /// Returns the world area the camera is kept within, if any.
+    #[inline]
+    #[must_use]
+    pub const fn bounds(&self) -> Option<Rect<f64>> {
+        self.bounds
+    }
+
+    /// Keep the visible area within a world area, e.g. [`Tilemap::bounds`]. If the visible area
+    /// is larger than `bounds`, the camera is centered on it instead. Rotation is ignored when
+    /// clamping.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let mut camera = Camera2D::new(100, 100);
+    /// camera.set_bounds(rect![0.0, 0.0, 400.0, 300.0]);
+    /// camera.set_position([0.0, 1000.0]);
+    /// assert_eq!(camera.position(), point!(50.0, 250.0));
+    /// ```
+    #[inline]
+    pub fn set_bounds<R: Into<Option<Rect<f64>>>>(&mut self, bounds: R) {
+        self.bounds = bounds.into();
+        self.clamp();
+    }
+
+    /// Smoothly move towards a world `target` each [update](Camera2D::update).
+    #[inline]
+    pub fn follow<P: Into<Point<f64>>>(&mut self, target: P) {
+        self.target = Some(target.into());
+    }
+
+    /// Stop following the current target.
+    #[inline]
+    pub fn stop_following(&mut self) {
+        self.target = None;
+    }
+
+    /// Returns the rate at which the camera catches up with its follow target. Defaults to `8.0`.
+    #[inline]
+    #[must_use]
+    pub const fn follow_speed(&self) -> f64 {
+        self.follow_speed
+    }
+
+    /// Set the rate at which the camera catches up with its follow target. The distance left
+    /// shrinks exponentially, with a fraction of `e^-speed` remaining after each second. Higher
+    /// values are snappier and [`f64::INFINITY`] snaps to the target immediately.
+    #[inline]
+    pub fn set_follow_speed(&mut self, speed: f64) {
+        self.follow_speed = speed.max(0.0);
+    }
+
+    /// Shake the camera by up to `intensity` screen pixels, fading out over `duration`. Replaces
+    /// any shake in progress. Negative intensities are treated as `0.0`.
+    #[inline]
+    pub fn shake(&mut self, intensity: f64, duration: Duration) {
+        self.shake = Shake {
+            intensity: intensity.max(0.0),
+            duration,
+            remaining: duration,
+            offset: Point::default(),
+        };
+    }
+
+    /// Whether a screen shake is in progress.
+    #[inline]
+    #[must_use]
+    pub fn is_shaking(&self) -> bool {
+        !self.shake.remaining.is_zero()
+    }
+
+    /// Advance following and screen shake by `delta` time, usually [`PixState::delta_time`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # use std::time::Duration;
+    /// let mut camera = Camera2D::new(100, 100);
+    /// camera.set_follow_speed(f64::INFINITY);
+    /// camera.follow([200.0, 50.0]);
+    /// camera.update(Duration::from_millis(16));
+    /// assert_eq!(camera.position(), point!(200.0, 50.0));
+    /// ```
+    pub fn update(&mut self, delta: Duration) {
+        if let Some(target) = self.target {
+            let t = 1.0 - (-self.follow_speed * delta.as_secs_f64()).exp();
+            let t = if t.is_nan() { 1.0 } else { t };
+            self.position = self.position + (target - self.position) * t;
+            self.clamp();
+        }
+
+        let shake = &mut self.shake;
+        shake.remaining = shake.remaining.saturating_sub(delta);
+        let strength = if shake.remaining.is_zero() {
+            0.0
+        } else {
+            shake.intensity * shake.remaining.as_secs_f64() / shake.duration.as_secs_f64()
+        };
+        shake.offset = if strength > 0.0 {
+            point!(random!(-strength, strength), random!(-strength, strength))
+        } else {
+            Point::default()
+        };
+    }
+
+    /// Returns the [Transform] from world to screen coordinates.
+    pub fn transform(&self) -> Transform {
+        let (width, height) = self.viewport;
+        let offset = self.shake.offset;
+        Transform::translation(
+            f64::from(width) / 2.0 + offset.x(),
+            f64::from(height) / 2.0 + offset.y(),
+        )
+        .rotate(-self.rotation)
+        .scale(self.zoom, self.zoom)
+        .translate(-self.position.x(), -self.position.y())
+    }
+
+    /// Convert a world `point` to screen coordinates.
+    #[inline]
+    pub fn world_to_screen<P: Into<Point<f64>>>(&self, point: P) -> Point<f64> {
+        self.transform().transform_point(point)
+    }
+
+    /// Convert a screen `point`, such as [`PixState::mouse_pos`], to world coordinates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let mut camera = Camera2D::new(100, 100);
+    /// camera.set_position([500.0, 500.0]);
+    /// camera.set_zoom(2.0);
+    /// // The center of the viewport is the camera position
+    /// assert_eq!(camera.screen_to_world([50, 50]), point!(500.0, 500.0));
+    /// assert_eq!(camera.screen_to_world([0, 0]), point!(475.0, 475.0));
+    /// ```
+    #[inline]
+    pub fn screen_to_world<P: Into<Point<i32>>>(&self, point: P) -> Point<f64> {
+        let point = point.into().as_::<f64>();
+        // Zoom is always positive, so the transform is invertible
+        self.transform()
+            .inverse()
+            .map_or(point, |inverse| inverse.transform_point(point))
+    }
+
+    /// Returns the world area visible through the viewport, rounded out to whole pixels. When
+    /// rotated, this is the bounding box of the visible area. Useful to only draw what's on
+    /// screen, e.g. with [`PixState::tilemap`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let mut camera = Camera2D::new(100, 50);
+    /// camera.set_position([0.0, 0.0]);
+    /// camera.set_zoom(2.0);
+    /// assert_eq!(camera.view(), rect![-25, -13, 50, 26]);
+    /// ```
+    pub fn view(&self) -> Rect<i32> {
+        let (width, height) = self.viewport;
+        let (width, height) = (width as i32, height as i32);
+        let corners = [[0, 0], [width, 0], [0, height], [width, height]]
+            .map(|corner| self.screen_to_world(corner));
+        let (mut min, mut max) = (corners[0], corners[0]);
+        for corner in &corners[1..] {
+            min = point!(min.x().min(corner.x()), min.y().min(corner.y()));
+            max = point!(max.x().max(corner.x()), max.y().max(corner.y()));
+        }
+        let (left, top) = (min.x().floor() as i32, min.y().floor() as i32);
+        let (right, bottom) = (max.x().ceil() as i32, max.y().ceil() as i32);
+        rect![left, top, right - left, bottom - top]
+    }
+}
+
+impl Camera2D {
+    /// Keep the camera position within its bounds.
+    fn clamp(&mut self) {
+        let Some(bounds) = self.bounds else {
+            return;
+        };
+        let (width, height) = self.viewport;
+        let half_width = f64::from(width) / self.zoom / 2.0;
+        let half_height = f64::from(height) / self.zoom / 2.0;
+        let clamp = |value: f64, min: f64, max: f64, half: f64| {
+            if max - min < 2.0 * half {
+                (min + max) / 2.0
+            } else {
+                value.clamp(min + half, max - half)
+            }
+        };
+        self.position = point!(
+            clamp(self.position.x(), bounds.left(), bounds.right(), half_width),
+            clamp(
+                self.position.y(),
+                bounds.top(),
+                bounds.bottom(),
+                half_height
+            )
+        );
+    }
+}
+
+impl PixState {
+    /// Draw subsequent shapes, images, sprites and tilemaps in world coordinates through a
+    /// [`Camera2D`] until [`PixState::end_camera`] is called. Pushes the current settings and
+    /// applies [`Camera2D::transform`] to the current [Transform].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { camera: Camera2D, ship: Point<i32> };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.begin_camera(&self.camera);
+    ///     s.rect([self.ship.x(), self.ship.y(), 10, 10])?;
+    ///     s.end_camera();
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn begin_camera(&mut self, camera: &Camera2D) {
+        self.push();
+        self.apply_transform(camera.transform());
+    }
+
+    /// Restore the settings pushed by [`PixState::begin_camera`], returning to screen coordinates.
+    #[inline]
+    pub fn end_camera(&mut self) {
+        self.pop();
+    }
+
+    /// Returns the world position under the mouse for a [`Camera2D`]. See
+    /// [`Camera2D::screen_to_world`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { camera: Camera2D };
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_mouse_pressed(
+    ///     &mut self,
+    ///     s: &mut PixState,
+    ///     btn: Mouse,
+    ///     pos: Point<i32>,
+    /// ) -> PixResult<bool> {
+    ///     if btn == Mouse::Middle {
+    ///         let target = s.world_mouse_pos(&self.camera);
+    ///         self.camera.follow(target);
+    ///     }
+    ///     Ok(false)
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn world_mouse_pos(&self, camera: &Camera2D) -> Point<f64> {
+        camera.screen_to_world(self.mouse_pos())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: Point<f64>, expected: Point<f64>) {
+        let distance = (actual - expected)
+            .to_vec()
+            .iter()
+            .map(|v| v * v)
+            .sum::<f64>();
+        assert!(distance < 1e-9, "{actual:?} != {expected:?}");
+    }
+
+    #[test]
+    fn test_world_to_screen() {
+        let mut camera = Camera2D::new(200, 100);
+        camera.set_position([1000.0, 500.0]);
+        assert_near(camera.world_to_screen([1000.0, 500.0]), point!(100.0, 50.0));
+        assert_near(camera.world_to_screen([1010.0, 500.0]), point!(110.0, 50.0));
+
+        camera.set_zoom(2.0);
+        assert_near(camera.world_to_screen([1010.0, 495.0]), point!(120.0, 40.0));
+        camera.set_rotation(std::f64::consts::FRAC_PI_2);
+        // Turning the camera clockwise turns the world counter-clockwise
+        assert_near(camera.world_to_screen([1010.0, 500.0]), point!(100.0, 30.0));
+
+        let screen = point!(37, 81);
+        let world = camera.screen_to_world(screen);
+        assert_near(camera.world_to_screen(world), screen.as_());
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut camera = Camera2D::new(100, 100);
+        camera.set_bounds(rect![0.0, 0.0, 300.0, 80.0]);
+        camera.set_position([-50.0, 0.0]);
+        // Too short for the viewport, so centered vertically
+        assert_eq!(camera.position(), point!(50.0, 40.0));
+        camera.set_zoom(2.0);
+        camera.set_position([400.0, 0.0]);
+        assert_eq!(camera.position(), point!(275.0, 25.0));
+        assert_eq!(camera.view(), rect![250, 0, 50, 50]);
+    }
+
+    #[test]
+    fn test_follow() {
+        let mut camera = Camera2D::new(100, 100);
+        camera.set_position([0.0, 0.0]);
+        camera.follow([100.0, 0.0]);
+        camera.set_follow_speed(2.0_f64.ln());
+        camera.update(Duration::from_secs(1));
+        assert_near(camera.position(), point!(50.0, 0.0));
+        camera.update(Duration::from_secs(1));
+        assert_near(camera.position(), point!(75.0, 0.0));
+        camera.stop_following();
+        camera.update(Duration::from_secs(1));
+        assert_near(camera.position(), point!(75.0, 0.0));
+    }
+
+    #[test]
+    fn test_shake() {
+        let mut camera = Camera2D::new(100, 100);
+        camera.shake(4.0, Duration::from_millis(100));
+        for _ in 0..9 {
+            camera.update(Duration::from_millis(10));
+            assert!(camera.is_shaking());
+            let offset = camera.world_to_screen([50.0, 50.0]) - point!(50.0, 50.0);
+            assert!(offset.x().abs() <= 4.0 && offset.y().abs() <= 4.0);
+        }
+        camera.update(Duration::from_millis(10));
+        assert!(!camera.is_shaking());
+        assert_eq!(camera.world_to_screen([50.0, 50.0]), point!(50.0, 50.0));
+
+        for intensity in [0.0, -4.0] {
+            camera.shake(intensity, Duration::from_millis(100));
+            camera.update(Duration::from_millis(10));
+            assert!(camera.is_shaking());
+            assert_eq!(camera.world_to_screen([50.0, 50.0]), point!(50.0, 50.0));
+        }
+    }
+}
@@ -24,6 +24,9 @@
 //! `0.0..=1.0`. The [Mode] can be changed any time to alter this behavior using
 //! [`set_mode`](Color::set_mode).
 //!
+//! Shapes can also be filled and stroked with gradients using a [`Paint`](paint::Paint). See the
+//! [paint] module for details.
+//!
 //! There are also several named color [constants] available in the
 //! [prelude](crate::prelude) matching the [SVG 1.0 Color
 //! Keywords](https://www.w3.org/TR/SVG11/types.html#ColorKeywords).
@@ -113,6 +116,7 @@ use std::fmt;
 pub mod constants;
 pub mod conversion;
 pub mod ops;
+pub mod paint;
 
 /// [Color] mode indicating level interpretation.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -156,6 +156,10 @@ impl Color {
     /// Constructs a `Color` by linear interpolating between two `Color`s by a given amount between
     /// `0.0` and `1.0`.
     ///
+    /// Levels are interpolated in the [`ColorMode`] of `self`, so [Hsb] and [Hsl] colors
+    /// interpolate hue, saturation and brightness or lightness before being converted back to
+    /// `RGB` channels. The returned color keeps the [`ColorMode`] of `self`.
+    ///
     /// # Examples
     ///
     /// ```
@@ -169,6 +173,11 @@ impl Color {
     /// let to = hsb!(120.0, 80.0, 100.0, 0.5);
     /// let lerped = from.lerp(to, 0.25); // `to` is implicity converted to RGB
     /// assert_eq!(lerped.channels(), [204, 64, 13, 223]);
+    ///
+    /// let from = hsb!(0.0, 100.0, 100.0);
+    /// let to = hsb!(240.0, 100.0, 100.0);
+    /// let lerped = from.lerp(to, 0.5); // Interpolates hue, passing through green
+    /// assert_eq!(lerped.channels(), [0, 255, 0, 255]);
     /// ```
     pub fn lerp<A>(&self, other: Color, amt: A) -> Self
     where
@@ -187,7 +196,7 @@ impl Color {
         ]);
         Self {
             mode: self.mode,
-            channels: calculate_channels(levels),
+            channels: calculate_channels(convert_levels(levels, self.mode, Rgb)),
         }
     }
 }
@@ -512,7 +521,10 @@ impl_from!(i32, u32, f64);
 
 #[cfg(test)]
 mod tests {
-    use crate::prelude::{hsb, hsl, rgb, Color};
+    use crate::{
+        color::Mode,
+        prelude::{hsb, hsl, rgb, Color},
+    };
 
     macro_rules! assert_color_eq {
         ($c1:expr, $c2:expr) => {
@@ -568,6 +580,24 @@ mod tests {
         let _: Color = [50.0f64, 100.0, 55.0, 100.0].into();
     }
 
+    #[test]
+    fn test_lerp() {
+        assert_color_eq!(
+            rgb!(255, 0, 0).lerp(rgb!(0, 0, 255), 0.5),
+            rgb!(128, 0, 128)
+        );
+
+        let lerped = hsb!(0.0, 0.0, 0.0).lerp(hsb!(0.0, 0.0, 100.0), 0.5);
+        assert_eq!(lerped.mode(), Mode::Hsb);
+        assert_color_eq!(lerped, rgb!(128, 128, 128));
+        assert_color_eq!(lerped, hsb!(0.0, 0.0, 50.0));
+
+        let lerped = hsl!(0.0, 100.0, 50.0).lerp(hsl!(240.0, 100.0, 50.0), 0.5);
+        assert_eq!(lerped.mode(), Mode::Hsl);
+        assert_color_eq!(lerped, rgb!(0, 255, 0));
+        assert_color_eq!(lerped, hsl!(120.0, 100.0, 50.0));
+    }
+
     #[test]
     fn test_hsb_to_rgb() {
         assert_color_eq!(hsb!(0.0, 0.0, 0.0), rgb!(0, 0, 0));
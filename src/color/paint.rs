@@ -0,0 +1,406 @@
+//! [Paint] types used to fill and stroke shapes with solid colors or gradients.
+//!
+//! A [Gradient] is a list of color stops at offsets ranging from `0.0..=1.0`. Colors between stops
+//! are interpolated using [`Color::lerp`] in the gradient's color [Mode], so the same stops can
+//! blend through `RGB` or around the `HSB`/`HSL` hue wheel.
+//!
+//! A [Paint] maps every point of a shape to a [Color], either as a single solid color or using a
+//! linear, radial, or conic [Gradient]. Gradient coordinates are in the same space as the shapes
+//! they're drawn with, so they move with the current [Transform].
+//!
+//! # Examples
+//!
+//! ```
+//! use pix_engine::prelude::*;
+//!
+//! let gradient = Gradient::new(ColorMode::Rgb, [(0.0, Color::RED), (1.0, Color::BLUE)]);
+//! assert_eq!(gradient.color_at(0.5).channels(), [128, 0, 128, 255]);
+//!
+//! let paint = Paint::linear([0.0, 0.0], [100.0, 0.0], gradient);
+//! assert_eq!(paint.color_at([0.0, 50.0]), Color::RED);
+//! assert_eq!(paint.color_at([100.0, 50.0]), Color::BLUE);
+//! ```
+
+use super::Mode;
+use crate::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    f64::consts::TAU,
+    hash::{Hash, Hasher},
+};
+
+/// A list of [Color] stops interpolated in a given color [Mode].
+///
+/// Please see the [module-level documentation] for examples.
+///
+/// [module-level documentation]: crate::color::paint
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[must_use]
+pub struct Gradient {
+    mode: Mode,
+    stops: Vec<(f64, Color)>,
+}
+
+impl Gradient {
+    /// Constructs a `Gradient` from a list of `(offset, color)` stops, interpolating colors in the
+    /// given [Mode]. Offsets are clamped to `0.0..=1.0`.
+    pub fn new<I, C>(mode: Mode, stops: I) -> Self
+    where
+        I: IntoIterator<Item = (f64, C)>,
+        C: Into<Color>,
+    {
+        let mut gradient = Self {
+            mode,
+            stops: Vec::new(),
+        };
+        for (offset, color) in stops {
+            gradient.add_stop(offset, color);
+        }
+        gradient
+    }
+
+    /// Add a [Color] stop at `offset`, clamped to `0.0..=1.0`. Stops at the same offset are kept
+    /// in the order they're added, creating a hard edge between them.
+    pub fn add_stop<C>(&mut self, offset: f64, color: C) -> &mut Self
+    where
+        C: Into<Color>,
+    {
+        let offset = if offset.is_nan() {
+            0.0
+        } else {
+            offset.clamp(0.0, 1.0)
+        };
+        let index = self.stops.partition_point(|&(o, _)| o <= offset);
+        self.stops.insert(index, (offset, color.into()));
+        self
+    }
+
+    /// Returns the color [Mode] used to interpolate between stops.
+    #[inline]
+    pub const fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the `(offset, color)` stops, ordered by offset.
+    #[inline]
+    pub fn stops(&self) -> &[(f64, Color)] {
+        &self.stops
+    }
+
+    /// Returns the interpolated [Color] at offset `t`, clamped to `0.0..=1.0`. Offsets before the
+    /// first stop or after the last use the color of that stop. An empty gradient is transparent.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let red = hsb!(0.0, 100.0, 100.0);
+    /// let blue = hsb!(240.0, 100.0, 100.0);
+    /// let gradient = Gradient::new(ColorMode::Hsb, [(0.0, red), (1.0, blue)]);
+    /// // Interpolating hue passes through green instead of gray.
+    /// assert_eq!(gradient.color_at(0.5).channels(), [0, 255, 0, 255]);
+    /// ```
+    pub fn color_at(&self, t: f64) -> Color {
+        let (Some(&(first_offset, first)), Some(&(last_offset, last))) =
+            (self.stops.first(), self.stops.last())
+        else {
+            return Color::TRANSPARENT;
+        };
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        if t <= first_offset {
+            return first;
+        } else if t >= last_offset {
+            return last;
+        }
+        let index = self.stops.partition_point(|&(o, _)| o <= t);
+        let (start_offset, mut start) = self.stops[index - 1];
+        let (end_offset, end) = self.stops[index];
+        start.set_mode(self.mode);
+        start.lerp(end, (t - start_offset) / (end_offset - start_offset))
+    }
+}
+
+impl PartialEq for Gradient {
+    fn eq(&self, other: &Self) -> bool {
+        self.mode == other.mode
+            && self.stops.len() == other.stops.len()
+            && self
+                .stops
+                .iter()
+                .zip(&other.stops)
+                .all(|((o1, c1), (o2, c2))| o1.to_bits() == o2.to_bits() && c1 == c2)
+    }
+}
+
+impl Eq for Gradient {}
+
+impl Hash for Gradient {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.mode.hash(state);
+        for (offset, color) in &self.stops {
+            offset.to_bits().hash(state);
+            color.hash(state);
+        }
+    }
+}
+
+/// A `Paint` used to fill or stroke shapes with a solid [Color] or a [Gradient].
+///
+/// Please see the [module-level documentation] for examples.
+///
+/// [module-level documentation]: crate::color::paint
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[must_use]
+pub enum Paint {
+    /// A single solid [Color].
+    Solid(Color),
+    /// A [Gradient] along the line from `start` to `end`. Points are colored based on how far
+    /// they project onto the line.
+    LinearGradient {
+        /// Where the gradient starts, at offset `0.0`.
+        start: Point<f64>,
+        /// Where the gradient ends, at offset `1.0`.
+        end: Point<f64>,
+        /// The gradient color stops.
+        gradient: Gradient,
+    },
+    /// A [Gradient] radiating out from `center`, reaching offset `1.0` at `radius`.
+    RadialGradient {
+        /// The center of the gradient, at offset `0.0`.
+        center: Point<f64>,
+        /// The distance from `center` at offset `1.0`.
+        radius: f64,
+        /// The gradient color stops.
+        gradient: Gradient,
+    },
+    /// A [Gradient] sweeping clockwise around `center`, starting at `angle` in radians.
+    ConicGradient {
+        /// The center of the gradient.
+        center: Point<f64>,
+        /// The angle in radians at offset `0.0`, measured clockwise from the positive x-axis.
+        angle: f64,
+        /// The gradient color stops.
+        gradient: Gradient,
+    },
+}
+
+impl Default for Paint {
+    fn default() -> Self {
+        Self::Solid(Color::default())
+    }
+}
+
+impl Paint {
+    /// Constructs a [`Paint::LinearGradient`] from `start` to `end`.
+    pub fn linear<P1, P2>(start: P1, end: P2, gradient: Gradient) -> Self
+    where
+        P1: Into<Point<f64>>,
+        P2: Into<Point<f64>>,
+    {
+        Self::LinearGradient {
+            start: start.into(),
+            end: end.into(),
+            gradient,
+        }
+    }
+
+    /// Constructs a [`Paint::RadialGradient`] around `center` out to `radius`.
+    pub fn radial<P>(center: P, radius: f64, gradient: Gradient) -> Self
+    where
+        P: Into<Point<f64>>,
+    {
+        Self::RadialGradient {
+            center: center.into(),
+            radius,
+            gradient,
+        }
+    }
+
+    /// Constructs a [`Paint::ConicGradient`] around `center`, starting at `angle` in radians.
+    pub fn conic<P>(center: P, angle: f64, gradient: Gradient) -> Self
+    where
+        P: Into<Point<f64>>,
+    {
+        Self::ConicGradient {
+            center: center.into(),
+            angle,
+            gradient,
+        }
+    }
+
+    /// Whether this paint is a single solid [Color].
+    #[inline]
+    #[must_use]
+    pub const fn is_solid(&self) -> bool {
+        matches!(self, Self::Solid(_))
+    }
+
+    /// Returns a single [Color] representing this paint, used where gradients aren't supported.
+    /// Gradients return the color of their first stop.
+    #[inline]
+    pub fn base_color(&self) -> Color {
+        match self {
+            Self::Solid(color) => *color,
+            Self::LinearGradient { gradient, .. }
+            | Self::RadialGradient { gradient, .. }
+            | Self::ConicGradient { gradient, .. } => gradient.color_at(0.0),
+        }
+    }
+
+    /// Returns the [Color] of this paint at point `p`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let gradient = Gradient::new(ColorMode::Rgb, [(0.0, Color::WHITE), (1.0, Color::BLACK)]);
+    /// let paint = Paint::radial([50.0, 50.0], 10.0, gradient);
+    /// assert_eq!(paint.color_at([50.0, 50.0]), Color::WHITE);
+    /// assert_eq!(paint.color_at([55.0, 50.0]).channels(), [128, 128, 128, 255]);
+    /// assert_eq!(paint.color_at([0.0, 0.0]), Color::BLACK);
+    /// ```
+    pub fn color_at<P>(&self, p: P) -> Color
+    where
+        P: Into<Point<f64>>,
+    {
+        let [x, y] = p.into().coords();
+        match self {
+            Self::Solid(color) => *color,
+            Self::LinearGradient {
+                start,
+                end,
+                gradient,
+            } => {
+                let [dx, dy] = [end.x() - start.x(), end.y() - start.y()];
+                let length = dx.mul_add(dx, dy * dy);
+                let t = if length > 0.0 {
+                    (x - start.x()).mul_add(dx, (y - start.y()) * dy) / length
+                } else {
+                    0.0
+                };
+                gradient.color_at(t)
+            }
+            Self::RadialGradient {
+                center,
+                radius,
+                gradient,
+            } => {
+                let distance = (x - center.x()).hypot(y - center.y());
+                let t = if *radius > 0.0 {
+                    distance / radius
+                } else {
+                    1.0
+                };
+                gradient.color_at(t)
+            }
+            Self::ConicGradient {
+                center,
+                angle,
+                gradient,
+            } => {
+                let theta = (y - center.y()).atan2(x - center.x());
+                gradient.color_at((theta - angle).rem_euclid(TAU) / TAU)
+            }
+        }
+    }
+}
+
+impl From<Color> for Paint {
+    fn from(color: Color) -> Self {
+        Self::Solid(color)
+    }
+}
+
+impl PartialEq for Paint {
+    fn eq(&self, other: &Self) -> bool {
+        let eq_point = |a: &Point<f64>, b: &Point<f64>| {
+            a.x().to_bits() == b.x().to_bits() && a.y().to_bits() == b.y().to_bits()
+        };
+        match (self, other) {
+            (Self::Solid(a), Self::Solid(b)) => a == b,
+            (
+                Self::LinearGradient {
+                    start: s1,
+                    end: e1,
+                    gradient: g1,
+                },
+                Self::LinearGradient {
+                    start: s2,
+                    end: e2,
+                    gradient: g2,
+                },
+            ) => eq_point(s1, s2) && eq_point(e1, e2) && g1 == g2,
+            (
+                Self::RadialGradient {
+                    center: c1,
+                    radius: r1,
+                    gradient: g1,
+                },
+                Self::RadialGradient {
+                    center: c2,
+                    radius: r2,
+                    gradient: g2,
+                },
+            ) => eq_point(c1, c2) && r1.to_bits() == r2.to_bits() && g1 == g2,
+            (
+                Self::ConicGradient {
+                    center: c1,
+                    angle: a1,
+                    gradient: g1,
+                },
+                Self::ConicGradient {
+                    center: c2,
+                    angle: a2,
+                    gradient: g2,
+                },
+            ) => eq_point(c1, c2) && a1.to_bits() == a2.to_bits() && g1 == g2,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Paint {}
+
+impl Hash for Paint {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        let hash_point = |p: &Point<f64>, state: &mut H| {
+            p.x().to_bits().hash(state);
+            p.y().to_bits().hash(state);
+        };
+        match self {
+            Self::Solid(color) => color.hash(state),
+            Self::LinearGradient {
+                start,
+                end,
+                gradient,
+            } => {
+                hash_point(start, state);
+                hash_point(end, state);
+                gradient.hash(state);
+            }
+            Self::RadialGradient {
+                center,
+                radius,
+                gradient,
+            } => {
+                hash_point(center, state);
+                radius.to_bits().hash(state);
+                gradient.hash(state);
+            }
+            Self::ConicGradient {
+                center,
+                angle,
+                gradient,
+            } => {
+                hash_point(center, state);
+                angle.to_bits().hash(state);
+                gradient.hash(state);
+            }
+        }
+    }
+}
@@ -5,7 +5,11 @@
 //! Provided [`PixState`] methods:
 //!
 //! - [`PixState::clear`]: Clear the render target to the current background [Color].
-//! - [`PixState::save_canvas`]: Save the current render target out to a [png] file.
+//! - [`PixState::to_bytes`]: Read the pixels of the current render target as `RGBA` bytes.
+//! - [`PixState::load_pixels`]: Load the pixels of the current render target into a mutable
+//!   [Image] buffer.
+//! - [`PixState::update_pixels`]: Write the loaded pixel buffer back to the render target.
+//! - [`PixState::save_canvas`]: Save the current render target out to an image file.
 //!
 //! # Example
 //!
@@ -25,11 +29,14 @@
 //! # }
 //! ```
 
-use anyhow::Context;
+use anyhow::anyhow;
 
-use crate::{prelude::*, renderer::Rendering};
+use crate::{
+    prelude::*,
+    renderer::{Rendering, TextureRenderer},
+};
 use log::info;
-use std::{fs::File, io::BufWriter, path::Path};
+use std::path::Path;
 
 /// Trait for objects that can be drawn to the screen.
 pub trait Draw {
@@ -83,16 +90,132 @@ impl PixState {
         self.renderer.clear()
     }
 
-    /// Save a portion `src` of the currently rendered target to a [png] file. Passing `None` for
-    /// `src` saves the entire target.
+    /// Returns the pixels of the current render target as a vector of `RGBA` bytes, in row-major
+    /// order starting from the top-left.
+    ///
+    /// # Errors
+    ///
+    /// If the current render target is closed or dropped, or the renderer fails to read pixels
+    /// from the current render target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.background(Color::RED);
+    ///     s.clear()?;
+    ///     let bytes = s.to_bytes()?;
+    ///     assert_eq!(&bytes[0..4], &[255, 0, 0, 255]);
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn to_bytes(&mut self) -> PixResult<Vec<u8>> {
+        self.renderer.to_bytes()
+    }
+
+    /// Loads the pixels of the current render target (window or texture set by
+    /// [`PixState::set_texture_target`]) into an `RGBA` [Image] buffer and returns it for
+    /// modification. Changes are written back to the render target by calling
+    /// [`PixState::update_pixels`].
+    ///
+    /// Calling `load_pixels` again before `update_pixels` discards any unsaved changes and
+    /// re-reads the render target.
+    ///
+    /// The buffer has one pixel per physical pixel of the render target, so it may be larger than
+    /// [`PixState::dimensions`] on high-DPI displays.
+    ///
+    /// # Errors
+    ///
+    /// If the current render target is closed or dropped, or the renderer fails to read pixels
+    /// from the current render target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let pixels = s.load_pixels()?;
+    ///     for pixel in pixels.as_mut_bytes().chunks_exact_mut(4) {
+    ///         // Invert each pixel, leaving alpha untouched
+    ///         pixel[0] = 255 - pixel[0];
+    ///         pixel[1] = 255 - pixel[1];
+    ///         pixel[2] = 255 - pixel[2];
+    ///     }
+    ///     s.update_pixels()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn load_pixels(&mut self) -> PixResult<&mut Image> {
+        let (width, height) = self.renderer.target_size()?;
+        let bytes = self.renderer.to_bytes()?;
+        let image = Image::from_bytes(width, height, bytes, PixelFormat::Rgba)?;
+        let target = self.renderer.texture_target();
+        Ok(&mut self.pixels.insert((target, image)).1)
+    }
+
+    /// Returns the pixel buffer previously loaded by [`PixState::load_pixels`], if any.
+    #[inline]
+    pub fn pixels_mut(&mut self) -> Option<&mut Image> {
+        self.pixels.as_mut().map(|(_, image)| image)
+    }
+
+    /// Writes the pixel buffer loaded by [`PixState::load_pixels`] back to the current render
+    /// target, ignoring the current clip region and [`BlendMode`]. The buffer is consumed and must
+    /// be loaded again for further changes.
+    ///
+    /// # Errors
+    ///
+    /// If no pixels have been loaded, the render target has changed since calling
+    /// [`PixState::load_pixels`], or the renderer fails to write pixels to the current render
+    /// target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let pixels = s.load_pixels()?;
+    ///     let width = pixels.width() as usize;
+    ///     // Draw a red line across the first row
+    ///     for pixel in pixels.as_mut_bytes().chunks_exact_mut(4).take(width) {
+    ///         pixel.copy_from_slice(&[255, 0, 0, 255]);
+    ///     }
+    ///     s.update_pixels()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn update_pixels(&mut self) -> PixResult<()> {
+        let (target, image) = self
+            .pixels
+            .take()
+            .ok_or_else(|| anyhow!("no pixels loaded, call `load_pixels` first"))?;
+        if target != self.renderer.texture_target() {
+            return Err(anyhow!("render target changed since calling `load_pixels`"));
+        }
+        self.renderer.update_bytes(image.as_bytes())
+    }
+
+    /// Save a portion `src` of the currently rendered target to an image file. Passing `None` for
+    /// `src` saves the entire target. The [`ImageFormat`] is chosen based on the file extension.
     ///
     /// # Errors
     ///
     /// Returns an error for any of the following:
     ///     - The current render target is closed or dropped.
     ///     - The renderer fails to read pixels from the current window target.
-    ///     - An [`io::Error`] occurs attempting to create the [png] file.
-    ///     - A [`png::EncodingError`] occurs attempting to write image bytes.
+    ///     - The file extension is not a supported [`ImageFormat`].
+    ///     - An [`io::Error`] occurs attempting to create or write to the file.
     ///
     /// [`io::Error`]: std::io::Error
     ///
@@ -132,18 +255,9 @@ impl PixState {
             self.delete_texture(src_texture)?;
             Ok(())
         } else {
-            let path = path.as_ref();
-            let png_file = BufWriter::new(File::create(path)?);
-            let mut png = png::Encoder::new(png_file, self.width()?, self.height()?);
-            png.set_color(PixelFormat::Rgba.into());
-            png.set_depth(png::BitDepth::Eight);
-            let mut writer = png
-                .write_header()
-                .with_context(|| format!("failed to write png header: {path:?}"))?;
-
-            writer
-                .write_image_data(&self.renderer.to_bytes()?)
-                .with_context(|| format!("failed to write png data: {path:?}"))
+            let (width, height) = self.renderer.target_size()?;
+            let bytes = self.renderer.to_bytes()?;
+            Image::from_bytes(width, height, bytes, PixelFormat::Rgba)?.save(path)
         }
     }
 }
@@ -54,10 +54,14 @@
 //! }
 //! ```
 
-use crate::{image::Icon, prelude::*, renderer::RendererSettings};
+use crate::{
+    image::Icon, input::DEFAULT_DEADZONE, prelude::*, renderer::RendererSettings,
+    state::environment::DEFAULT_MAX_FIXED_STEPS,
+};
 use log::{debug, error, info};
 use std::{
     num::NonZeroUsize,
+    path::Path,
     thread,
     time::{Duration, Instant},
 };
@@ -143,6 +147,39 @@ pub trait PixEngine {
     /// ```
     fn on_update(&mut self, s: &mut PixState) -> PixResult<()>;
 
+    /// Called zero or more times each frame before [`PixEngine::on_update`], once for every
+    /// [fixed timestep] elapsed since the last call.
+    ///
+    /// Only called when a fixed timestep is set with [`EngineBuilder::fixed_timestep`]. Useful for
+    /// physics and other simulation that needs to advance deterministically, independent of frame
+    /// rate. [`PixState::interpolation_alpha`] can be used in [`PixEngine::on_update`] to smooth
+    /// rendering between fixed updates.
+    ///
+    /// [fixed timestep]: PixState::fixed_timestep
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application and call [`PixEngine::on_stop`]. See
+    /// the `Errors` section in [`PixEngine::on_update`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { position: f64, velocity: f64 };
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_fixed_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let step = s.fixed_timestep().unwrap_or_default();
+    ///     self.position += self.velocity * step.as_secs_f64();
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    fn on_fixed_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        Ok(())
+    }
+
     /// Called when the engine detects a close/exit event such as calling [`PixState::quit`] or if an
     /// error is returned during program execution by any [`PixEngine`] methods.
     ///
@@ -717,6 +754,200 @@ pub trait PixEngine {
         Ok(false)
     }
 
+    /// Called each time a finger is pressed on a touch device.
+    ///
+    /// Returning `true` consumes this touch, preventing any [Gesture]s from being recognized for
+    /// the finger until it's released. The finger is still tracked in [`PixState::touches`] and
+    /// passed to [`PixEngine::on_touch_move`] and [`PixEngine::on_touch_end`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application and call [`PixEngine::on_stop`]. See
+    /// the `Errors` section in [`PixEngine::on_update`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { strokes: Vec<Vec<Point<f64>>> };
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_touch_start(&mut self, s: &mut PixState, touch: Touch) -> PixResult<bool> {
+    ///     // Start a new brush stroke for each finger
+    ///     self.strokes.push(vec![touch.pos()]);
+    ///     Ok(false)
+    /// }
+    /// # }
+    /// ```
+    fn on_touch_start(&mut self, s: &mut PixState, touch: Touch) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a finger pressed on a touch device is moved.
+    ///
+    /// Returning `true` consumes this event, preventing any [Gesture]s from being recognized for
+    /// it.
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application and call [`PixEngine::on_stop`]. See
+    /// the `Errors` section in [`PixEngine::on_update`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { pos: Point<f64> };
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_touch_move(&mut self, s: &mut PixState, touch: Touch) -> PixResult<bool> {
+    ///     self.pos += touch.delta();
+    ///     Ok(false)
+    /// }
+    /// # }
+    /// ```
+    fn on_touch_move(&mut self, s: &mut PixState, touch: Touch) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a finger is released from a touch device.
+    ///
+    /// Returning `true` consumes this event, preventing any [Gesture]s from being recognized for
+    /// it.
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application and call [`PixEngine::on_stop`]. See
+    /// the `Errors` section in [`PixEngine::on_update`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { dragging: bool };
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_touch_end(&mut self, s: &mut PixState, touch: Touch) -> PixResult<bool> {
+    ///     if s.touches().is_empty() {
+    ///         self.dragging = false;
+    ///     }
+    ///     Ok(false)
+    /// }
+    /// # }
+    /// ```
+    fn on_touch_end(&mut self, s: &mut PixState, touch: Touch) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a [Gesture] is recognized from touch events, such as a tap, pan or pinch.
+    /// See the [touch](crate::touch) module for details.
+    ///
+    /// Returning `true` consumes this event, preventing any further event triggering.
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application and call [`PixEngine::on_stop`]. See
+    /// the `Errors` section in [`PixEngine::on_update`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { zoom: f64 };
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_gesture(&mut self, s: &mut PixState, gesture: Gesture) -> PixResult<bool> {
+    ///     match gesture {
+    ///         Gesture::Pinch { scale, .. } => self.zoom *= scale,
+    ///         Gesture::DoubleTap { .. } => self.zoom = 1.0,
+    ///         _ => return Ok(false),
+    ///     }
+    ///     Ok(true)
+    /// }
+    /// # }
+    /// ```
+    fn on_gesture(&mut self, s: &mut PixState, gesture: Gesture) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a file is dropped onto a window, such as by dragging it from a file
+    /// manager. When multiple files are dropped at once, this is called once for each file.
+    ///
+    /// Dropped text and the start and end of a drop can be handled in [`PixEngine::on_event`] with
+    /// [`Event::DropText`], [`Event::DropBegin`] and [`Event::DropComplete`].
+    ///
+    /// Returning `true` consumes this event, preventing any further event triggering.
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application and call [`PixEngine::on_stop`]. See
+    /// the `Errors` section in [`PixEngine::on_update`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # use std::path::Path;
+    /// # struct App { image: Option<Image> };
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_file_dropped(
+    ///     &mut self,
+    ///     s: &mut PixState,
+    ///     window_id: WindowId,
+    ///     path: &Path,
+    /// ) -> PixResult<bool> {
+    ///     if path.extension().is_some_and(|ext| ext == "png") {
+    ///         self.image = Some(Image::from_file(path)?);
+    ///         return Ok(true);
+    ///     }
+    ///     Ok(false)
+    /// }
+    /// # }
+    /// ```
+    fn on_file_dropped(
+        &mut self,
+        s: &mut PixState,
+        window_id: WindowId,
+        path: &Path,
+    ) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a custom [`UserEvent`] sent by an [`EventSender`] is received. Use
+    /// [`UserEvent::downcast_ref`] to access the payload.
+    ///
+    /// Returning `true` consumes this event, preventing any further event triggering.
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application and call [`PixEngine::on_stop`]. See
+    /// the `Errors` section in [`PixEngine::on_update`] for more details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// enum Loaded {
+    ///     Level(usize),
+    /// }
+    ///
+    /// # struct App { level: usize };
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_user_event(&mut self, s: &mut PixState, event: &UserEvent) -> PixResult<bool> {
+    ///     if let Some(Loaded::Level(level)) = event.downcast_ref::<Loaded>() {
+    ///         self.level = *level;
+    ///         return Ok(true);
+    ///     }
+    ///     Ok(false)
+    /// }
+    /// # }
+    /// ```
+    fn on_user_event(&mut self, s: &mut PixState, event: &UserEvent) -> PixResult<bool> {
+        Ok(false)
+    }
+
     /// Called each time a window event occurs.
     ///
     /// # Errors
@@ -826,7 +1057,10 @@ pub trait PixEngine {
 pub struct EngineBuilder {
     settings: RendererSettings,
     theme: Theme,
-    joystick_deadzone: i32,
+    deadzone: i32,
+    clock_step: Option<Duration>,
+    fixed_timestep: Option<Duration>,
+    max_fixed_steps: usize,
 }
 
 impl Default for EngineBuilder {
@@ -834,7 +1068,10 @@ impl Default for EngineBuilder {
         Self {
             settings: RendererSettings::default(),
             theme: Theme::default(),
-            joystick_deadzone: 8000,
+            deadzone: DEFAULT_DEADZONE,
+            clock_step: None,
+            fixed_timestep: None,
+            max_fixed_steps: DEFAULT_MAX_FIXED_STEPS,
         }
     }
 }
@@ -955,9 +1192,10 @@ impl EngineBuilder {
         self
     }
 
-    /// Alter the joystick axis deadzone.
+    /// Alter the controller axis deadzone. Axis values between `-value` and `value` are treated as
+    /// `0`. Defaults to `8000`. See [`PixState::deadzone`].
     pub fn deadzone(&mut self, value: i32) -> &mut Self {
-        self.joystick_deadzone = value;
+        self.deadzone = value;
         self
     }
 
@@ -973,6 +1211,31 @@ impl EngineBuilder {
         self
     }
 
+    /// Render using a software renderer to in-memory framebuffers without opening any windows or
+    /// requiring a display, audio device or GPU.
+    ///
+    /// Useful for automated testing, servers and generating images offline. Input events are not
+    /// generated, but can be injected with [`PixState::push_event`] or
+    /// [`PixState::replay_events`]. Text is rendered using an embedded bitmap font. The rendered
+    /// frame can be read with [`PixState::to_bytes`] or saved with [`PixState::save_canvas`].
+    ///
+    /// The `SDL2` libraries are only required by the default `sdl` feature. Disabling default
+    /// features builds the headless renderer alone, in which case every `Engine` must be headless.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # fn main() -> PixResult<()> {
+    /// let mut engine = Engine::builder().dimensions(64, 64).headless().build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn headless(&mut self) -> &mut Self {
+        self.settings.headless = true;
+        self
+    }
+
     /// Enable average frame rate (FPS) in title.
     pub fn show_frame_rate(&mut self) -> &mut Self {
         self.settings.show_frame_rate = true;
@@ -986,6 +1249,50 @@ impl EngineBuilder {
         self
     }
 
+    /// Advance time by a fixed `step` each frame instead of measuring elapsed wall time, making
+    /// [`PixState::delta_time`] and [`PixState::elapsed`] deterministic. Frame rate limiting with
+    /// [`EngineBuilder::target_frame_rate`] is disabled.
+    ///
+    /// Useful for testing and rendering frames offline in combination with
+    /// [`EngineBuilder::headless`].
+    pub fn deterministic_clock(&mut self, step: Duration) -> &mut Self {
+        self.clock_step = Some(step);
+        self
+    }
+
+    /// Call [`PixEngine::on_fixed_update`] once for every fixed `step` of elapsed time,
+    /// independent of the frame rate.
+    ///
+    /// Elapsed time is accumulated each frame and any time left over is carried into the next
+    /// frame and exposed as [`PixState::interpolation_alpha`]. To avoid falling further behind
+    /// after a long frame, at most [`EngineBuilder::max_fixed_steps`] fixed updates are run per
+    /// frame and any excess time is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # use std::time::Duration;
+    /// # fn main() -> PixResult<()> {
+    /// let mut engine = Engine::builder()
+    ///     .fixed_timestep(Duration::from_secs(1) / 50)
+    ///     .headless()
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fixed_timestep(&mut self, step: Duration) -> &mut Self {
+        self.fixed_timestep = Some(step);
+        self
+    }
+
+    /// Set the maximum number of [`PixEngine::on_fixed_update`] calls per frame when using a
+    /// [fixed timestep](EngineBuilder::fixed_timestep). Defaults to `8`.
+    pub fn max_fixed_steps(&mut self, steps: usize) -> &mut Self {
+        self.max_fixed_steps = steps;
+        self
+    }
+
     /// Set a custom texture cache size other than the default of `20`.
     /// Affects font family and image rendering caching operations.
     pub fn texture_cache(&mut self, size: NonZeroUsize) -> &mut Self {
@@ -1007,13 +1314,14 @@ impl EngineBuilder {
     /// If the engine fails to create a new renderer, then an error is returned.
     ///
     /// Possible errors include the title containing a `nul` character, the position or dimensions
-    /// being invalid values or overlowing and an internal renderer error such as running out of
-    /// memory or a software driver issue.
+    /// being invalid values or overlowing, an internal renderer error such as running out of
+    /// memory or a software driver issue, or a non-headless engine without the `sdl` feature.
     pub fn build(&self) -> PixResult<Engine> {
-        Ok(Engine {
-            state: PixState::new(self.settings.clone(), self.theme.clone())?,
-            joystick_deadzone: self.joystick_deadzone,
-        })
+        let mut state = PixState::new(self.settings.clone(), self.theme.clone())?;
+        state.set_clock_step(self.clock_step);
+        state.set_fixed_timestep(self.fixed_timestep, self.max_fixed_steps);
+        state.set_deadzone(self.deadzone);
+        Ok(Engine { state })
     }
 }
 
@@ -1021,8 +1329,7 @@ impl EngineBuilder {
 #[must_use]
 #[derive(Debug)]
 pub struct Engine {
-    state: PixState,
-    joystick_deadzone: i32,
+    pub(crate) state: PixState,
 }
 
 impl Engine {
@@ -1058,6 +1365,30 @@ impl Engine {
     /// }
     /// ```
     pub fn run<A>(&mut self, app: &mut A) -> PixResult<()>
+    where
+        A: PixEngine,
+    {
+        self.run_loop(app, None)
+    }
+}
+
+impl Engine {
+    /// Run the application for a given number of frames and return the last rendered frame.
+    ///
+    /// Used by the [testing](crate::testing) harness to capture application output.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn run_frames<A>(&mut self, app: &mut A, frames: usize) -> PixResult<Image>
+    where
+        A: PixEngine,
+    {
+        self.run_loop(app, Some(frames))?;
+        let (width, height) = self.state.dimensions()?;
+        let bytes = self.state.to_bytes()?;
+        Image::from_bytes(width, height, bytes, PixelFormat::Rgba)
+    }
+
+    /// Execute the frame loop, quitting after `frame_limit` iterations if provided.
+    fn run_loop<A>(&mut self, app: &mut A, frame_limit: Option<usize>) -> PixResult<()>
     where
         A: PixEngine,
     {
@@ -1079,19 +1410,31 @@ impl Engine {
         self.state.present();
 
         // on_stop loop enables on_stop to prevent application close if necessary
+        let mut frames = 0;
         'on_stop: loop {
             debug!("Starting `PixEngine::on_update` loop.");
             // running loop continues until an event or on_update returns false or errors
             let result = 'running: loop {
                 let start_time = Instant::now();
-                let time_since_last = start_time - self.state.last_frame_time();
+                let time_since_last = self.state.time_since_last(start_time);
 
                 self.handle_events(app)?;
+                if frame_limit.is_some_and(|limit| frames >= limit) {
+                    self.state.quit();
+                }
                 if self.state.should_quit() {
                     break 'running Ok(());
                 }
 
                 if self.state.is_running() {
+                    let steps = self.state.fixed_update_steps(time_since_last);
+                    for _ in 0..steps {
+                        let on_fixed_update = app.on_fixed_update(&mut self.state);
+                        if on_fixed_update.is_err() {
+                            self.state.quit();
+                            break 'running on_fixed_update;
+                        }
+                    }
                     self.state.pre_update();
                     let on_update = app.on_update(&mut self.state);
                     if on_update.is_err() {
@@ -1100,12 +1443,15 @@ impl Engine {
                     }
                     self.state.on_update()?;
                     self.state.post_update();
+                    #[cfg(not(target_arch = "wasm32"))]
+                    self.state.record_frame(time_since_last);
                     self.state.present();
                     self.state.set_delta_time(start_time, time_since_last);
                     self.state.increment_frame(time_since_last)?;
                 }
+                frames += 1;
 
-                if !self.state.vsync_enabled() {
+                if !self.state.vsync_enabled() && !self.state.is_deterministic() {
                     if let Some(target_delta_time) = self.state.target_delta_time() {
                         let time_to_next_frame = start_time + target_delta_time;
                         let now = Instant::now();
@@ -1120,13 +1466,13 @@ impl Engine {
             let on_stop = app.on_stop(&mut self.state);
             if self.state.should_quit() {
                 info!("Quitting `Engine`...");
+                #[cfg(not(target_arch = "wasm32"))]
+                let on_stop = on_stop.and(self.state.stop_recording());
                 break 'on_stop on_stop.and(result);
             }
         }
     }
-}
 
-impl Engine {
     /// Handle user and system events.
     #[inline]
     fn handle_events<A>(&mut self, app: &mut A) -> PixResult<()>
@@ -1134,6 +1480,8 @@ impl Engine {
         A: PixEngine,
     {
         let state = &mut self.state;
+        state.input.begin_frame();
+        state.begin_event_frame();
         while let Some(event) = state.poll_event() {
             if let Event::ControllerAxisMotion { .. }
             | Event::JoyAxisMotion { .. }
@@ -1146,6 +1494,7 @@ impl Engine {
             } else {
                 debug!("Polling event {:?}", event);
             }
+            state.input.handle_event(&event);
             let handled = app.on_event(state, &event)?;
             if !handled {
                 match event {
@@ -1205,12 +1554,12 @@ impl Engine {
                         value,
                     } => {
                         let value = i32::from(value);
-                        let value =
-                            if (-self.joystick_deadzone..self.joystick_deadzone).contains(&value) {
-                                0
-                            } else {
-                                value
-                            };
+                        let deadzone = state.deadzone();
+                        let value = if (-deadzone..deadzone).contains(&value) {
+                            0
+                        } else {
+                            value
+                        };
                         let id = ControllerId(controller_id);
                         app.on_controller_axis_motion(state, id, axis, value)?;
                     }
@@ -1264,7 +1613,7 @@ impl Engine {
                     }
                     Event::MouseUp { button, x, y } => {
                         if state.ui.mouse.is_down(button) {
-                            let now = Instant::now();
+                            let now = state.now();
                             if let Some(clicked) = state.ui.mouse.last_clicked(button) {
                                 if now - *clicked < Duration::from_millis(500)
                                     && !app.on_mouse_dbl_clicked(state, button, point!(x, y))?
@@ -1285,10 +1634,46 @@ impl Engine {
                             state.on_mouse_wheel(x, y);
                         }
                     }
+                    Event::DropFile {
+                        window_id,
+                        ref path,
+                    } => {
+                        app.on_file_dropped(state, WindowId(window_id), path)?;
+                    }
+                    Event::User(ref event) => {
+                        app.on_user_event(state, event)?;
+                    }
+                    Event::FingerDown { .. }
+                    | Event::FingerMotion { .. }
+                    | Event::FingerUp { .. } => {
+                        if let Some((touch, gestures)) = state.on_touch(&event)? {
+                            let handled = match event {
+                                Event::FingerDown { .. } => {
+                                    let handled = app.on_touch_start(state, touch)?;
+                                    if handled {
+                                        state.touch.consume(&touch);
+                                    }
+                                    handled
+                                }
+                                Event::FingerMotion { .. } => app.on_touch_move(state, touch)?,
+                                _ => app.on_touch_end(state, touch)?,
+                            };
+                            if !handled {
+                                for gesture in gestures {
+                                    app.on_gesture(state, gesture)?;
+                                }
+                            }
+                        }
+                    }
                     _ => (),
                 }
             }
         }
+        let now = state.now();
+        if let Some(gesture) = state.touch.update(now) {
+            app.on_gesture(state, gesture)?;
+        }
+        state.end_event_frame();
         Ok(())
     }
 }
@@ -1,11 +1,25 @@
 //! User and system [Event]s.
 
+use crate::prelude::PixResult;
+use anyhow::anyhow;
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+use anyhow::Context;
 use bitflags::bitflags;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use std::{
+    any::{self, Any},
     fmt,
+    marker::PhantomData,
     ops::{Deref, DerefMut},
+    path::PathBuf,
+    sync::{mpsc::Sender, Arc},
+};
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
 };
 
 /// System or User `Event`.
@@ -229,6 +243,30 @@ pub enum Event {
         /// Amount of finger pressure being applied during press.
         pressure: f32,
     },
+    /// A file was dropped onto a window.
+    DropFile {
+        /// Specific window identifier the file was dropped on, or `0` if unknown.
+        window_id: u32,
+        /// Path of the dropped file.
+        path: PathBuf,
+    },
+    /// Text was dropped onto a window.
+    DropText {
+        /// Specific window identifier the text was dropped on, or `0` if unknown.
+        window_id: u32,
+        /// Dropped text.
+        text: String,
+    },
+    /// One or more files or text are about to be dropped onto a window.
+    DropBegin {
+        /// Specific window identifier being dropped on, or `0` if unknown.
+        window_id: u32,
+    },
+    /// All files or text for the current drop have been dropped onto a window.
+    DropComplete {
+        /// Specific window identifier dropped on, or `0` if unknown.
+        window_id: u32,
+    },
     /// Audio device connected event.
     AudioDeviceAdded {
         /// Specific audio device identifier.
@@ -243,6 +281,10 @@ pub enum Event {
         /// Whether this device is a capture device or not.
         iscapture: bool,
     },
+    /// A custom application event sent using an [`EventSender`]. User events can't be
+    /// serialized and are not included in an [`EventRecording`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    User(UserEvent),
     /// An unknown/unsupported event.
     #[default]
     Unhandled,
@@ -636,3 +678,282 @@ impl ControllerEvent {
         }
     }
 }
+
+/// An [Event] captured during an [`EventRecording`], along with the frame it occurred on.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecordedEvent {
+    /// Frame the event occurred on, relative to the start of the recording. Frame `0` represents
+    /// events handled prior to the first call to [`PixEngine::on_update`] after recording started.
+    ///
+    /// [`PixEngine::on_update`]: crate::prelude::PixEngine::on_update
+    pub frame: usize,
+    /// The recorded event.
+    pub event: Event,
+}
+
+/// A sequence of [Event]s with frame timestamps that can be replayed with
+/// [`PixState::replay_events`].
+///
+/// Recordings can either be captured from a live session using [`PixState::record_events`] or
+/// scripted by pushing events for specific frames, which is useful for driving automated tests.
+/// With the `serde` feature enabled, recordings can be saved to and loaded from `JSON` files.
+///
+/// # Example
+///
+/// ```
+/// # use pix_engine::prelude::*;
+/// let mut events = EventRecording::new();
+/// events.push(1, Event::MouseMotion { x: 10, y: 10, xrel: 0, yrel: 0 });
+/// events.push(2, Event::MouseDown { button: Mouse::Left, x: 10, y: 10 });
+/// events.push(3, Event::MouseUp { button: Mouse::Left, x: 10, y: 10 });
+/// assert_eq!(events.len(), 3);
+/// ```
+///
+/// [`PixState::replay_events`]: crate::prelude::PixState::replay_events
+/// [`PixState::record_events`]: crate::prelude::PixState::record_events
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EventRecording {
+    events: Vec<RecordedEvent>,
+}
+
+impl EventRecording {
+    /// Constructs an empty `EventRecording`.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an [Event] to occur on a given `frame`, relative to the start of the replay. Events on
+    /// the same frame are replayed in the order they were added.
+    #[inline]
+    pub fn push(&mut self, frame: usize, event: Event) {
+        let index = self
+            .events
+            .partition_point(|recorded| recorded.frame <= frame);
+        self.events.insert(index, RecordedEvent { frame, event });
+    }
+
+    /// Returns the list of recorded events, ordered by frame.
+    #[inline]
+    #[must_use]
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// Returns the number of recorded events.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether there are no recorded events.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Returns the last frame with a recorded event, if any.
+    #[inline]
+    #[must_use]
+    pub fn last_frame(&self) -> Option<usize> {
+        self.events.last().map(|recorded| recorded.frame)
+    }
+
+    /// Save the recording to a `JSON` file.
+    ///
+    /// # Errors
+    ///
+    /// If the file fails to be created or written to, then an error is returned.
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> PixResult<()> {
+        let path = path.as_ref();
+        let file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(file, self)
+            .with_context(|| format!("failed to write event recording: {path:?}"))
+    }
+
+    /// Load a recording previously saved with [`EventRecording::save`] from a `JSON` file.
+    ///
+    /// # Errors
+    ///
+    /// If the file fails to be read or isn't a valid recording, then an error is returned.
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> PixResult<Self> {
+        let path = path.as_ref();
+        let file = BufReader::new(File::open(path)?);
+        serde_json::from_reader(file)
+            .with_context(|| format!("failed to read event recording: {path:?}"))
+    }
+}
+
+impl IntoIterator for EventRecording {
+    type Item = RecordedEvent;
+    type IntoIter = std::vec::IntoIter<RecordedEvent>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.events.into_iter()
+    }
+}
+
+/// A custom application [Event] payload sent using an [`EventSender`].
+///
+/// The payload can be inspected with [`UserEvent::downcast_ref`]. Cloning a `UserEvent` is
+/// cheap and shares the same payload, which is also what two `UserEvent`s are compared by.
+///
+/// # Example
+///
+/// ```
+/// # use pix_engine::prelude::*;
+/// let event = UserEvent::new(42u32);
+/// assert!(event.is::<u32>());
+/// assert_eq!(event.downcast_ref::<u32>(), Some(&42));
+/// assert_eq!(event.downcast_ref::<i32>(), None);
+/// ```
+#[derive(Clone)]
+pub struct UserEvent {
+    payload: Arc<dyn Any + Send + Sync>,
+    type_name: &'static str,
+}
+
+impl UserEvent {
+    /// Constructs a `UserEvent` with a given payload.
+    #[inline]
+    pub fn new<T>(payload: T) -> Self
+    where
+        T: Any + Send + Sync,
+    {
+        Self {
+            payload: Arc::new(payload),
+            type_name: any::type_name::<T>(),
+        }
+    }
+
+    /// Whether the payload is of type `T`.
+    #[inline]
+    #[must_use]
+    pub fn is<T: Any>(&self) -> bool {
+        self.payload.is::<T>()
+    }
+
+    /// Returns a reference to the payload if it is of type `T`, otherwise `None`.
+    #[inline]
+    #[must_use]
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.payload.downcast_ref::<T>()
+    }
+
+    /// Returns the type name of the payload, for diagnostic purposes.
+    #[inline]
+    #[must_use]
+    pub const fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+impl fmt::Debug for UserEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UserEvent")
+            .field("type_name", &self.type_name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for UserEvent {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.payload, &other.payload)
+    }
+}
+
+/// A handle for sending custom [`Event::User`] events of type `T` to the [`Engine`] from any
+/// thread, created with [`PixState::event_sender`].
+///
+/// Sent events are handled on the next frame, after any pending system events, and are passed to
+/// [`PixEngine::on_event`] followed by [`PixEngine::on_user_event`]. At most 128 user events are
+/// handled each frame, with the rest delayed to later frames. `EventSender` can be cheaply cloned
+/// and moved into other threads.
+///
+/// # Example
+///
+/// ```
+/// # use pix_engine::prelude::*;
+/// # use std::thread;
+/// # struct App;
+/// # impl PixEngine for App {
+/// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+/// fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+///     let sender = s.event_sender::<String>();
+///     thread::spawn(move || {
+///         // Load some resources...
+///         sender.send("loaded".to_owned())
+///     });
+///     Ok(())
+/// }
+///
+/// fn on_user_event(&mut self, s: &mut PixState, event: &UserEvent) -> PixResult<bool> {
+///     if let Some(message) = event.downcast_ref::<String>() {
+///         println!("{message}");
+///         return Ok(true);
+///     }
+///     Ok(false)
+/// }
+/// # }
+/// ```
+///
+/// [`Engine`]: crate::prelude::Engine
+/// [`PixState::event_sender`]: crate::prelude::PixState::event_sender
+/// [`PixEngine::on_event`]: crate::prelude::PixEngine::on_event
+/// [`PixEngine::on_user_event`]: crate::prelude::PixEngine::on_user_event
+pub struct EventSender<T> {
+    sender: Sender<UserEvent>,
+    marker: PhantomData<fn(T)>,
+}
+
+impl<T> EventSender<T>
+where
+    T: Any + Send + Sync,
+{
+    #[inline]
+    pub(crate) const fn new(sender: Sender<UserEvent>) -> Self {
+        Self {
+            sender,
+            marker: PhantomData,
+        }
+    }
+
+    /// Send a custom event to the [`Engine`] to be handled on the next frame.
+    ///
+    /// # Errors
+    ///
+    /// If the [`Engine`] has been dropped, then an error is returned.
+    ///
+    /// [`Engine`]: crate::prelude::Engine
+    #[inline]
+    pub fn send(&self, event: T) -> PixResult<()> {
+        self.sender
+            .send(UserEvent::new(event))
+            .map_err(|_| anyhow!("failed to send user event: engine has been dropped"))
+    }
+}
+
+impl<T> Clone for EventSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for EventSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventSender")
+            .field("type_name", &any::type_name::<T>())
+            .finish_non_exhaustive()
+    }
+}
@@ -236,6 +236,17 @@ impl PixState {
         [stroke, bg, fg]
     }
 
+    /// Returns the theme [Paint] for the given surface type, if one is set.
+    #[inline]
+    pub(crate) fn surface_paint(&self, surface_color: ColorType) -> Option<Paint> {
+        let paints = &self.theme.paints;
+        match surface_color {
+            ColorType::Background => paints.background.clone(),
+            ColorType::Surface => paints.surface.clone(),
+            _ => None,
+        }
+    }
+
     /// Return the size of text, clamped to i32.
     #[inline]
     pub(crate) fn text_size(&self, text: &str) -> PixResult<(i32, i32)> {
@@ -207,7 +207,7 @@ impl PixState {
             // Render
             s.rect_mode(RectMode::Corner);
             let clip = tab_rect.offset_size([1, 0]);
-            s.clip(clip)?;
+            s.push_clip(clip)?;
             if hovered {
                 s.frame_cursor(&Cursor::hand())?;
             }
@@ -223,7 +223,8 @@ impl PixState {
                 s.fill(colors.background);
             }
             if active {
-                s.clip(tab_rect.offset_size([2, 0]))?;
+                s.pop_clip()?;
+                s.push_clip(tab_rect.offset_size([2, 0]))?;
                 s.rect(tab_rect.offset([1, 1]))?;
             } else {
                 s.rect(tab_rect)?;
@@ -242,7 +243,7 @@ impl PixState {
                 s.fill(colors.secondary_variant.blended(bg, 0.60));
             }
             s.text(tab_label)?;
-            s.clip(None)?;
+            s.pop_clip()?;
 
             s.ui.pop_cursor();
             s.pop();
@@ -74,19 +74,19 @@ impl PixState {
 
         s.set_texture_target(texture_id)?;
         s.background(colors.background);
+        if let Some(paint) = s.surface_paint(ColorType::Background) {
+            s.stroke(None);
+            s.fill_paint(paint);
+            s.rect([0, 0, scroll_width, scroll_height])?;
+        }
 
+        s.push_clip(rect![fpad, right - fpad.x(), bottom - fpad.y()])?;
         s.set_cursor_pos(s.cursor_pos() - scroll);
         s.stroke(None);
         s.fill(fg);
         f(s)?;
         let max_cursor_pos = s.cursor_pos() + scroll;
-
-        // Since clip doesn't work texture targets, we fake it
-        s.fill(colors.background);
-        s.rect([0, 0, scroll_width, fpad.y()])?; // Top
-        s.rect([0, 0, fpad.x(), scroll_height])?; // Left
-        s.rect([right, 0, fpad.x(), scroll_height])?; // Right
-        s.rect([0, bottom, scroll_width, fpad.y()])?; // Bottom
+        s.pop_clip()?;
 
         s.stroke(stroke);
         s.fill(None);
@@ -86,6 +86,7 @@ pub struct ThemeBuilder {
     size: u32,
     styles: FontStyles,
     colors: Colors,
+    paints: Paints,
     spacing: Spacing,
 }
 
@@ -98,6 +99,7 @@ impl Default for ThemeBuilder {
             size: theme.font_size,
             styles: theme.styles,
             colors: theme.colors,
+            paints: theme.paints,
             spacing: theme.spacing,
         }
     }
@@ -201,6 +203,18 @@ impl ThemeBuilder {
         self
     }
 
+    /// Set a [Paint], such as a [Gradient], used to fill background surfaces like scroll areas.
+    pub fn background_paint<P: Into<Paint>>(&mut self, paint: P) -> &mut Self {
+        self.paints.background = Some(paint.into());
+        self
+    }
+
+    /// Set a [Paint], such as a [Gradient], used to fill raised surfaces like tooltips.
+    pub fn surface_paint<P: Into<Paint>>(&mut self, paint: P) -> &mut Self {
+        self.paints.surface = Some(paint.into());
+        self
+    }
+
     /// Set element padding space.
     pub fn spacing(&mut self, spacing: Spacing) -> &mut Self {
         self.spacing = spacing;
@@ -215,6 +229,7 @@ impl ThemeBuilder {
             font_size: self.size,
             styles: self.styles,
             colors: self.colors,
+            paints: self.paints.clone(),
             spacing: self.spacing,
         }
     }
@@ -309,7 +324,7 @@ impl Font {
     }
 
     /// Returns the source data of the font family.
-    #[cfg(not(target_arch = "wasm32"))]
+    #[cfg(all(feature = "sdl", not(target_arch = "wasm32")))]
     #[inline]
     #[must_use]
     pub(crate) const fn source(&self) -> &FontSrc {
@@ -498,6 +513,18 @@ impl Default for Colors {
     }
 }
 
+/// A set of optional [Paint]s for theming UI surfaces with gradients. Surfaces without a paint
+/// are filled with their [Colors] value.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Paints {
+    /// Background paint, drawn behind scrollable content.
+    pub background: Option<Paint>,
+    /// Surface paint, drawn behind raised elements like tooltips.
+    pub surface: Option<Paint>,
+}
+
 /// Builds a [Spacing] instance by customizing various space and padding settings.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[non_exhaustive]
@@ -617,6 +644,9 @@ pub struct Theme {
     pub styles: FontStyles,
     /// The colors used in this theme.
     pub colors: Colors,
+    /// The optional surface paints used in this theme.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub paints: Paints,
     /// The padding, offsets, and other styles used in this theme.
     pub spacing: Spacing,
 }
@@ -642,6 +672,7 @@ impl Theme {
         Self {
             name: "Dark".into(),
             colors: Colors::dark(),
+            paints: Paints::default(),
             fonts: Fonts::default(),
             font_size: 12,
             styles: FontStyles::default(),
@@ -655,6 +686,7 @@ impl Theme {
         Self {
             name: "Light".into(),
             colors: Colors::light(),
+            paints: Paints::default(),
             fonts: Fonts::default(),
             font_size: 12,
             styles: FontStyles::default(),
@@ -98,12 +98,12 @@ impl PixState {
 
         // Button text
         s.rect_mode(RectMode::Center);
-        s.clip(button)?;
+        s.push_clip(button)?;
         s.set_cursor_pos(button.center());
         s.stroke(None);
         s.fill(fg);
         s.text(label)?;
-        s.clip(None)?;
+        s.pop_clip()?;
 
         s.ui.pop_cursor();
         s.pop();
@@ -134,7 +134,7 @@ impl PixState {
         if arrow_x + arrow_width - fpad.x() <= select_box.right() {
             s.stroke(None);
             s.fill(fg);
-            s.clip(arrow_box)?;
+            s.push_clip(arrow_box)?;
             s.arrow(
                 [
                     arrow_x + fpad.y(),
@@ -143,10 +143,11 @@ impl PixState {
                 Direction::Down,
                 f64::from(fpad.y()) / 8.0,
             )?;
+            s.pop_clip()?;
         }
 
         // Item
-        s.clip(rect![
+        s.push_clip(rect![
             select_box.top_left(),
             select_box.width() - arrow_box.width(),
             select_box.height()
@@ -158,7 +159,7 @@ impl PixState {
         s.fill(fg);
         s.text(&items[*selected])?;
 
-        s.clip(None)?;
+        s.pop_clip()?;
         s.ui.pop_cursor();
         s.pop();
         s.advance_cursor([select_box.right() - pos.x(), select_box.height()]);
@@ -446,7 +447,7 @@ impl PixState {
         let mpos = s.mouse_pos();
 
         let border_clip = select_list.shrink([1, 1]);
-        s.clip(border_clip)?;
+        s.push_clip(border_clip)?;
         let content_clip = border_clip.shrink(fpad);
         let item_clip = rect![
             select_list.x() + 1,
@@ -471,7 +472,7 @@ impl PixState {
             let clickable =
                 item_rect.bottom() > content_clip.y() || item_rect.top() < select_list.height();
             s.push();
-            s.clip(item_clip)?;
+            s.push_clip(item_clip)?;
             if hovered && clickable && item_rect.contains(mpos) {
                 s.frame_cursor(&Cursor::hand())?;
                 s.stroke(None);
@@ -490,8 +491,9 @@ impl PixState {
                 }
                 s.rect([item_clip.x(), y, item_clip.width(), line_height])?;
             }
+            s.pop_clip()?;
             s.pop();
-            s.clip(content_clip)?;
+            s.push_clip(content_clip)?;
             s.set_cursor_pos([x, y + ipad.y()]);
             s.stroke(None);
             if *selected == i {
@@ -500,11 +502,11 @@ impl PixState {
                 s.fill(fg);
             }
             s.text(item)?;
-            s.clip(border_clip)?;
+            s.pop_clip()?;
             y += line_height;
         }
 
-        s.clip(None)?;
+        s.pop_clip()?;
 
         Ok(())
     }
@@ -120,6 +120,11 @@ impl PixState {
                 |s: &mut PixState| {
                     let [stroke, bg, fg] = s.widget_colors(id, ColorType::Surface);
                     s.background(bg);
+                    if let Some(paint) = s.surface_paint(ColorType::Surface) {
+                        s.stroke(None);
+                        s.fill_paint(paint);
+                        s.rect([0, 0, text_width, text_height])?;
+                    }
 
                     s.stroke(stroke);
                     s.fill(None);
@@ -191,6 +196,11 @@ impl PixState {
             |s: &mut PixState| {
                 let [stroke, bg, fg] = s.widget_colors(id, ColorType::Surface);
                 s.background(bg);
+                if let Some(paint) = s.surface_paint(ColorType::Surface) {
+                    s.stroke(None);
+                    s.fill_paint(paint);
+                    s.rect([0, 0, text_width, text_height])?;
+                }
 
                 s.stroke(stroke);
                 s.fill(None);
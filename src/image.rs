@@ -1,20 +1,33 @@
 //! [Image] and [`PixelFormat`] functions.
+//!
+//! See the [ops] module for image processing operations such as resizing, rotating and filtering.
 
 use crate::{ops::clamp_dimensions, prelude::*, renderer::Rendering};
 #[cfg(not(target_arch = "wasm32"))]
 use anyhow::Context;
-#[cfg(not(target_arch = "wasm32"))]
-use png::{BitDepth, ColorType, Decoder};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-#[cfg(not(target_arch = "wasm32"))]
 use std::{
     ffi::OsStr,
-    fs::File,
-    io::{self, BufReader, BufWriter},
-    path::{Path, PathBuf},
+    fmt,
+    iter::Copied,
+    path::Path,
+    slice::{self, ChunksExact},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter},
+    path::PathBuf,
 };
-use std::{fmt, iter::Copied, slice};
+
+mod animated;
+#[cfg(not(target_arch = "wasm32"))]
+mod codec;
+mod ops;
+
+pub use animated::{AnimatedImage, Disposal, Frame, Playback};
+pub use ops::{Kernel, ResizeFilter};
 
 /// Format for interpreting image data.
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -27,6 +40,10 @@ pub enum PixelFormat {
     /// 8-bit Red, Green, Blue, and Alpha
     #[default]
     Rgba,
+    /// 8-bit Grayscale
+    Gray,
+    /// 8-bit Grayscale and Alpha
+    GrayAlpha,
 }
 
 impl PixelFormat {
@@ -37,6 +54,157 @@ impl PixelFormat {
         match self {
             PixelFormat::Rgb => 3,
             PixelFormat::Rgba => 4,
+            PixelFormat::Gray => 1,
+            PixelFormat::GrayAlpha => 2,
+        }
+    }
+
+    /// Returns whether the format has an alpha channel.
+    #[inline]
+    #[must_use]
+    pub const fn has_alpha(&self) -> bool {
+        matches!(self, PixelFormat::Rgba | PixelFormat::GrayAlpha)
+    }
+
+    /// Returns whether the format is grayscale.
+    #[inline]
+    #[must_use]
+    pub const fn is_gray(&self) -> bool {
+        matches!(self, PixelFormat::Gray | PixelFormat::GrayAlpha)
+    }
+
+    /// Converts a `pixel` in this format to `RGBA` channels.
+    #[inline]
+    pub(crate) fn to_rgba(self, pixel: &[u8]) -> [u8; 4] {
+        match *pixel {
+            [red, green, blue] => [red, green, blue, 255],
+            [red, green, blue, alpha] => [red, green, blue, alpha],
+            [gray] => [gray, gray, gray, 255],
+            [gray, alpha] => [gray, gray, gray, alpha],
+            _ => [0; 4],
+        }
+    }
+
+    /// Writes `RGBA` channels to a `pixel` in this format, converting color to luma for grayscale
+    /// formats.
+    #[inline]
+    pub(crate) fn write_rgba(self, rgba: [u8; 4], pixel: &mut [u8]) {
+        match self {
+            PixelFormat::Rgb | PixelFormat::Rgba => {
+                pixel.copy_from_slice(&rgba[..self.channels()]);
+            }
+            PixelFormat::Gray | PixelFormat::GrayAlpha => {
+                pixel[0] = luma(rgba);
+                if let Some(alpha) = pixel.get_mut(1) {
+                    *alpha = rgba[3];
+                }
+            }
+        }
+    }
+}
+
+/// Returns the `ITU-R BT.601` luma of an `RGBA` color.
+#[inline]
+pub(crate) fn luma([red, green, blue, _]: [u8; 4]) -> u8 {
+    ((77 * u32::from(red) + 150 * u32::from(green) + 29 * u32::from(blue) + 128) >> 8) as u8
+}
+
+/// Encoded file format of an [Image].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ImageFormat {
+    /// Portable Network Graphics (`.png`).
+    Png,
+    /// Windows Bitmap (`.bmp`, `.dib`).
+    Bmp,
+    /// Truevision TGA (`.tga`, `.icb`, `.vda`, `.vst`).
+    Tga,
+    /// Netpbm Portable Bitmap (`.pbm`).
+    Pbm,
+    /// Netpbm Portable Graymap (`.pgm`).
+    Pgm,
+    /// Netpbm Portable Pixmap (`.ppm`, `.pnm`).
+    Ppm,
+    /// Quite OK Image Format (`.qoi`).
+    Qoi,
+    /// Graphics Interchange Format (`.gif`).
+    Gif,
+}
+
+impl ImageFormat {
+    /// Returns the `ImageFormat` matching a file extension, ignoring case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// assert_eq!(ImageFormat::from_extension("BMP"), Some(ImageFormat::Bmp));
+    /// assert_eq!(ImageFormat::from_extension("jpg"), None);
+    /// ```
+    #[must_use]
+    pub fn from_extension<S: AsRef<OsStr>>(extension: S) -> Option<Self> {
+        let extension = extension.as_ref().to_str()?.to_ascii_lowercase();
+        let format = match extension.as_str() {
+            "png" => Self::Png,
+            "bmp" | "dib" => Self::Bmp,
+            "tga" | "icb" | "vda" | "vst" => Self::Tga,
+            "pbm" => Self::Pbm,
+            "pgm" => Self::Pgm,
+            "ppm" | "pnm" => Self::Ppm,
+            "qoi" => Self::Qoi,
+            "gif" => Self::Gif,
+            _ => return None,
+        };
+        Some(format)
+    }
+
+    /// Returns the `ImageFormat` matching the extension of a file path, ignoring case.
+    #[must_use]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        path.as_ref().extension().and_then(Self::from_extension)
+    }
+
+    /// Returns the `ImageFormat` identified by the signature of encoded image `bytes`.
+    ///
+    /// `TGA` files have no signature and are only identified if they include a `TGA 2.0` footer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// assert_eq!(ImageFormat::from_magic(b"GIF89a..."), Some(ImageFormat::Gif));
+    /// assert_eq!(ImageFormat::from_magic(b"P6\n1 1\n255\n..."), Some(ImageFormat::Ppm));
+    /// ```
+    #[must_use]
+    pub fn from_magic(bytes: &[u8]) -> Option<Self> {
+        let format = match bytes {
+            [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n', ..] => Self::Png,
+            [b'B', b'M', ..] => Self::Bmp,
+            [b'q', b'o', b'i', b'f', ..] => Self::Qoi,
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Self::Gif,
+            [b'P', b'1' | b'4', whitespace, ..] if whitespace.is_ascii_whitespace() => Self::Pbm,
+            [b'P', b'2' | b'5', whitespace, ..] if whitespace.is_ascii_whitespace() => Self::Pgm,
+            [b'P', b'3' | b'6', whitespace, ..] if whitespace.is_ascii_whitespace() => Self::Ppm,
+            _ if bytes.ends_with(b"TRUEVISION-XFILE.\0") => Self::Tga,
+            _ => return None,
+        };
+        Some(format)
+    }
+
+    /// Returns the default file extension for the format.
+    #[inline]
+    #[must_use]
+    pub const fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Bmp => "bmp",
+            Self::Tga => "tga",
+            Self::Pbm => "pbm",
+            Self::Pgm => "pgm",
+            Self::Ppm => "ppm",
+            Self::Qoi => "qoi",
+            Self::Gif => "gif",
         }
     }
 }
@@ -53,7 +221,9 @@ impl TryFrom<png::ColorType> for PixelFormat {
         match color_type {
             png::ColorType::Rgb => Ok(Self::Rgb),
             png::ColorType::Rgba => Ok(Self::Rgba),
-            _ => Err(TryFromColorTypeError(())),
+            png::ColorType::Grayscale => Ok(Self::Gray),
+            png::ColorType::GrayscaleAlpha => Ok(Self::GrayAlpha),
+            png::ColorType::Indexed => Err(TryFromColorTypeError(())),
         }
     }
 }
@@ -64,6 +234,8 @@ impl From<PixelFormat> for png::ColorType {
         match format {
             PixelFormat::Rgb => Self::Rgb,
             PixelFormat::Rgba => Self::Rgba,
+            PixelFormat::Gray => Self::Grayscale,
+            PixelFormat::GrayAlpha => Self::GrayscaleAlpha,
         }
     }
 }
@@ -157,13 +329,10 @@ impl Image {
             }
             .into());
         }
-        let bytes: Vec<u8> = match format {
-            PixelFormat::Rgb => pixels
-                .iter()
-                .flat_map(|p| [p.red(), p.green(), p.blue()])
-                .collect(),
-            PixelFormat::Rgba => pixels.iter().flat_map(Color::channels).collect(),
-        };
+        let mut bytes = vec![0x00; pixels.len() * format.channels()];
+        for (pixel, color) in bytes.chunks_exact_mut(format.channels()).zip(pixels) {
+            format.write_rgba(color.channels(), pixel);
+        }
         Ok(Self::from_vec(width, height, bytes, format))
     }
 
@@ -178,61 +347,72 @@ impl Image {
         }
     }
 
-    /// Constructs an `Image` from a [png] file.
+    /// Constructs an `Image` from an image file. The [`ImageFormat`] is identified by the file
+    /// contents, falling back to the file extension for formats without a signature, such as
+    /// `TGA`.
     ///
     /// # Errors
     ///
-    /// If the file format is not supported or extension is not `.png`, then an error is returned.
+    /// If the file format is not supported, the file data is invalid, or there is an
+    /// [`io::Error`] reading the file, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pix_engine::prelude::*;
+    /// let sprite = Image::from_file("./sprite.bmp")?;
+    /// let texture = Image::from_file("./texture.tga")?;
+    /// # Ok::<(), PixError>(())
+    /// ```
     #[cfg(not(target_arch = "wasm32"))]
     pub fn from_file<P: AsRef<Path>>(path: P) -> PixResult<Self> {
         let path = path.as_ref();
-        let ext = path.extension();
-        if ext != Some(OsStr::new("png")) {
-            return Err(PixError::UnsupportedFileType(ext.map(OsStr::to_os_string)).into());
-        }
-        Self::from_read(File::open(path)?)
+        let bytes = fs::read(path)?;
+        let format = ImageFormat::from_magic(&bytes)
+            .or_else(|| ImageFormat::from_path(path))
+            .ok_or_else(|| {
+                PixError::UnsupportedFileType(path.extension().map(OsStr::to_os_string))
+            })?;
+        codec::decode(&bytes, format)
+            .with_context(|| format!("failed to decode {format:?} image: {path:?}"))
     }
 
-    /// Constructs an `Image` from a [png] reader.
+    /// Constructs an `Image` from a reader. The [`ImageFormat`] is identified by the data
+    /// signature.
     ///
     /// # Errors
     ///
-    /// If the file format is not supported or there is an [`io::Error`] reading the file then an
-    /// error is returned.
+    /// If the data format is not supported or is invalid, or there is an [`io::Error`] reading
+    /// the data, then an error is returned.
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn from_read<R: io::Read + io::Seek>(read: R) -> PixResult<Self> {
-        let png_file = BufReader::new(read);
-        let png = Decoder::new(png_file);
-
-        // TODO: Make this machine-dependent to best match display capabilities for performance
-        // EXPL: Switch RGBA32 (RGBA8888) format to ARGB8888 by swapping alpha
-        // EXPL: Expand paletted to RGB and non-8-bit grayscale to 8-bits
-        // png.set_transformations(Transformations::SWAP_ALPHA | Transformations::EXPAND);
-
-        let mut reader = png.read_info().context("failed to read png data")?;
-        let mut buf = vec![0x00; reader.output_buffer_size().unwrap_or_default()];
-        let info = reader
-            .next_frame(&mut buf)
-            .context("failed to read png data frame")?;
-        let bit_depth = info.bit_depth;
-        let color_type = info.color_type;
-        if bit_depth != BitDepth::Eight || !matches!(color_type, ColorType::Rgb | ColorType::Rgba) {
-            return Err(PixError::UnsupportedImageFormat {
-                bit_depth,
-                color_type,
-            }
-            .into());
-        }
+    pub fn from_read<R: io::Read + io::Seek>(mut read: R) -> PixResult<Self> {
+        let mut bytes = Vec::new();
+        read.read_to_end(&mut bytes)?;
+        let format = ImageFormat::from_magic(&bytes).ok_or(PixError::UnsupportedFileType(None))?;
+        codec::decode(&bytes, format)
+    }
 
-        let data = &buf[..info.buffer_size()];
-        let format = info
-            .color_type
-            .try_into()
-            .map_err(|_| PixError::UnsupportedImageFormat {
-                bit_depth,
-                color_type,
-            })?;
-        Self::from_bytes(info.width, info.height, data, format)
+    /// Constructs an `Image` from a reader of data encoded in the given [`ImageFormat`].
+    ///
+    /// # Errors
+    ///
+    /// If the data is invalid or the encoding is not supported, or there is an [`io::Error`]
+    /// reading the data, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let pgm: &[u8] = b"P2 2 1 255 0 255";
+    /// let image = Image::from_read_format(pgm, ImageFormat::Pgm)?;
+    /// assert_eq!(image.get_pixel(1, 0), Color::WHITE);
+    /// # Ok::<(), PixError>(())
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_read_format<R: io::Read>(mut read: R, format: ImageFormat) -> PixResult<Self> {
+        let mut bytes = Vec::new();
+        read.read_to_end(&mut bytes)?;
+        codec::decode(&bytes, format)
     }
 
     /// Returns the `Image` width.
@@ -326,7 +506,10 @@ impl Image {
     /// Returns the `Image` pixel data as an iterator of [Color]s.
     #[inline]
     pub fn pixels(&self) -> Pixels<'_> {
-        Pixels(self.format.channels(), self.as_bytes().iter().copied())
+        Pixels(
+            self.format,
+            self.as_bytes().chunks_exact(self.format.channels()),
+        )
     }
 
     /// Returns the `Image` pixel data as a [`Vec<Color>`].
@@ -337,14 +520,7 @@ impl Image {
     #[inline]
     #[must_use]
     pub fn into_pixels(self) -> Vec<Color> {
-        self.data
-            .chunks(self.format.channels())
-            .map(|slice| match *slice {
-                [red, green, blue] => Color::rgb(red, green, blue),
-                [red, green, blue, alpha] => Color::rgba(red, green, blue, alpha),
-                _ => Color::TRANSPARENT,
-            })
-            .collect()
+        self.pixels().collect()
     }
 
     /// Returns the color value at the given `(x, y)` position.
@@ -357,11 +533,12 @@ impl Image {
     pub fn get_pixel(&self, x: u32, y: u32) -> Color {
         let idx = self.idx(x, y);
         let channels = self.format.channels();
-        match self.data.get(idx..idx + channels) {
-            Some([red, green, blue]) => Color::rgb(*red, *green, *blue),
-            Some([red, green, blue, alpha]) => Color::rgba(*red, *green, *blue, *alpha),
-            _ => Color::TRANSPARENT,
-        }
+        self.data
+            .get(idx..idx + channels)
+            .map_or(Color::TRANSPARENT, |pixel| {
+                let [red, green, blue, alpha] = self.format.to_rgba(pixel);
+                Color::rgba(red, green, blue, alpha)
+            })
     }
 
     /// Sets the color value at the given `(x, y)` position.
@@ -370,7 +547,8 @@ impl Image {
         let color = color.into();
         let idx = self.idx(x, y);
         let channels = self.format.channels();
-        self.data[idx..(idx + channels)].clone_from_slice(&color.channels()[..channels]);
+        self.format
+            .write_rgba(color.channels(), &mut self.data[idx..(idx + channels)]);
     }
 
     /// Update the `Image` with a  [u8] [prim@slice] representing RGB/A values.
@@ -379,19 +557,75 @@ impl Image {
         self.data.clone_from_slice(bytes.as_ref());
     }
 
+    /// Draw `src` over this `Image` with its top-left corner at `position`, blending pixels on the
+    /// CPU using the given [`BlendMode`]. Pixels outside of this `Image` are skipped.
+    ///
+    /// This can be used to composite images using blend modes a renderer doesn't support, and is
+    /// what renderers fall back to in that case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let mut image = Image::from_pixels(2, 1, &[Color::WHITE, Color::GRAY], PixelFormat::Rgba)?;
+    /// let red = Image::from_pixels(1, 1, &[Color::RED], PixelFormat::Rgba)?;
+    /// image.composite(&red, [0, 0], BlendMode::Multiply);
+    /// image.composite(&red, [1, 0], BlendMode::Screen);
+    /// assert_eq!(image.get_pixel(0, 0), Color::RED);
+    /// assert_eq!(image.get_pixel(1, 0), rgb!(255, 128, 128));
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn composite<P>(&mut self, src: &Image, position: P, mode: BlendMode)
+    where
+        P: Into<Point<i32>>,
+    {
+        self.blit(src, None, position, mode);
+    }
+
     /// Returns the `Image` pixel format.
     #[inline]
     pub const fn format(&self) -> PixelFormat {
         self.format
     }
 
-    /// Save the `Image` to a [png] file.
+    /// Returns a copy of the `Image` converted to the given [`PixelFormat`]. Converting color to
+    /// grayscale uses the `ITU-R BT.601` luma of each pixel and converting to a format without
+    /// alpha discards it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::from_pixels(1, 1, &[Color::WHITE], PixelFormat::Rgb)?;
+    /// let gray = image.convert(PixelFormat::Gray);
+    /// assert_eq!(gray.as_bytes(), &[255]);
+    /// assert_eq!(gray.convert(PixelFormat::Rgba).as_bytes(), &[255, 255, 255, 255]);
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn convert(&self, format: PixelFormat) -> Self {
+        if format == self.format {
+            return self.clone();
+        }
+        let mut data = vec![0x00; self.width as usize * self.height as usize * format.channels()];
+        let pixels = self.data.chunks_exact(self.format.channels());
+        for (dst, src) in data.chunks_exact_mut(format.channels()).zip(pixels) {
+            format.write_rgba(self.format.to_rgba(src), dst);
+        }
+        Self::from_vec(self.width, self.height, data, format)
+    }
+
+    /// Save the `Image` to a file. The [`ImageFormat`] is chosen based on the file extension.
+    ///
+    /// Formats that don't support alpha, such as `BMP` with an [`PixelFormat::Rgb`] image or the
+    /// netpbm formats, discard it. `GIF` images are reduced to 256 colors and treat pixels with an
+    /// alpha below `128` as transparent.
     ///
     /// # Errors
     ///
     /// Returns an error for any of the following:
-    ///     - An [`io::Error`] occurs attempting to create the [png] file.
-    ///     - A [`png::EncodingError`] occurs attempting to write image bytes.
+    ///     - The file extension is not a supported [`ImageFormat`].
+    ///     - An [`io::Error`] occurs attempting to create or write to the file.
+    ///     - The `Image` can't be encoded in the chosen format, e.g. it's too large.
     ///
     /// # Example
     ///
@@ -403,6 +637,7 @@ impl Image {
     /// fn on_key_pressed(&mut self, s: &mut PixState, event: KeyEvent) -> PixResult<bool> {
     ///     if let Key::S = event.key {
     ///         self.image.save("test_image.png")?;
+    ///         self.image.save("test_image.qoi")?;
     ///     }
     ///     Ok(false)
     /// }
@@ -414,16 +649,34 @@ impl Image {
         P: AsRef<Path>,
     {
         let path = path.as_ref();
-        let png_file = BufWriter::new(File::create(path)?);
-        let mut png = png::Encoder::new(png_file, self.width, self.height);
-        png.set_color(self.format.into());
-        png.set_depth(png::BitDepth::Eight);
-        let mut writer = png
-            .write_header()
-            .with_context(|| format!("failed to write png header: {path:?}"))?;
-        writer
-            .write_image_data(self.as_bytes())
-            .with_context(|| format!("failed to write png data: {path:?}"))
+        let format = ImageFormat::from_path(path).ok_or_else(|| {
+            PixError::UnsupportedFileType(path.extension().map(OsStr::to_os_string))
+        })?;
+        let file = BufWriter::new(File::create(path)?);
+        codec::encode(self, format, file)
+            .with_context(|| format!("failed to encode {format:?} image: {path:?}"))
+    }
+
+    /// Write the `Image` encoded in the given [`ImageFormat`] to `writer`.
+    ///
+    /// # Errors
+    ///
+    /// If an [`io::Error`] occurs writing, or the `Image` can't be encoded in the given format,
+    /// then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::from_pixels(1, 1, &[Color::RED], PixelFormat::Rgb)?;
+    /// let mut bytes = Vec::new();
+    /// image.write_to(&mut bytes, ImageFormat::Ppm)?;
+    /// assert_eq!(bytes, b"P6\n1 1\n255\n\xFF\x00\x00");
+    /// # Ok::<(), PixError>(())
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_to<W: io::Write>(&self, writer: W, format: ImageFormat) -> PixResult<()> {
+        codec::encode(self, format, writer)
     }
 }
 
@@ -435,6 +688,63 @@ impl Image {
     }
 }
 
+/// Multiplies two `u8` color channels as if they were normalized to `0.0..=1.0`.
+#[inline]
+pub(crate) const fn mul(a: u8, b: u8) -> u8 {
+    ((a as u16 * b as u16 + 127) / 255) as u8
+}
+
+/// Blends an `RGBA` source color onto an `RGBA` destination pixel. [`BlendMode::None`],
+/// [`BlendMode::Blend`], [`BlendMode::Add`] and [`BlendMode::Mod`] use the same equations as `SDL`.
+pub(crate) fn blend(dst: &mut [u8], src: [u8; 4], mode: BlendMode) {
+    let [r, g, b, a] = src;
+    let inv = 255 - a;
+    let lerp = |s: u8, d: u8| {
+        ((u16::from(s) * u16::from(a) + u16::from(d) * u16::from(inv) + 127) / 255) as u8
+    };
+    let separable: fn(u8, u8) -> u8 = match mode {
+        BlendMode::None => return dst.copy_from_slice(&src),
+        BlendMode::Blend => |s, _| s,
+        BlendMode::Add => {
+            dst[0] = dst[0].saturating_add(mul(r, a));
+            dst[1] = dst[1].saturating_add(mul(g, a));
+            dst[2] = dst[2].saturating_add(mul(b, a));
+            return;
+        }
+        BlendMode::Mod => {
+            dst[0] = mul(dst[0], r);
+            dst[1] = mul(dst[1], g);
+            dst[2] = mul(dst[2], b);
+            return;
+        }
+        BlendMode::Multiply => mul,
+        BlendMode::Screen => |s, d| {
+            let (s, d) = (u32::from(s), u32::from(d));
+            (s + d - (s * d + 127) / 255) as u8
+        },
+        BlendMode::Overlay => |s, d| {
+            let (s, d) = (u32::from(s), u32::from(d));
+            if d < 128 {
+                ((2 * s * d + 127) / 255) as u8
+            } else {
+                (255 - (2 * (255 - s) * (255 - d) + 127) / 255) as u8
+            }
+        },
+        BlendMode::Darken => std::cmp::min,
+        BlendMode::Lighten => std::cmp::max,
+        BlendMode::Difference => u8::abs_diff,
+        BlendMode::Exclusion => |s, d| {
+            let (s, d) = (u32::from(s), u32::from(d));
+            (s + d - (2 * s * d + 127) / 255) as u8
+        },
+        BlendMode::Subtract => |s, d| d.saturating_sub(s),
+    };
+    dst[0] = lerp(separable(r, dst[0]), dst[0]);
+    dst[1] = lerp(separable(g, dst[1]), dst[1]);
+    dst[2] = lerp(separable(b, dst[2]), dst[2]);
+    dst[3] = a + mul(dst[3], inv);
+}
+
 impl PixState {
     /// Draw an [Image] to the current canvas.
     ///
@@ -516,14 +826,16 @@ impl PixState {
         if s.angle_mode == AngleMode::Radians {
             angle = angle.to_degrees();
         };
+        let (dst, angle, center, flipped) =
+            self.transform_image(dst, angle, center.into(), flipped.into())?;
         self.renderer.image(
             img,
             src.into(),
             dst,
             angle,
-            center.into(),
-            flipped.into(),
-            s.image_tint,
+            center,
+            flipped,
+            self.settings.image_tint,
         )
     }
 }
@@ -561,24 +873,14 @@ impl Iterator for Bytes<'_> {
 /// See its documentation for more.
 #[derive(Debug, Clone)]
 #[must_use]
-pub struct Pixels<'a>(usize, Copied<slice::Iter<'a, u8>>);
+pub struct Pixels<'a>(PixelFormat, ChunksExact<'a, u8>);
 
 impl Iterator for Pixels<'_> {
     type Item = Color;
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        let r = self.1.next()?;
-        let g = self.1.next()?;
-        let b = self.1.next()?;
-        let channels = self.0;
-        match channels {
-            3 => Some(Color::rgb(r, g, b)),
-            4 => {
-                let a = self.1.next()?;
-                Some(Color::rgba(r, g, b, a))
-            }
-            _ => Some(Color::TRANSPARENT),
-        }
+        let [r, g, b, a] = self.0.to_rgba(self.1.next()?);
+        Some(Color::rgba(r, g, b, a))
     }
 }
 
@@ -0,0 +1,641 @@
+//! [`AnimatedImage`] functions.
+//!
+//! An `AnimatedImage` is a sequence of [Frame]s decoded from an animated `PNG` (`APNG`) or `GIF`
+//! file, or constructed from a list of [Image]s. Each frame is stored fully composited, so drawing
+//! a frame never depends on the frames before it.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use pix_engine::prelude::*;
+//! # struct App { spinner: AnimatedImage };
+//! # impl PixEngine for App {
+//! fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     // Draws the frame for the time elapsed since the application started
+//!     s.animated_image(&self.spinner, [10, 10])?;
+//!     // Draws the first frame
+//!     s.animated_image_frame(&self.spinner, 0, [50, 10])?;
+//!     Ok(())
+//! }
+//! # }
+//! ```
+
+#[cfg(not(target_arch = "wasm32"))]
+use super::codec::RawAnimation;
+use crate::prelude::*;
+use anyhow::anyhow;
+#[cfg(not(target_arch = "wasm32"))]
+use anyhow::Context;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{ffi::OsStr, fs, io, path::Path};
+
+/// How the region of a [Frame] is treated before the next frame is drawn over it.
+#[non_exhaustive]
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Disposal {
+    /// Leave the frame in place.
+    #[default]
+    None,
+    /// Clear the frame region to transparent.
+    Background,
+    /// Restore the frame region to what it was before the frame was drawn.
+    Previous,
+}
+
+/// How an [`AnimatedImage`] plays back over time.
+#[non_exhaustive]
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Playback {
+    /// Restart from the first frame after the last frame.
+    #[default]
+    Loop,
+    /// Play forwards then backwards, repeating.
+    PingPong,
+    /// Play once and remain on the last frame.
+    Once,
+    /// Play a number of times, then remain on the last frame.
+    Repeat(u32),
+}
+
+impl Playback {
+    /// Returns the order of `len` frames displayed in one cycle, which is empty if `len` is `0`.
+    pub(crate) fn sequence(self, len: usize) -> impl Iterator<Item = usize> {
+        let last = len.saturating_sub(1);
+        let backward = if self == Self::PingPong {
+            1..last
+        } else {
+            0..0
+        };
+        (0..len).chain(backward.rev())
+    }
+
+    /// Returns the number of times the frames play before stopping, or `None` if they repeat
+    /// forever.
+    pub(crate) const fn plays(self) -> Option<u32> {
+        match self {
+            Self::Loop | Self::PingPong => None,
+            Self::Once => Some(1),
+            Self::Repeat(plays) => Some(plays),
+        }
+    }
+
+    /// Returns the index of the frame displayed after `elapsed` time out of `len` frames, each
+    /// displayed for `delay(index)`. Returns `0` without calling `delay` if `len` is `0`.
+    pub(crate) fn index_at<F>(self, len: usize, delay: F, elapsed: Duration) -> usize
+    where
+        F: Fn(usize) -> Duration,
+    {
+        let last = len.saturating_sub(1);
+        let cycle: Duration = self.sequence(len).map(&delay).sum();
+        if let Some(plays) = self.plays() {
+            if elapsed >= cycle.saturating_mul(plays) {
+                return last;
+            }
+        }
+        if cycle.is_zero() {
+            return 0;
+        }
+        let mut elapsed = Duration::from_nanos((elapsed.as_nanos() % cycle.as_nanos()) as u64);
+        for index in self.sequence(len) {
+            if elapsed < delay(index) {
+                return index;
+            }
+            elapsed -= delay(index);
+        }
+        last
+    }
+}
+
+/// A single frame of an [`AnimatedImage`].
+#[derive(Debug, Clone)]
+pub struct Frame {
+    image: Image,
+    delay: Duration,
+    disposal: Disposal,
+    bounds: Rect<i32>,
+}
+
+impl Frame {
+    /// Returns the fully composited frame [Image], the size of the [`AnimatedImage`].
+    #[inline]
+    pub const fn image(&self) -> &Image {
+        &self.image
+    }
+
+    /// Returns how long the frame is displayed.
+    #[inline]
+    #[must_use]
+    pub const fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Returns how the frame region is disposed of before the next frame is drawn.
+    #[inline]
+    #[must_use]
+    pub const fn disposal(&self) -> Disposal {
+        self.disposal
+    }
+
+    /// Returns the region of the [`AnimatedImage`] this frame updated.
+    #[inline]
+    pub const fn bounds(&self) -> Rect<i32> {
+        self.bounds
+    }
+}
+
+/// An animated [Image] made up of timed [Frame]s.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct AnimatedImage {
+    width: u32,
+    height: u32,
+    frames: Vec<Frame>,
+    playback: Playback,
+}
+
+impl AnimatedImage {
+    /// Constructs an `AnimatedImage` from [Image]s and how long each is displayed. Frames are
+    /// converted to [`PixelFormat::Rgba`].
+    ///
+    /// # Errors
+    ///
+    /// If there are no frames, or the frames don't all have the same dimensions, then an error is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # use std::time::Duration;
+    /// let delay = Duration::from_millis(100);
+    /// let blink = AnimatedImage::from_frames([
+    ///     (Image::from_pixels(1, 1, &[Color::RED], PixelFormat::Rgb)?, delay),
+    ///     (Image::from_pixels(1, 1, &[Color::BLACK], PixelFormat::Rgb)?, delay),
+    /// ])?;
+    /// assert_eq!(blink.frame_count(), 2);
+    /// assert_eq!(blink.duration(), Duration::from_millis(200));
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn from_frames<I>(frames: I) -> PixResult<Self>
+    where
+        I: IntoIterator<Item = (Image, Duration)>,
+    {
+        let mut frames = frames.into_iter().peekable();
+        let (width, height) = frames
+            .peek()
+            .map(|(image, _)| image.dimensions())
+            .ok_or_else(|| anyhow!("animated image requires at least one frame"))?;
+        let frames = frames
+            .map(|(image, delay)| {
+                if image.dimensions() != (width, height) {
+                    let (frame_width, frame_height) = image.dimensions();
+                    return Err(anyhow!(
+                        "animated image frame size {frame_width}x{frame_height} does not match \
+                         {width}x{height}"
+                    ));
+                }
+                Ok(Frame {
+                    bounds: image.bounding_rect(),
+                    image: image.convert(PixelFormat::Rgba),
+                    delay,
+                    disposal: Disposal::None,
+                })
+            })
+            .collect::<PixResult<_>>()?;
+        Ok(Self {
+            width,
+            height,
+            frames,
+            playback: Playback::Loop,
+        })
+    }
+
+    /// Constructs an `AnimatedImage` from an animated `PNG` or `GIF` file. The [`ImageFormat`] is
+    /// identified the same way as [`Image::from_file`], and formats without animation are loaded
+    /// as a single frame. [`Playback`] defaults to [`Playback::Loop`] for files that repeat
+    /// forever, [`Playback::Once`] for files that play once and [`Playback::Repeat`] with the
+    /// stored count otherwise.
+    ///
+    /// # Errors
+    ///
+    /// If the file format is not supported, the file data is invalid, the decoded frames would be
+    /// too large, or there is an [`io::Error`] reading the file, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pix_engine::prelude::*;
+    /// let spinner = AnimatedImage::from_file("./spinner.gif")?;
+    /// let explosion = AnimatedImage::from_file("./explosion.png")?;
+    /// # Ok::<(), PixError>(())
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> PixResult<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)?;
+        let format = ImageFormat::from_magic(&bytes)
+            .or_else(|| ImageFormat::from_path(path))
+            .ok_or_else(|| {
+                PixError::UnsupportedFileType(path.extension().map(OsStr::to_os_string))
+            })?;
+        let animation = super::codec::decode_animation(&bytes, format)
+            .with_context(|| format!("failed to decode {format:?} animation: {path:?}"))?;
+        Ok(Self::from_raw(animation))
+    }
+
+    /// Constructs an `AnimatedImage` from a reader. The [`ImageFormat`] is identified by the
+    /// data signature.
+    ///
+    /// # Errors
+    ///
+    /// If the data format is not supported or is invalid, the decoded frames would be too large,
+    /// or there is an [`io::Error`] reading the data, then an error is returned.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_read<R: io::Read>(mut read: R) -> PixResult<Self> {
+        let mut bytes = Vec::new();
+        read.read_to_end(&mut bytes)?;
+        let format = ImageFormat::from_magic(&bytes).ok_or(PixError::UnsupportedFileType(None))?;
+        let animation = super::codec::decode_animation(&bytes, format)?;
+        Ok(Self::from_raw(animation))
+    }
+
+    /// Save the `AnimatedImage` to a file as an animated `PNG` (`APNG`) or `GIF`, chosen based on
+    /// the file extension. Every frame is saved covering the full canvas and [`Playback::PingPong`]
+    /// is saved as a looping sequence of frames played forwards then backwards.
+    ///
+    /// `GIF` frames are reduced to 256 colors, treat pixels with an alpha below `128` as
+    /// transparent and have delays rounded to hundredths of a second.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for any of the following:
+    ///     - The file extension is not `png` or `gif`.
+    ///     - An [`io::Error`] occurs attempting to create or write to the file.
+    ///     - The `AnimatedImage` can't be encoded in the chosen format, e.g. it's too large.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pix_engine::prelude::*;
+    /// let spinner = AnimatedImage::from_file("./spinner.gif")?;
+    /// spinner.save("./spinner.png")?;
+    /// # Ok::<(), PixError>(())
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> PixResult<()> {
+        let path = path.as_ref();
+        let format = ImageFormat::from_path(path).ok_or_else(|| {
+            PixError::UnsupportedFileType(path.extension().map(OsStr::to_os_string))
+        })?;
+        let file = io::BufWriter::new(fs::File::create(path)?);
+        super::codec::encode_animation(self, format, file)
+            .with_context(|| format!("failed to encode {format:?} animation: {path:?}"))
+    }
+
+    /// Write the `AnimatedImage` encoded as [`ImageFormat::Png`] or [`ImageFormat::Gif`] to
+    /// `writer`. See [`AnimatedImage::save`] for details.
+    ///
+    /// # Errors
+    ///
+    /// If the format doesn't support animation, an [`io::Error`] occurs writing, or the
+    /// `AnimatedImage` can't be encoded in the given format, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # use std::time::Duration;
+    /// let blink = AnimatedImage::from_frames([
+    ///     (Image::from_pixels(1, 1, &[Color::RED], PixelFormat::Rgb)?, Duration::from_millis(500)),
+    ///     (Image::from_pixels(1, 1, &[Color::BLUE], PixelFormat::Rgb)?, Duration::from_millis(500)),
+    /// ])?;
+    /// let mut bytes = Vec::new();
+    /// blink.write_to(&mut bytes, ImageFormat::Gif)?;
+    /// assert_eq!(AnimatedImage::from_read(&bytes[..])?.frame_count(), 2);
+    /// # Ok::<(), PixError>(())
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_to<W: io::Write>(&self, writer: W, format: ImageFormat) -> PixResult<()> {
+        super::codec::encode_animation(self, format, writer)
+    }
+
+    /// Composites decoded frames, applying each frame's blending and disposal.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn from_raw(animation: RawAnimation) -> Self {
+        let RawAnimation {
+            width,
+            height,
+            frames: raw_frames,
+            plays,
+        } = animation;
+        let mut canvas = Image::rgba(width, height);
+        let mut frames = Vec::with_capacity(raw_frames.len());
+        for frame in raw_frames {
+            let (frame_width, frame_height) = frame.image.dimensions();
+            let bounds = Rect::new(
+                frame.x as i32,
+                frame.y as i32,
+                frame_width as i32,
+                frame_height as i32,
+            );
+            let previous = (frame.disposal == Disposal::Previous).then(|| canvas.clone());
+            canvas.blit(&frame.image, None, bounds.top_left(), frame.blend_mode);
+            frames.push(Frame {
+                image: canvas.clone(),
+                delay: frame.delay,
+                disposal: frame.disposal,
+                bounds,
+            });
+            match (frame.disposal, previous) {
+                (Disposal::Background, _) => {
+                    let clear = Image::rgba(frame_width, frame_height);
+                    canvas.blit(&clear, None, bounds.top_left(), BlendMode::None);
+                }
+                (Disposal::Previous, Some(previous)) => canvas = previous,
+                _ => (),
+            }
+        }
+        Self {
+            width,
+            height,
+            frames,
+            playback: match plays {
+                0 => Playback::Loop,
+                1 => Playback::Once,
+                plays => Playback::Repeat(plays),
+            },
+        }
+    }
+
+    /// Returns the `AnimatedImage` width.
+    #[inline]
+    #[must_use]
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the `AnimatedImage` height.
+    #[inline]
+    #[must_use]
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the `AnimatedImage` dimensions as `(width, height)`.
+    #[inline]
+    #[must_use]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the `AnimatedImage` frames.
+    #[inline]
+    #[must_use]
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Returns the [Frame] at `index`, if it exists.
+    #[inline]
+    #[must_use]
+    pub fn frame(&self, index: usize) -> Option<&Frame> {
+        self.frames.get(index)
+    }
+
+    /// Returns the number of frames.
+    #[inline]
+    #[must_use]
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns the time it takes to play through every frame once.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(Frame::delay).sum()
+    }
+
+    /// Returns the current [Playback] mode.
+    #[inline]
+    #[must_use]
+    pub const fn playback(&self) -> Playback {
+        self.playback
+    }
+
+    /// Set the [Playback] mode.
+    #[inline]
+    pub fn set_playback(&mut self, playback: Playback) {
+        self.playback = playback;
+    }
+
+    /// Returns the index of the frame displayed after `elapsed` time based on the [Playback]
+    /// mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # use std::time::Duration;
+    /// let frame = Image::new(1, 1);
+    /// let delay = Duration::from_millis(100);
+    /// let mut animation = AnimatedImage::from_frames(vec![(frame, delay); 3])?;
+    /// let elapsed = Duration::from_millis(350);
+    /// assert_eq!(animation.frame_index_at(elapsed), 0);
+    /// animation.set_playback(Playback::PingPong);
+    /// assert_eq!(animation.frame_index_at(elapsed), 1);
+    /// animation.set_playback(Playback::Once);
+    /// assert_eq!(animation.frame_index_at(elapsed), 2);
+    /// # Ok::<(), PixError>(())
+    /// ```
+    #[must_use]
+    pub fn frame_index_at(&self, elapsed: Duration) -> usize {
+        self.playback
+            .index_at(self.frames.len(), |index| self.frames[index].delay, elapsed)
+    }
+
+    /// Returns the [Frame] displayed after `elapsed` time based on the [Playback] mode.
+    #[inline]
+    #[must_use]
+    pub fn frame_at(&self, elapsed: Duration) -> &Frame {
+        &self.frames[self.frame_index_at(elapsed)]
+    }
+}
+
+impl AnimatedImage {
+    /// Returns the frames displayed for one cycle of the [Playback] mode, in order.
+    pub(crate) fn frames_in_cycle(&self) -> impl Iterator<Item = &Frame> {
+        self.playback
+            .sequence(self.frames.len())
+            .map(|index| &self.frames[index])
+    }
+
+    /// Returns the number of times the animation plays when encoded, where `0` repeats forever.
+    pub(crate) fn plays(&self) -> u32 {
+        self.playback.plays().unwrap_or(0)
+    }
+}
+
+impl PixState {
+    /// Draw the frame of an [`AnimatedImage`] for the time [elapsed](PixState::elapsed) since the
+    /// application started to the current canvas, with its top-left corner at the given
+    /// `position`. Frames are drawn the same as [`PixState::image`].
+    ///
+    /// To play an animation from a later starting time, pass
+    /// [`AnimatedImage::frame_index_at`] for the time since starting to
+    /// [`PixState::animated_image_frame`].
+    ///
+    /// # Errors
+    ///
+    /// If the renderer fails to draw to the current render target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { spinner: AnimatedImage };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.animated_image(&self.spinner, [10, 10])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn animated_image<P>(&mut self, img: &AnimatedImage, position: P) -> PixResult<()>
+    where
+        P: Into<Point<i32>>,
+    {
+        let index = img.frame_index_at(self.elapsed());
+        self.animated_image_frame(img, index, position)
+    }
+
+    /// Draw the frame at `index` of an [`AnimatedImage`] to the current canvas, with its top-left
+    /// corner at the given `position`. The `index` wraps around the number of frames, so it can be
+    /// driven by a counter such as [`PixState::frame_count`].
+    ///
+    /// # Errors
+    ///
+    /// If the renderer fails to draw to the current render target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { walk: AnimatedImage };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     // Advance one frame every 6 rendered frames
+    ///     let index = s.frame_count() / 6;
+    ///     s.animated_image_frame(&self.walk, index, [10, 10])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn animated_image_frame<P>(
+        &mut self,
+        img: &AnimatedImage,
+        index: usize,
+        position: P,
+    ) -> PixResult<()>
+    where
+        P: Into<Point<i32>>,
+    {
+        match img.frames.get(index % img.frames.len().max(1)) {
+            Some(frame) => self.image(&frame.image, position),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use crate::image::codec::RawFrame;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn with_delays(delays: &[u64]) -> PixResult<AnimatedImage> {
+        AnimatedImage::from_frames(delays.iter().map(|&delay| (Image::new(1, 1), ms(delay))))
+    }
+
+    #[test]
+    fn test_from_frames() -> PixResult<()> {
+        assert!(AnimatedImage::from_frames([]).is_err());
+        assert!(AnimatedImage::from_frames([
+            (Image::new(1, 1), ms(10)),
+            (Image::new(2, 1), ms(10)),
+        ])
+        .is_err());
+
+        let animation = AnimatedImage::from_frames([(Image::new(2, 1), ms(10))])?;
+        assert_eq!(animation.dimensions(), (2, 1));
+        assert_eq!(animation.frames()[0].image().format(), PixelFormat::Rgba);
+        assert_eq!(animation.frames()[0].bounds(), rect![0, 0, 2, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_frame_index_at() -> PixResult<()> {
+        let mut animation = with_delays(&[10, 20, 30])?;
+        let indexes = |animation: &AnimatedImage| {
+            [0, 9, 10, 29, 30, 59, 60, 79, 80, 120]
+                .map(|elapsed| animation.frame_index_at(ms(elapsed)))
+        };
+        assert_eq!(indexes(&animation), [0, 0, 1, 1, 2, 2, 0, 1, 1, 0]);
+        animation.set_playback(Playback::PingPong);
+        assert_eq!(indexes(&animation), [0, 0, 1, 1, 2, 2, 1, 1, 0, 2]);
+        animation.set_playback(Playback::Once);
+        assert_eq!(indexes(&animation), [0, 0, 1, 1, 2, 2, 2, 2, 2, 2]);
+        animation.set_playback(Playback::Repeat(2));
+        assert_eq!(indexes(&animation), [0, 0, 1, 1, 2, 2, 0, 1, 1, 2]);
+
+        let animation = with_delays(&[0, 0])?;
+        assert_eq!(animation.frame_index_at(ms(100)), 0);
+
+        for playback in [Playback::Loop, Playback::PingPong, Playback::Repeat(2)] {
+            assert_eq!(playback.sequence(0).count(), 0, "{playback:?}");
+            let index = playback.index_at(0, |_| unreachable!("no frames"), ms(100));
+            assert_eq!(index, 0, "{playback:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn test_disposal() -> PixResult<()> {
+        let pixel = |color: Color| Image::from_pixels(1, 1, [color], PixelFormat::Rgba);
+        let frame = |image, x, disposal| RawFrame {
+            image,
+            x,
+            y: 0,
+            delay: ms(10),
+            disposal,
+            blend_mode: BlendMode::Blend,
+        };
+        let animation = AnimatedImage::from_raw(RawAnimation {
+            width: 2,
+            height: 1,
+            frames: vec![
+                frame(pixel(Color::RED)?, 0, Disposal::Previous),
+                frame(pixel(Color::BLUE)?, 1, Disposal::Background),
+                frame(pixel(Color::LIME)?, 0, Disposal::None),
+            ],
+            plays: 0,
+        });
+        let colors = |index: usize| {
+            let image = animation.frames()[index].image();
+            [image.get_pixel(0, 0), image.get_pixel(1, 0)]
+        };
+        assert_eq!(colors(0), [Color::RED, Color::TRANSPARENT]);
+        assert_eq!(colors(1), [Color::TRANSPARENT, Color::BLUE]);
+        assert_eq!(colors(2), [Color::LIME, Color::TRANSPARENT]);
+        Ok(())
+    }
+}
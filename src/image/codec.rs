@@ -0,0 +1,783 @@
+//! Encoding and decoding [Image]s to and from the supported [`ImageFormat`]s.
+//!
+//! Every codec is pure Rust and operates on in-memory byte buffers. Decoders produce the
+//! [`PixelFormat`] closest to the source data, and encoders convert from any [`PixelFormat`].
+//! `PNG` and `GIF` can also decode every frame of an animation as a [`RawAnimation`] and encode an
+//! [`AnimatedImage`].
+
+use crate::{
+    error::{Error, Result},
+    prelude::*,
+};
+use ::png::{BitDepth, ColorType};
+use anyhow::anyhow;
+use std::{io, time::Duration};
+
+mod bmp;
+mod gif;
+mod png;
+mod pnm;
+mod qoi;
+mod tga;
+
+/// Decode `bytes` in the given [`ImageFormat`] into an [Image].
+pub(crate) fn decode(bytes: &[u8], format: ImageFormat) -> Result<Image> {
+    match format {
+        ImageFormat::Png => png::decode(bytes),
+        ImageFormat::Bmp => bmp::decode(bytes),
+        ImageFormat::Tga => tga::decode(bytes),
+        ImageFormat::Pbm | ImageFormat::Pgm | ImageFormat::Ppm => pnm::decode(bytes),
+        ImageFormat::Qoi => qoi::decode(bytes),
+        ImageFormat::Gif => gif::decode(bytes),
+    }
+}
+
+/// An animation decoded from an [`ImageFormat`], before frames are composited.
+#[derive(Debug)]
+pub(crate) struct RawAnimation {
+    /// Width of the animation canvas.
+    pub(crate) width: u32,
+    /// Height of the animation canvas.
+    pub(crate) height: u32,
+    /// Frames in display order.
+    pub(crate) frames: Vec<RawFrame>,
+    /// Number of times the animation plays, where `0` repeats forever.
+    pub(crate) plays: u32,
+}
+
+/// A decoded animation frame, covering a region of the animation canvas.
+#[derive(Debug)]
+pub(crate) struct RawFrame {
+    /// Frame pixels.
+    pub(crate) image: Image,
+    /// Horizontal offset of the frame within the canvas.
+    pub(crate) x: u32,
+    /// Vertical offset of the frame within the canvas.
+    pub(crate) y: u32,
+    /// How long the frame is displayed.
+    pub(crate) delay: Duration,
+    /// How the frame region is disposed of before the next frame.
+    pub(crate) disposal: Disposal,
+    /// How the frame is drawn over the canvas, either [`BlendMode::None`] or
+    /// [`BlendMode::Blend`].
+    pub(crate) blend_mode: BlendMode,
+}
+
+/// Decode every frame of `bytes` in the given [`ImageFormat`]. Formats without animation decode
+/// as a single frame that plays forever.
+pub(crate) fn decode_animation(bytes: &[u8], format: ImageFormat) -> Result<RawAnimation> {
+    match format {
+        ImageFormat::Png => png::decode_animation(bytes),
+        ImageFormat::Gif => gif::decode_animation(bytes),
+        _ => {
+            let image = decode(bytes, format)?;
+            Ok(RawAnimation {
+                width: image.width(),
+                height: image.height(),
+                frames: vec![RawFrame {
+                    image,
+                    x: 0,
+                    y: 0,
+                    delay: Duration::ZERO,
+                    disposal: Disposal::None,
+                    blend_mode: BlendMode::None,
+                }],
+                plays: 0,
+            })
+        }
+    }
+}
+
+/// Encode an [Image] in the given [`ImageFormat`] to `writer`.
+pub(crate) fn encode<W: io::Write>(image: &Image, format: ImageFormat, writer: W) -> Result<()> {
+    match format {
+        ImageFormat::Png => png::encode(image, writer),
+        ImageFormat::Bmp => bmp::encode(image, writer),
+        ImageFormat::Tga => tga::encode(image, writer),
+        ImageFormat::Pbm | ImageFormat::Pgm | ImageFormat::Ppm => {
+            pnm::encode(image, format, writer)
+        }
+        ImageFormat::Qoi => qoi::encode(image, writer),
+        ImageFormat::Gif => gif::encode(image, writer),
+    }
+}
+
+/// Encode every frame of an [`AnimatedImage`] in the given [`ImageFormat`] to `writer`.
+pub(crate) fn encode_animation<W: io::Write>(
+    animation: &AnimatedImage,
+    format: ImageFormat,
+    writer: W,
+) -> Result<()> {
+    match format {
+        ImageFormat::Png => png::encode_animation(animation, writer),
+        ImageFormat::Gif => gif::encode_animation(animation, writer),
+        _ => Err(anyhow!("{format:?} does not support animation")),
+    }
+}
+
+/// Returns an [`Error::UnsupportedImageFormat`] describing a valid, but unsupported, image
+/// encoding.
+fn unsupported(bit_depth: BitDepth, color_type: ColorType) -> anyhow::Error {
+    Error::UnsupportedImageFormat {
+        bit_depth,
+        color_type,
+    }
+    .into()
+}
+
+/// Returns the [`BitDepth`] closest to a number of bits per channel or palette index.
+fn bit_depth(bits: u32) -> BitDepth {
+    match bits {
+        1 => BitDepth::One,
+        2 => BitDepth::Two,
+        4 => BitDepth::Four,
+        16 => BitDepth::Sixteen,
+        _ => BitDepth::Eight,
+    }
+}
+
+/// Maximum number of pixels a decoded image can have, matching the limit of the reference `QOI`
+/// decoder. Prevents a few bytes of malformed header from requesting a huge allocation.
+const MAX_PIXELS: u64 = 400_000_000;
+
+/// Returns the number of pixels in an image, guarding against malformed dimensions exceeding
+/// [`MAX_PIXELS`].
+fn pixel_count(width: u32, height: u32) -> Result<usize> {
+    let count = u64::from(width) * u64::from(height);
+    if count > MAX_PIXELS {
+        return Err(anyhow!("image dimensions too large: {width}x{height}"));
+    }
+    usize::try_from(count).map_err(|_| anyhow!("image dimensions too large: {width}x{height}"))
+}
+
+/// Checks that compositing `frames` animation frames onto a `width`x`height` canvas, which keeps
+/// a full copy of the canvas per frame, doesn't exceed [`MAX_PIXELS`] in total.
+fn check_animation_size(width: u32, height: u32, frames: usize) -> Result<()> {
+    let total = (u64::from(width) * u64::from(height)).saturating_mul(frames as u64);
+    if total > MAX_PIXELS {
+        return Err(anyhow!(
+            "animation too large: {frames} frames of {width}x{height}"
+        ));
+    }
+    Ok(())
+}
+
+/// Allocates a zeroed pixel buffer for an image, guarding against overflow and excessive
+/// allocations from malformed dimensions.
+fn pixel_buffer(width: u32, height: u32, format: PixelFormat) -> Result<Vec<u8>> {
+    let size = pixel_count(width, height)?
+        .checked_mul(format.channels())
+        .filter(|&size| isize::try_from(size).is_ok())
+        .ok_or_else(|| anyhow!("image dimensions too large: {width}x{height}"))?;
+    Ok(vec![0x00; size])
+}
+
+/// Returns the `RGBA` channels of every pixel of an [Image], in row-major order.
+fn rgba_pixels(image: &Image) -> impl Iterator<Item = [u8; 4]> + '_ {
+    image.pixels().map(|color| color.channels())
+}
+
+/// A cursor for reading encoded image data, which returns errors instead of panicking when data
+/// is truncated.
+#[derive(Debug, Clone)]
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    name: &'static str,
+}
+
+impl<'a> Reader<'a> {
+    /// Constructs a `Reader` over `bytes`, using `name` to describe the format in errors.
+    const fn new(bytes: &'a [u8], name: &'static str) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            name,
+        }
+    }
+
+    /// The current position in bytes.
+    const fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Move to the absolute position `pos` in bytes.
+    fn seek(&mut self, pos: usize) -> Result<()> {
+        if pos > self.bytes.len() {
+            return Err(self.eof());
+        }
+        self.pos = pos;
+        Ok(())
+    }
+
+    /// Returns the next `len` bytes.
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self
+            .pos
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(self.pos..end))
+            .ok_or_else(|| self.eof())?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Skips the next `len` bytes.
+    fn skip(&mut self, len: usize) -> Result<()> {
+        self.take(len).map(|_| ())
+    }
+
+    /// Returns the next `N` bytes as an array.
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0x00; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        let [byte] = self.array()?;
+        Ok(byte)
+    }
+
+    fn u16_le(&mut self) -> Result<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    fn u32_le(&mut self) -> Result<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn i32_le(&mut self) -> Result<i32> {
+        self.array().map(i32::from_le_bytes)
+    }
+
+    fn u32_be(&mut self) -> Result<u32> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    /// Returns an error indicating the data ended early.
+    fn eof(&self) -> anyhow::Error {
+        anyhow!("unexpected end of {} data", self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::Frame;
+
+    fn test_image(format: PixelFormat) -> Image {
+        let mut image = Image::from_vec(5, 3, vec![0; 5 * 3 * format.channels()], format);
+        for y in 0..3 {
+            for x in 0..5 {
+                let alpha = if x == 4 { 0 } else { 255 - 40 * x as u8 };
+                image.set_pixel(x, y, [(x * 60) as u8, (y * 120) as u8, 200, alpha]);
+            }
+        }
+        image
+    }
+
+    fn round_trip(image: &Image, format: ImageFormat) -> Result<Image> {
+        let mut bytes = vec![];
+        encode(image, format, &mut bytes)?;
+        assert_eq!(ImageFormat::from_magic(&bytes), Some(format), "{format:?}");
+        decode(&bytes, format)
+    }
+
+    #[test]
+    fn test_lossless_round_trip() -> Result<()> {
+        for format in [
+            ImageFormat::Png,
+            ImageFormat::Bmp,
+            ImageFormat::Tga,
+            ImageFormat::Qoi,
+        ] {
+            for pixel_format in [PixelFormat::Rgb, PixelFormat::Rgba] {
+                let image = test_image(pixel_format);
+                let decoded = round_trip(&image, format)?;
+                assert_eq!(decoded.dimensions(), image.dimensions(), "{format:?}");
+                assert_eq!(decoded.format(), pixel_format, "{format:?}");
+                assert_eq!(decoded.as_bytes(), image.as_bytes(), "{format:?}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_oversized_headers() {
+        assert!(pixel_buffer(20_000, 20_001, PixelFormat::Gray).is_err());
+        assert!(pixel_buffer(u32::MAX, u32::MAX, PixelFormat::Rgba).is_err());
+        assert_eq!(pixel_count(20_000, 20_000).ok(), Some(400_000_000));
+
+        let mut qoi = b"qoif".to_vec();
+        qoi.extend(100_000u32.to_be_bytes());
+        qoi.extend(100_000u32.to_be_bytes());
+        qoi.extend([4, 0]);
+        assert!(decode(&qoi, ImageFormat::Qoi).is_err());
+
+        let mut gif = b"GIF89a".to_vec();
+        gif.extend([0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00]);
+        assert!(decode(&gif, ImageFormat::Gif).is_err());
+
+        // File header followed by an info header claiming the maximum size
+        let mut bmp = b"BM".to_vec();
+        bmp.extend([0; 8]);
+        bmp.extend(54u32.to_le_bytes());
+        bmp.extend(u32::MAX.to_le_bytes());
+        bmp.extend(1i32.to_le_bytes());
+        bmp.extend(1i32.to_le_bytes());
+        bmp.extend(1u16.to_le_bytes());
+        bmp.extend(24u16.to_le_bytes());
+        bmp.extend([0; 24]);
+        assert!(decode(&bmp, ImageFormat::Bmp).is_err());
+    }
+
+    #[test]
+    fn test_decode_oversized_animations() {
+        // 10000x10000 logical screen with single pixel frames
+        let gif = |frames| {
+            let mut gif = b"GIF89a".to_vec();
+            gif.extend([0x10, 0x27, 0x10, 0x27, 0x80, 0, 0, 255, 0, 0, 0, 0, 255]);
+            for _ in 0..frames {
+                gif.extend([0x2C, 0, 0, 0, 0, 1, 0, 1, 0, 0, 2, 2, 0x44, 0x01, 0]);
+            }
+            gif.push(0x3B);
+            gif
+        };
+        assert!(decode_animation(&gif(4), ImageFormat::Gif).is_ok());
+        assert!(decode_animation(&gif(5), ImageFormat::Gif).is_err());
+        assert!(check_animation_size(u32::MAX, u32::MAX, usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_grayscale_round_trip() -> Result<()> {
+        for format in [
+            ImageFormat::Png,
+            ImageFormat::Bmp,
+            ImageFormat::Tga,
+            ImageFormat::Qoi,
+        ] {
+            for pixel_format in [PixelFormat::Gray, PixelFormat::GrayAlpha] {
+                let image = test_image(pixel_format);
+                let decoded = round_trip(&image, format)?;
+                assert_eq!(decoded.dimensions(), image.dimensions(), "{format:?}");
+                let converted = decoded.convert(pixel_format);
+                assert_eq!(converted.as_bytes(), image.as_bytes(), "{format:?}");
+                assert_eq!(decoded.format().has_alpha(), pixel_format.has_alpha());
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_png(
+        (width, height): (u32, u32),
+        color: ::png::ColorType,
+        depth: ::png::BitDepth,
+        setup: impl FnOnce(&mut ::png::Encoder<'_, &mut Vec<u8>>),
+        data: &[u8],
+    ) -> Result<Vec<u8>> {
+        let mut bytes = vec![];
+        let mut png = ::png::Encoder::new(&mut bytes, width, height);
+        png.set_color(color);
+        png.set_depth(depth);
+        setup(&mut png);
+        png.write_header()?.write_image_data(data)?;
+        Ok(bytes)
+    }
+
+    #[test]
+    fn test_decode_paletted_png() -> Result<()> {
+        use ::png::{BitDepth, ColorType};
+
+        // 2-bit indices: red, lime, blue, transparent
+        let bytes = encode_png(
+            (4, 1),
+            ColorType::Indexed,
+            BitDepth::Two,
+            |png| {
+                png.set_palette(vec![255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0]);
+                png.set_trns(vec![255, 255, 255, 0]);
+            },
+            &[0b0001_1011],
+        )?;
+        let image = decode(&bytes, ImageFormat::Png)?;
+        assert_eq!(image.format(), PixelFormat::Rgba);
+        assert_eq!(image.get_pixel(0, 0), Color::RED);
+        assert_eq!(image.get_pixel(1, 0), Color::LIME);
+        assert_eq!(image.get_pixel(2, 0), Color::BLUE);
+        assert_eq!(image.get_pixel(3, 0).alpha(), 0);
+
+        let bytes = encode_png(
+            (2, 1),
+            ColorType::Indexed,
+            BitDepth::Eight,
+            |png| {
+                png.set_palette(vec![255, 0, 0, 0, 0, 255]);
+            },
+            &[1, 0],
+        )?;
+        let image = decode(&bytes, ImageFormat::Png)?;
+        assert_eq!(image.format(), PixelFormat::Rgb);
+        assert_eq!(image.get_pixel(0, 0), Color::BLUE);
+        assert_eq!(image.get_pixel(1, 0), Color::RED);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_grayscale_png() -> Result<()> {
+        use ::png::{BitDepth, ColorType};
+
+        let bytes = encode_png(
+            (2, 1),
+            ColorType::Grayscale,
+            BitDepth::Sixteen,
+            |_| (),
+            &[0x12, 0x34, 0xFF, 0xFF],
+        )?;
+        let image = decode(&bytes, ImageFormat::Png)?;
+        assert_eq!(image.format(), PixelFormat::Gray);
+        assert_eq!(image.as_bytes(), [0x12, 0xFF]);
+        assert_eq!(image.get_pixel(0, 0), rgb!(0x12));
+
+        let bytes = encode_png(
+            (8, 1),
+            ColorType::Grayscale,
+            BitDepth::One,
+            |_| (),
+            &[0b1010_0000],
+        )?;
+        let image = decode(&bytes, ImageFormat::Png)?;
+        assert_eq!(image.format(), PixelFormat::Gray);
+        assert_eq!(image.as_bytes(), [255, 0, 255, 0, 0, 0, 0, 0]);
+
+        let bytes = encode_png(
+            (1, 1),
+            ColorType::GrayscaleAlpha,
+            BitDepth::Sixteen,
+            |_| (),
+            &[0x80, 0x00, 0x40, 0x00],
+        )?;
+        let image = decode(&bytes, ImageFormat::Png)?;
+        assert_eq!(image.format(), PixelFormat::GrayAlpha);
+        assert_eq!(image.get_pixel(0, 0), rgb!(0x80, 0x40));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_interlaced_png() -> Result<()> {
+        // 3x3 8-bit grayscale, Adam7 interlaced
+        #[rustfmt::skip]
+        let bytes = [
+            137, 80, 78, 71, 13, 10, 26, 10, 0, 0, 0, 13, 73, 72, 68, 82, 0, 0, 0, 3, 0, 0, 0, 3,
+            8, 0, 0, 0, 1, 4, 68, 218, 245, 0, 0, 0, 23, 73, 68, 65, 84, 120, 218, 99, 224, 98,
+            48, 98, 104, 90, 197, 32, 199, 48, 141, 193, 45, 42, 15, 0, 19, 181, 3, 43, 230, 105,
+            179, 187, 0, 0, 0, 0, 73, 69, 78, 68, 174, 66, 96, 130,
+        ];
+        let image = decode(&bytes, ImageFormat::Png)?;
+        assert_eq!(image.format(), PixelFormat::Gray);
+        assert_eq!(image.as_bytes(), [10, 30, 50, 70, 90, 110, 130, 150, 170]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pnm_round_trip() -> Result<()> {
+        let image = test_image(PixelFormat::Rgb);
+        let decoded = round_trip(&image, ImageFormat::Ppm)?;
+        assert_eq!(decoded.as_bytes(), image.as_bytes());
+
+        let decoded = round_trip(&image, ImageFormat::Pgm)?;
+        assert_eq!(decoded.format(), PixelFormat::Gray);
+        assert_eq!(
+            decoded.as_bytes(),
+            image.convert(PixelFormat::Gray).as_bytes()
+        );
+
+        let decoded = round_trip(&image, ImageFormat::Pbm)?;
+        assert_eq!(decoded.get_pixel(0, 0), Color::BLACK);
+        assert_eq!(decoded.get_pixel(4, 2), Color::WHITE);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gif_round_trip() -> Result<()> {
+        let image = test_image(PixelFormat::Rgba);
+        let decoded = round_trip(&image, ImageFormat::Gif)?;
+        assert_eq!(decoded.dimensions(), image.dimensions());
+        for (x, y) in [(0, 0), (2, 1), (3, 2)] {
+            let [r, g, b, _] = image.get_pixel(x, y).channels();
+            assert_eq!(decoded.get_pixel(x, y), rgb!(r, g, b), "({x}, {y})");
+        }
+        assert_eq!(decoded.get_pixel(4, 0), Color::TRANSPARENT);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gif_quantizes_large_palettes() -> Result<()> {
+        let mut image = Image::rgb(64, 64);
+        for y in 0..64 {
+            for x in 0..64 {
+                image.set_pixel(x, y, [(x * 4) as u8, (y * 4) as u8, 128]);
+            }
+        }
+        let decoded = round_trip(&image, ImageFormat::Gif)?;
+        for (expected, actual) in image.pixels().zip(decoded.pixels()) {
+            let [r1, g1, b1, _] = expected.channels();
+            let [r2, g2, b2, a] = actual.channels();
+            assert!(r1.abs_diff(r2) <= 16 && g1.abs_diff(g2) <= 16 && b1.abs_diff(b2) <= 16);
+            assert_eq!(a, 255);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_ascii_pnm() -> Result<()> {
+        let image = decode(b"P1\n# comment\n3 2\n010\n1 0 1\n", ImageFormat::Pbm)?;
+        assert_eq!(image.dimensions(), (3, 2));
+        assert_eq!(image.get_pixel(0, 0), Color::WHITE);
+        assert_eq!(image.get_pixel(1, 0), Color::BLACK);
+        assert_eq!(image.get_pixel(1, 1), Color::WHITE);
+
+        let image = decode(b"P3 1 1 1023 1023 0 512", ImageFormat::Ppm)?;
+        assert_eq!(image.get_pixel(0, 0), rgb!(255, 0, 128));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_paletted_bmp() -> Result<()> {
+        #[rustfmt::skip]
+        let bytes = [
+            // File header
+            b'B', b'M', 66, 0, 0, 0, 0, 0, 0, 0, 62, 0, 0, 0,
+            // Info header: 2x2, 1 bit per pixel
+            40, 0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0, 1, 0, 1, 0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0,
+            // Palette: blue, red
+            255, 0, 0, 0, 0, 0, 255, 0,
+            // Bottom-up rows, padded to 4 bytes
+            0b0100_0000, 0, 0, 0, 0b1000_0000, 0, 0, 0,
+        ];
+        let image = decode(&bytes, ImageFormat::Bmp)?;
+        assert_eq!(image.format(), PixelFormat::Rgb);
+        assert_eq!(image.get_pixel(0, 0), Color::RED);
+        assert_eq!(image.get_pixel(1, 0), Color::BLUE);
+        assert_eq!(image.get_pixel(0, 1), Color::BLUE);
+        assert_eq!(image.get_pixel(1, 1), Color::RED);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_rle_tga() -> Result<()> {
+        #[rustfmt::skip]
+        let bytes = [
+            // Header: RLE true-color, 3x1, 24 bits, top-left origin
+            0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3, 0, 1, 0, 24, 0x20,
+            // Run of two red pixels, raw green pixel
+            0x81, 0, 0, 255, 0x00, 0, 255, 0,
+        ];
+        let image = decode(&bytes, ImageFormat::Tga)?;
+        assert_eq!(image.get_pixel(0, 0), Color::RED);
+        assert_eq!(image.get_pixel(1, 0), Color::RED);
+        assert_eq!(image.get_pixel(2, 0), Color::LIME);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_apng() -> Result<()> {
+        use ::png::{BlendOp, ColorType, DisposeOp, Encoder};
+
+        let mut bytes = vec![];
+        let mut png = Encoder::new(&mut bytes, 2, 1);
+        png.set_color(ColorType::Rgba);
+        png.set_animated(2, 1)?;
+        let mut writer = png.write_header()?;
+        writer.set_frame_delay(1, 10)?;
+        writer.set_dispose_op(DisposeOp::Background)?;
+        writer.write_image_data(&[255, 0, 0, 255, 255, 0, 0, 255])?;
+        writer.set_frame_dimension(1, 1)?;
+        writer.set_frame_position(1, 0)?;
+        writer.set_frame_delay(3, 0)?;
+        writer.set_dispose_op(DisposeOp::None)?;
+        writer.set_blend_op(BlendOp::Over)?;
+        writer.write_image_data(&[0, 0, 255, 255])?;
+        writer.finish()?;
+
+        let animation = AnimatedImage::from_raw(decode_animation(&bytes, ImageFormat::Png)?);
+        assert_eq!(animation.dimensions(), (2, 1));
+        assert_eq!(animation.playback(), Playback::Once);
+        let frames = animation.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].delay(), Duration::from_millis(100));
+        assert_eq!(frames[0].disposal(), Disposal::Background);
+        assert_eq!(frames[0].image().get_pixel(1, 0), Color::RED);
+        assert_eq!(frames[1].delay(), Duration::from_millis(30));
+        assert_eq!(frames[1].bounds(), rect![1, 0, 1, 1]);
+        assert_eq!(frames[1].image().get_pixel(0, 0), Color::TRANSPARENT);
+        assert_eq!(frames[1].image().get_pixel(1, 0), Color::BLUE);
+
+        // Still images decode as a single frame
+        let mut bytes = vec![];
+        encode(&test_image(PixelFormat::Rgb), ImageFormat::Png, &mut bytes)?;
+        let animation = AnimatedImage::from_raw(decode_animation(&bytes, ImageFormat::Png)?);
+        assert_eq!(animation.frame_count(), 1);
+        assert_eq!(animation.playback(), Playback::Loop);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_apng_default_image() -> Result<()> {
+        use ::png::{ColorType, Encoder};
+
+        let mut bytes = vec![];
+        let mut png = Encoder::new(&mut bytes, 1, 1);
+        png.set_color(ColorType::Rgb);
+        png.set_animated(1, 0)?;
+        png.set_sep_def_img(true)?;
+        let mut writer = png.write_header()?;
+        writer.write_image_data(&[0, 255, 0])?;
+        writer.write_image_data(&[255, 255, 255])?;
+        writer.finish()?;
+
+        // The default image isn't part of the animation
+        let animation = AnimatedImage::from_raw(decode_animation(&bytes, ImageFormat::Png)?);
+        assert_eq!(animation.frame_count(), 1);
+        assert_eq!(animation.frames()[0].image().get_pixel(0, 0), Color::WHITE);
+        // But is the still image
+        assert_eq!(
+            decode(&bytes, ImageFormat::Png)?.get_pixel(0, 0),
+            Color::LIME
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_animated_gif() -> Result<()> {
+        #[rustfmt::skip]
+        let bytes = [
+            // Header: 2x1, 2 color global palette of red and blue
+            b'G', b'I', b'F', b'8', b'9', b'a', 2, 0, 1, 0, 0x80, 0, 0,
+            255, 0, 0, 0, 0, 255,
+            // Loop forever
+            0x21, 0xFF, 11, b'N', b'E', b'T', b'S', b'C', b'A', b'P', b'E', b'2', b'.', b'0',
+            3, 1, 0, 0, 0,
+            // 50ms red pixel at (0, 0), left in place
+            0x21, 0xF9, 4, 0x04, 5, 0, 0, 0,
+            0x2C, 0, 0, 0, 0, 1, 0, 1, 0, 0, 2, 2, 0x44, 0x01, 0,
+            // Default delay blue pixel at (1, 0) with red transparent, cleared to background
+            0x21, 0xF9, 4, 0x09, 0, 0, 0, 0,
+            0x2C, 1, 0, 0, 0, 1, 0, 1, 0, 0, 2, 2, 0x4C, 0x01, 0,
+            0x3B,
+        ];
+        let animation = AnimatedImage::from_raw(decode_animation(&bytes, ImageFormat::Gif)?);
+        assert_eq!(animation.playback(), Playback::Loop);
+        let frames = animation.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].delay(), Duration::from_millis(50));
+        assert_eq!(frames[0].image().get_pixel(0, 0), Color::RED);
+        assert_eq!(frames[0].image().get_pixel(1, 0), Color::TRANSPARENT);
+        assert_eq!(frames[1].delay(), Duration::from_millis(100));
+        assert_eq!(frames[1].disposal(), Disposal::Background);
+        assert_eq!(frames[1].image().get_pixel(0, 0), Color::RED);
+        assert_eq!(frames[1].image().get_pixel(1, 0), Color::BLUE);
+
+        let image = decode(&bytes, ImageFormat::Gif)?;
+        assert_eq!(image.as_bytes(), frames[0].image().as_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_animation_round_trip() -> Result<()> {
+        let frame = |color: Color, millis| -> Result<(Image, Duration)> {
+            let image = Image::from_pixels(2, 1, [color, Color::TRANSPARENT], PixelFormat::Rgba)?;
+            Ok((image, Duration::from_millis(millis)))
+        };
+        let mut animation = AnimatedImage::from_frames([
+            frame(Color::RED, 30)?,
+            frame(Color::LIME, 30)?,
+            frame(Color::BLUE, 40)?,
+        ])?;
+        animation.set_playback(Playback::PingPong);
+        let colors = |animation: &AnimatedImage| -> Vec<_> {
+            animation
+                .frames()
+                .iter()
+                .map(|frame| {
+                    let image = frame.image();
+                    (image.get_pixel(0, 0), image.get_pixel(1, 0), frame.delay())
+                })
+                .collect()
+        };
+
+        for format in [ImageFormat::Png, ImageFormat::Gif] {
+            let mut bytes = vec![];
+            encode_animation(&animation, format, &mut bytes)?;
+            let decoded = AnimatedImage::from_raw(decode_animation(&bytes, format)?);
+            assert_eq!(decoded.playback(), Playback::Loop, "{format:?}");
+            // Ping pong plays forwards then backwards, excluding the first and last frames
+            let ms = Duration::from_millis;
+            assert_eq!(
+                colors(&decoded),
+                [
+                    (Color::RED, Color::TRANSPARENT, ms(30)),
+                    (Color::LIME, Color::TRANSPARENT, ms(30)),
+                    (Color::BLUE, Color::TRANSPARENT, ms(40)),
+                    (Color::LIME, Color::TRANSPARENT, ms(30)),
+                ],
+                "{format:?}"
+            );
+        }
+
+        animation.set_playback(Playback::Once);
+        let mut bytes = vec![];
+        encode_animation(&animation, ImageFormat::Png, &mut bytes)?;
+        let decoded = AnimatedImage::from_raw(decode_animation(&bytes, ImageFormat::Png)?);
+        assert_eq!(decoded.playback(), Playback::Once);
+        assert_eq!(decoded.frame_count(), 3);
+
+        animation.set_playback(Playback::Repeat(3));
+        for format in [ImageFormat::Png, ImageFormat::Gif] {
+            let mut bytes = vec![];
+            encode_animation(&animation, format, &mut bytes)?;
+            let decoded = AnimatedImage::from_raw(decode_animation(&bytes, format)?);
+            assert_eq!(decoded.playback(), Playback::Repeat(3), "{format:?}");
+        }
+        assert!(encode_animation(&animation, ImageFormat::Qoi, &mut bytes).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_gif_delays() -> Result<()> {
+        let gif_delays = |delay: Duration| -> Result<Vec<Duration>> {
+            let frame = |color: Color| -> Result<(Image, Duration)> {
+                Ok((Image::from_pixels(1, 1, [color], PixelFormat::Rgb)?, delay))
+            };
+            let animation = AnimatedImage::from_frames([
+                frame(Color::RED)?,
+                frame(Color::BLUE)?,
+                frame(Color::RED)?,
+            ])?;
+            let mut bytes = vec![];
+            encode_animation(&animation, ImageFormat::Gif, &mut bytes)?;
+            let decoded = AnimatedImage::from_raw(decode_animation(&bytes, ImageFormat::Gif)?);
+            Ok(decoded.frames().iter().map(Frame::delay).collect())
+        };
+        let cs = Duration::from_millis(10);
+        // Rounding carries over to keep the total duration accurate
+        assert_eq!(
+            gif_delays(Duration::from_secs(1) / 30)?,
+            [cs * 3, cs * 4, cs * 3]
+        );
+        // Delays too short to be displayed as written are lengthened
+        assert_eq!(gif_delays(Duration::from_secs(1) / 60)?, [cs * 2; 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncated_data() -> Result<()> {
+        for format in [ImageFormat::Bmp, ImageFormat::Qoi, ImageFormat::Gif] {
+            let mut bytes = vec![];
+            encode(&test_image(PixelFormat::Rgba), format, &mut bytes)?;
+            bytes.truncate(bytes.len() / 2);
+            assert!(decode(&bytes, format).is_err(), "{format:?}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,335 @@
+//! [BMP](https://en.wikipedia.org/wiki/BMP_file_format) encoding and decoding.
+//!
+//! Decoding supports `OS/2` core headers and Windows info headers up to `BITMAPV5HEADER`, with
+//! 1, 4, and 8-bit palettes (uncompressed or run-length encoded) and 16, 24 and 32-bit pixels
+//! with optional bit field masks. Images are encoded as 24-bit `RGB`, or 32-bit `RGBA` using a
+//! `BITMAPV4HEADER` when they have an alpha channel.
+
+use super::Reader;
+use crate::{error::Result, prelude::*};
+use ::png::ColorType;
+use anyhow::anyhow;
+use std::io;
+
+/// Size of the bitmap file header in bytes.
+const FILE_HEADER_SIZE: u32 = 14;
+/// Size of the `BITMAPCOREHEADER` in bytes.
+const CORE_HEADER_SIZE: u32 = 12;
+/// Size of the `BITMAPINFOHEADER` in bytes.
+const INFO_HEADER_SIZE: u32 = 40;
+/// Size of the `BITMAPV4HEADER` in bytes.
+const V4_HEADER_SIZE: u32 = 108;
+
+/// Uncompressed pixels.
+const BI_RGB: u32 = 0;
+/// Run-length encoded 8-bit palette indexes.
+const BI_RLE8: u32 = 1;
+/// Run-length encoded 4-bit palette indexes.
+const BI_RLE4: u32 = 2;
+/// Uncompressed pixels with `RGB` bit field masks.
+const BI_BITFIELDS: u32 = 3;
+/// Uncompressed pixels with `RGBA` bit field masks.
+const BI_ALPHABITFIELDS: u32 = 6;
+
+/// A color channel stored as a bit field within a pixel.
+#[derive(Debug, Copy, Clone)]
+struct Channel {
+    mask: u32,
+    shift: u32,
+    max: u32,
+}
+
+impl Channel {
+    fn new(mask: u32) -> Self {
+        let shift = if mask == 0 { 0 } else { mask.trailing_zeros() };
+        let max = mask.checked_shr(shift).unwrap_or_default();
+        Self { mask, shift, max }
+    }
+
+    /// Extract the channel from `pixel`, scaled to 8 bits.
+    fn get(self, pixel: u32) -> u8 {
+        if self.max == 0 {
+            return 0;
+        }
+        let value = u64::from((pixel & self.mask) >> self.shift);
+        ((value * 255 + u64::from(self.max) / 2) / u64::from(self.max)) as u8
+    }
+}
+
+/// Decode `BMP` bytes into an [Image].
+pub(super) fn decode(bytes: &[u8]) -> Result<Image> {
+    let mut reader = Reader::new(bytes, "bmp");
+    if reader.take(2)? != b"BM" {
+        return Err(anyhow!("invalid bmp signature"));
+    }
+    reader.skip(8)?;
+    let data_offset = reader.u32_le()? as usize;
+
+    let header_size = reader.u32_le()?;
+    let (width, height, bpp, compression, palette_len) = if header_size == CORE_HEADER_SIZE {
+        let width = i32::from(reader.u16_le()?);
+        let height = i32::from(reader.u16_le()?);
+        reader.skip(2)?;
+        let bpp = reader.u16_le()?;
+        (width, height, bpp, BI_RGB, 0)
+    } else if header_size >= INFO_HEADER_SIZE {
+        let width = reader.i32_le()?;
+        let height = reader.i32_le()?;
+        reader.skip(2)?;
+        let bpp = reader.u16_le()?;
+        let compression = reader.u32_le()?;
+        reader.skip(12)?;
+        let palette_len = reader.u32_le()?;
+        reader.skip(4)?;
+        (width, height, bpp, compression, palette_len)
+    } else {
+        return Err(anyhow!("invalid bmp header size: {header_size}"));
+    };
+    let bpp = u32::from(bpp);
+    let color_type = if bpp <= 8 {
+        ColorType::Indexed
+    } else {
+        ColorType::Rgba
+    };
+    let unsupported = || super::unsupported(super::bit_depth(bpp), color_type);
+
+    let valid = match compression {
+        BI_RGB => matches!(bpp, 1 | 4 | 8 | 16 | 24 | 32),
+        BI_RLE8 => bpp == 8,
+        BI_RLE4 => bpp == 4,
+        BI_BITFIELDS | BI_ALPHABITFIELDS => matches!(bpp, 16 | 32),
+        _ => false,
+    };
+    if !valid {
+        return Err(unsupported());
+    }
+
+    // Masks follow an info header, or are included in later header versions
+    let [mut red, mut green, mut blue, mut alpha] = match bpp {
+        16 => [0x7C00, 0x03E0, 0x001F, 0],
+        _ => [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0],
+    };
+    if matches!(compression, BI_BITFIELDS | BI_ALPHABITFIELDS) {
+        red = reader.u32_le()?;
+        green = reader.u32_le()?;
+        blue = reader.u32_le()?;
+        if compression == BI_ALPHABITFIELDS || header_size >= 56 {
+            alpha = reader.u32_le()?;
+        }
+    }
+    let header_end = FILE_HEADER_SIZE
+        .checked_add(header_size)
+        .ok_or_else(|| anyhow!("invalid bmp header size: {header_size}"))?
+        as usize;
+    if header_size > INFO_HEADER_SIZE {
+        reader.seek(header_end)?;
+    }
+
+    let palette = if bpp <= 8 {
+        let entry_size = if header_size == CORE_HEADER_SIZE {
+            3
+        } else {
+            4
+        };
+        let max_len = 1usize << bpp;
+        let len = match palette_len {
+            0 => max_len,
+            len => (len as usize).min(max_len),
+        };
+        // Palettes may be truncated when the pixel data offset overlaps them
+        let available = data_offset.saturating_sub(reader.pos()) / entry_size;
+        let palette = reader.take(len.min(available) * entry_size)?;
+        palette
+            .chunks_exact(entry_size)
+            .map(|bgr| [bgr[2], bgr[1], bgr[0]])
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let top_down = height < 0;
+    let width = u32::try_from(width).map_err(|_| anyhow!("invalid bmp width: {width}"))?;
+    let height = height.unsigned_abs();
+    if top_down && matches!(compression, BI_RLE8 | BI_RLE4) {
+        return Err(anyhow!("invalid top-down run-length encoded bmp"));
+    }
+
+    let format = if alpha == 0 {
+        PixelFormat::Rgb
+    } else {
+        PixelFormat::Rgba
+    };
+    let mut data = super::pixel_buffer(width, height, format)?;
+    let channels = format.channels();
+    let row_len = width as usize * channels;
+    let color = |index: u8| palette.get(index as usize).copied().unwrap_or_default();
+
+    reader.seek(data_offset)?;
+    if matches!(compression, BI_RLE8 | BI_RLE4) {
+        decode_rle(&mut reader, bpp, width, height, |x, y, index| {
+            let row = (height - 1 - y) as usize;
+            let idx = row * row_len + x as usize * channels;
+            data[idx..idx + 3].copy_from_slice(&color(index));
+        })?;
+        return Ok(Image::from_vec(width, height, data, format));
+    }
+
+    let stride = (bpp as usize * width as usize + 31) / 32 * 4;
+    let channels_masks = [red, green, blue, alpha].map(Channel::new);
+    for y in 0..height as usize {
+        let src = reader.take(stride)?;
+        let row = if top_down { y } else { height as usize - 1 - y };
+        let dst = &mut data[row * row_len..(row + 1) * row_len];
+        for (x, pixel) in dst.chunks_exact_mut(channels).enumerate() {
+            match bpp {
+                1 | 4 | 8 => {
+                    let bit = x * bpp as usize;
+                    let shift = 8 - bpp as usize - bit % 8;
+                    let index = (src[bit / 8] >> shift) & (0xFF >> (8 - bpp));
+                    pixel.copy_from_slice(&color(index));
+                }
+                24 => pixel.copy_from_slice(&[src[x * 3 + 2], src[x * 3 + 1], src[x * 3]]),
+                _ => {
+                    let value = if bpp == 16 {
+                        u32::from(u16::from_le_bytes([src[x * 2], src[x * 2 + 1]]))
+                    } else {
+                        let i = x * 4;
+                        u32::from_le_bytes([src[i], src[i + 1], src[i + 2], src[i + 3]])
+                    };
+                    for (channel, mask) in pixel.iter_mut().zip(channels_masks) {
+                        *channel = mask.get(value);
+                    }
+                }
+            }
+        }
+    }
+    Ok(Image::from_vec(width, height, data, format))
+}
+
+/// Decode run-length encoded palette indexes, calling `plot` with the `(x, y)` position of each
+/// pixel from the bottom-left.
+fn decode_rle<F>(
+    reader: &mut Reader<'_>,
+    bpp: u32,
+    width: u32,
+    height: u32,
+    mut plot: F,
+) -> Result<()>
+where
+    F: FnMut(u32, u32, u8),
+{
+    let (mut x, mut y) = (0, 0);
+    let mut put = |x: &mut u32, y: u32, index: u8| {
+        if *x < width && y < height {
+            plot(*x, y, index);
+        }
+        *x += 1;
+    };
+    while y < height {
+        let count = reader.u8()?;
+        let value = reader.u8()?;
+        if count > 0 {
+            // Encoded run, alternating nibbles for 4-bit indexes
+            for i in 0..count {
+                let index = if bpp == 4 {
+                    if i % 2 == 0 {
+                        value >> 4
+                    } else {
+                        value & 0x0F
+                    }
+                } else {
+                    value
+                };
+                put(&mut x, y, index);
+            }
+            continue;
+        }
+        match value {
+            0 => {
+                x = 0;
+                y += 1;
+            }
+            1 => break,
+            2 => {
+                x += u32::from(reader.u8()?);
+                y += u32::from(reader.u8()?);
+            }
+            count => {
+                // Absolute run, padded to a 16-bit boundary
+                let len = if bpp == 4 {
+                    (usize::from(count) + 1) / 2
+                } else {
+                    usize::from(count)
+                };
+                let indexes = reader.take(len)?;
+                for i in 0..usize::from(count) {
+                    let index = if bpp == 4 {
+                        (indexes[i / 2] >> (4 * (1 - i % 2))) & 0x0F
+                    } else {
+                        indexes[i]
+                    };
+                    put(&mut x, y, index);
+                }
+                reader.skip(len % 2)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Encode an [Image] as `BMP` to `writer`.
+pub(super) fn encode<W: io::Write>(image: &Image, mut writer: W) -> Result<()> {
+    let (width, height) = image.dimensions();
+    let has_alpha = image.format().has_alpha();
+    let (header_size, bpp) = if has_alpha {
+        (V4_HEADER_SIZE, 32)
+    } else {
+        (INFO_HEADER_SIZE, 24)
+    };
+    let stride = (bpp * width as usize + 31) / 32 * 4;
+    let data_size = u32::try_from(stride * height as usize)
+        .map_err(|_| anyhow!("image too large for bmp: {width}x{height}"))?;
+    let data_offset = FILE_HEADER_SIZE + header_size;
+    let width = i32::try_from(width).map_err(|_| anyhow!("invalid bmp width: {width}"))?;
+    let height = i32::try_from(height).map_err(|_| anyhow!("invalid bmp height: {height}"))?;
+
+    let mut header = Vec::with_capacity(data_offset as usize);
+    header.extend_from_slice(b"BM");
+    header.extend_from_slice(&(data_offset + data_size).to_le_bytes());
+    header.extend_from_slice(&[0x00; 4]);
+    header.extend_from_slice(&data_offset.to_le_bytes());
+    header.extend_from_slice(&header_size.to_le_bytes());
+    header.extend_from_slice(&width.to_le_bytes());
+    header.extend_from_slice(&height.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes());
+    header.extend_from_slice(&(bpp as u16).to_le_bytes());
+    let compression = if has_alpha { BI_BITFIELDS } else { BI_RGB };
+    header.extend_from_slice(&compression.to_le_bytes());
+    header.extend_from_slice(&data_size.to_le_bytes());
+    // 72 DPI
+    header.extend_from_slice(&2835i32.to_le_bytes());
+    header.extend_from_slice(&2835i32.to_le_bytes());
+    header.extend_from_slice(&[0x00; 8]);
+    if has_alpha {
+        for mask in [0x00FF_0000u32, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000] {
+            header.extend_from_slice(&mask.to_le_bytes());
+        }
+        // sRGB color space, with unused endpoints and gamma
+        header.extend_from_slice(b"BGRs");
+        header.extend_from_slice(&[0x00; 48]);
+    }
+    writer.write_all(&header)?;
+
+    let pixels: Vec<_> = super::rgba_pixels(image).collect();
+    let mut row = vec![0x00; stride];
+    for src in pixels.chunks_exact(image.width().max(1) as usize).rev() {
+        for (dst, &[r, g, b, a]) in row.chunks_exact_mut(bpp / 8).zip(src) {
+            dst[..3].copy_from_slice(&[b, g, r]);
+            if has_alpha {
+                dst[3] = a;
+            }
+        }
+        writer.write_all(&row)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,610 @@
+//! [GIF](https://www.w3.org/Graphics/GIF/spec-gif89a.txt) encoding and decoding.
+//!
+//! Decoding an [Image] returns the first frame composited onto a transparent canvas the size of the
+//! logical screen, or every frame when decoding an animation. Encoding quantizes images with more
+//! than 256 colors using median cut, and pixels with an alpha below `128` are encoded as
+//! transparent. Animation frames are each encoded with their own palette.
+
+use super::{RawAnimation, RawFrame, Reader};
+use crate::{error::Result, prelude::*};
+use anyhow::anyhow;
+use std::{cmp::Ordering, collections::HashMap, io, time::Duration};
+
+/// Extension block introducer.
+const EXTENSION: u8 = 0x21;
+/// Image descriptor introducer.
+const IMAGE: u8 = 0x2C;
+/// End of the data stream.
+const TRAILER: u8 = 0x3B;
+/// Graphic control extension label.
+const GRAPHIC_CONTROL: u8 = 0xF9;
+/// Application extension label.
+const APPLICATION: u8 = 0xFF;
+/// Application identifier and authentication code of the looping extension.
+const LOOPING: &[u8] = b"NETSCAPE2.0";
+/// Minimum frame delay in hundredths of a second that's displayed as written.
+const MIN_DELAY: u128 = 2;
+/// Maximum number of `LZW` codes.
+const MAX_CODES: usize = 4096;
+/// Maximum `LZW` code size in bits.
+const MAX_CODE_SIZE: u32 = 12;
+
+/// An `RGB` palette color.
+type Rgb = [u8; 3];
+
+/// Decode `GIF` bytes into an [Image].
+pub(super) fn decode(bytes: &[u8]) -> Result<Image> {
+    let mut decoder = Decoder::new(bytes)?;
+    let frame = decoder
+        .next_frame()?
+        .ok_or_else(|| anyhow!("gif contains no image data"))?;
+    let mut image = Image::rgba(decoder.width, decoder.height);
+    let position = [frame.x as i32, frame.y as i32];
+    image.blit(&frame.image, None, position, BlendMode::Blend);
+    Ok(image)
+}
+
+/// Decode every frame of an animated `GIF`.
+pub(super) fn decode_animation(bytes: &[u8]) -> Result<RawAnimation> {
+    let mut decoder = Decoder::new(bytes)?;
+    let mut frames = Vec::new();
+    while let Some(frame) = decoder.next_frame()? {
+        frames.push(frame);
+        super::check_animation_size(decoder.width, decoder.height, frames.len())?;
+    }
+    if frames.is_empty() {
+        return Err(anyhow!("gif contains no image data"));
+    }
+    Ok(RawAnimation {
+        width: decoder.width,
+        height: decoder.height,
+        frames,
+        plays: decoder.plays,
+    })
+}
+
+/// A `GIF` decoder that reads one frame at a time.
+#[derive(Debug)]
+struct Decoder<'a> {
+    reader: Reader<'a>,
+    width: u32,
+    height: u32,
+    global_palette: Vec<Rgb>,
+    /// Number of times the animation plays, where `0` repeats forever.
+    plays: u32,
+}
+
+impl<'a> Decoder<'a> {
+    /// Read the `GIF` header and global color table.
+    fn new(bytes: &'a [u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes, "gif");
+        if !matches!(reader.take(6)?, b"GIF87a" | b"GIF89a") {
+            return Err(anyhow!("invalid gif signature"));
+        }
+        let width = u32::from(reader.u16_le()?);
+        let height = u32::from(reader.u16_le()?);
+        // Every frame is composited onto a canvas of the logical screen size
+        super::pixel_count(width, height)?;
+        let flags = reader.u8()?;
+        reader.skip(2)?;
+        let global_palette = read_palette(&mut reader, flags)?;
+        Ok(Self {
+            reader,
+            width,
+            height,
+            global_palette,
+            // Without a looping extension, animations play once
+            plays: 1,
+        })
+    }
+
+    /// Read the next frame, or `None` at the end of the data stream.
+    fn next_frame(&mut self) -> Result<Option<RawFrame>> {
+        let reader = &mut self.reader;
+        let mut control = [0x00; 4];
+        loop {
+            match reader.u8()? {
+                EXTENSION => {
+                    let label = reader.u8()?;
+                    let data = read_sub_blocks(reader)?;
+                    match (label, data.as_slice()) {
+                        (GRAPHIC_CONTROL, [flags, delay_lo, delay_hi, transparent, ..]) => {
+                            control = [*flags, *delay_lo, *delay_hi, *transparent];
+                        }
+                        (APPLICATION, [id @ .., 0x01, lo, hi]) if id == LOOPING => {
+                            let repeats = u32::from(u16::from_le_bytes([*lo, *hi]));
+                            self.plays = if repeats == 0 { 0 } else { repeats + 1 };
+                        }
+                        _ => (),
+                    }
+                }
+                IMAGE => break,
+                TRAILER => return Ok(None),
+                block => return Err(anyhow!("invalid gif block: {block:#04X}")),
+            }
+        }
+
+        let x = u32::from(reader.u16_le()?);
+        let y = u32::from(reader.u16_le()?);
+        let width = u32::from(reader.u16_le()?);
+        let height = u32::from(reader.u16_le()?);
+        let flags = reader.u8()?;
+        let local_palette = read_palette(reader, flags)?;
+        let palette = if flags & 0x80 == 0 {
+            &self.global_palette
+        } else {
+            &local_palette
+        };
+        let min_code_size = reader.u8()?;
+        let format = PixelFormat::Rgba;
+        let mut data = super::pixel_buffer(width, height, format)?;
+        let indexes = decode_lzw(
+            &read_sub_blocks(reader)?,
+            min_code_size,
+            super::pixel_count(width, height)?,
+        )?;
+
+        let [control_flags, delay_lo, delay_hi, transparent] = control;
+        let transparent = (control_flags & 0x01 != 0).then_some(transparent);
+        let rows = interlaced_rows(height, flags & 0x40 != 0);
+        for (row, y) in indexes.chunks(width.max(1) as usize).zip(rows) {
+            for (x, &index) in row.iter().enumerate() {
+                if Some(index) == transparent {
+                    continue;
+                }
+                let [r, g, b] = palette.get(usize::from(index)).copied().unwrap_or_default();
+                let idx = (y as usize * width as usize + x) * format.channels();
+                data[idx..idx + 4].copy_from_slice(&[r, g, b, 255]);
+            }
+        }
+
+        // Browsers display frames with delays of 10ms or less for 100ms
+        let delay = match u16::from_le_bytes([delay_lo, delay_hi]) {
+            0 | 1 => 10,
+            delay => u64::from(delay),
+        };
+        Ok(Some(RawFrame {
+            image: Image::from_vec(width, height, data, format),
+            x,
+            y,
+            delay: Duration::from_millis(10 * delay),
+            disposal: match (control_flags >> 2) & 0x07 {
+                2 => Disposal::Background,
+                3 => Disposal::Previous,
+                _ => Disposal::None,
+            },
+            blend_mode: BlendMode::Blend,
+        }))
+    }
+}
+
+/// Read a global or local color table, if present based on `flags`.
+fn read_palette(reader: &mut Reader<'_>, flags: u8) -> Result<Vec<Rgb>> {
+    if flags & 0x80 == 0 {
+        return Ok(Vec::new());
+    }
+    let len = 2 << (flags & 0x07);
+    Ok(reader
+        .take(3 * len)?
+        .chunks_exact(3)
+        .map(|rgb| [rgb[0], rgb[1], rgb[2]])
+        .collect())
+}
+
+/// Read and concatenate data sub-blocks up to the block terminator.
+fn read_sub_blocks(reader: &mut Reader<'_>) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    loop {
+        let len = reader.u8()?;
+        if len == 0 {
+            return Ok(data);
+        }
+        data.extend_from_slice(reader.take(len.into())?);
+    }
+}
+
+/// Returns the order of rows in a frame of `height` rows.
+fn interlaced_rows(height: u32, interlaced: bool) -> Vec<u32> {
+    if interlaced {
+        [(0, 8), (4, 8), (2, 4), (1, 2)]
+            .into_iter()
+            .flat_map(|(start, step)| (start..height).step_by(step))
+            .collect()
+    } else {
+        (0..height).collect()
+    }
+}
+
+/// Decode `LZW` compressed palette indexes, returning up to `len` indexes.
+fn decode_lzw(data: &[u8], min_code_size: u8, len: usize) -> Result<Vec<u8>> {
+    if !(1..=11).contains(&min_code_size) {
+        return Err(anyhow!("invalid gif lzw code size: {min_code_size}"));
+    }
+    let clear = 1 << min_code_size;
+    let end = clear + 1;
+
+    // Each code is a prefix code followed by a suffix index
+    let mut prefixes = [0u16; MAX_CODES];
+    let mut suffixes = [0u8; MAX_CODES];
+    let mut lengths = [0u16; MAX_CODES];
+    for code in 0..clear {
+        suffixes[code] = code as u8;
+        lengths[code] = 1;
+    }
+
+    let mut indexes = Vec::with_capacity(len);
+    let mut code_size = u32::from(min_code_size) + 1;
+    let mut next = end + 1;
+    let mut prev: Option<usize> = None;
+    let (mut bits, mut bit_count) = (0u32, 0);
+    let mut bytes = data.iter();
+    while indexes.len() < len {
+        while bit_count < code_size {
+            let Some(&byte) = bytes.next() else {
+                return Ok(indexes);
+            };
+            bits |= u32::from(byte) << bit_count;
+            bit_count += 8;
+        }
+        let code = (bits & ((1 << code_size) - 1)) as usize;
+        bits >>= code_size;
+        bit_count -= code_size;
+
+        if code == clear {
+            code_size = u32::from(min_code_size) + 1;
+            next = end + 1;
+            prev = None;
+            continue;
+        } else if code == end {
+            break;
+        }
+
+        let Some(prev_code) = prev else {
+            if code > clear {
+                return Err(anyhow!("invalid gif lzw code: {code}"));
+            }
+            indexes.push(code as u8);
+            prev = Some(code);
+            continue;
+        };
+        // A code not yet in the table repeats the previous code plus its first index
+        let repeat = code == next;
+        let output = match code.cmp(&next) {
+            Ordering::Less => code,
+            Ordering::Equal => prev_code,
+            Ordering::Greater => return Err(anyhow!("invalid gif lzw code: {code}")),
+        };
+        let first = first_index(&prefixes, &lengths, &suffixes, output);
+        if next < MAX_CODES {
+            prefixes[next] = prev_code as u16;
+            suffixes[next] = first;
+            lengths[next] = lengths[prev_code] + 1;
+            next += 1;
+            if next == 1 << code_size && code_size < MAX_CODE_SIZE {
+                code_size += 1;
+            }
+        }
+
+        // Walk the prefix chain backwards to emit the indexes for the code
+        let start = indexes.len();
+        indexes.resize(start + usize::from(lengths[output]), 0);
+        let mut current = output;
+        for index in indexes[start..].iter_mut().rev() {
+            *index = suffixes[current];
+            current = usize::from(prefixes[current]);
+        }
+        if repeat {
+            indexes.push(first);
+        }
+        prev = Some(code);
+    }
+    indexes.truncate(len);
+    Ok(indexes)
+}
+
+/// Returns the first index emitted by an `LZW` code.
+fn first_index(prefixes: &[u16], lengths: &[u16], suffixes: &[u8], mut code: usize) -> u8 {
+    for _ in 1..lengths[code] {
+        code = usize::from(prefixes[code]);
+    }
+    suffixes[code]
+}
+
+/// Encode an [Image] as `GIF` to `writer`.
+pub(super) fn encode<W: io::Write>(image: &Image, mut writer: W) -> Result<()> {
+    let (width, height) = screen_size(image.dimensions())?;
+    let (palette, indexes, transparent) = quantize(image);
+    let bits = palette_bits(palette.len());
+
+    let mut data = Vec::new();
+    write_screen(
+        &mut data,
+        width,
+        height,
+        0x80 | ((bits - 1) << 4) | (bits - 1),
+    );
+    write_palette(&mut data, &palette, bits);
+    if let Some(transparent) = transparent {
+        data.extend_from_slice(&[EXTENSION, GRAPHIC_CONTROL, 4, 0x01, 0, 0, transparent, 0]);
+    }
+    write_image(&mut data, (width, height), None, &indexes, bits);
+    data.push(TRAILER);
+    writer.write_all(&data)?;
+    Ok(())
+}
+
+/// Encode an [`AnimatedImage`] as `GIF` to `writer`. Every frame covers the full canvas and is
+/// cleared before the next frame is drawn. Delays are rounded to hundredths of a second, carrying
+/// the rounding error over to following frames, with a minimum of [`MIN_DELAY`] as most decoders
+/// display shorter delays as `10`.
+pub(super) fn encode_animation<W: io::Write>(
+    animation: &AnimatedImage,
+    mut writer: W,
+) -> Result<()> {
+    let (width, height) = screen_size(animation.dimensions())?;
+    let mut data = Vec::new();
+    write_screen(&mut data, width, height, 0);
+    if animation.plays() != 1 {
+        let repeats = animation.plays().saturating_sub(1).min(u32::from(u16::MAX)) as u16;
+        data.extend_from_slice(&[EXTENSION, APPLICATION, LOOPING.len() as u8]);
+        data.extend_from_slice(LOOPING);
+        data.extend_from_slice(&[3, 0x01]);
+        data.extend_from_slice(&repeats.to_le_bytes());
+        data.push(0);
+    }
+
+    let centis = |time: Duration| (time.as_millis() + 5) / 10;
+    let mut elapsed = Duration::ZERO;
+    let mut written = 0;
+    for frame in animation.frames_in_cycle() {
+        let (palette, indexes, transparent) = quantize(frame.image());
+        elapsed += frame.delay();
+        let delay = centis(elapsed)
+            .saturating_sub(written)
+            .clamp(MIN_DELAY, u128::from(u16::MAX));
+        written += delay;
+        let delay = delay as u16;
+        // Restore to background, with an optional transparent index
+        let flags = (2 << 2) | u8::from(transparent.is_some());
+        data.extend_from_slice(&[EXTENSION, GRAPHIC_CONTROL, 4, flags]);
+        data.extend_from_slice(&delay.to_le_bytes());
+        data.extend_from_slice(&[transparent.unwrap_or_default(), 0]);
+        let bits = palette_bits(palette.len());
+        write_image(&mut data, (width, height), Some(&palette), &indexes, bits);
+    }
+    data.push(TRAILER);
+    writer.write_all(&data)?;
+    Ok(())
+}
+
+/// Returns image dimensions as a `GIF` logical screen size.
+fn screen_size((width, height): (u32, u32)) -> Result<(u16, u16)> {
+    let too_large = || anyhow!("image too large for gif: {width}x{height}");
+    let width = u16::try_from(width).map_err(|_| too_large())?;
+    let height = u16::try_from(height).map_err(|_| too_large())?;
+    Ok((width, height))
+}
+
+/// Write the `GIF` header and logical screen descriptor.
+fn write_screen(data: &mut Vec<u8>, width: u16, height: u16, flags: u8) {
+    data.extend_from_slice(b"GIF89a");
+    data.extend_from_slice(&width.to_le_bytes());
+    data.extend_from_slice(&height.to_le_bytes());
+    data.extend_from_slice(&[flags, 0, 0]);
+}
+
+/// Write a palette, padded to `2^bits` colors.
+fn write_palette(data: &mut Vec<u8>, palette: &[Rgb], bits: u8) {
+    for i in 0..1 << bits {
+        data.extend_from_slice(&palette.get(i).copied().unwrap_or_default());
+    }
+}
+
+/// Write an image descriptor covering the logical screen, followed by an optional local palette
+/// and the `LZW` compressed palette indexes, where `bits` is the palette size in bits.
+fn write_image(
+    data: &mut Vec<u8>,
+    (width, height): (u16, u16),
+    local_palette: Option<&[Rgb]>,
+    indexes: &[u8],
+    bits: u8,
+) {
+    data.push(IMAGE);
+    data.extend_from_slice(&[0; 4]);
+    data.extend_from_slice(&width.to_le_bytes());
+    data.extend_from_slice(&height.to_le_bytes());
+    match local_palette {
+        Some(palette) => {
+            data.push(0x80 | (bits - 1));
+            write_palette(data, palette, bits);
+        }
+        None => data.push(0),
+    }
+
+    let min_code_size = bits.max(2);
+    data.push(min_code_size);
+    for block in encode_lzw(indexes, min_code_size).chunks(255) {
+        data.push(block.len() as u8);
+        data.extend_from_slice(block);
+    }
+    data.push(0);
+}
+
+/// Returns the number of bits needed to index a palette of `len` colors, at least `1`.
+fn palette_bits(len: usize) -> u8 {
+    let mut bits = 1;
+    while 1 << bits < len {
+        bits += 1;
+    }
+    bits
+}
+
+/// Encode palette indexes using `LZW` compression.
+fn encode_lzw(indexes: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut writer = BitWriter::default();
+    let mut code_size = u32::from(min_code_size) + 1;
+    let mut codes: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    writer.write(clear, code_size);
+    if let Some((&first, rest)) = indexes.split_first() {
+        let mut prefix = u16::from(first);
+        for &index in rest {
+            if let Some(&code) = codes.get(&(prefix, index)) {
+                prefix = code;
+                continue;
+            }
+            writer.write(prefix, code_size);
+            if usize::from(next) < MAX_CODES {
+                codes.insert((prefix, index), next);
+                next += 1;
+                if u32::from(next) > 1 << code_size && code_size < MAX_CODE_SIZE {
+                    code_size += 1;
+                }
+            } else {
+                writer.write(clear, code_size);
+                codes.clear();
+                next = end + 1;
+                code_size = u32::from(min_code_size) + 1;
+            }
+            prefix = u16::from(index);
+        }
+        writer.write(prefix, code_size);
+    }
+    writer.write(end, code_size);
+    writer.finish()
+}
+
+/// Packs variable length codes into bytes, least significant bit first.
+#[derive(Default, Debug)]
+struct BitWriter {
+    data: Vec<u8>,
+    bits: u32,
+    bit_count: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, code_size: u32) {
+        self.bits |= u32::from(code) << self.bit_count;
+        self.bit_count += code_size;
+        while self.bit_count >= 8 {
+            self.data.push(self.bits as u8);
+            self.bits >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    /// Flush any remaining bits, returning the packed bytes.
+    fn finish(mut self) -> Vec<u8> {
+        if self.bit_count > 0 {
+            self.data.push(self.bits as u8);
+        }
+        self.data
+    }
+}
+
+/// Reduce an [Image] to a palette of at most 256 colors, returning the palette, the palette
+/// index of each pixel and the transparent index, if any pixels are transparent.
+fn quantize(image: &Image) -> (Vec<Rgb>, Vec<u8>, Option<u8>) {
+    let pixels: Vec<_> = super::rgba_pixels(image).collect();
+    let has_transparency = pixels.iter().any(|&[_, _, _, a]| a < 128);
+    let mut counts: HashMap<Rgb, u32> = HashMap::new();
+    for &[r, g, b, a] in &pixels {
+        if a >= 128 {
+            *counts.entry([r, g, b]).or_default() += 1;
+        }
+    }
+    let mut colors: Vec<_> = counts.into_iter().collect();
+    colors.sort_unstable();
+
+    let max_colors = 256 - usize::from(has_transparency);
+    let mut palette = if colors.len() <= max_colors {
+        colors.into_iter().map(|(color, _)| color).collect()
+    } else {
+        median_cut(colors, max_colors)
+    };
+    let transparent = has_transparency.then(|| {
+        palette.push([0; 3]);
+        (palette.len() - 1) as u8
+    });
+
+    let mut nearest: HashMap<Rgb, u8> = HashMap::new();
+    let indexes = pixels
+        .iter()
+        .map(|&[r, g, b, a]| match transparent {
+            Some(transparent) if a < 128 => transparent,
+            _ => *nearest.entry([r, g, b]).or_insert_with(|| {
+                let distance = |&[pr, pg, pb]: &Rgb| {
+                    let d = |c1: u8, c2: u8| u32::from(c1.abs_diff(c2)).pow(2);
+                    d(r, pr) + d(g, pg) + d(b, pb)
+                };
+                let colors = &palette[..palette.len() - usize::from(has_transparency)];
+                (0..colors.len())
+                    .min_by_key(|&i| distance(&colors[i]))
+                    .unwrap_or_default() as u8
+            }),
+        })
+        .collect();
+    (palette, indexes, transparent)
+}
+
+/// Reduce weighted colors to at most `max_colors` using median cut.
+fn median_cut(colors: Vec<(Rgb, u32)>, max_colors: usize) -> Vec<Rgb> {
+    let range = |colors: &[(Rgb, u32)], channel: usize| {
+        let (min, max) = colors.iter().fold((255, 0), |(min, max), (color, _)| {
+            (color[channel].min(min), color[channel].max(max))
+        });
+        max - min
+    };
+    let widest = |colors: &[(Rgb, u32)]| {
+        (0..3)
+            .map(|channel| (range(colors, channel), channel))
+            .max()
+            .unwrap_or_default()
+    };
+
+    let mut boxes = vec![colors];
+    while boxes.len() < max_colors {
+        let Some((i, channel)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, colors)| colors.len() > 1)
+            .map(|(i, colors)| (widest(colors), i))
+            .max()
+            .map(|((_, channel), i)| (i, channel))
+        else {
+            break;
+        };
+        let mut colors = boxes.swap_remove(i);
+        colors.sort_unstable_by_key(|(color, _)| color[channel]);
+        let total: u64 = colors.iter().map(|&(_, count)| u64::from(count)).sum();
+        let mut sum = 0;
+        let median = colors
+            .iter()
+            .position(|&(_, count)| {
+                sum += u64::from(count);
+                sum * 2 >= total
+            })
+            .unwrap_or_default();
+        let split = (median + 1).clamp(1, colors.len() - 1);
+        let upper = colors.split_off(split);
+        boxes.push(colors);
+        boxes.push(upper);
+    }
+
+    boxes
+        .iter()
+        .map(|colors| {
+            let total: u64 = colors.iter().map(|&(_, count)| u64::from(count)).sum();
+            let average = |channel: usize| {
+                let sum: u64 = colors
+                    .iter()
+                    .map(|&(color, count)| u64::from(color[channel]) * u64::from(count))
+                    .sum();
+                ((sum + total / 2) / total.max(1)) as u8
+            };
+            [average(0), average(1), average(2)]
+        })
+        .collect()
+}
@@ -0,0 +1,153 @@
+//! [PNG](https://www.w3.org/TR/png/) encoding and decoding using the [`png`](::png) crate.
+
+use super::{RawAnimation, RawFrame};
+use crate::{error::Result, prelude::*};
+use ::png::{BitDepth, BlendOp, ColorType, Decoder, DisposeOp, Encoder, Reader, Transformations};
+use anyhow::{anyhow, Context};
+use std::{
+    io::{self, Cursor},
+    time::Duration,
+};
+
+/// Decode `PNG` bytes into an [Image].
+///
+/// Paletted images are expanded to `RGB`, or `RGBA` with a transparency chunk, low bit-depth
+/// grayscale is expanded to 8 bits and 16-bit channels are reduced to 8 bits. Interlaced images are
+/// de-interlaced.
+pub(super) fn decode(bytes: &[u8]) -> Result<Image> {
+    let mut reader = read_info(bytes)?;
+    let mut buf = vec![0x00; reader.output_buffer_size().unwrap_or_default()];
+    read_frame(&mut reader, &mut buf)
+}
+
+/// Decode every frame of an animated `PNG` (`APNG`). Images without animation decode as a single
+/// frame that plays forever.
+pub(super) fn decode_animation(bytes: &[u8]) -> Result<RawAnimation> {
+    let mut reader = read_info(bytes)?;
+    let (width, height) = (reader.info().width, reader.info().height);
+    let mut buf = vec![0x00; reader.output_buffer_size().unwrap_or_default()];
+    let Some(animation) = reader.info().animation_control().copied() else {
+        let image = read_frame(&mut reader, &mut buf)?;
+        return Ok(RawAnimation {
+            width,
+            height,
+            frames: vec![RawFrame {
+                image,
+                x: 0,
+                y: 0,
+                delay: Duration::ZERO,
+                disposal: Disposal::None,
+                blend_mode: BlendMode::None,
+            }],
+            plays: 0,
+        });
+    };
+
+    super::check_animation_size(width, height, animation.num_frames as usize)?;
+
+    // The default image is only part of the animation if a frame control precedes it
+    if reader.info().frame_control().is_none() {
+        reader
+            .next_frame(&mut buf)
+            .context("failed to read png default image")?;
+    }
+    let mut frames = Vec::new();
+    for _ in 0..animation.num_frames {
+        let image = read_frame(&mut reader, &mut buf)?;
+        let control = reader
+            .info()
+            .frame_control()
+            .copied()
+            .ok_or_else(|| anyhow!("missing png frame control"))?;
+        let delay_den = match control.delay_den {
+            0 => 100,
+            den => u64::from(den),
+        };
+        frames.push(RawFrame {
+            image,
+            x: control.x_offset,
+            y: control.y_offset,
+            delay: Duration::from_nanos(u64::from(control.delay_num) * 1_000_000_000 / delay_den),
+            disposal: match control.dispose_op {
+                DisposeOp::None => Disposal::None,
+                DisposeOp::Background => Disposal::Background,
+                DisposeOp::Previous => Disposal::Previous,
+            },
+            blend_mode: match control.blend_op {
+                BlendOp::Source => BlendMode::None,
+                BlendOp::Over => BlendMode::Blend,
+            },
+        });
+    }
+    if frames.is_empty() {
+        return Err(anyhow!("png animation contains no frames"));
+    }
+    Ok(RawAnimation {
+        width,
+        height,
+        frames,
+        plays: animation.num_plays,
+    })
+}
+
+/// Read the `PNG` header, normalizing output to 8 bits per channel.
+fn read_info(bytes: &[u8]) -> Result<Reader<Cursor<&[u8]>>> {
+    let mut png = Decoder::new(Cursor::new(bytes));
+    png.set_transformations(Transformations::normalize_to_color8());
+    png.read_info().context("failed to read png data")
+}
+
+/// Read the next frame into an [Image] using `buf` as scratch space.
+fn read_frame(reader: &mut Reader<Cursor<&[u8]>>, buf: &mut [u8]) -> Result<Image> {
+    let info = reader
+        .next_frame(buf)
+        .context("failed to read png data frame")?;
+    let (color_type, bit_depth) = (info.color_type, info.bit_depth);
+    if bit_depth != BitDepth::Eight {
+        return Err(super::unsupported(bit_depth, color_type));
+    }
+    let format = color_type
+        .try_into()
+        .map_err(|_| super::unsupported(bit_depth, color_type))?;
+    let data = buf[..info.buffer_size()].to_vec();
+    Ok(Image::from_vec(info.width, info.height, data, format))
+}
+
+/// Encode an [Image] as `PNG` to `writer`.
+pub(super) fn encode<W: io::Write>(image: &Image, writer: W) -> Result<()> {
+    let mut png = Encoder::new(writer, image.width(), image.height());
+    png.set_color(image.format().into());
+    png.set_depth(BitDepth::Eight);
+    let mut writer = png.write_header().context("failed to write png header")?;
+    writer
+        .write_image_data(image.as_bytes())
+        .context("failed to write png data")
+}
+
+/// Encode an [`AnimatedImage`] as an animated `PNG` (`APNG`) to `writer`. Every frame covers the
+/// full canvas, replacing the previous frame.
+pub(super) fn encode_animation<W: io::Write>(animation: &AnimatedImage, writer: W) -> Result<()> {
+    let frames: Vec<_> = animation.frames_in_cycle().collect();
+    let mut png = Encoder::new(writer, animation.width(), animation.height());
+    png.set_color(ColorType::Rgba);
+    png.set_depth(BitDepth::Eight);
+    png.set_animated(frames.len() as u32, animation.plays())
+        .context("failed to write png animation control")?;
+    let mut writer = png.write_header().context("failed to write png header")?;
+    for frame in frames {
+        let (delay_num, delay_den) = delay_fraction(frame.delay());
+        writer.set_frame_delay(delay_num, delay_den)?;
+        writer
+            .write_image_data(frame.image().as_bytes())
+            .context("failed to write png frame data")?;
+    }
+    writer.finish().context("failed to finish png animation")
+}
+
+/// Returns a frame delay as a fraction of a second, in milliseconds where possible.
+fn delay_fraction(delay: Duration) -> (u16, u16) {
+    match u16::try_from(delay.as_millis()) {
+        Ok(millis) => (millis, 1000),
+        Err(_) => (u16::try_from(delay.as_secs()).unwrap_or(u16::MAX), 1),
+    }
+}
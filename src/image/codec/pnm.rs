@@ -0,0 +1,175 @@
+//! [Netpbm](https://netpbm.sourceforge.net/doc/) `PBM`, `PGM` and `PPM` encoding and decoding.
+//!
+//! Decoding supports both plain (`ASCII`) and raw (binary) variants with any maximum value.
+//! Bitmap and graymap images are decoded as [`PixelFormat::Gray`]. Images are encoded in the raw
+//! variant of the requested format, discarding alpha.
+
+use crate::{error::Result, image::luma, prelude::*};
+use anyhow::anyhow;
+use std::io;
+
+/// A tokenizer over `netpbm` header and plain raster data.
+#[derive(Debug)]
+struct Tokens<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Tokens<'a> {
+    /// Skip whitespace and `#` comments.
+    fn skip_whitespace(&mut self) {
+        while let Some(&byte) = self.bytes.get(self.pos) {
+            if byte == b'#' {
+                while self.bytes.get(self.pos).is_some_and(|&byte| byte != b'\n') {
+                    self.pos += 1;
+                }
+            } else if byte.is_ascii_whitespace() {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Returns the next non-whitespace byte.
+    fn byte(&mut self) -> Result<u8> {
+        self.skip_whitespace();
+        let byte = *self.bytes.get(self.pos).ok_or_else(eof)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    /// Returns the next decimal integer.
+    fn number(&mut self) -> Result<u32> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self.bytes.get(self.pos).is_some_and(u8::is_ascii_digit) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.bytes[start..self.pos])
+            .ok()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or_else(|| anyhow!("invalid pnm number at byte {start}"))
+    }
+
+    /// Returns the remaining raster bytes following the single whitespace after the header.
+    fn raster(&self) -> &'a [u8] {
+        self.bytes.get(self.pos + 1..).unwrap_or_default()
+    }
+}
+
+/// Returns an error indicating the data ended early.
+fn eof() -> anyhow::Error {
+    anyhow!("unexpected end of pnm data")
+}
+
+/// Decode `PBM`, `PGM` or `PPM` bytes into an [Image].
+pub(super) fn decode(bytes: &[u8]) -> Result<Image> {
+    let mut tokens = Tokens { bytes, pos: 0 };
+    let kind = match bytes {
+        [b'P', kind @ b'1'..=b'6', ..] => kind - b'0',
+        _ => return Err(anyhow!("invalid pnm signature")),
+    };
+    tokens.pos = 2;
+    let width = tokens.number()?;
+    let height = tokens.number()?;
+    let max = if matches!(kind, 1 | 4) {
+        1
+    } else {
+        tokens.number()?
+    };
+    if !(1..=u32::from(u16::MAX)).contains(&max) {
+        return Err(anyhow!("invalid pnm maximum value: {max}"));
+    }
+
+    let format = if matches!(kind, 3 | 6) {
+        PixelFormat::Rgb
+    } else {
+        PixelFormat::Gray
+    };
+    let mut data = super::pixel_buffer(width, height, format)?;
+    let scale = |value: u32| -> Result<u8> {
+        if value > max {
+            return Err(anyhow!("invalid pnm sample {value} exceeds maximum {max}"));
+        }
+        Ok(((value * 255 + max / 2) / max) as u8)
+    };
+    let pixels = data.chunks_exact_mut(format.channels());
+
+    match kind {
+        1 => {
+            for pixel in pixels {
+                let value = match tokens.byte()? {
+                    b'0' => 255,
+                    b'1' => 0,
+                    byte => return Err(anyhow!("invalid pbm bit: {:?}", char::from(byte))),
+                };
+                pixel.fill(value);
+            }
+        }
+        4 => {
+            let stride = (width as usize + 7) / 8;
+            let raster = tokens.raster();
+            if raster.len() < stride * height as usize {
+                return Err(eof());
+            }
+            for (i, pixel) in pixels.enumerate() {
+                let (x, y) = (i % width as usize, i / width as usize);
+                let bit = raster[y * stride + x / 8] >> (7 - x % 8) & 1;
+                pixel.fill(if bit == 1 { 0 } else { 255 });
+            }
+        }
+        2 | 3 => {
+            for value in data.iter_mut() {
+                *value = scale(tokens.number()?)?;
+            }
+        }
+        _ => {
+            let sample_size = if max > 255 { 2 } else { 1 };
+            let raster = tokens.raster();
+            let raster = raster.get(..data.len() * sample_size).ok_or_else(eof)?;
+            for (value, bytes) in data.iter_mut().zip(raster.chunks_exact(sample_size)) {
+                let raw = match *bytes {
+                    [hi, lo] => u32::from(u16::from_be_bytes([hi, lo])),
+                    _ => u32::from(bytes[0]),
+                };
+                *value = scale(raw)?;
+            }
+        }
+    }
+    Ok(Image::from_vec(width, height, data, format))
+}
+
+/// Encode an [Image] as raw `PBM`, `PGM` or `PPM` to `writer`, based on `format`.
+pub(super) fn encode<W: io::Write>(
+    image: &Image,
+    format: ImageFormat,
+    mut writer: W,
+) -> Result<()> {
+    let (width, height) = image.dimensions();
+    let pixels = super::rgba_pixels(image);
+    match format {
+        ImageFormat::Pbm => {
+            write!(writer, "P4\n{width} {height}\n")?;
+            let stride = (width as usize + 7) / 8;
+            let mut data = vec![0x00; stride * height as usize];
+            for (i, pixel) in pixels.enumerate() {
+                let (x, y) = (i % width as usize, i / width as usize);
+                if luma(pixel) < 128 {
+                    data[y * stride + x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+            writer.write_all(&data)?;
+        }
+        ImageFormat::Pgm => {
+            write!(writer, "P5\n{width} {height}\n255\n")?;
+            writer.write_all(&pixels.map(luma).collect::<Vec<_>>())?;
+        }
+        _ => {
+            write!(writer, "P6\n{width} {height}\n255\n")?;
+            let data: Vec<_> = pixels.flat_map(|[r, g, b, _]| [r, g, b]).collect();
+            writer.write_all(&data)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,141 @@
+//! [QOI](https://qoiformat.org/qoi-specification.pdf) encoding and decoding.
+
+use super::Reader;
+use crate::{error::Result, prelude::*};
+use anyhow::anyhow;
+use std::io;
+
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+const OP_RUN: u8 = 0xC0;
+const OP_RGB: u8 = 0xFE;
+const OP_RGBA: u8 = 0xFF;
+const MASK: u8 = 0xC0;
+
+/// Bytes marking the end of the data stream.
+const END_MARKER: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+/// Returns the index of a color in the array of previously seen colors.
+const fn hash([r, g, b, a]: [u8; 4]) -> usize {
+    (r as usize * 3 + g as usize * 5 + b as usize * 7 + a as usize * 11) % 64
+}
+
+/// Decode `QOI` bytes into an [Image].
+pub(super) fn decode(bytes: &[u8]) -> Result<Image> {
+    let mut reader = Reader::new(bytes, "qoi");
+    if reader.take(4)? != b"qoif" {
+        return Err(anyhow!("invalid qoi signature"));
+    }
+    let width = reader.u32_be()?;
+    let height = reader.u32_be()?;
+    let format = match reader.u8()? {
+        3 => PixelFormat::Rgb,
+        4 => PixelFormat::Rgba,
+        channels => return Err(anyhow!("invalid qoi channels: {channels}")),
+    };
+    reader.skip(1)?;
+
+    let mut data = super::pixel_buffer(width, height, format)?;
+    let mut seen = [[0x00; 4]; 64];
+    let mut pixel = [0x00, 0x00, 0x00, 0xFF];
+    let mut run = 0;
+    for dst in data.chunks_exact_mut(format.channels()) {
+        if run > 0 {
+            run -= 1;
+        } else {
+            let op = reader.u8()?;
+            match op {
+                OP_RGB => pixel[..3].copy_from_slice(reader.take(3)?),
+                OP_RGBA => pixel.copy_from_slice(reader.take(4)?),
+                _ => match op & MASK {
+                    OP_INDEX => pixel = seen[usize::from(op)],
+                    OP_DIFF => {
+                        pixel[0] = pixel[0].wrapping_add((op >> 4) & 0x03).wrapping_sub(2);
+                        pixel[1] = pixel[1].wrapping_add((op >> 2) & 0x03).wrapping_sub(2);
+                        pixel[2] = pixel[2].wrapping_add(op & 0x03).wrapping_sub(2);
+                    }
+                    OP_LUMA => {
+                        let byte = reader.u8()?;
+                        let dg = (op & 0x3F).wrapping_sub(32);
+                        let dr = dg.wrapping_add(byte >> 4).wrapping_sub(8);
+                        let db = dg.wrapping_add(byte & 0x0F).wrapping_sub(8);
+                        pixel[0] = pixel[0].wrapping_add(dr);
+                        pixel[1] = pixel[1].wrapping_add(dg);
+                        pixel[2] = pixel[2].wrapping_add(db);
+                    }
+                    _ => run = op & 0x3F,
+                },
+            }
+            seen[hash(pixel)] = pixel;
+        }
+        dst.copy_from_slice(&pixel[..dst.len()]);
+    }
+    Ok(Image::from_vec(width, height, data, format))
+}
+
+/// Encode an [Image] as `QOI` to `writer`.
+pub(super) fn encode<W: io::Write>(image: &Image, mut writer: W) -> Result<()> {
+    let channels = if image.format().has_alpha() { 4 } else { 3 };
+    let size = image.width() as usize * image.height() as usize * usize::from(channels);
+    let mut data = Vec::with_capacity(14 + size + END_MARKER.len());
+    data.extend_from_slice(b"qoif");
+    data.extend_from_slice(&image.width().to_be_bytes());
+    data.extend_from_slice(&image.height().to_be_bytes());
+    // sRGB with linear alpha
+    data.extend_from_slice(&[channels, 0]);
+
+    let mut seen = [[0x00; 4]; 64];
+    let mut prev = [0x00, 0x00, 0x00, 0xFF];
+    let mut run = 0u8;
+    for pixel in super::rgba_pixels(image) {
+        if pixel == prev {
+            run += 1;
+            if run == 62 {
+                data.push(OP_RUN | (run - 1));
+                run = 0;
+            }
+            continue;
+        }
+        if run > 0 {
+            data.push(OP_RUN | (run - 1));
+            run = 0;
+        }
+
+        let index = hash(pixel);
+        if seen[index] == pixel {
+            data.push(OP_INDEX | index as u8);
+        } else {
+            seen[index] = pixel;
+            if pixel[3] == prev[3] {
+                let diff = |channel: usize| pixel[channel].wrapping_sub(prev[channel]) as i8;
+                let (dr, dg, db) = (diff(0), diff(1), diff(2));
+                let (dr_dg, db_dg) = (dr.wrapping_sub(dg), db.wrapping_sub(dg));
+                if (-2..=1).contains(&dr) && (-2..=1).contains(&dg) && (-2..=1).contains(&db) {
+                    data.push(
+                        OP_DIFF | ((dr + 2) as u8) << 4 | ((dg + 2) as u8) << 2 | (db + 2) as u8,
+                    );
+                } else if (-32..=31).contains(&dg)
+                    && (-8..=7).contains(&dr_dg)
+                    && (-8..=7).contains(&db_dg)
+                {
+                    data.push(OP_LUMA | (dg + 32) as u8);
+                    data.push(((dr_dg + 8) as u8) << 4 | (db_dg + 8) as u8);
+                } else {
+                    data.push(OP_RGB);
+                    data.extend_from_slice(&pixel[..3]);
+                }
+            } else {
+                data.push(OP_RGBA);
+                data.extend_from_slice(&pixel);
+            }
+        }
+        prev = pixel;
+    }
+    if run > 0 {
+        data.push(OP_RUN | (run - 1));
+    }
+    data.extend_from_slice(&END_MARKER);
+    writer.write_all(&data)?;
+    Ok(())
+}
@@ -0,0 +1,205 @@
+//! [TGA](https://en.wikipedia.org/wiki/Truevision_TGA) encoding and decoding.
+//!
+//! Decoding supports color-mapped, true-color and grayscale images, uncompressed or run-length
+//! encoded, in any origin. Images are encoded uncompressed as true-color or grayscale with a
+//! top-left origin and a `TGA 2.0` footer so they can be identified by content.
+
+use super::Reader;
+use crate::{error::Result, prelude::*};
+use ::png::ColorType;
+use anyhow::anyhow;
+use std::io;
+
+/// Signature at the end of a `TGA 2.0` footer.
+const SIGNATURE: &[u8] = b"TRUEVISION-XFILE.\0";
+
+/// Decode `TGA` bytes into an [Image].
+pub(super) fn decode(bytes: &[u8]) -> Result<Image> {
+    let mut reader = Reader::new(bytes, "tga");
+    let id_len = reader.u8()?;
+    let color_map_type = reader.u8()?;
+    let image_type = reader.u8()?;
+    let color_map_start = reader.u16_le()?;
+    let color_map_len = reader.u16_le()?;
+    let color_map_depth = reader.u8()?;
+    reader.skip(4)?;
+    let width = u32::from(reader.u16_le()?);
+    let height = u32::from(reader.u16_le()?);
+    let depth = reader.u8()?;
+    let descriptor = reader.u8()?;
+    let alpha_bits = descriptor & 0x0F;
+    reader.skip(id_len.into())?;
+
+    let rle = image_type & 0x08 != 0;
+    let (color_type, valid) = match image_type & !0x08 {
+        1 => (
+            ColorType::Indexed,
+            color_map_type == 1 && matches!(depth, 8 | 16),
+        ),
+        2 => (ColorType::Rgba, matches!(depth, 15 | 16 | 24 | 32)),
+        3 => (ColorType::GrayscaleAlpha, matches!(depth, 8 | 16)),
+        _ => return Err(anyhow!("invalid tga image type: {image_type}")),
+    };
+    if !valid {
+        return Err(super::unsupported(
+            super::bit_depth(depth.into()),
+            color_type,
+        ));
+    }
+
+    // Color maps are only used by color-mapped images, but may be present in any image
+    let color_map = if color_map_type == 1 {
+        let entry_size = (usize::from(color_map_depth) + 7) / 8;
+        if !matches!(color_map_depth, 15 | 16 | 24 | 32) {
+            return Err(super::unsupported(
+                super::bit_depth(color_map_depth.into()),
+                ColorType::Indexed,
+            ));
+        }
+        let entries = reader.take(usize::from(color_map_len) * entry_size)?;
+        entries
+            .chunks_exact(entry_size)
+            .map(|entry| read_color(entry, color_map_depth, alpha_bits))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let has_alpha = alpha_bits > 0
+        && match color_type {
+            ColorType::Indexed => color_map_depth != 24,
+            _ => depth != 24,
+        };
+    let format = match (color_type, has_alpha) {
+        (ColorType::GrayscaleAlpha, true) => PixelFormat::GrayAlpha,
+        (ColorType::GrayscaleAlpha, false) => PixelFormat::Gray,
+        (_, true) => PixelFormat::Rgba,
+        (_, false) => PixelFormat::Rgb,
+    };
+    let channels = format.channels();
+    let mut data = super::pixel_buffer(width, height, format)?;
+    let pixel_size = (usize::from(depth) + 7) / 8;
+    let pixel_count = width as usize * height as usize;
+
+    let to_color = |pixel: &[u8]| -> Result<[u8; 4]> {
+        match color_type {
+            ColorType::Indexed => {
+                let index = match *pixel {
+                    [index] => usize::from(index),
+                    [lo, hi] => usize::from(u16::from_le_bytes([lo, hi])),
+                    _ => 0,
+                };
+                index
+                    .checked_sub(color_map_start.into())
+                    .and_then(|index| color_map.get(index))
+                    .copied()
+                    .ok_or_else(|| anyhow!("invalid tga color map index: {index}"))
+            }
+            ColorType::GrayscaleAlpha => {
+                let alpha = if has_alpha {
+                    pixel.get(1).copied().unwrap_or(255)
+                } else {
+                    255
+                };
+                Ok([pixel[0], pixel[0], pixel[0], alpha])
+            }
+            _ => Ok(read_color(pixel, depth, alpha_bits)),
+        }
+    };
+
+    let mut plot = |i: usize, color: [u8; 4]| {
+        let (x, y) = (i % width as usize, i / width as usize);
+        let x = if descriptor & 0x10 == 0 {
+            x
+        } else {
+            width as usize - 1 - x
+        };
+        let y = if descriptor & 0x20 == 0 {
+            height as usize - 1 - y
+        } else {
+            y
+        };
+        let idx = (y * width as usize + x) * channels;
+        format.write_rgba(color, &mut data[idx..idx + channels]);
+    };
+
+    let mut i = 0;
+    while i < pixel_count {
+        if rle {
+            let packet = reader.u8()?;
+            let count = (usize::from(packet & 0x7F) + 1).min(pixel_count - i);
+            if packet & 0x80 == 0 {
+                for _ in 0..count {
+                    plot(i, to_color(reader.take(pixel_size)?)?);
+                    i += 1;
+                }
+            } else {
+                let color = to_color(reader.take(pixel_size)?)?;
+                for _ in 0..count {
+                    plot(i, color);
+                    i += 1;
+                }
+            }
+        } else {
+            plot(i, to_color(reader.take(pixel_size)?)?);
+            i += 1;
+        }
+    }
+    Ok(Image::from_vec(width, height, data, format))
+}
+
+/// Read a `BGR(A)` or `ARRRRRGG GGGBBBBB` color of the given `depth` in bits.
+fn read_color(pixel: &[u8], depth: u8, alpha_bits: u8) -> [u8; 4] {
+    match depth {
+        15 | 16 => {
+            let value = u16::from_le_bytes([pixel[0], pixel[1]]);
+            let scale = |bits: u16| ((bits & 0x1F) * 255 / 31) as u8;
+            let alpha = if depth == 16 && alpha_bits > 0 && value & 0x8000 == 0 {
+                0
+            } else {
+                255
+            };
+            [scale(value >> 10), scale(value >> 5), scale(value), alpha]
+        }
+        24 => [pixel[2], pixel[1], pixel[0], 255],
+        _ => {
+            let alpha = if alpha_bits > 0 { pixel[3] } else { 255 };
+            [pixel[2], pixel[1], pixel[0], alpha]
+        }
+    }
+}
+
+/// Encode an [Image] as `TGA` to `writer`.
+pub(super) fn encode<W: io::Write>(image: &Image, mut writer: W) -> Result<()> {
+    let (width, height) = image.dimensions();
+    let too_large = || anyhow!("image too large for tga: {width}x{height}");
+    let width = u16::try_from(width).map_err(|_| too_large())?;
+    let height = u16::try_from(height).map_err(|_| too_large())?;
+    let (image_type, depth, descriptor) = match image.format() {
+        PixelFormat::Rgb => (2, 24, 0x20),
+        PixelFormat::Rgba => (2, 32, 0x28),
+        PixelFormat::Gray => (3, 8, 0x20),
+        PixelFormat::GrayAlpha => (3, 16, 0x28),
+    };
+
+    let mut header = [0x00; 18];
+    header[2] = image_type;
+    header[12..14].copy_from_slice(&width.to_le_bytes());
+    header[14..16].copy_from_slice(&height.to_le_bytes());
+    header[16] = depth;
+    header[17] = descriptor;
+    writer.write_all(&header)?;
+
+    let mut data = image.as_bytes().to_vec();
+    if !image.format().is_gray() {
+        for pixel in data.chunks_exact_mut(image.format().channels()) {
+            pixel.swap(0, 2);
+        }
+    }
+    writer.write_all(&data)?;
+
+    // No extension or developer areas
+    writer.write_all(&[0x00; 8])?;
+    writer.write_all(SIGNATURE)?;
+    Ok(())
+}
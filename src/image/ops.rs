@@ -0,0 +1,863 @@
+//! [Image] processing operations.
+//!
+//! Provides functions to transform and filter images on the CPU, e.g. for preprocessing sprites
+//! before creating textures:
+//!
+//! - [`Image::crop`]: Copy a rectangular region.
+//! - [`Image::resize`]: Scale to new dimensions using a [`ResizeFilter`].
+//! - [`Image::rotate90`], [`Image::rotate180`], [`Image::rotate270`]: Lossless quarter turns.
+//! - [`Image::rotate`]: Rotate by an arbitrary angle, expanding to fit the result.
+//! - [`Image::flip`]: Mirror horizontally and/or vertically.
+//! - [`Image::blit`]: Draw a region of another image using a [`BlendMode`].
+//! - [`Image::convolve`]: Apply a convolution [`Kernel`], with [`Image::blur`],
+//!   [`Image::sharpen`] and [`Image::edge_detect`] as shorthands.
+//! - [`Image::threshold`], [`Image::invert`], [`Image::grayscale`] and [`Image::posterize`]: Per
+//!   pixel color filters.
+//!
+//! Every operation except [`Image::blit`] returns a new `Image` in the same [`PixelFormat`],
+//! leaving the original unchanged, so they can be chained:
+//!
+//! ```
+//! # use pix_engine::prelude::*;
+//! let image = Image::new(64, 32);
+//! let sprite = image
+//!     .crop(rect![0, 0, 16, 16])
+//!     .resize(32, 32, ResizeFilter::Nearest)
+//!     .flip(Flipped::Horizontal);
+//! assert_eq!(sprite.dimensions(), (32, 32));
+//! ```
+
+use super::{blend, luma};
+use crate::prelude::*;
+use anyhow::anyhow;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Filter used to sample pixels when resizing or rotating an [Image].
+#[non_exhaustive]
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ResizeFilter {
+    /// Nearest-neighbor sampling. Preserves hard pixel edges, which suits pixel art.
+    Nearest,
+    /// Bilinear interpolation of the nearest `2x2` pixels.
+    #[default]
+    Bilinear,
+    /// Bicubic (`Catmull-Rom`) interpolation of the nearest `4x4` pixels. Sharper than
+    /// [`ResizeFilter::Bilinear`] at the cost of speed.
+    Bicubic,
+}
+
+/// A convolution kernel used to filter an [Image] with [`Image::convolve`].
+///
+/// Each output pixel is the sum of its neighbors multiplied by the corresponding kernel weights,
+/// with the kernel centered on the pixel. Pixels beyond the image edges repeat the nearest edge
+/// pixel.
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "KernelData"))]
+pub struct Kernel {
+    width: u32,
+    height: u32,
+    weights: Vec<f64>,
+}
+
+/// Unvalidated [Kernel] fields, deserialized through [`Kernel::new`].
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct KernelData {
+    width: u32,
+    height: u32,
+    weights: Vec<f64>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<KernelData> for Kernel {
+    type Error = anyhow::Error;
+    fn try_from(data: KernelData) -> PixResult<Self> {
+        Self::new(data.width, data.height, data.weights)
+    }
+}
+
+impl Kernel {
+    /// Constructs a `Kernel` from row-major `weights`.
+    ///
+    /// # Errors
+    ///
+    /// If `width` or `height` is not odd, or the number of `weights` doesn't match the dimensions,
+    /// then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// // Horizontal motion blur
+    /// let kernel = Kernel::new(3, 1, vec![1.0 / 3.0; 3])?;
+    /// assert_eq!(kernel.dimensions(), (3, 1));
+    /// assert!(Kernel::new(2, 2, vec![0.25; 4]).is_err());
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn new(width: u32, height: u32, weights: Vec<f64>) -> PixResult<Self> {
+        if width % 2 == 0 || height % 2 == 0 {
+            return Err(anyhow!(
+                "kernel dimensions must be odd, got {width}x{height}"
+            ));
+        }
+        if weights.len() != width as usize * height as usize {
+            return Err(anyhow!(
+                "invalid kernel {width}x{height}: expected {} weights, got {}",
+                width as usize * height as usize,
+                weights.len()
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            weights,
+        })
+    }
+
+    /// Constructs a box blur `Kernel` averaging pixels within `radius`.
+    pub fn box_blur(radius: u32) -> Self {
+        let size = 2 * radius + 1;
+        let count = size as usize * size as usize;
+        Self {
+            width: size,
+            height: size,
+            weights: vec![1.0 / count as f64; count],
+        }
+    }
+
+    /// Constructs a gaussian blur `Kernel` with the given `radius`, using a standard deviation of
+    /// half the `radius`.
+    pub fn gaussian_blur(radius: u32) -> Self {
+        let size = 2 * radius + 1;
+        let sigma = (f64::from(radius) / 2.0).max(0.5);
+        let offset = |i: u32| f64::from(i) - f64::from(radius);
+        let mut weights: Vec<_> = (0..size * size)
+            .map(|i| {
+                let (x, y) = (offset(i % size), offset(i / size));
+                (-(x * x + y * y) / (2.0 * sigma * sigma)).exp()
+            })
+            .collect();
+        let sum: f64 = weights.iter().sum();
+        for weight in &mut weights {
+            *weight /= sum;
+        }
+        Self {
+            width: size,
+            height: size,
+            weights,
+        }
+    }
+
+    /// Constructs a `3x3` sharpening `Kernel`.
+    pub fn sharpen() -> Self {
+        Self {
+            width: 3,
+            height: 3,
+            weights: vec![0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0],
+        }
+    }
+
+    /// Constructs a `3x3` Laplacian edge detection `Kernel`.
+    pub fn edge_detect() -> Self {
+        Self {
+            width: 3,
+            height: 3,
+            weights: vec![-1.0, -1.0, -1.0, -1.0, 8.0, -1.0, -1.0, -1.0, -1.0],
+        }
+    }
+
+    /// Returns the `Kernel` dimensions as `(width, height)`.
+    #[inline]
+    #[must_use]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the row-major `Kernel` weights.
+    #[inline]
+    #[must_use]
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+}
+
+/// Premultiplied `RGBA` pixels of an [Image] used for interpolated sampling.
+struct Samples {
+    width: i64,
+    height: i64,
+    pixels: Vec<[f64; 4]>,
+}
+
+impl Samples {
+    fn new(image: &Image) -> Self {
+        let pixels = image
+            .pixels()
+            .map(|color| {
+                let [r, g, b, a] = color.channels().map(f64::from);
+                let alpha = a / 255.0;
+                [r * alpha, g * alpha, b * alpha, a]
+            })
+            .collect();
+        Self {
+            width: image.width().into(),
+            height: image.height().into(),
+            pixels,
+        }
+    }
+
+    /// Returns the pixel at `(x, y)`. Out of bounds pixels repeat the nearest edge pixel when
+    /// `clamp` is `true`, and are transparent otherwise.
+    fn get(&self, x: i64, y: i64, clamp: bool) -> [f64; 4] {
+        let in_bounds = (0..self.width).contains(&x) && (0..self.height).contains(&y);
+        if !in_bounds && !clamp {
+            return [0.0; 4];
+        }
+        let x = x.clamp(0, self.width - 1);
+        let y = y.clamp(0, self.height - 1);
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Samples the `Image` at `(x, y)`, where integer coordinates are pixel centers.
+    fn sample(&self, x: f64, y: f64, filter: ResizeFilter, clamp: bool) -> [u8; 4] {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+        let mut sum = [0.0; 4];
+        let mut accumulate = |x: i64, y: i64, weight: f64| {
+            if weight != 0.0 {
+                let pixel = self.get(x, y, clamp);
+                for (sum, value) in sum.iter_mut().zip(pixel) {
+                    *sum += value * weight;
+                }
+            }
+        };
+        match filter {
+            ResizeFilter::Nearest => {
+                accumulate((x + 0.5).floor() as i64, (y + 0.5).floor() as i64, 1.0);
+            }
+            ResizeFilter::Bilinear => {
+                accumulate(x0, y0, (1.0 - fx) * (1.0 - fy));
+                accumulate(x0 + 1, y0, fx * (1.0 - fy));
+                accumulate(x0, y0 + 1, (1.0 - fx) * fy);
+                accumulate(x0 + 1, y0 + 1, fx * fy);
+            }
+            ResizeFilter::Bicubic => {
+                let wx = [cubic(1.0 + fx), cubic(fx), cubic(1.0 - fx), cubic(2.0 - fx)];
+                let wy = [cubic(1.0 + fy), cubic(fy), cubic(1.0 - fy), cubic(2.0 - fy)];
+                for (j, wy) in (-1..=2).zip(wy) {
+                    for (i, wx) in (-1..=2).zip(wx) {
+                        accumulate(x0 + i, y0 + j, wx * wy);
+                    }
+                }
+            }
+        }
+        unpremultiply(sum)
+    }
+}
+
+/// `Catmull-Rom` cubic convolution weight for a sample `t` pixels away.
+fn cubic(t: f64) -> f64 {
+    const A: f64 = -0.5;
+    let t = t.abs();
+    if t <= 1.0 {
+        ((A + 2.0) * t - (A + 3.0)) * t * t + 1.0
+    } else if t < 2.0 {
+        ((A * t - 5.0 * A) * t + 8.0 * A) * t - 4.0 * A
+    } else {
+        0.0
+    }
+}
+
+/// Converts a premultiplied sample back to `RGBA` channels, clamping any filter overshoot.
+fn unpremultiply([r, g, b, a]: [f64; 4]) -> [u8; 4] {
+    let alpha = a.clamp(0.0, 255.0);
+    if alpha < 0.5 {
+        return [0; 4];
+    }
+    let channel = |value: f64| (value.clamp(0.0, alpha) * 255.0 / alpha).round() as u8;
+    [channel(r), channel(g), channel(b), alpha.round() as u8]
+}
+
+impl Image {
+    /// Returns the region of the `Image` within `rect`, clamped to the `Image` bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::from_pixels(2, 1, &[Color::RED, Color::BLUE], PixelFormat::Rgb)?;
+    /// let cropped = image.crop(rect![1, 0, 5, 5]);
+    /// assert_eq!(cropped.dimensions(), (1, 1));
+    /// assert_eq!(cropped.get_pixel(0, 0), Color::BLUE);
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn crop<R: Into<Rect<i32>>>(&self, rect: R) -> Self {
+        let Some((x, y, width, height)) = self.clamp_rect(rect.into()) else {
+            return Self::from_vec(0, 0, vec![], self.format);
+        };
+        let channels = self.format.channels();
+        let row_len = width as usize * channels;
+        let mut data = Vec::with_capacity(row_len * height as usize);
+        for row in y..y + height {
+            let start = self.idx(x, row);
+            data.extend_from_slice(&self.data[start..start + row_len]);
+        }
+        Self::from_vec(width, height, data, self.format)
+    }
+
+    /// Returns the `Image` resized to `width` and `height` using the given [`ResizeFilter`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::from_pixels(2, 1, &[Color::BLACK, Color::WHITE], PixelFormat::Rgb)?;
+    /// let nearest = image.resize(4, 1, ResizeFilter::Nearest);
+    /// assert_eq!(nearest.get_pixel(1, 0), Color::BLACK);
+    /// assert_eq!(nearest.get_pixel(2, 0), Color::WHITE);
+    /// let bilinear = image.resize(4, 1, ResizeFilter::Bilinear);
+    /// assert_eq!(bilinear.get_pixel(1, 0), rgb!(64));
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn resize(&self, width: u32, height: u32, filter: ResizeFilter) -> Self {
+        let format = self.format;
+        let channels = format.channels();
+        let mut data = vec![0x00; width as usize * height as usize * channels];
+        if width == 0 || height == 0 || self.width == 0 || self.height == 0 {
+            return Self::from_vec(width, height, data, format);
+        }
+        let x_ratio = f64::from(self.width) / f64::from(width);
+        let y_ratio = f64::from(self.height) / f64::from(height);
+        let source = |x: u32, ratio: f64| (f64::from(x) + 0.5) * ratio - 0.5;
+        let samples = (filter != ResizeFilter::Nearest).then(|| Samples::new(self));
+        let rows = data.chunks_exact_mut(width as usize * channels);
+        for (y, row) in (0..height).zip(rows) {
+            let sy = source(y, y_ratio);
+            for (x, pixel) in (0..width).zip(row.chunks_exact_mut(channels)) {
+                let sx = source(x, x_ratio);
+                if let Some(samples) = &samples {
+                    format.write_rgba(samples.sample(sx, sy, filter, true), pixel);
+                } else {
+                    let sx = ((sx + 0.5) as u32).min(self.width - 1);
+                    let sy = ((sy + 0.5) as u32).min(self.height - 1);
+                    let idx = self.idx(sx, sy);
+                    pixel.copy_from_slice(&self.data[idx..idx + channels]);
+                }
+            }
+        }
+        Self::from_vec(width, height, data, format)
+    }
+
+    /// Returns the `Image` rotated `90` degrees clockwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::from_pixels(2, 1, &[Color::RED, Color::BLUE], PixelFormat::Rgb)?;
+    /// let rotated = image.rotate90();
+    /// assert_eq!(rotated.dimensions(), (1, 2));
+    /// assert_eq!(rotated.get_pixel(0, 0), Color::RED);
+    /// assert_eq!(rotated.get_pixel(0, 1), Color::BLUE);
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn rotate90(&self) -> Self {
+        let (width, height) = (self.height, self.width);
+        self.remap(width, height, |x, y| (y, self.height - 1 - x))
+    }
+
+    /// Returns the `Image` rotated `180` degrees.
+    pub fn rotate180(&self) -> Self {
+        self.flip(Flipped::Both)
+    }
+
+    /// Returns the `Image` rotated `270` degrees clockwise, or `90` degrees counter-clockwise.
+    pub fn rotate270(&self) -> Self {
+        let (width, height) = (self.height, self.width);
+        self.remap(width, height, |x, y| (self.width - 1 - y, x))
+    }
+
+    /// Returns the `Image` rotated clockwise by `angle` in degrees about its center, sampled using
+    /// the given [`ResizeFilter`]. The result is expanded to fit the rotated `Image`, with
+    /// uncovered pixels left transparent, or black for formats without alpha.
+    ///
+    /// Multiples of `90` degrees are rotated losslessly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::new(20, 10);
+    /// assert_eq!(image.rotate(90.0, ResizeFilter::Bilinear).dimensions(), (10, 20));
+    /// assert_eq!(image.rotate(45.0, ResizeFilter::Bilinear).dimensions(), (22, 22));
+    /// ```
+    pub fn rotate(&self, angle: f64, filter: ResizeFilter) -> Self {
+        let angle = angle.rem_euclid(360.0);
+        if angle % 90.0 == 0.0 {
+            return match angle as u32 {
+                90 => self.rotate90(),
+                180 => self.rotate180(),
+                270 => self.rotate270(),
+                _ => self.clone(),
+            };
+        }
+
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let (src_width, src_height) = (f64::from(self.width), f64::from(self.height));
+        let fit = |a: f64, b: f64| (a * cos.abs() + b * sin.abs() - 1e-6).ceil().max(0.0) as u32;
+        let (width, height) = (fit(src_width, src_height), fit(src_height, src_width));
+
+        let format = self.format;
+        let channels = format.channels();
+        let mut data = vec![0x00; width as usize * height as usize * channels];
+        if width == 0 || height == 0 || self.width == 0 || self.height == 0 {
+            return Self::from_vec(width, height, data, format);
+        }
+        let samples = Samples::new(self);
+        let (cx, cy) = (f64::from(width) / 2.0, f64::from(height) / 2.0);
+        let rows = data.chunks_exact_mut(width as usize * channels);
+        for (y, row) in (0..height).zip(rows) {
+            let ry = f64::from(y) + 0.5 - cy;
+            for (x, pixel) in (0..width).zip(row.chunks_exact_mut(channels)) {
+                let rx = f64::from(x) + 0.5 - cx;
+                let sx = rx * cos + ry * sin + src_width / 2.0 - 0.5;
+                let sy = -rx * sin + ry * cos + src_height / 2.0 - 0.5;
+                format.write_rgba(samples.sample(sx, sy, filter, false), pixel);
+            }
+        }
+        Self::from_vec(width, height, data, format)
+    }
+
+    /// Returns the `Image` mirrored in the given [`Flipped`] direction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::from_pixels(2, 1, &[Color::RED, Color::BLUE], PixelFormat::Rgb)?;
+    /// let flipped = image.flip(Flipped::Horizontal);
+    /// assert_eq!(flipped.get_pixel(0, 0), Color::BLUE);
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn flip(&self, flipped: Flipped) -> Self {
+        let (width, height) = self.dimensions();
+        match flipped {
+            Flipped::None => self.clone(),
+            Flipped::Horizontal => self.remap(width, height, |x, y| (width - 1 - x, y)),
+            Flipped::Vertical => self.remap(width, height, |x, y| (x, height - 1 - y)),
+            Flipped::Both => self.remap(width, height, |x, y| (width - 1 - x, height - 1 - y)),
+        }
+    }
+
+    /// Draw the region of `src` within `src_rect`, or all of `src` if `None`, over this `Image`
+    /// with its top-left corner at `position`, blending pixels using the given [`BlendMode`].
+    /// Pixels outside of either `Image` are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let mut image = Image::new(2, 2);
+    /// let src = Image::from_pixels(2, 1, &[Color::RED, Color::BLUE], PixelFormat::Rgba)?;
+    /// image.blit(&src, rect![1, 0, 1, 1], [0, 1], BlendMode::Blend);
+    /// assert_eq!(image.get_pixel(0, 1), Color::BLUE);
+    /// assert_eq!(image.get_pixel(0, 0), Color::TRANSPARENT);
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn blit<R, P>(&mut self, src: &Image, src_rect: R, position: P, mode: BlendMode)
+    where
+        R: Into<Option<Rect<i32>>>,
+        P: Into<Point<i32>>,
+    {
+        let rect = src_rect.into().unwrap_or_else(|| src.bounding_rect());
+        let Some((src_x, src_y, width, height)) = src.clamp_rect(rect) else {
+            return;
+        };
+        let [x, y] = position.into().coords();
+        // Shift by any part of `src_rect` clamped off of the top-left of `src`
+        let x = i64::from(x) + i64::from(src_x) - i64::from(rect.x());
+        let y = i64::from(y) + i64::from(src_y) - i64::from(rect.y());
+        let channels = self.format.channels();
+        let src_channels = src.format.channels();
+        for row in 0..height {
+            let Ok(dst_y) = u32::try_from(y + i64::from(row)) else {
+                continue;
+            };
+            if dst_y >= self.height {
+                break;
+            }
+            for col in 0..width {
+                let Some(dst_x) = u32::try_from(x + i64::from(col))
+                    .ok()
+                    .filter(|&x| x < self.width)
+                else {
+                    continue;
+                };
+                let src_idx = src.idx(src_x + col, src_y + row);
+                let color = src
+                    .format
+                    .to_rgba(&src.data[src_idx..src_idx + src_channels]);
+                let idx = self.idx(dst_x, dst_y);
+                let dst = &mut self.data[idx..idx + channels];
+                let mut rgba = self.format.to_rgba(dst);
+                blend(&mut rgba, color, mode);
+                self.format.write_rgba(rgba, dst);
+            }
+        }
+    }
+
+    /// Returns the `Image` filtered by a convolution [`Kernel`]. Color channels are convolved and
+    /// alpha is preserved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::from_pixels(3, 1, &[Color::BLACK, Color::WHITE, Color::BLACK], PixelFormat::Rgb)?;
+    /// let blurred = image.convolve(&Kernel::new(3, 1, vec![0.25, 0.5, 0.25])?);
+    /// assert_eq!(blurred.get_pixel(0, 0), rgb!(64));
+    /// assert_eq!(blurred.get_pixel(1, 0), rgb!(128));
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn convolve(&self, kernel: &Kernel) -> Self {
+        let pixels: Vec<_> = self.pixels().map(|color| color.channels()).collect();
+        let (width, height) = (i64::from(self.width), i64::from(self.height));
+        let (kernel_width, kernel_height) = (i64::from(kernel.width), i64::from(kernel.height));
+        let mut image = self.clone();
+        let channels = self.format.channels();
+        for (i, dst) in image.data.chunks_exact_mut(channels).enumerate() {
+            let (x, y) = (i as i64 % width, i as i64 / width);
+            let mut sum = [0.0; 3];
+            for (k, &weight) in kernel.weights.iter().enumerate() {
+                let kx = x + k as i64 % kernel_width - kernel_width / 2;
+                let ky = y + k as i64 / kernel_width - kernel_height / 2;
+                let kx = kx.clamp(0, width - 1);
+                let ky = ky.clamp(0, height - 1);
+                let pixel = pixels[(ky * width + kx) as usize];
+                for (sum, value) in sum.iter_mut().zip(pixel) {
+                    *sum += f64::from(value) * weight;
+                }
+            }
+            let [r, g, b] = sum.map(|value| value.round().clamp(0.0, 255.0) as u8);
+            self.format.write_rgba([r, g, b, pixels[i][3]], dst);
+        }
+        image
+    }
+
+    /// Returns the `Image` blurred using a [`Kernel::gaussian_blur`] of the given `radius`.
+    pub fn blur(&self, radius: u32) -> Self {
+        self.convolve(&Kernel::gaussian_blur(radius))
+    }
+
+    /// Returns the `Image` sharpened using [`Kernel::sharpen`].
+    pub fn sharpen(&self) -> Self {
+        self.convolve(&Kernel::sharpen())
+    }
+
+    /// Returns the edges of the `Image` detected using [`Kernel::edge_detect`].
+    pub fn edge_detect(&self) -> Self {
+        self.convolve(&Kernel::edge_detect())
+    }
+
+    /// Returns the `Image` with pixels whose luma is at least `level` set to white and all other
+    /// pixels set to black. Alpha is preserved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::from_pixels(2, 1, &[Color::RED, Color::YELLOW], PixelFormat::Rgb)?;
+    /// let threshold = image.threshold(128);
+    /// assert_eq!(threshold.get_pixel(0, 0), Color::BLACK);
+    /// assert_eq!(threshold.get_pixel(1, 0), Color::WHITE);
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn threshold(&self, level: u8) -> Self {
+        self.map_pixels(|rgba| {
+            let value = if luma(rgba) >= level { 255 } else { 0 };
+            [value, value, value, rgba[3]]
+        })
+    }
+
+    /// Returns the `Image` with inverted colors. Alpha is preserved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::from_pixels(1, 1, &[Color::RED], PixelFormat::Rgb)?;
+    /// assert_eq!(image.invert().get_pixel(0, 0), Color::AQUA);
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn invert(&self) -> Self {
+        self.map_pixels(|[r, g, b, a]| [255 - r, 255 - g, 255 - b, a])
+    }
+
+    /// Returns the `Image` with colors replaced by their luma, keeping the same [`PixelFormat`].
+    /// Use [`Image::convert`] to change to a grayscale [`PixelFormat`] instead.
+    pub fn grayscale(&self) -> Self {
+        self.map_pixels(|rgba| {
+            let value = luma(rgba);
+            [value, value, value, rgba[3]]
+        })
+    }
+
+    /// Returns the `Image` with each color channel reduced to the given number of evenly spaced
+    /// `levels`, clamped to at least `2`. Alpha is preserved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::from_pixels(1, 1, &[rgb!(100, 150, 250)], PixelFormat::Rgb)?;
+    /// assert_eq!(image.posterize(2).get_pixel(0, 0), rgb!(0, 255, 255));
+    /// assert_eq!(image.posterize(3).get_pixel(0, 0), rgb!(128, 128, 255));
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn posterize(&self, levels: u8) -> Self {
+        let step = 255.0 / f64::from(levels.max(2) - 1);
+        let quantize = |value: u8| ((f64::from(value) / step).round() * step).round() as u8;
+        self.map_pixels(|[r, g, b, a]| [quantize(r), quantize(g), quantize(b), a])
+    }
+}
+
+impl Image {
+    /// Returns the portion of `rect` within the `Image` bounds as `(x, y, width, height)`, or
+    /// `None` if they don't overlap.
+    fn clamp_rect(&self, rect: Rect<i32>) -> Option<(u32, u32, u32, u32)> {
+        let clamp = |value: i32, max: u32| value.clamp(0, max as i32) as u32;
+        let (left, top) = (
+            clamp(rect.left(), self.width),
+            clamp(rect.top(), self.height),
+        );
+        let right = clamp(rect.right(), self.width);
+        let bottom = clamp(rect.bottom(), self.height);
+        (right > left && bottom > top).then(|| (left, top, right - left, bottom - top))
+    }
+
+    /// Returns a new `Image` of the given dimensions where each pixel `(x, y)` is copied from the
+    /// source pixel returned by `source(x, y)`.
+    fn remap<F>(&self, width: u32, height: u32, source: F) -> Self
+    where
+        F: Fn(u32, u32) -> (u32, u32),
+    {
+        let channels = self.format.channels();
+        let mut data = Vec::with_capacity(self.data.len());
+        for y in 0..height {
+            for x in 0..width {
+                let (sx, sy) = source(x, y);
+                let idx = self.idx(sx, sy);
+                data.extend_from_slice(&self.data[idx..idx + channels]);
+            }
+        }
+        Self::from_vec(width, height, data, self.format)
+    }
+
+    /// Returns a new `Image` with each pixel mapped by an `RGBA` function `f`.
+    fn map_pixels<F>(&self, f: F) -> Self
+    where
+        F: Fn([u8; 4]) -> [u8; 4],
+    {
+        let mut image = self.clone();
+        for pixel in image.data.chunks_exact_mut(self.format.channels()) {
+            self.format.write_rgba(f(self.format.to_rgba(pixel)), pixel);
+        }
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_image() -> PixResult<Image> {
+        let pixels: Vec<_> = (0..12u8).map(|i| rgb!(i * 20, 255 - i * 20, 100)).collect();
+        Image::from_pixels(4, 3, pixels, PixelFormat::Rgba)
+    }
+
+    #[test]
+    fn test_rotate_quarter_turns() -> PixResult<()> {
+        let image = test_image()?;
+        let rotated = image.rotate90();
+        assert_eq!(rotated.dimensions(), (3, 4));
+        assert_eq!(rotated.get_pixel(2, 0), image.get_pixel(0, 0));
+        assert_eq!(rotated.get_pixel(0, 3), image.get_pixel(3, 2));
+        assert_eq!(image.rotate270().get_pixel(0, 3), image.get_pixel(0, 0));
+
+        let full = rotated.rotate90().rotate90().rotate90();
+        assert_eq!(full.as_bytes(), image.as_bytes());
+        let half = image.rotate180();
+        assert_eq!(half.as_bytes(), image.rotate90().rotate90().as_bytes());
+        assert_eq!(
+            image.rotate(-90.0, ResizeFilter::Bicubic).as_bytes(),
+            image.rotate270().as_bytes()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rotate_arbitrary() -> PixResult<()> {
+        let image = Image::from_pixels(3, 3, [Color::WHITE; 9], PixelFormat::Rgba)?;
+        let rotated = image.rotate(45.0, ResizeFilter::Bilinear);
+        assert_eq!(rotated.dimensions(), (5, 5));
+        assert_eq!(rotated.get_pixel(2, 2), Color::WHITE);
+        assert_eq!(rotated.get_pixel(0, 0), Color::TRANSPARENT);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_kernel() -> PixResult<()> {
+        let kernel: Kernel = serde_json::from_str(r#"{"width":3,"height":1,"weights":[1,2,1]}"#)?;
+        assert_eq!(kernel.dimensions(), (3, 1));
+        assert!(serde_json::from_str::<Kernel>(r#"{"width":0,"height":0,"weights":[1]}"#).is_err());
+        assert!(serde_json::from_str::<Kernel>(r#"{"width":3,"height":3,"weights":[1]}"#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_flip() -> PixResult<()> {
+        let image = test_image()?;
+        let horizontal = image.flip(Flipped::Horizontal);
+        assert_eq!(horizontal.get_pixel(0, 1), image.get_pixel(3, 1));
+        let vertical = image.flip(Flipped::Vertical);
+        assert_eq!(vertical.get_pixel(1, 0), image.get_pixel(1, 2));
+        let both = horizontal.flip(Flipped::Vertical);
+        assert_eq!(both.as_bytes(), image.flip(Flipped::Both).as_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_crop() -> PixResult<()> {
+        let image = test_image()?;
+        let cropped = image.crop(rect![1, 1, 2, 2]);
+        assert_eq!(cropped.dimensions(), (2, 2));
+        assert_eq!(cropped.get_pixel(0, 0), image.get_pixel(1, 1));
+        assert_eq!(cropped.get_pixel(1, 1), image.get_pixel(2, 2));
+        assert_eq!(image.crop(rect![-5, -5, 2, 2]).dimensions(), (0, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_resize() -> PixResult<()> {
+        let image = test_image()?;
+        for filter in [
+            ResizeFilter::Nearest,
+            ResizeFilter::Bilinear,
+            ResizeFilter::Bicubic,
+        ] {
+            let same = image.resize(4, 3, filter);
+            assert_eq!(same.as_bytes(), image.as_bytes(), "{filter:?}");
+            let resized = image.resize(7, 2, filter);
+            assert_eq!(resized.dimensions(), (7, 2), "{filter:?}");
+        }
+        let doubled = image.resize(8, 6, ResizeFilter::Nearest);
+        assert_eq!(doubled.get_pixel(7, 5), image.get_pixel(3, 2));
+        assert_eq!(doubled.get_pixel(2, 1), image.get_pixel(1, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_resize_empty() -> PixResult<()> {
+        let image = test_image()?;
+        for filter in [
+            ResizeFilter::Nearest,
+            ResizeFilter::Bilinear,
+            ResizeFilter::Bicubic,
+        ] {
+            assert_eq!(
+                image.resize(0, 10, filter).dimensions(),
+                (0, 10),
+                "{filter:?}"
+            );
+            assert_eq!(
+                image.resize(10, 0, filter).dimensions(),
+                (10, 0),
+                "{filter:?}"
+            );
+            assert!(
+                image.resize(0, 0, filter).as_bytes().is_empty(),
+                "{filter:?}"
+            );
+            let resized = Image::new(0, 0).resize(2, 2, filter);
+            assert_eq!(resized.get_pixel(1, 1), Color::TRANSPARENT, "{filter:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_rotate_empty() {
+        let empty = Image::new(0, 0);
+        for angle in [45.0, 90.0, 180.0, 270.0] {
+            let rotated = empty.rotate(angle, ResizeFilter::Bilinear);
+            assert_eq!(rotated.dimensions(), (0, 0), "{angle}");
+        }
+        let rotated = Image::new(0, 3).rotate(45.0, ResizeFilter::Bilinear);
+        assert_eq!(rotated.dimensions(), (3, 3));
+        assert_eq!(rotated.get_pixel(1, 1), Color::TRANSPARENT);
+    }
+
+    #[test]
+    fn test_resize_ignores_transparent_colors() -> PixResult<()> {
+        let pixels = [Color::RED, Color::rgba(0, 0, 255, 0)];
+        let image = Image::from_pixels(2, 1, pixels, PixelFormat::Rgba)?;
+        let resized = image.resize(4, 1, ResizeFilter::Bilinear);
+        assert_eq!(resized.get_pixel(1, 0), Color::rgba(255, 0, 0, 191));
+        assert_eq!(resized.get_pixel(2, 0), Color::rgba(255, 0, 0, 64));
+        Ok(())
+    }
+
+    #[test]
+    fn test_blit() -> PixResult<()> {
+        let mut image = Image::from_pixels(2, 2, [Color::WHITE; 4], PixelFormat::Rgb)?;
+        let src = test_image()?;
+        image.blit(&src, rect![-1, 2, 3, 5], [0, 0], BlendMode::None);
+        assert_eq!(image.get_pixel(0, 0), Color::WHITE);
+        assert_eq!(image.get_pixel(1, 0), src.get_pixel(0, 2));
+        assert_eq!(image.get_pixel(1, 1), Color::WHITE);
+        Ok(())
+    }
+
+    #[test]
+    fn test_convolve() -> PixResult<()> {
+        let image = test_image()?;
+        let kernel = Kernel::new(1, 1, vec![1.0])?;
+        assert_eq!(image.convolve(&kernel).as_bytes(), image.as_bytes());
+
+        let flat = Image::from_pixels(3, 3, [Color::GRAY; 9], PixelFormat::Rgb)?;
+        assert_eq!(flat.blur(2).as_bytes(), flat.as_bytes());
+        assert_eq!(flat.sharpen().as_bytes(), flat.as_bytes());
+        assert!(flat
+            .edge_detect()
+            .pixels()
+            .all(|color| color == Color::BLACK));
+        Ok(())
+    }
+
+    #[test]
+    fn test_filters_preserve_alpha() -> PixResult<()> {
+        let image = Image::from_pixels(1, 1, [Color::rgba(10, 20, 30, 40)], PixelFormat::Rgba)?;
+        assert_eq!(
+            image.invert().get_pixel(0, 0),
+            Color::rgba(245, 235, 225, 40)
+        );
+        assert_eq!(
+            image.grayscale().get_pixel(0, 0),
+            Color::rgba(18, 18, 18, 40)
+        );
+        assert_eq!(
+            image.threshold(10).get_pixel(0, 0),
+            Color::rgba(255, 255, 255, 40)
+        );
+        assert_eq!(image.posterize(2).get_pixel(0, 0), Color::rgba(0, 0, 0, 40));
+
+        let gray = image.convert(PixelFormat::Gray).invert();
+        assert_eq!(gray.format(), PixelFormat::Gray);
+        assert_eq!(gray.as_bytes(), [237]);
+        Ok(())
+    }
+}
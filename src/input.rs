@@ -0,0 +1,724 @@
+//! [`InputMap`] functions for binding named actions and axes to user input.
+//!
+//! An `InputMap` binds named actions such as `"jump"` to any mix of [Key]s, [Mouse] buttons,
+//! [`ControllerButton`]s and directions of a controller [Axis], and binds named axes such as
+//! `"move_x"` to either an analog controller [Axis] or a pair of inputs. Querying actions by name
+//! instead of checking specific keys allows players to rebind controls.
+//!
+//! The current `InputMap` is stored in [`PixState`] and is queried each frame with
+//! [`PixState::action_down`], [`PixState::action_pressed`] and [`PixState::axis_value`]. Input
+//! state is tracked for every event received, even those handled by the application. Controller
+//! axes ignore values within the [deadzone](PixState::deadzone) set with
+//! [`EngineBuilder::deadzone`](crate::engine::EngineBuilder::deadzone).
+//!
+//! For rebinding menus, [`PixState::listen_for_input`] captures the next key, mouse button,
+//! controller button or axis pressed, which can be retrieved with
+//! [`PixState::take_listened_input`].
+//!
+//! With the `serde` feature enabled, an `InputMap` can be saved to and loaded from `JSON` files.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::input_map`]: Returns the current [`InputMap`].
+//! - [`PixState::input_map_mut`]: Returns the current [`InputMap`] for modification.
+//! - [`PixState::set_input_map`]: Set the current [`InputMap`].
+//! - [`PixState::action_down`]: Whether any input bound to an action is being held.
+//! - [`PixState::action_pressed`]: Whether any input bound to an action was pressed this frame.
+//! - [`PixState::axis_value`]: Returns the value of an axis between `-1.0` and `1.0`.
+//! - [`PixState::deadzone`]: Returns the controller axis deadzone.
+//! - [`PixState::set_deadzone`]: Set the controller axis deadzone.
+//! - [`PixState::listen_for_input`]: Capture the next input pressed.
+//! - [`PixState::is_listening_for_input`]: Whether the next input pressed will be captured.
+//! - [`PixState::take_listened_input`]: Returns the input captured after
+//!   [`PixState::listen_for_input`].
+//!
+//! # Example
+//!
+//! ```
+//! # use pix_engine::prelude::*;
+//! # struct App { pos: Point<f64> };
+//! # impl PixEngine for App {
+//! fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     let mut input = InputMap::new();
+//!     input
+//!         .bind_action("jump", Input::Key(Key::Space))
+//!         .bind_action("jump", Input::Button(ControllerButton::A))
+//!         .bind_axis("move_x", AxisBinding::Analog(Axis::LeftX))
+//!         .bind_axis(
+//!             "move_x",
+//!             AxisBinding::Digital {
+//!                 negative: Input::Key(Key::A),
+//!                 positive: Input::Key(Key::D),
+//!             },
+//!         );
+//!     s.set_input_map(input);
+//!     Ok(())
+//! }
+//!
+//! fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     if s.action_pressed("jump") {
+//!         self.pos.offset_y(-10.0);
+//!     }
+//!     self.pos.offset_x(s.axis_value("move_x") * 2.0);
+//!     s.circle([self.pos.x() as i32, self.pos.y() as i32, 8])?;
+//!     Ok(())
+//! }
+//! # }
+//! ```
+
+use crate::prelude::*;
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+use anyhow::Context;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+/// Default controller axis deadzone.
+pub(crate) const DEFAULT_DEADZONE: i32 = 8000;
+/// Controller axis value required to capture an axis with [`PixState::listen_for_input`].
+const LISTEN_THRESHOLD: i32 = 16_384;
+
+/// A direction along a controller [Axis].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AxisDirection {
+    /// Negative axis values, such as left or up.
+    Negative,
+    /// Positive axis values, such as right or down.
+    Positive,
+}
+
+/// A single input that can be bound to an action in an [`InputMap`].
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Input {
+    /// A keyboard [Key].
+    Key(Key),
+    /// A [Mouse] button.
+    Mouse(Mouse),
+    /// A [`ControllerButton`].
+    Button(ControllerButton),
+    /// A controller [Axis] moved past the deadzone in a given direction.
+    Axis(Axis, AxisDirection),
+}
+
+/// A binding for a named axis in an [`InputMap`].
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AxisBinding {
+    /// An analog controller [Axis], ranging from `-1.0` to `1.0` outside of the deadzone.
+    Analog(Axis),
+    /// A pair of inputs, returning `-1.0` while `negative` is held, `1.0` while `positive` is held
+    /// and `0.0` if neither or both are held.
+    Digital {
+        /// Input for negative axis values.
+        negative: Input,
+        /// Input for positive axis values.
+        positive: Input,
+    },
+}
+
+/// A set of named actions and axes bound to user input. See the [module-level
+/// documentation](self) for more details.
+///
+/// # Example
+///
+/// ```
+/// # use pix_engine::prelude::*;
+/// let mut input = InputMap::new();
+/// input
+///     .bind_action("fire", Input::Key(Key::Space))
+///     .bind_action("fire", Input::Mouse(Mouse::Left));
+/// assert_eq!(input.action_bindings("fire").len(), 2);
+///
+/// input.rebind_action("fire", Input::Key(Key::F));
+/// assert_eq!(input.action_bindings("fire"), [Input::Key(Key::F)]);
+/// ```
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[must_use]
+pub struct InputMap {
+    actions: BTreeMap<String, Vec<Input>>,
+    axes: BTreeMap<String, Vec<AxisBinding>>,
+    controller: Option<ControllerId>,
+}
+
+impl InputMap {
+    /// Constructs an empty `InputMap`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind an [Input] to a named action, in addition to any existing bindings.
+    pub fn bind_action<S>(&mut self, action: S, input: Input) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        let bindings = self.actions.entry(action.into()).or_default();
+        if !bindings.contains(&input) {
+            bindings.push(input);
+        }
+        self
+    }
+
+    /// Replace all bindings for a named action with a single [Input].
+    pub fn rebind_action<S>(&mut self, action: S, input: Input) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        self.actions.insert(action.into(), vec![input]);
+        self
+    }
+
+    /// Remove a named action and all of its bindings.
+    #[inline]
+    pub fn unbind_action(&mut self, action: &str) -> &mut Self {
+        self.actions.remove(action);
+        self
+    }
+
+    /// Returns the [Input]s bound to a named action.
+    #[inline]
+    #[must_use]
+    pub fn action_bindings(&self, action: &str) -> &[Input] {
+        self.actions.get(action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns an iterator over all named actions.
+    #[inline]
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.keys().map(String::as_str)
+    }
+
+    /// Bind an [`AxisBinding`] to a named axis, in addition to any existing bindings.
+    pub fn bind_axis<S>(&mut self, axis: S, binding: AxisBinding) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        let bindings = self.axes.entry(axis.into()).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+        self
+    }
+
+    /// Replace all bindings for a named axis with a single [`AxisBinding`].
+    pub fn rebind_axis<S>(&mut self, axis: S, binding: AxisBinding) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        self.axes.insert(axis.into(), vec![binding]);
+        self
+    }
+
+    /// Remove a named axis and all of its bindings.
+    #[inline]
+    pub fn unbind_axis(&mut self, axis: &str) -> &mut Self {
+        self.axes.remove(axis);
+        self
+    }
+
+    /// Returns the [`AxisBinding`]s bound to a named axis.
+    #[inline]
+    #[must_use]
+    pub fn axis_bindings(&self, axis: &str) -> &[AxisBinding] {
+        self.axes.get(axis).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns an iterator over all named axes.
+    #[inline]
+    pub fn axes(&self) -> impl Iterator<Item = &str> {
+        self.axes.keys().map(String::as_str)
+    }
+
+    /// Returns the controller this map reads input from, or `None` if any controller is used.
+    #[inline]
+    #[must_use]
+    pub const fn controller(&self) -> Option<ControllerId> {
+        self.controller
+    }
+
+    /// Only read controller input from a given controller, allowing separate maps for each player.
+    /// `None` reads input from any controller, which is the default.
+    #[inline]
+    pub fn set_controller(&mut self, controller: Option<ControllerId>) -> &mut Self {
+        self.controller = controller;
+        self
+    }
+
+    /// Save the input map to a `JSON` file.
+    ///
+    /// # Errors
+    ///
+    /// If the file fails to be created or written to, then an error is returned.
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> PixResult<()> {
+        let path = path.as_ref();
+        let file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(file, self)
+            .with_context(|| format!("failed to write input map: {path:?}"))
+    }
+
+    /// Load an input map previously saved with [`InputMap::save`] from a `JSON` file.
+    ///
+    /// # Errors
+    ///
+    /// If the file fails to be read or isn't a valid input map, then an error is returned.
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> PixResult<Self> {
+        let path = path.as_ref();
+        let file = BufReader::new(File::open(path)?);
+        serde_json::from_reader(file).with_context(|| format!("failed to read input map: {path:?}"))
+    }
+
+    /// Whether a given controller matches the controller set for this map.
+    #[inline]
+    fn reads(&self, controller: Option<ControllerId>) -> bool {
+        match (self.controller, controller) {
+            (Some(expected), Some(id)) => expected == id,
+            _ => true,
+        }
+    }
+}
+
+/// Keep track of raw input state between frames, independent of events handled by the
+/// application.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct InputState {
+    pub(crate) map: InputMap,
+    pub(crate) deadzone: i32,
+    keys: HashSet<Key>,
+    mouse: HashSet<Mouse>,
+    buttons: HashSet<(ControllerId, ControllerButton)>,
+    axes: HashMap<(ControllerId, Axis), i16>,
+    pressed: HashSet<(Option<ControllerId>, Input)>,
+    listening: bool,
+    listened: Option<Input>,
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self {
+            map: InputMap::default(),
+            deadzone: DEFAULT_DEADZONE,
+            keys: HashSet::new(),
+            mouse: HashSet::new(),
+            buttons: HashSet::new(),
+            axes: HashMap::new(),
+            pressed: HashSet::new(),
+            listening: false,
+            listened: None,
+        }
+    }
+}
+
+impl InputState {
+    /// Clear inputs pressed last frame.
+    #[inline]
+    pub(crate) fn begin_frame(&mut self) {
+        self.pressed.clear();
+    }
+
+    /// Update input state from an [Event].
+    pub(crate) fn handle_event(&mut self, event: &Event) {
+        match *event {
+            Event::KeyDown {
+                key: Some(key),
+                repeat: false,
+                ..
+            } => {
+                self.keys.insert(key);
+                self.press(None, Input::Key(key));
+            }
+            Event::KeyUp { key: Some(key), .. } => {
+                self.keys.remove(&key);
+            }
+            Event::MouseDown { button, .. } => {
+                self.mouse.insert(button);
+                self.press(None, Input::Mouse(button));
+            }
+            Event::MouseUp { button, .. } => {
+                self.mouse.remove(&button);
+            }
+            Event::ControllerDown {
+                controller_id,
+                button,
+            } => {
+                let id = ControllerId(controller_id);
+                self.buttons.insert((id, button));
+                self.press(Some(id), Input::Button(button));
+            }
+            Event::ControllerUp {
+                controller_id,
+                button,
+            } => {
+                self.buttons.remove(&(ControllerId(controller_id), button));
+            }
+            Event::ControllerAxisMotion {
+                controller_id,
+                axis,
+                value,
+            } => {
+                let id = ControllerId(controller_id);
+                let previous = self.axes.insert((id, axis), value).unwrap_or_default();
+                let (previous, value) = (i32::from(previous), i32::from(value));
+                for (direction, sign) in
+                    [(AxisDirection::Negative, -1), (AxisDirection::Positive, 1)]
+                {
+                    let was_down = previous * sign >= self.deadzone.max(1);
+                    if !was_down && value * sign >= self.deadzone.max(1) {
+                        self.pressed
+                            .insert((Some(id), Input::Axis(axis, direction)));
+                    }
+                    if self.listening && value * sign >= LISTEN_THRESHOLD {
+                        self.listen(Input::Axis(axis, direction));
+                    }
+                }
+            }
+            Event::ControllerRemoved { controller_id } => {
+                let id = ControllerId(controller_id);
+                self.buttons.retain(|&(controller, _)| controller != id);
+                self.axes.retain(|&(controller, _), _| controller != id);
+            }
+            _ => (),
+        }
+    }
+
+    /// Store an input pressed this frame.
+    #[inline]
+    fn press(&mut self, controller: Option<ControllerId>, input: Input) {
+        self.pressed.insert((controller, input));
+        if self.listening {
+            self.listen(input);
+        }
+    }
+
+    /// Capture an input while listening.
+    #[inline]
+    fn listen(&mut self, input: Input) {
+        self.listening = false;
+        self.listened = Some(input);
+    }
+
+    /// Returns the value of a controller axis with the largest magnitude, ignoring controllers not
+    /// read by the current map.
+    fn raw_axis(&self, axis: Axis) -> i32 {
+        self.axes
+            .iter()
+            .filter(|(&(id, a), _)| a == axis && self.map.reads(Some(id)))
+            .map(|(_, &value)| i32::from(value))
+            .max_by_key(|value| value.abs())
+            .unwrap_or_default()
+    }
+
+    /// Whether an [Input] is currently being held.
+    fn is_down(&self, input: Input) -> bool {
+        match input {
+            Input::Key(key) => self.keys.contains(&key),
+            Input::Mouse(button) => self.mouse.contains(&button),
+            Input::Button(button) => self
+                .buttons
+                .iter()
+                .any(|&(id, b)| b == button && self.map.reads(Some(id))),
+            Input::Axis(axis, direction) => {
+                let value = self.raw_axis(axis);
+                let threshold = self.deadzone.max(1);
+                match direction {
+                    AxisDirection::Negative => value <= -threshold,
+                    AxisDirection::Positive => value >= threshold,
+                }
+            }
+        }
+    }
+
+    /// Whether an [Input] was pressed this frame.
+    fn was_pressed(&self, input: Input) -> bool {
+        self.pressed
+            .iter()
+            .any(|&(id, pressed)| pressed == input && self.map.reads(id))
+    }
+
+    /// Returns the value of an [`AxisBinding`] between `-1.0` and `1.0`.
+    fn binding_value(&self, binding: AxisBinding) -> f64 {
+        match binding {
+            AxisBinding::Analog(axis) => {
+                let value = self.raw_axis(axis);
+                let deadzone = self.deadzone.clamp(0, i32::from(i16::MAX) - 1);
+                if value.abs() < deadzone {
+                    0.0
+                } else {
+                    let range = f64::from(i32::from(i16::MAX) - deadzone);
+                    let magnitude = (f64::from(value.abs() - deadzone) / range).min(1.0);
+                    magnitude.copysign(f64::from(value))
+                }
+            }
+            AxisBinding::Digital { negative, positive } => {
+                let negative = if self.is_down(negative) { 1.0 } else { 0.0 };
+                let positive = if self.is_down(positive) { 1.0 } else { 0.0 };
+                positive - negative
+            }
+        }
+    }
+}
+
+impl PixState {
+    /// Returns the current [`InputMap`].
+    #[inline]
+    pub const fn input_map(&self) -> &InputMap {
+        &self.input.map
+    }
+
+    /// Returns the current [`InputMap`] for modification.
+    #[inline]
+    pub fn input_map_mut(&mut self) -> &mut InputMap {
+        &mut self.input.map
+    }
+
+    /// Set the current [`InputMap`] used by [`PixState::action_down`],
+    /// [`PixState::action_pressed`] and [`PixState::axis_value`].
+    #[inline]
+    pub fn set_input_map(&mut self, map: InputMap) {
+        self.input.map = map;
+    }
+
+    /// Whether any [Input] bound to a named action is currently being held. Returns `false` for
+    /// unknown actions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { speed: f64 };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     self.speed = if s.action_down("run") { 2.0 } else { 1.0 };
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn action_down(&self, action: &str) -> bool {
+        let input = &self.input;
+        input
+            .map
+            .action_bindings(action)
+            .iter()
+            .any(|&binding| input.is_down(binding))
+    }
+
+    /// Whether any [Input] bound to a named action was pressed since the last frame. Returns
+    /// `false` for unknown actions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { jumping: bool };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     if s.action_pressed("jump") {
+    ///         self.jumping = true;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn action_pressed(&self, action: &str) -> bool {
+        let input = &self.input;
+        input
+            .map
+            .action_bindings(action)
+            .iter()
+            .any(|&binding| input.was_pressed(binding))
+    }
+
+    /// Returns the value of a named axis between `-1.0` and `1.0`, using the binding with the
+    /// largest magnitude. Returns `0.0` for unknown axes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { pos: Point<f64> };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let speed = 100.0 * s.delta_time().as_secs_f64();
+    ///     self.pos.offset_x(s.axis_value("move_x") * speed);
+    ///     self.pos.offset_y(s.axis_value("move_y") * speed);
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn axis_value(&self, axis: &str) -> f64 {
+        let input = &self.input;
+        input
+            .map
+            .axis_bindings(axis)
+            .iter()
+            .map(|&binding| input.binding_value(binding))
+            .fold(0.0, |value, binding| {
+                if binding.abs() > value.abs() {
+                    binding
+                } else {
+                    value
+                }
+            })
+    }
+
+    /// Returns the controller axis deadzone. Axis values between `-deadzone` and `deadzone` are
+    /// treated as `0`.
+    #[inline]
+    #[must_use]
+    pub const fn deadzone(&self) -> i32 {
+        self.input.deadzone
+    }
+
+    /// Set the controller axis deadzone. See
+    /// [`EngineBuilder::deadzone`](crate::engine::EngineBuilder::deadzone).
+    #[inline]
+    pub fn set_deadzone(&mut self, deadzone: i32) {
+        self.input.deadzone = deadzone;
+    }
+
+    /// Capture the next [Input] pressed, replacing any previously captured input. Useful for
+    /// rebinding controls. The captured input can be retrieved with
+    /// [`PixState::take_listened_input`].
+    ///
+    /// Events are still sent to the application while listening, so input used to start listening,
+    /// such as clicking a button, should be released first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     if s.button("Rebind jump")? {
+    ///         s.listen_for_input();
+    ///     }
+    ///     if s.is_listening_for_input() {
+    ///         s.text("Press any key or button...")?;
+    ///     } else if let Some(input) = s.take_listened_input() {
+    ///         s.input_map_mut().rebind_action("jump", input);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn listen_for_input(&mut self) {
+        self.input.listening = true;
+        self.input.listened = None;
+    }
+
+    /// Stop listening for input without capturing anything.
+    #[inline]
+    pub fn stop_listening_for_input(&mut self) {
+        self.input.listening = false;
+    }
+
+    /// Whether the next [Input] pressed will be captured.
+    #[inline]
+    #[must_use]
+    pub const fn is_listening_for_input(&self) -> bool {
+        self.input.listening
+    }
+
+    /// Returns the [Input] captured after calling [`PixState::listen_for_input`], if any, clearing
+    /// it.
+    #[inline]
+    pub fn take_listened_input(&mut self) -> Option<Input> {
+        self.input.listened.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn axis_motion(axis: Axis, value: i16) -> Event {
+        Event::ControllerAxisMotion {
+            controller_id: 0,
+            axis,
+            value,
+        }
+    }
+
+    #[test]
+    fn test_actions() {
+        let mut state = InputState::default();
+        state
+            .map
+            .bind_action("jump", Input::Key(Key::Space))
+            .bind_action("jump", Input::Button(ControllerButton::A));
+
+        state.handle_event(&Event::ControllerDown {
+            controller_id: 0,
+            button: ControllerButton::A,
+        });
+        assert!(state.is_down(Input::Button(ControllerButton::A)));
+        assert!(state.was_pressed(Input::Button(ControllerButton::A)));
+
+        state.begin_frame();
+        assert!(state.is_down(Input::Button(ControllerButton::A)));
+        assert!(!state.was_pressed(Input::Button(ControllerButton::A)));
+
+        // Input from other controllers is ignored
+        state.map.set_controller(Some(ControllerId(1)));
+        assert!(!state.is_down(Input::Button(ControllerButton::A)));
+    }
+
+    #[test]
+    fn test_axis_deadzone() {
+        let mut state = InputState {
+            deadzone: 10_000,
+            ..InputState::default()
+        };
+
+        state.handle_event(&axis_motion(Axis::LeftX, 9_999));
+        assert_eq!(state.binding_value(AxisBinding::Analog(Axis::LeftX)), 0.0);
+        assert!(!state.is_down(Input::Axis(Axis::LeftX, AxisDirection::Positive)));
+
+        state.handle_event(&axis_motion(Axis::LeftX, i16::MIN));
+        assert_eq!(state.binding_value(AxisBinding::Analog(Axis::LeftX)), -1.0);
+        assert!(state.is_down(Input::Axis(Axis::LeftX, AxisDirection::Negative)));
+        assert!(state.was_pressed(Input::Axis(Axis::LeftX, AxisDirection::Negative)));
+
+        let value = state.binding_value(AxisBinding::Analog(Axis::LeftY));
+        assert_eq!(value, 0.0);
+        state.handle_event(&axis_motion(Axis::LeftY, 21_384));
+        let value = state.binding_value(AxisBinding::Analog(Axis::LeftY));
+        assert!(
+            (value - 0.5).abs() < 1e-4,
+            "rescaled past deadzone: {value}"
+        );
+    }
+
+    #[test]
+    fn test_listen() {
+        let mut state = InputState::default();
+        state.handle_event(&axis_motion(Axis::RightY, 12_000));
+        state.listening = true;
+        // Below listen threshold
+        state.handle_event(&axis_motion(Axis::RightY, -12_000));
+        assert!(state.listening);
+        state.handle_event(&axis_motion(Axis::RightY, -20_000));
+        assert!(!state.listening);
+        assert_eq!(
+            state.listened,
+            Some(Input::Axis(Axis::RightY, AxisDirection::Negative))
+        );
+    }
+}
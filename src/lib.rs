@@ -49,8 +49,10 @@ pub mod error;
 #[macro_use]
 pub mod shape;
 pub mod audio;
+pub mod camera;
 pub mod event;
 pub mod image;
+pub mod input;
 pub mod lighting;
 pub mod ops;
 pub mod state;
@@ -62,8 +64,14 @@ pub mod math;
 pub mod vector;
 pub mod gui;
 pub mod renderer;
+pub mod scene;
 #[cfg(feature = "serde")]
 pub mod serialize;
+pub mod sprite;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod testing;
+pub mod tilemap;
+pub mod touch;
 pub mod transform;
 
 /// Exports most commonly used types, traits, and functions.
@@ -72,27 +80,48 @@ pub mod prelude {
         AudioCallback, AudioDevice, AudioDeviceDriver, AudioFormat, AudioFormatNum, AudioSpec,
         AudioSpecDesired, AudioStatus,
     };
-    pub use super::color::{Color, Mode as ColorMode};
+    pub use super::camera::Camera2D;
+    pub use super::color::{
+        paint::{Gradient, Paint},
+        Color, Mode as ColorMode,
+    };
     pub use super::draw::Draw;
     pub use super::engine::{Engine, PixEngine};
     pub use super::error::{Error as PixError, Result as PixResult};
     pub use super::event::{
-        Axis, ControllerButton, ControllerEvent, ControllerId, ControllerUpdate, Event, HatState,
-        Key, KeyEvent, KeyMod, Mouse, Scan, WindowEvent,
+        Axis, ControllerButton, ControllerEvent, ControllerId, ControllerUpdate, Event,
+        EventRecording, EventSender, HatState, Key, KeyEvent, KeyMod, Mouse, RecordedEvent, Scan,
+        UserEvent, WindowEvent,
     };
     pub use super::gui::theme::{self, ColorType, Font, Theme};
-    pub use super::image::{Image, PixelFormat};
+    pub use super::image::{
+        AnimatedImage, Disposal, Image, ImageFormat, Kernel, PixelFormat, Playback, ResizeFilter,
+    };
+    pub use super::input::{AxisBinding, AxisDirection, Input, InputMap};
     pub use super::lighting::{Light, LightSource};
     pub use super::math::{map, random_rng, Float, Num};
-    pub use super::shape::{Contains, Ellipse, Intersects, Line, Point, Quad, Rect, Sphere, Tri};
+    pub use super::scene::{Scene, SceneStack, Transition};
+    pub use super::shape::{
+        Contains, Ellipse, Intersects, Line, Path, Point, Quad, Rect, Sphere, Tri,
+    };
+    pub use super::sprite::{Animation, AnimationClip, Sprite, SpriteSheet, SpriteSource};
+    #[cfg(not(target_arch = "wasm32"))]
+    pub use super::state::recording::RecordingFormat;
     pub use super::state::{
+        clip::Clip,
         settings::{
             AngleMode, ArcMode, BlendMode, DrawMode, EllipseMode, FontStyle, ImageMode, RectMode,
+            StrokeCap, StrokeJoin,
         },
         PixState,
     };
-    pub use super::texture::TextureId;
-    pub use super::transform::Flipped;
+    pub use super::texture::{
+        atlas::{AtlasRegion, TextureAtlas},
+        TextureId,
+    };
+    pub use super::tilemap::{Tile, TileLayer, Tilemap, Tileset};
+    pub use super::touch::{Gesture, Touch};
+    pub use super::transform::{Flipped, Transform};
     pub use super::vector::Vector;
     pub use super::window::{Cursor, Position, SystemCursor, WindowId};
 
@@ -1,14 +1,20 @@
 //! Graphics renderer functions.
 
-use crate::{error::Result, image::Icon, prelude::*};
-use std::num::NonZeroUsize;
+use crate::{error::Result, image::Icon, prelude::*, state::clip::Mask};
+use std::{num::NonZeroUsize, rc::Rc};
 
 pub(crate) use crate::{texture::TextureRenderer, window::WindowRenderer};
 
 #[cfg(not(target_arch = "wasm32"))]
+mod backend;
+#[cfg(not(target_arch = "wasm32"))]
+mod headless;
+#[cfg(all(feature = "sdl", not(target_arch = "wasm32")))]
 pub mod sdl;
 #[cfg(not(target_arch = "wasm32"))]
-pub(crate) use sdl::Renderer;
+pub(crate) use backend::Renderer;
+#[cfg(all(not(feature = "sdl"), not(target_arch = "wasm32")))]
+pub use headless::{AudioDevice, AudioFormatNum};
 
 #[cfg(target_arch = "wasm32")]
 pub mod wasm;
@@ -18,6 +24,16 @@ pub(crate) use wasm::Renderer;
 const TEXTURE_CACHE_SIZE: usize = 256;
 const TEXT_CACHE_SIZE: usize = 512;
 
+/// Determines which regions of overlapping polygons are filled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum FillRule {
+    /// Fill regions crossed by an odd number of edges.
+    EvenOdd,
+    /// Fill regions with a non-zero winding number, so that overlapping polygons wound in the
+    /// same direction are only filled once.
+    NonZero,
+}
+
 /// Settings used to set up the renderer.
 #[derive(Debug, Clone)]
 pub(crate) struct RendererSettings {
@@ -55,6 +71,8 @@ pub(crate) struct RendererSettings {
     pub(crate) allow_highdpi: bool,
     /// Hide window.
     pub(crate) hidden: bool,
+    /// Render to in-memory framebuffers without opening any windows.
+    pub(crate) headless: bool,
     /// Show frame rate per second in title bar.
     pub(crate) show_frame_rate: bool,
     /// Limit [`Engine::on_update`] to target frame frate per second.
@@ -86,6 +104,7 @@ impl Default for RendererSettings {
             borderless: false,
             allow_highdpi: false,
             hidden: false,
+            headless: false,
             show_frame_rate: false,
             target_frame_rate: None,
             texture_cache_size: TEXTURE_CACHE_SIZE.try_into().expect("valid cache size"),
@@ -111,6 +130,9 @@ pub(crate) trait Rendering: Sized {
     /// Sets the blend mode used by the renderer to drawing.
     fn blend_mode(&mut self, mode: BlendMode);
 
+    /// Sets the clip mask used by the renderer to draw to the current canvas.
+    fn mask(&mut self, mask: Option<Rc<Mask>>);
+
     /// Updates the canvas from the current back buffer.
     fn present(&mut self);
 
@@ -203,6 +225,29 @@ pub(crate) trait Rendering: Sized {
     where
         I: Iterator<Item = Point<i32>>;
 
+    /// Draw a path made up of one or more contours to the current canvas. Contours are filled
+    /// together using the even-odd rule and each is stroked as a separate polyline, connecting
+    /// the last point to the first if it's closed.
+    fn path(
+        &mut self,
+        contours: &[(Vec<Point<i32>>, bool)],
+        smooth: bool,
+        width: u8,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()>;
+
+    /// Fill one or more polygons to the current canvas as a single shape using the given
+    /// [`FillRule`]. Gradient paints are sampled at each pixel, mapped into the coordinate space
+    /// of the paint by `transform`.
+    fn fill_polygons(
+        &mut self,
+        polygons: &[Vec<Point<f64>>],
+        rule: FillRule,
+        paint: &Paint,
+        transform: &Transform,
+    ) -> Result<()>;
+
     /// Draw a ellipse to the current canvas.
     fn ellipse(
         &mut self,
@@ -242,6 +287,15 @@ pub(crate) trait Rendering: Sized {
     /// Return the current rendered target pixels as an array of bytes.
     fn to_bytes(&mut self) -> Result<Vec<u8>>;
 
+    /// Returns the size in pixels of the current render target as `(width, height)`, matching
+    /// the bytes returned by [`Rendering::to_bytes`]. This may be larger than the target
+    /// dimensions on high-DPI displays.
+    fn target_size(&mut self) -> Result<(u32, u32)>;
+
+    /// Replace the current rendered target pixels with an array of `RGBA` bytes, ignoring the
+    /// clip region and blend mode.
+    fn update_bytes(&mut self, bytes: &[u8]) -> Result<()>;
+
     /// Connect a controller with the given joystick index to start receiving events.
     fn open_controller(&mut self, controller_id: ControllerId) -> Result<()>;
 
@@ -0,0 +1,567 @@
+//! Renderer backend selection.
+//!
+//! [Renderer] forwards all rendering operations to either the `SDL` renderer or the
+//! [headless](super::headless) software renderer, chosen at runtime by
+//! [`EngineBuilder::headless`](crate::engine::EngineBuilder::headless). The `SDL` renderer is
+//! only available with the `sdl` feature.
+
+#[cfg(feature = "sdl")]
+use super::sdl;
+use super::{headless, FillRule, RendererSettings, Rendering};
+#[cfg(not(feature = "sdl"))]
+use crate::error::Error;
+use crate::{
+    audio::AudioDriver,
+    error::Result,
+    prelude::*,
+    renderer::{TextureRenderer, WindowRenderer},
+    state::clip::Mask,
+};
+use std::rc::Rc;
+
+/// Forwards a method call to the selected renderer backend.
+macro_rules! dispatch {
+    ($self:expr, $renderer:ident => $call:expr) => {
+        match $self {
+            #[cfg(feature = "sdl")]
+            Renderer::Sdl($renderer) => $call,
+            Renderer::Headless($renderer) => $call,
+        }
+    };
+}
+
+/// A [Renderer] backend.
+#[derive(Debug)]
+pub(crate) enum Renderer {
+    /// Hardware accelerated `SDL2` renderer.
+    #[cfg(feature = "sdl")]
+    Sdl(Box<sdl::Renderer>),
+    /// Software renderer drawing to in-memory framebuffers.
+    Headless(Box<headless::Renderer>),
+}
+
+impl Rendering for Renderer {
+    #[inline]
+    fn new(settings: RendererSettings) -> Result<Self> {
+        if settings.headless {
+            return Ok(Self::Headless(Box::new(headless::Renderer::new(settings)?)));
+        }
+        #[cfg(feature = "sdl")]
+        return Ok(Self::Sdl(Box::new(sdl::Renderer::new(settings)?)));
+        #[cfg(not(feature = "sdl"))]
+        Err(Error::Renderer(
+            "windowed rendering requires the `sdl` feature, use `EngineBuilder::headless`".into(),
+        )
+        .into())
+    }
+
+    #[inline]
+    fn clear(&mut self) -> Result<()> {
+        dispatch!(self, r => r.clear())
+    }
+
+    #[inline]
+    fn set_draw_color(&mut self, color: Color) -> Result<()> {
+        dispatch!(self, r => r.set_draw_color(color))
+    }
+
+    #[inline]
+    fn clip(&mut self, rect: Option<Rect<i32>>) -> Result<()> {
+        dispatch!(self, r => r.clip(rect))
+    }
+
+    #[inline]
+    fn blend_mode(&mut self, mode: BlendMode) {
+        dispatch!(self, r => r.blend_mode(mode));
+    }
+
+    #[inline]
+    fn mask(&mut self, mask: Option<Rc<Mask>>) {
+        dispatch!(self, r => r.mask(mask));
+    }
+
+    #[inline]
+    fn present(&mut self) {
+        dispatch!(self, r => r.present());
+    }
+
+    #[inline]
+    fn scale(&mut self, x: f32, y: f32) -> Result<()> {
+        dispatch!(self, r => r.scale(x, y))
+    }
+
+    #[inline]
+    fn font_size(&mut self, size: u32) -> Result<()> {
+        dispatch!(self, r => r.font_size(size))
+    }
+
+    #[inline]
+    fn font_style(&mut self, style: FontStyle) {
+        dispatch!(self, r => r.font_style(style));
+    }
+
+    #[inline]
+    fn font_family(&mut self, font: &Font) -> Result<()> {
+        dispatch!(self, r => r.font_family(font))
+    }
+
+    #[inline]
+    fn clipboard_text(&self) -> String {
+        dispatch!(self, r => r.clipboard_text())
+    }
+
+    #[inline]
+    fn set_clipboard_text(&self, value: &str) -> Result<()> {
+        dispatch!(self, r => r.set_clipboard_text(value))
+    }
+
+    #[inline]
+    fn open_url(&self, url: &str) -> Result<()> {
+        dispatch!(self, r => r.open_url(url))
+    }
+
+    #[inline]
+    fn text(
+        &mut self,
+        position: Point<i32>,
+        text: &str,
+        wrap_width: Option<u32>,
+        angle: Option<f64>,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+        fill: Option<Color>,
+        outline: u16,
+    ) -> Result<(u32, u32)> {
+        dispatch!(self, r => r.text(position, text, wrap_width, angle, center, flipped, fill, outline))
+    }
+
+    #[inline]
+    fn size_of(&self, text: &str, wrap_width: Option<u32>) -> Result<(u32, u32)> {
+        dispatch!(self, r => r.size_of(text, wrap_width))
+    }
+
+    #[inline]
+    fn point(&mut self, p: Point<i32>, color: Color) -> Result<()> {
+        dispatch!(self, r => r.point(p, color))
+    }
+
+    #[inline]
+    fn line(&mut self, line: Line<i32>, smooth: bool, width: u8, color: Color) -> Result<()> {
+        dispatch!(self, r => r.line(line, smooth, width, color))
+    }
+
+    #[inline]
+    fn bezier<I>(&mut self, ps: I, detail: i32, stroke: Option<Color>) -> Result<()>
+    where
+        I: Iterator<Item = Point<i32>>,
+    {
+        dispatch!(self, r => r.bezier(ps, detail, stroke))
+    }
+
+    #[inline]
+    fn triangle(
+        &mut self,
+        tri: Tri<i32>,
+        smooth: bool,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        dispatch!(self, r => r.triangle(tri, smooth, fill, stroke))
+    }
+
+    #[inline]
+    fn rect(
+        &mut self,
+        rect: Rect<i32>,
+        radius: Option<i32>,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        dispatch!(self, r => r.rect(rect, radius, fill, stroke))
+    }
+
+    #[inline]
+    fn quad(
+        &mut self,
+        quad: Quad<i32>,
+        smooth: bool,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        dispatch!(self, r => r.quad(quad, smooth, fill, stroke))
+    }
+
+    #[inline]
+    fn polygon<I>(
+        &mut self,
+        ps: I,
+        smooth: bool,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()>
+    where
+        I: Iterator<Item = Point<i32>>,
+    {
+        dispatch!(self, r => r.polygon(ps, smooth, fill, stroke))
+    }
+
+    #[inline]
+    fn path(
+        &mut self,
+        contours: &[(Vec<Point<i32>>, bool)],
+        smooth: bool,
+        width: u8,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        dispatch!(self, r => r.path(contours, smooth, width, fill, stroke))
+    }
+
+    #[inline]
+    fn fill_polygons(
+        &mut self,
+        polygons: &[Vec<Point<f64>>],
+        rule: FillRule,
+        paint: &Paint,
+        transform: &Transform,
+    ) -> Result<()> {
+        dispatch!(self, r => r.fill_polygons(polygons, rule, paint, transform))
+    }
+
+    #[inline]
+    fn ellipse(
+        &mut self,
+        ellipse: Ellipse<i32>,
+        smooth: bool,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        dispatch!(self, r => r.ellipse(ellipse, smooth, fill, stroke))
+    }
+
+    #[inline]
+    fn arc(
+        &mut self,
+        p: Point<i32>,
+        radius: i32,
+        start: i32,
+        end: i32,
+        mode: ArcMode,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        dispatch!(self, r => r.arc(p, radius, start, end, mode, fill, stroke))
+    }
+
+    #[inline]
+    fn image(
+        &mut self,
+        img: &Image,
+        src: Option<Rect<i32>>,
+        dst: Option<Rect<i32>>,
+        angle: f64,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+        tint: Option<Color>,
+    ) -> Result<()> {
+        dispatch!(self, r => r.image(img, src, dst, angle, center, flipped, tint))
+    }
+
+    #[inline]
+    fn to_bytes(&mut self) -> Result<Vec<u8>> {
+        dispatch!(self, r => r.to_bytes())
+    }
+
+    #[inline]
+    fn target_size(&mut self) -> Result<(u32, u32)> {
+        dispatch!(self, r => r.target_size())
+    }
+
+    #[inline]
+    fn update_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        dispatch!(self, r => r.update_bytes(bytes))
+    }
+
+    #[inline]
+    fn open_controller(&mut self, controller_id: ControllerId) -> Result<()> {
+        dispatch!(self, r => r.open_controller(controller_id))
+    }
+
+    #[inline]
+    fn close_controller(&mut self, controller_id: ControllerId) {
+        dispatch!(self, r => r.close_controller(controller_id));
+    }
+}
+
+impl WindowRenderer for Renderer {
+    #[inline]
+    fn window_count(&self) -> usize {
+        dispatch!(self, r => r.window_count())
+    }
+
+    #[inline]
+    fn primary_window_id(&self) -> WindowId {
+        dispatch!(self, r => r.primary_window_id())
+    }
+
+    #[inline]
+    fn window_id(&self) -> WindowId {
+        dispatch!(self, r => r.window_id())
+    }
+
+    #[inline]
+    fn create_window(&mut self, s: &mut RendererSettings) -> Result<WindowId> {
+        dispatch!(self, r => r.create_window(s))
+    }
+
+    #[inline]
+    fn close_window(&mut self, id: WindowId) -> Result<()> {
+        dispatch!(self, r => r.close_window(id))
+    }
+
+    #[inline]
+    fn cursor(&mut self, cursor: Option<&Cursor>) -> Result<()> {
+        dispatch!(self, r => r.cursor(cursor))
+    }
+
+    #[inline]
+    fn poll_event(&mut self) -> Option<Event> {
+        dispatch!(self, r => r.poll_event())
+    }
+
+    #[inline]
+    fn title(&self) -> &str {
+        dispatch!(self, r => r.title())
+    }
+
+    #[inline]
+    fn set_title(&mut self, title: &str) -> Result<()> {
+        dispatch!(self, r => r.set_title(title))
+    }
+
+    #[inline]
+    fn set_fps(&mut self, fps: f32) -> Result<()> {
+        dispatch!(self, r => r.set_fps(fps))
+    }
+
+    #[inline]
+    fn dimensions(&self) -> Result<(u32, u32)> {
+        dispatch!(self, r => r.dimensions())
+    }
+
+    #[inline]
+    fn window_dimensions(&self) -> Result<(u32, u32)> {
+        dispatch!(self, r => r.window_dimensions())
+    }
+
+    #[inline]
+    fn window_position(&self) -> Result<(i32, i32)> {
+        dispatch!(self, r => r.window_position())
+    }
+
+    #[inline]
+    fn set_window_dimensions(&mut self, dimensions: (u32, u32)) -> Result<()> {
+        dispatch!(self, r => r.set_window_dimensions(dimensions))
+    }
+
+    #[inline]
+    fn viewport(&self) -> Result<Rect<i32>> {
+        dispatch!(self, r => r.viewport())
+    }
+
+    #[inline]
+    fn set_viewport(&mut self, rect: Option<Rect<i32>>) -> Result<()> {
+        dispatch!(self, r => r.set_viewport(rect))
+    }
+
+    #[inline]
+    fn display_dimensions(&self) -> Result<(u32, u32)> {
+        dispatch!(self, r => r.display_dimensions())
+    }
+
+    #[inline]
+    fn fullscreen(&self) -> Result<bool> {
+        dispatch!(self, r => r.fullscreen())
+    }
+
+    #[inline]
+    fn set_fullscreen(&mut self, val: bool) -> Result<()> {
+        dispatch!(self, r => r.set_fullscreen(val))
+    }
+
+    #[inline]
+    fn vsync(&self) -> bool {
+        dispatch!(self, r => r.vsync())
+    }
+
+    #[inline]
+    fn set_vsync(&mut self, val: bool) -> Result<WindowId> {
+        dispatch!(self, r => r.set_vsync(val))
+    }
+
+    #[inline]
+    fn set_window_target(&mut self, id: WindowId) -> Result<()> {
+        dispatch!(self, r => r.set_window_target(id))
+    }
+
+    #[inline]
+    fn reset_window_target(&mut self) {
+        dispatch!(self, r => r.reset_window_target());
+    }
+
+    #[inline]
+    fn show(&mut self) -> Result<()> {
+        dispatch!(self, r => r.show())
+    }
+
+    #[inline]
+    fn hide(&mut self) -> Result<()> {
+        dispatch!(self, r => r.hide())
+    }
+}
+
+impl TextureRenderer for Renderer {
+    #[inline]
+    fn create_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: Option<PixelFormat>,
+    ) -> Result<TextureId> {
+        dispatch!(self, r => r.create_texture(width, height, format))
+    }
+
+    #[inline]
+    fn delete_texture(&mut self, texture_id: TextureId) -> Result<()> {
+        dispatch!(self, r => r.delete_texture(texture_id))
+    }
+
+    #[inline]
+    fn update_texture<P: AsRef<[u8]>>(
+        &mut self,
+        texture_id: TextureId,
+        rect: Option<Rect<i32>>,
+        pixels: P,
+        pitch: usize,
+    ) -> Result<()> {
+        dispatch!(self, r => r.update_texture(texture_id, rect, pixels, pitch))
+    }
+
+    #[inline]
+    fn texture(
+        &mut self,
+        texture_id: TextureId,
+        src: Option<Rect<i32>>,
+        dst: Option<Rect<i32>>,
+        angle: f64,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+        tint: Option<Color>,
+    ) -> Result<()> {
+        dispatch!(self, r => r.texture(texture_id, src, dst, angle, center, flipped, tint))
+    }
+
+    #[inline]
+    fn texture_target(&self) -> Option<TextureId> {
+        dispatch!(self, r => r.texture_target())
+    }
+
+    #[inline]
+    fn set_texture_target(&mut self, id: TextureId) -> Result<()> {
+        dispatch!(self, r => r.set_texture_target(id))
+    }
+
+    #[inline]
+    fn clear_texture_target(&mut self) {
+        dispatch!(self, r => r.clear_texture_target());
+    }
+
+    #[inline]
+    fn has_texture_target(&self) -> bool {
+        dispatch!(self, r => r.has_texture_target())
+    }
+
+    #[inline]
+    fn clear_texture_cache(&mut self) {
+        dispatch!(self, r => r.clear_texture_cache());
+    }
+}
+
+impl AudioDriver for Renderer {
+    #[inline]
+    fn enqueue_audio(&mut self, samples: &[f32]) -> Result<()> {
+        dispatch!(self, r => r.enqueue_audio(samples))
+    }
+
+    #[inline]
+    fn clear_audio(&mut self) {
+        dispatch!(self, r => r.clear_audio());
+    }
+
+    #[inline]
+    fn audio_status(&self) -> AudioStatus {
+        dispatch!(self, r => r.audio_status())
+    }
+
+    #[inline]
+    fn audio_driver(&self) -> &'static str {
+        dispatch!(self, r => r.audio_driver())
+    }
+
+    #[inline]
+    fn audio_sample_rate(&self) -> i32 {
+        dispatch!(self, r => r.audio_sample_rate())
+    }
+
+    #[inline]
+    fn audio_queued_size(&self) -> u32 {
+        dispatch!(self, r => r.audio_queued_size())
+    }
+
+    #[inline]
+    fn audio_size(&self) -> u32 {
+        dispatch!(self, r => r.audio_size())
+    }
+
+    #[inline]
+    fn resume_audio(&mut self) {
+        dispatch!(self, r => r.resume_audio());
+    }
+
+    #[inline]
+    fn pause_audio(&mut self) {
+        dispatch!(self, r => r.pause_audio());
+    }
+
+    #[allow(single_use_lifetimes)]
+    #[inline]
+    fn open_playback<'a, CB, F, D>(
+        &self,
+        device: D,
+        desired_spec: &AudioSpecDesired,
+        get_callback: F,
+    ) -> Result<AudioDevice<CB>>
+    where
+        CB: AudioCallback,
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        dispatch!(self, r => r.open_playback(device, desired_spec, get_callback))
+    }
+
+    #[allow(single_use_lifetimes)]
+    #[inline]
+    fn open_capture<'a, CB, F, D>(
+        &self,
+        device: D,
+        desired_spec: &AudioSpecDesired,
+        get_callback: F,
+    ) -> Result<AudioDevice<CB>>
+    where
+        CB: AudioCallback,
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        dispatch!(self, r => r.open_capture(device, desired_spec, get_callback))
+    }
+}
@@ -0,0 +1,583 @@
+//! Headless Renderer
+//!
+//! A software renderer that draws into in-memory `RGBA` framebuffers without opening any windows
+//! or requiring a display, audio device or GPU. Useful for automated testing, servers and
+//! generating images offline.
+//!
+//! Windows are simulated as framebuffers and the only system events generated are window events
+//! for visible windows being shown and gaining focus when created. Text is drawn using an embedded
+//! bitmap font, so font families are accepted but not loaded.
+
+use crate::{
+    error::{Error, Result},
+    prelude::*,
+    renderer::{FillRule, RendererSettings, Rendering},
+    state::clip::Mask,
+};
+use canvas::{Canvas, Source, Vertex};
+use log::debug;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    rc::Rc,
+};
+use window::WindowCanvas;
+
+mod audio;
+mod canvas;
+
+#[cfg(not(feature = "sdl"))]
+pub use audio::{AudioDevice, AudioFormatNum};
+mod font;
+mod texture;
+mod window;
+
+/// A headless [Renderer] implementation.
+#[derive(Debug)]
+pub(crate) struct Renderer {
+    title: String,
+    settings: RendererSettings,
+    clipboard: RefCell<String>,
+    audio: audio::AudioQueue,
+    blend_mode: BlendMode,
+    mask: Option<Rc<Mask>>,
+    font_size: u32,
+    font_style: FontStyle,
+    primary_window_id: WindowId,
+    window_target: WindowId,
+    texture_target: Option<TextureId>,
+    windows: HashMap<WindowId, WindowCanvas>,
+    textures: HashMap<TextureId, Canvas>,
+    events: VecDeque<Event>,
+    next_window_id: u32,
+    next_texture_id: usize,
+}
+
+impl Renderer {
+    /// Returns the current render target canvas.
+    fn canvas(&self) -> Result<&Canvas> {
+        match self.texture_target {
+            Some(texture_id) => Ok(self
+                .textures
+                .get(&texture_id)
+                .ok_or(Error::InvalidTexture(texture_id))?),
+            None => Ok(&self.window_canvas()?.canvas),
+        }
+    }
+
+    /// Returns the current render target canvas.
+    fn canvas_mut(&mut self) -> Result<&mut Canvas> {
+        let blend_mode = self.blend_mode;
+        let mask = self.mask.clone();
+        let canvas = match self.texture_target {
+            Some(texture_id) => self
+                .textures
+                .get_mut(&texture_id)
+                .ok_or(Error::InvalidTexture(texture_id))?,
+            None => &mut self.window_canvas_mut()?.canvas,
+        };
+        canvas.set_blend_mode(blend_mode);
+        canvas.set_mask(mask);
+        Ok(canvas)
+    }
+}
+
+impl Renderer {
+    /// Queues the window events a system would send when a visible window is created.
+    fn window_created(&mut self, id: WindowId) {
+        if self.windows.get(&id).is_some_and(|window| !window.hidden) {
+            for win_event in [WindowEvent::Shown, WindowEvent::FocusGained] {
+                self.events.push_back(Event::Window {
+                    window_id: *id,
+                    win_event,
+                });
+            }
+        }
+    }
+}
+
+impl Rendering for Renderer {
+    /// Initializes the headless `Renderer` using the given settings and creates a new window
+    /// framebuffer.
+    #[inline]
+    fn new(mut s: RendererSettings) -> Result<Self> {
+        debug!("Initializing HeadlessRenderer");
+
+        let primary_window = WindowCanvas::new(WindowId(1), &mut s)?;
+        let window_target = primary_window.id;
+        let mut windows = HashMap::new();
+        windows.insert(window_target, primary_window);
+
+        let mut renderer = Self {
+            title: s.title.clone(),
+            clipboard: RefCell::new(String::new()),
+            audio: audio::AudioQueue::new(&s),
+            settings: s,
+            blend_mode: BlendMode::None,
+            mask: None,
+            font_size: 14,
+            font_style: FontStyle::NORMAL,
+            primary_window_id: window_target,
+            window_target,
+            texture_target: None,
+            windows,
+            textures: HashMap::new(),
+            events: VecDeque::new(),
+            next_window_id: window_target.0 + 1,
+            next_texture_id: 0,
+        };
+        renderer.window_created(window_target);
+        Ok(renderer)
+    }
+
+    /// Clears the canvas to the current clear color.
+    #[inline]
+    fn clear(&mut self) -> Result<()> {
+        self.canvas_mut()?.clear();
+        Ok(())
+    }
+
+    /// Sets the color used by the renderer to draw to the current canvas.
+    #[inline]
+    fn set_draw_color(&mut self, color: Color) -> Result<()> {
+        self.canvas_mut()?.set_draw_color(color);
+        Ok(())
+    }
+
+    /// Sets the clip rect used by the renderer to draw to the current canvas.
+    #[inline]
+    fn clip(&mut self, rect: Option<Rect<i32>>) -> Result<()> {
+        self.canvas_mut()?.set_clip(rect);
+        Ok(())
+    }
+
+    /// Sets the blend mode used by the renderer to drawing.
+    #[inline]
+    fn blend_mode(&mut self, mode: BlendMode) {
+        self.blend_mode = mode;
+    }
+
+    /// Sets the clip mask used by the renderer to draw to the current canvas.
+    #[inline]
+    fn mask(&mut self, mask: Option<Rc<Mask>>) {
+        self.mask = mask;
+    }
+
+    /// Updates the canvas from the current back buffer. Framebuffers are always up to date, so
+    /// this does nothing.
+    #[inline]
+    fn present(&mut self) {}
+
+    /// Set the rendering scale of the current canvas. Drawing coordinates are scaled by x/y
+    /// factors before being drawn to the canvas.
+    #[inline]
+    fn scale(&mut self, x: f32, y: f32) -> Result<()> {
+        self.canvas_mut()?.set_scale(x, y)
+    }
+
+    /// Set the font size for drawing to the current canvas.
+    #[inline]
+    fn font_size(&mut self, size: u32) -> Result<()> {
+        self.font_size = size;
+        Ok(())
+    }
+
+    /// Set the font style for drawing to the current canvas.
+    #[inline]
+    fn font_style(&mut self, style: FontStyle) {
+        self.font_style = style;
+    }
+
+    /// Set the font family for drawing to the current canvas. The headless renderer always uses
+    /// its embedded bitmap font.
+    #[inline]
+    fn font_family(&mut self, _font: &Font) -> Result<()> {
+        Ok(())
+    }
+
+    /// Get clipboard text from the system clipboard.
+    #[inline]
+    fn clipboard_text(&self) -> String {
+        self.clipboard.borrow().clone()
+    }
+
+    /// Set clipboard text to the system clipboard.
+    #[inline]
+    fn set_clipboard_text(&self, value: &str) -> Result<()> {
+        value.clone_into(&mut self.clipboard.borrow_mut());
+        Ok(())
+    }
+
+    /// Open a URL in the default system browser. Does nothing when headless.
+    #[inline]
+    fn open_url(&self, url: &str) -> Result<()> {
+        debug!("Ignoring request to open url: {}", url);
+        Ok(())
+    }
+
+    /// Draw text to the current canvas.
+    #[inline]
+    fn text(
+        &mut self,
+        pos: Point<i32>,
+        text: &str,
+        wrap_width: Option<u32>,
+        angle: Option<f64>,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+        fill: Option<Color>,
+        outline: u16,
+    ) -> Result<(u32, u32)> {
+        if text.is_empty() {
+            return self.size_of(text, wrap_width);
+        }
+        if let Some(fill) = fill {
+            let (pixels, width, height) = font::render(
+                text,
+                self.font_size,
+                self.font_style,
+                wrap_width,
+                fill,
+                outline,
+            );
+            let src = Source {
+                pixels: &pixels,
+                width,
+                height,
+                format: PixelFormat::Rgba,
+            };
+            let dst = Rect::new(pos.x(), pos.y(), width as i32, height as i32);
+            let angle = angle.unwrap_or(0.0);
+            self.canvas_mut()?.copy(
+                src,
+                None,
+                Some(dst),
+                angle,
+                center,
+                flipped,
+                None,
+                BlendMode::Blend,
+            );
+            Ok((width, height))
+        } else {
+            self.size_of(text, wrap_width)
+        }
+    }
+
+    /// Returns the rendered dimensions of the given text using the current font
+    /// as `(width, height)`.
+    #[inline]
+    fn size_of(&self, text: &str, wrap_width: Option<u32>) -> Result<(u32, u32)> {
+        Ok(font::size_of(text, self.font_size, wrap_width, 0))
+    }
+
+    /// Draw a pixel to the current canvas.
+    #[inline]
+    fn point(&mut self, p: Point<i32>, color: Color) -> Result<()> {
+        let canvas = self.canvas_mut()?;
+        let bounds = canvas.bounds();
+        canvas.plot(
+            &bounds,
+            p.x(),
+            p.y(),
+            color.channels(),
+            canvas.shape_blend_mode(),
+        );
+        Ok(())
+    }
+
+    /// Draw a line to the current canvas.
+    #[inline]
+    fn line(&mut self, line: Line<i32>, _smooth: bool, width: u8, color: Color) -> Result<()> {
+        let canvas = self.canvas_mut()?;
+        canvas.thick_line(line.start().into(), line.end().into(), width, color);
+        Ok(())
+    }
+
+    /// Draw a cubic Bezier curve to the current canvas.
+    #[inline]
+    fn bezier<I>(&mut self, ps: I, detail: i32, stroke: Option<Color>) -> Result<()>
+    where
+        I: Iterator<Item = Point<i32>>,
+    {
+        if let Some(stroke) = stroke {
+            let points: Vec<Vertex> = ps.map(vertex).collect();
+            let vertices = canvas::bezier_vertices(&points, detail);
+            self.canvas_mut()?.polyline(&vertices, false, stroke);
+        }
+        Ok(())
+    }
+
+    /// Draw a triangle to the current canvas.
+    #[inline]
+    fn triangle(
+        &mut self,
+        tri: Tri<i32>,
+        smooth: bool,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        self.polygon(tri.points().into_iter(), smooth, fill, stroke)
+    }
+
+    /// Draw a rectangle to the current canvas.
+    #[inline]
+    fn rect(
+        &mut self,
+        rect: Rect<i32>,
+        radius: Option<i32>,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        let canvas = self.canvas_mut()?;
+        let [x, y, width, height] = rect.map(f64::from);
+        let vertices = radius.map_or_else(
+            || {
+                vec![
+                    [x, y],
+                    [x + width, y],
+                    [x + width, y + height],
+                    [x, y + height],
+                ]
+            },
+            |radius| canvas::rounded_rect_vertices(rect, radius),
+        );
+        if let Some(fill) = fill {
+            // Rect vertices lie on pixel edges rather than pixel centers, so sampling pixel
+            // centers fills exactly `width` by `height` pixels, matching polygon fills through
+            // the same corners.
+            canvas.fill_polygon(&[&vertices], fill);
+        }
+        if let Some(stroke) = stroke {
+            // Outline the outermost pixels covered by the fill.
+            let outline: Vec<Vertex> = vertices
+                .iter()
+                .map(|&[vx, vy]| [vx.min(x + width - 1.0), vy.min(y + height - 1.0)])
+                .collect();
+            canvas.polyline(&outline, true, stroke);
+        }
+        Ok(())
+    }
+
+    /// Draw a quadrilateral to the current canvas.
+    #[inline]
+    fn quad(
+        &mut self,
+        quad: Quad<i32>,
+        smooth: bool,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        self.polygon(quad.points().into_iter(), smooth, fill, stroke)
+    }
+
+    /// Draw a polygon to the current canvas.
+    #[inline]
+    fn polygon<I>(
+        &mut self,
+        ps: I,
+        _smooth: bool,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()>
+    where
+        I: Iterator<Item = Point<i32>>,
+    {
+        let canvas = self.canvas_mut()?;
+        let vertices: Vec<Vertex> = ps.map(vertex).collect();
+        if let Some(fill) = fill {
+            canvas.fill_polygon(&[&pixel_centers(&vertices)], fill);
+        }
+        if let Some(stroke) = stroke {
+            canvas.polyline(&vertices, true, stroke);
+        }
+        Ok(())
+    }
+
+    /// Draw a path made up of one or more contours to the current canvas.
+    #[inline]
+    fn path(
+        &mut self,
+        contours: &[(Vec<Point<i32>>, bool)],
+        _smooth: bool,
+        width: u8,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        let canvas = self.canvas_mut()?;
+        let vertices: Vec<Vec<Vertex>> = contours
+            .iter()
+            .map(|(points, _)| points.iter().copied().map(vertex).collect())
+            .collect();
+        if let Some(fill) = fill {
+            let rings: Vec<Vec<Vertex>> = vertices.iter().map(|v| pixel_centers(v)).collect();
+            let rings: Vec<&[Vertex]> = rings.iter().map(Vec::as_slice).collect();
+            canvas.fill_polygon(&rings, fill);
+        }
+        if let Some(stroke) = stroke {
+            for ((points, closed), vertices) in contours.iter().zip(&vertices) {
+                if width > 1 {
+                    let closing = points.last().zip(points.first()).filter(|_| *closed);
+                    let segments = points.windows(2).map(|w| (&w[0], &w[1]));
+                    for (p1, p2) in segments.chain(closing) {
+                        canvas.thick_line(p1.coords(), p2.coords(), width, stroke);
+                    }
+                } else {
+                    canvas.polyline(vertices, *closed, stroke);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fill one or more polygons to the current canvas as a single shape.
+    #[inline]
+    fn fill_polygons(
+        &mut self,
+        polygons: &[Vec<Point<f64>>],
+        rule: FillRule,
+        paint: &Paint,
+        transform: &Transform,
+    ) -> Result<()> {
+        let canvas = self.canvas_mut()?;
+        let polygons: Vec<Vec<Vertex>> = polygons
+            .iter()
+            .map(|polygon| {
+                polygon
+                    .iter()
+                    .map(|p| p.coords().map(|v| v + 0.5))
+                    .collect()
+            })
+            .collect();
+        let polygons: Vec<&[Vertex]> = polygons.iter().map(Vec::as_slice).collect();
+        if let Paint::Solid(color) = paint {
+            let color = color.channels();
+            canvas.fill(&polygons, rule, |_, _| color);
+        } else {
+            canvas.fill(&polygons, rule, |x, y| {
+                let p = transform.transform_point([f64::from(x), f64::from(y)]);
+                paint.color_at(p).channels()
+            });
+        }
+        Ok(())
+    }
+
+    /// Draw a ellipse to the current canvas.
+    #[inline]
+    fn ellipse(
+        &mut self,
+        ellipse: Ellipse<i32>,
+        _smooth: bool,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        let canvas = self.canvas_mut()?;
+        let [x, y, width, height] = ellipse.map(f64::from);
+        let vertices = canvas::ellipse_vertices(x, y, width / 2.0, height / 2.0);
+        if let Some(fill) = fill {
+            canvas.fill_polygon(&[&pixel_centers(&vertices)], fill);
+        }
+        if let Some(stroke) = stroke {
+            canvas.polyline(&vertices, true, stroke);
+        }
+        Ok(())
+    }
+
+    /// Draw an arc to the current canvas.
+    #[inline]
+    fn arc(
+        &mut self,
+        p: Point<i32>,
+        radius: i32,
+        start: i32,
+        end: i32,
+        mode: ArcMode,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        let canvas = self.canvas_mut()?;
+        let [x, y] = vertex(p);
+        let mut vertices =
+            canvas::arc_vertices(x, y, f64::from(radius), f64::from(start), f64::from(end));
+        match mode {
+            ArcMode::Default => {
+                if let Some(stroke) = stroke {
+                    canvas.polyline(&vertices, false, stroke);
+                }
+            }
+            ArcMode::Pie => {
+                vertices.push([x, y]);
+                if let Some(fill) = fill {
+                    canvas.fill_polygon(&[&pixel_centers(&vertices)], fill);
+                }
+                if let Some(stroke) = stroke {
+                    canvas.polyline(&vertices, true, stroke);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Draw an image to the current canvas, optionally rotated about a `center`, flipped or tinted
+    #[inline]
+    fn image(
+        &mut self,
+        img: &Image,
+        src: Option<Rect<i32>>,
+        dst: Option<Rect<i32>>,
+        angle: f64,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+        tint: Option<Color>,
+    ) -> Result<()> {
+        let source = Source {
+            pixels: img.as_bytes(),
+            width: img.width(),
+            height: img.height(),
+            format: img.format(),
+        };
+        let mode = self.blend_mode;
+        self.canvas_mut()?
+            .copy(source, src, dst, angle, center, flipped, tint, mode);
+        Ok(())
+    }
+
+    /// Return the current rendered target pixels as an array of bytes.
+    #[inline]
+    fn to_bytes(&mut self) -> Result<Vec<u8>> {
+        Ok(self.canvas()?.as_bytes().to_vec())
+    }
+
+    /// Returns the size in pixels of the current render target as `(width, height)`.
+    #[inline]
+    fn target_size(&mut self) -> Result<(u32, u32)> {
+        Ok(self.canvas()?.dimensions())
+    }
+
+    /// Replace the current rendered target pixels with an array of `RGBA` bytes.
+    #[inline]
+    fn update_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.canvas_mut()?.set_bytes(bytes)
+    }
+
+    /// Connect a controller with the given joystick index to start receiving events. Controllers
+    /// are not supported when headless, so this does nothing.
+    fn open_controller(&mut self, _controller_id: ControllerId) -> Result<()> {
+        Ok(())
+    }
+
+    /// Disconnect a controller with the given joystick index to stop receiving events.
+    fn close_controller(&mut self, _controller_id: ControllerId) {}
+}
+
+/// Convert a [`Point<i32>`] to a floating point [Vertex].
+#[inline]
+fn vertex(p: Point<i32>) -> Vertex {
+    [f64::from(p.x()), f64::from(p.y())]
+}
+
+/// Offsets vertices given in pixel coordinates to pixel centers, so that fills line up with
+/// outlines drawn through the same vertices.
+fn pixel_centers(vertices: &[Vertex]) -> Vec<Vertex> {
+    vertices.iter().map(|v| v.map(|v| v + 0.5)).collect()
+}
@@ -0,0 +1,178 @@
+//! Headless Audio
+
+use super::Renderer;
+#[cfg(not(feature = "sdl"))]
+use crate::audio::AudioDeviceDriver;
+use crate::{
+    audio::AudioDriver,
+    error::{Error, Result},
+    prelude::*,
+    renderer::RendererSettings,
+};
+#[cfg(not(feature = "sdl"))]
+use std::{convert::Infallible, marker::PhantomData};
+
+const DEFAULT_SAMPLE_RATE: i32 = 48_000;
+const DEFAULT_CHANNELS: u8 = 1;
+const DEFAULT_BUFFER_SIZE: u16 = 4096;
+
+/// Audio callback or playback device that can be paused and resumed. Audio devices can't be
+/// opened without the `sdl` feature, so this can never be constructed.
+#[cfg(not(feature = "sdl"))]
+#[derive(Debug)]
+pub struct AudioDevice<CB: AudioCallback>(Infallible, PhantomData<CB>);
+
+#[cfg(not(feature = "sdl"))]
+impl<CB: AudioCallback> AudioDeviceDriver for AudioDevice<CB> {
+    fn status(&self) -> AudioStatus {
+        match self.0 {}
+    }
+
+    fn driver(&self) -> &'static str {
+        match self.0 {}
+    }
+
+    fn spec(&self) -> AudioSpec {
+        match self.0 {}
+    }
+
+    fn resume(&self) {
+        match self.0 {}
+    }
+
+    fn pause(&self) {
+        match self.0 {}
+    }
+}
+
+/// Represents a valid Channel format for generic audio sample types.
+#[cfg(not(feature = "sdl"))]
+pub trait AudioFormatNum {}
+
+#[cfg(not(feature = "sdl"))]
+impl AudioFormatNum for i8 {}
+#[cfg(not(feature = "sdl"))]
+impl AudioFormatNum for u8 {}
+#[cfg(not(feature = "sdl"))]
+impl AudioFormatNum for i16 {}
+#[cfg(not(feature = "sdl"))]
+impl AudioFormatNum for u16 {}
+#[cfg(not(feature = "sdl"))]
+impl AudioFormatNum for i32 {}
+#[cfg(not(feature = "sdl"))]
+impl AudioFormatNum for f32 {}
+
+/// A simulated audio queue. Samples are consumed immediately while playing and retained while
+/// paused, so queue sizes behave predictably without an audio device.
+#[derive(Debug)]
+pub(super) struct AudioQueue {
+    samples: Vec<f32>,
+    status: AudioStatus,
+    sample_rate: i32,
+    size: u32,
+}
+
+impl AudioQueue {
+    pub(super) fn new(s: &RendererSettings) -> Self {
+        let channels = s.audio_channels.unwrap_or(DEFAULT_CHANNELS);
+        let buffer_size = s.audio_buffer_size.unwrap_or(DEFAULT_BUFFER_SIZE);
+        Self {
+            samples: Vec::new(),
+            status: AudioStatus::Paused,
+            sample_rate: s.audio_sample_rate.unwrap_or(DEFAULT_SAMPLE_RATE),
+            size: u32::from(buffer_size) * u32::from(channels) * 4,
+        }
+    }
+}
+
+impl AudioDriver for Renderer {
+    /// Add audio samples to the audio buffer queue.
+    #[inline]
+    fn enqueue_audio(&mut self, samples: &[f32]) -> Result<()> {
+        if self.audio.status != AudioStatus::Playing {
+            self.audio.samples.extend_from_slice(samples);
+        }
+        Ok(())
+    }
+
+    /// Clear audio samples from the audio buffer queue.
+    #[inline]
+    fn clear_audio(&mut self) {
+        self.audio.samples.clear();
+    }
+
+    /// Return the status of the current audio device.
+    #[inline]
+    fn audio_status(&self) -> AudioStatus {
+        self.audio.status
+    }
+
+    /// Return the driver of current audio queue device.
+    fn audio_driver(&self) -> &'static str {
+        "headless"
+    }
+
+    /// Return the sample rate of the current audio device.
+    fn audio_sample_rate(&self) -> i32 {
+        self.audio.sample_rate
+    }
+
+    /// Returns the queued buffer size (in bytes) of the current audio queue device.
+    fn audio_queued_size(&self) -> u32 {
+        (self.audio.samples.len() * std::mem::size_of::<f32>()) as u32
+    }
+
+    /// Returns the buffer size (in bytes) of the current audio queue device.
+    fn audio_size(&self) -> u32 {
+        self.audio.size
+    }
+
+    /// Resume playback of the current audio device, consuming any queued samples.
+    #[inline]
+    fn resume_audio(&mut self) {
+        self.audio.samples.clear();
+        self.audio.status = AudioStatus::Playing;
+    }
+
+    /// Pause playback of the current audio device.
+    #[inline]
+    fn pause_audio(&mut self) {
+        self.audio.status = AudioStatus::Paused;
+    }
+
+    /// Opens and returns an audio callback device for playback. Audio devices are not available
+    /// when headless, so an error is always returned.
+    #[allow(single_use_lifetimes)]
+    #[inline]
+    fn open_playback<'a, CB, F, D>(
+        &self,
+        _device: D,
+        _desired_spec: &AudioSpecDesired,
+        _get_callback: F,
+    ) -> Result<AudioDevice<CB>>
+    where
+        CB: AudioCallback,
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        Err(Error::Renderer("audio playback devices are not supported when headless".into()).into())
+    }
+
+    /// Opens and returns an audio capture device for recording. Audio devices are not available
+    /// when headless, so an error is always returned.
+    #[allow(single_use_lifetimes)]
+    #[inline]
+    fn open_capture<'a, CB, F, D>(
+        &self,
+        _device: D,
+        _desired_spec: &AudioSpecDesired,
+        _get_callback: F,
+    ) -> Result<AudioDevice<CB>>
+    where
+        CB: AudioCallback,
+        F: FnOnce(AudioSpec) -> CB,
+        D: Into<Option<&'a str>>,
+    {
+        Err(Error::Renderer("audio capture devices are not supported when headless".into()).into())
+    }
+}
@@ -0,0 +1,651 @@
+//! Software rasterized canvas used by the headless [Renderer](super::Renderer).
+//!
+//! All drawing coordinates are logical coordinates relative to the current viewport, matching
+//! how the `SDL` canvas behaves. Each logical pixel is expanded by the current render scale when
+//! written to the underlying `RGBA` framebuffer.
+
+use crate::{
+    error::Result,
+    image::{blend, mul},
+    prelude::*,
+    renderer::FillRule,
+    state::clip::Mask,
+};
+use anyhow::anyhow;
+use std::{f64::consts::PI, fmt, rc::Rc};
+
+/// A floating point vertex used to rasterize shapes.
+pub(crate) type Vertex = [f64; 2];
+
+/// Source pixel data to copy onto a [Canvas].
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Source<'a> {
+    pub(crate) pixels: &'a [u8],
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) format: PixelFormat,
+}
+
+/// The drawable region of a [Canvas] in logical coordinates relative to the viewport, computed
+/// once per primitive and passed to [`Canvas::plot`].
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Bounds {
+    left: i32,
+    top: i32,
+    /// Exclusive right edge.
+    right: i32,
+    /// Exclusive bottom edge.
+    bottom: i32,
+    /// Viewport position added to plotted coordinates.
+    origin: [i32; 2],
+}
+
+/// An `RGBA` framebuffer along with the render state `SDL` keeps for each canvas.
+#[derive(Clone)]
+pub(crate) struct Canvas {
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    pixels: Vec<u8>,
+    draw_color: Color,
+    clip: Option<Rect<i32>>,
+    viewport: Option<Rect<i32>>,
+    scale: (f32, f32),
+    blend_mode: BlendMode,
+    mask: Option<Rc<Mask>>,
+}
+
+impl Canvas {
+    /// Creates a new, fully transparent canvas. `format` determines how pixel data passed to
+    /// [`Canvas::update`] is interpreted.
+    pub(crate) fn new(width: u32, height: u32, format: PixelFormat) -> Self {
+        Self {
+            width,
+            height,
+            format,
+            pixels: vec![0; 4 * width as usize * height as usize],
+            draw_color: Color::BLACK,
+            clip: None,
+            viewport: None,
+            scale: (1.0, 1.0),
+            blend_mode: BlendMode::None,
+            mask: None,
+        }
+    }
+
+    /// Returns the canvas dimensions as `(width, height)`.
+    #[inline]
+    pub(crate) const fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the `RGBA` framebuffer.
+    #[inline]
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Replaces the `RGBA` framebuffer, ignoring the viewport and clip rect.
+    ///
+    /// # Errors
+    ///
+    /// If `bytes` doesn't match the size of the framebuffer, then an error is returned.
+    pub(crate) fn set_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        if bytes.len() != self.pixels.len() {
+            return Err(anyhow!(
+                "invalid pixel data for canvas update: expected {} bytes, got {}",
+                self.pixels.len(),
+                bytes.len()
+            ));
+        }
+        self.pixels.copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Resizes the canvas, discarding the current contents.
+    pub(crate) fn resize(&mut self, width: u32, height: u32) {
+        *self = Self {
+            width,
+            height,
+            pixels: vec![0; 4 * width as usize * height as usize],
+            ..self.clone()
+        };
+    }
+
+    /// Sets the color used by [`Canvas::clear`].
+    #[inline]
+    pub(crate) fn set_draw_color(&mut self, color: Color) {
+        self.draw_color = color;
+    }
+
+    /// Sets the [`BlendMode`] used to draw shapes.
+    #[inline]
+    pub(crate) fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.blend_mode = mode;
+    }
+
+    /// Returns the [`BlendMode`] used to draw shapes, which are always alpha blended unless
+    /// another mode is set.
+    #[inline]
+    pub(crate) const fn shape_blend_mode(&self) -> BlendMode {
+        match self.blend_mode {
+            BlendMode::None => BlendMode::Blend,
+            mode => mode,
+        }
+    }
+
+    /// Sets the clip mask, relative to the viewport.
+    #[inline]
+    pub(crate) fn set_mask(&mut self, mask: Option<Rc<Mask>>) {
+        self.mask = mask;
+    }
+
+    /// Sets the clip rect, relative to the viewport.
+    #[inline]
+    pub(crate) fn set_clip(&mut self, rect: Option<Rect<i32>>) {
+        self.clip = rect;
+    }
+
+    /// Returns the current viewport in logical coordinates.
+    pub(crate) fn viewport(&self) -> Rect<i32> {
+        self.viewport.unwrap_or_else(|| {
+            let (sx, sy) = self.scale;
+            let width = (self.width as f32 / sx) as i32;
+            let height = (self.height as f32 / sy) as i32;
+            Rect::new(0, 0, width, height)
+        })
+    }
+
+    /// Sets the viewport in logical coordinates, or resets it to the full canvas if `None`.
+    #[inline]
+    pub(crate) fn set_viewport(&mut self, rect: Option<Rect<i32>>) {
+        self.viewport = rect;
+    }
+
+    /// Sets the render scale.
+    ///
+    /// # Errors
+    ///
+    /// If either scale factor is not a positive number, then an error is returned.
+    pub(crate) fn set_scale(&mut self, x: f32, y: f32) -> Result<()> {
+        if x <= 0.0 || y <= 0.0 || !x.is_finite() || !y.is_finite() {
+            return Err(anyhow!("invalid render scale: ({x}, {y})"));
+        }
+        self.scale = (x, y);
+        Ok(())
+    }
+
+    /// Fills the entire canvas with the draw color, ignoring the viewport and clip rect.
+    pub(crate) fn clear(&mut self) {
+        let color = self.draw_color.channels();
+        for pixel in self.pixels.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&color);
+        }
+    }
+
+    /// Replaces pixels within `rect` with `pixels` laid out in the canvas [`PixelFormat`] with
+    /// `pitch` bytes per row.
+    ///
+    /// # Errors
+    ///
+    /// If `pixels` is too small for the given `rect` and `pitch`, then an error is returned.
+    pub(crate) fn update(
+        &mut self,
+        rect: Option<Rect<i32>>,
+        pixels: &[u8],
+        pitch: usize,
+    ) -> Result<()> {
+        let rect = rect.unwrap_or_else(|| Rect::new(0, 0, self.width as i32, self.height as i32));
+        let channels = self.format.channels();
+        let width = rect.width().max(0) as usize;
+        let height = rect.height().max(0) as usize;
+        if height > 0 && pixels.len() < (height - 1) * pitch + width * channels {
+            return Err(anyhow!(
+                "invalid pixel data for texture update: expected {} bytes, got {}",
+                height * pitch,
+                pixels.len()
+            ));
+        }
+        for row in 0..height {
+            let y = rect.y() + row as i32;
+            if y < 0 || y >= self.height as i32 {
+                continue;
+            }
+            for col in 0..width {
+                let x = rect.x() + col as i32;
+                if x < 0 || x >= self.width as i32 {
+                    continue;
+                }
+                let src = row * pitch + col * channels;
+                let dst = 4 * (y as usize * self.width as usize + x as usize);
+                let color = self.format.to_rgba(&pixels[src..src + channels]);
+                self.pixels[dst..dst + 4].copy_from_slice(&color);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the drawable [Bounds], limited by the viewport and clip rect.
+    pub(crate) fn bounds(&self) -> Bounds {
+        let viewport = self.viewport();
+        let (mut left, mut top) = (0, 0);
+        let (mut right, mut bottom) = (viewport.width(), viewport.height());
+        if let Some(clip) = self.clip {
+            left = left.max(clip.x());
+            top = top.max(clip.y());
+            right = right.min(clip.x() + clip.width());
+            bottom = bottom.min(clip.y() + clip.height());
+        }
+        Bounds {
+            left,
+            top,
+            right,
+            bottom,
+            origin: [viewport.x(), viewport.y()],
+        }
+    }
+
+    /// Blends a single logical pixel onto the canvas, skipping pixels outside `bounds`.
+    pub(crate) fn plot(
+        &mut self,
+        bounds: &Bounds,
+        x: i32,
+        y: i32,
+        mut color: [u8; 4],
+        mut mode: BlendMode,
+    ) {
+        if x < bounds.left || x >= bounds.right || y < bounds.top || y >= bounds.bottom {
+            return;
+        }
+        if let Some(mask) = &self.mask {
+            let coverage = mask.coverage(x, y);
+            if coverage == 0 {
+                return;
+            }
+            color[3] = mul(color[3], coverage);
+            if mode == BlendMode::None {
+                mode = BlendMode::Blend;
+            }
+        }
+        let [origin_x, origin_y] = bounds.origin;
+        let (x, y) = (origin_x + x, origin_y + y);
+        let (sx, sy) = self.scale;
+        let (x0, x1) = scaled_span(x, sx);
+        let (y0, y1) = scaled_span(y, sy);
+        let (width, height) = (self.width as i32, self.height as i32);
+        for py in y0.max(0)..y1.min(height) {
+            for px in x0.max(0)..x1.min(width) {
+                let index = 4 * (py as usize * self.width as usize + px as usize);
+                blend(&mut self.pixels[index..index + 4], color, mode);
+            }
+        }
+    }
+
+    /// Draws a single pixel wide line using Bresenham's algorithm.
+    pub(crate) fn line(&mut self, p1: [i32; 2], p2: [i32; 2], color: Color) {
+        let (bounds, color, mode) = (self.bounds(), color.channels(), self.shape_blend_mode());
+        for [x, y] in line_points(p1, p2) {
+            self.plot(&bounds, x, y, color, mode);
+        }
+    }
+
+    /// Draws a line of the given `width`, centered on the line from `p1` to `p2`.
+    pub(crate) fn thick_line(&mut self, p1: [i32; 2], p2: [i32; 2], width: u8, color: Color) {
+        if width <= 1 {
+            return self.line(p1, p2, color);
+        }
+        let [x1, y1] = p1.map(|v| f64::from(v) + 0.5);
+        let [x2, y2] = p2.map(|v| f64::from(v) + 0.5);
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let length = dx.hypot(dy);
+        let half = f64::from(width) / 2.0;
+        let (nx, ny) = if length > 0.0 {
+            (-dy / length * half, dx / length * half)
+        } else {
+            (0.0, half)
+        };
+        // Zero-length lines still cover a square the size of the line width.
+        let (ex, ey) = if length > 0.0 {
+            (0.0, 0.0)
+        } else {
+            (half, 0.0)
+        };
+        self.fill_polygon(
+            &[&[
+                [x1 + nx - ex, y1 + ny - ey],
+                [x2 + nx + ex, y2 + ny + ey],
+                [x2 - nx + ex, y2 - ny + ey],
+                [x1 - nx - ex, y1 - ny - ey],
+            ]],
+            color,
+        );
+    }
+
+    /// Draws connected single pixel wide lines through each vertex, optionally connecting the
+    /// last vertex back to the first. Shared end points are only drawn once.
+    pub(crate) fn polyline(&mut self, vertices: &[Vertex], closed: bool, color: Color) {
+        let points: Vec<[i32; 2]> = vertices
+            .iter()
+            .map(|v| v.map(|v| v.round() as i32))
+            .collect();
+        let mut pixels: Vec<[i32; 2]> = Vec::new();
+        let segments = points.windows(2).map(|p| (p[0], p[1]));
+        let closing = points
+            .first()
+            .zip(points.last())
+            .filter(|_| closed && points.len() > 2)
+            .map(|(&first, &last)| (last, first));
+        for (p1, p2) in segments.chain(closing) {
+            for p in line_points(p1, p2) {
+                if pixels.last() != Some(&p) {
+                    pixels.push(p);
+                }
+            }
+        }
+        if closed && pixels.len() > 1 && pixels.first() == pixels.last() {
+            pixels.pop();
+        }
+        if pixels.is_empty() {
+            if let Some(&[x, y]) = points.first() {
+                pixels.push([x, y]);
+            }
+        }
+        let (bounds, color, mode) = (self.bounds(), color.channels(), self.shape_blend_mode());
+        for [x, y] in pixels {
+            self.plot(&bounds, x, y, color, mode);
+        }
+    }
+
+    /// Fills one or more contours as a single polygon using the even-odd rule, sampling pixel
+    /// centers.
+    pub(crate) fn fill_polygon(&mut self, contours: &[&[Vertex]], color: Color) {
+        let color = color.channels();
+        self.fill(contours, FillRule::EvenOdd, |_, _| color);
+    }
+
+    /// Fills contours using scanlines sampled at pixel centers, coloring each pixel covered
+    /// using `color_at`.
+    pub(crate) fn fill<F>(&mut self, contours: &[&[Vertex]], rule: FillRule, mut color_at: F)
+    where
+        F: FnMut(i32, i32) -> [u8; 4],
+    {
+        let bounds = self.bounds();
+        let mode = self.shape_blend_mode();
+        let (min_y, max_y) = contours
+            .iter()
+            .flat_map(|contour| contour.iter())
+            .fold((f64::MAX, f64::MIN), |(min, max), &[_, y]| {
+                (min.min(y), max.max(y))
+            });
+        if min_y > max_y {
+            return;
+        }
+        let y0 = (min_y.floor() as i32).max(bounds.top);
+        let y1 = (max_y.ceil() as i32).min(bounds.bottom);
+        let mut crossings = Vec::new();
+        for y in y0..y1 {
+            let sample_y = f64::from(y) + 0.5;
+            crossings.clear();
+            for contour in contours {
+                let edges = contour.iter().zip(contour.iter().cycle().skip(1));
+                for (&[ax, ay], &[bx, by]) in edges {
+                    let winding = if ay <= sample_y && by > sample_y {
+                        1
+                    } else if by <= sample_y && ay > sample_y {
+                        -1
+                    } else {
+                        continue;
+                    };
+                    crossings.push((ax + (sample_y - ay) * (bx - ax) / (by - ay), winding));
+                }
+            }
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut winding = 0;
+            for pair in crossings.windows(2) {
+                winding += pair[0].1;
+                let inside = match rule {
+                    FillRule::EvenOdd => winding % 2 != 0,
+                    FillRule::NonZero => winding != 0,
+                };
+                if inside {
+                    let x0 = ((pair[0].0 - 0.5).ceil() as i32).max(bounds.left);
+                    let x1 = ((pair[1].0 - 0.5).ceil() as i32).min(bounds.right);
+                    for x in x0..x1 {
+                        self.plot(&bounds, x, y, color_at(x, y), mode);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Copies `src` onto the canvas, optionally scaled, rotated about `center`, flipped and
+    /// tinted. `angle` is in degrees, clockwise.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn copy(
+        &mut self,
+        src: Source<'_>,
+        src_rect: Option<Rect<i32>>,
+        dst_rect: Option<Rect<i32>>,
+        angle: f64,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+        tint: Option<Color>,
+        mode: BlendMode,
+    ) {
+        let (src_width, src_height) = (src.width as i32, src.height as i32);
+        let src_rect = src_rect.map_or(Rect::new(0, 0, src_width, src_height), |r| {
+            let x = r.x().clamp(0, src_width);
+            let y = r.y().clamp(0, src_height);
+            let width = (r.x() + r.width()).clamp(0, src_width) - x;
+            let height = (r.y() + r.height()).clamp(0, src_height) - y;
+            Rect::new(x, y, width, height)
+        });
+        let dst_rect = dst_rect.unwrap_or_else(|| {
+            let viewport = self.viewport();
+            Rect::new(0, 0, viewport.width(), viewport.height())
+        });
+        if src_rect.width() <= 0
+            || src_rect.height() <= 0
+            || dst_rect.width() <= 0
+            || dst_rect.height() <= 0
+        {
+            return;
+        }
+
+        let (dst_width, dst_height) = (f64::from(dst_rect.width()), f64::from(dst_rect.height()));
+        let [cx, cy] = center.map_or([dst_width / 2.0, dst_height / 2.0], |c| {
+            [f64::from(c.x()), f64::from(c.y())]
+        });
+        let pivot_x = f64::from(dst_rect.x()) + cx;
+        let pivot_y = f64::from(dst_rect.y()) + cy;
+        let (sin, cos) = angle.to_radians().sin_cos();
+
+        // Bounding box of the rotated destination.
+        let corners = [
+            [0.0, 0.0],
+            [dst_width, 0.0],
+            [dst_width, dst_height],
+            [0.0, dst_height],
+        ]
+        .map(|[x, y]| {
+            let (x, y) = (x - cx, y - cy);
+            [pivot_x + x * cos - y * sin, pivot_y + x * sin + y * cos]
+        });
+        let bounds = self.bounds();
+        let fold = |f: fn(f64, f64) -> f64, init: f64, axis: usize| {
+            corners.iter().fold(init, |acc, c| f(acc, c[axis]))
+        };
+        let x0 = (fold(f64::min, f64::MAX, 0).floor() as i32).max(bounds.left);
+        let x1 = (fold(f64::max, f64::MIN, 0).ceil() as i32).min(bounds.right);
+        let y0 = (fold(f64::min, f64::MAX, 1).floor() as i32).max(bounds.top);
+        let y1 = (fold(f64::max, f64::MIN, 1).ceil() as i32).min(bounds.bottom);
+
+        let horizontal = matches!(flipped, Some(Flipped::Horizontal | Flipped::Both));
+        let vertical = matches!(flipped, Some(Flipped::Vertical | Flipped::Both));
+        let tint = tint.map_or([255; 4], |t| t.channels());
+        let channels = src.format.channels();
+        let x_ratio = f64::from(src_rect.width()) / dst_width;
+        let y_ratio = f64::from(src_rect.height()) / dst_height;
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let rx = f64::from(x) + 0.5 - pivot_x;
+                let ry = f64::from(y) + 0.5 - pivot_y;
+                let u = rx * cos + ry * sin + cx;
+                let v = -rx * sin + ry * cos + cy;
+                if u < 0.0 || v < 0.0 || u >= dst_width || v >= dst_height {
+                    continue;
+                }
+                let mut sx = ((u * x_ratio) as i32).min(src_rect.width() - 1);
+                let mut sy = ((v * y_ratio) as i32).min(src_rect.height() - 1);
+                if horizontal {
+                    sx = src_rect.width() - 1 - sx;
+                }
+                if vertical {
+                    sy = src_rect.height() - 1 - sy;
+                }
+                let index = ((src_rect.y() + sy) as usize * src.width as usize
+                    + (src_rect.x() + sx) as usize)
+                    * channels;
+                let Some(pixel) = src.pixels.get(index..index + channels) else {
+                    continue;
+                };
+                let [red, green, blue, alpha] = src.format.to_rgba(pixel);
+                let color = [
+                    mul(red, tint[0]),
+                    mul(green, tint[1]),
+                    mul(blue, tint[2]),
+                    mul(alpha, tint[3]),
+                ];
+                self.plot(&bounds, x, y, color, mode);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Canvas")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("format", &self.format)
+            .field("draw_color", &self.draw_color)
+            .field("clip", &self.clip)
+            .field("viewport", &self.viewport)
+            .field("scale", &self.scale)
+            .field("blend_mode", &self.blend_mode)
+            .field("mask", &self.mask)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the range of device pixels covered by logical pixel `v` at the given `scale`.
+#[inline]
+fn scaled_span(v: i32, scale: f32) -> (i32, i32) {
+    if (scale - 1.0).abs() < f32::EPSILON {
+        (v, v + 1)
+    } else {
+        let start = (v as f32 * scale).floor() as i32;
+        let end = ((v + 1) as f32 * scale).floor() as i32;
+        (start, end.max(start + 1))
+    }
+}
+
+/// Returns the pixels on the line from `p1` to `p2`, inclusive, using Bresenham's algorithm.
+pub(crate) fn line_points(p1: [i32; 2], p2: [i32; 2]) -> Vec<[i32; 2]> {
+    let [mut x, mut y] = p1;
+    let [x2, y2] = p2;
+    let dx = (x2 - x).abs();
+    let dy = -(y2 - y).abs();
+    let sx = if x < x2 { 1 } else { -1 };
+    let sy = if y < y2 { 1 } else { -1 };
+    let mut err = dx + dy;
+    let mut points = Vec::with_capacity(dx.max(-dy) as usize + 1);
+    loop {
+        points.push([x, y]);
+        if x == x2 && y == y2 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+    points
+}
+
+/// Returns the number of segments used to approximate a curve with the given `radius`.
+#[inline]
+fn segments(radius: f64, sweep: f64) -> usize {
+    ((radius * sweep / 2.0).ceil() as usize).clamp(4, 720)
+}
+
+/// Returns vertices approximating an ellipse centered at `(x, y)`.
+pub(crate) fn ellipse_vertices(x: f64, y: f64, rx: f64, ry: f64) -> Vec<Vertex> {
+    let count = segments(rx.max(ry), 2.0 * PI);
+    (0..count)
+        .map(|i| {
+            let t = 2.0 * PI * i as f64 / count as f64;
+            [x + rx * t.cos(), y + ry * t.sin()]
+        })
+        .collect()
+}
+
+/// Returns vertices along an arc centered at `(x, y)` from `start` to `end` in degrees,
+/// clockwise starting from the positive x-axis.
+pub(crate) fn arc_vertices(x: f64, y: f64, radius: f64, start: f64, end: f64) -> Vec<Vertex> {
+    let start = start.rem_euclid(360.0);
+    let mut end = end.rem_euclid(360.0);
+    if end <= start {
+        end += 360.0;
+    }
+    let sweep = (end - start).to_radians();
+    let count = segments(radius, sweep);
+    (0..=count)
+        .map(|i| {
+            let t = start.to_radians() + sweep * i as f64 / count as f64;
+            [x + radius * t.cos(), y + radius * t.sin()]
+        })
+        .collect()
+}
+
+/// Returns vertices approximating a rectangle with rounded corners.
+pub(crate) fn rounded_rect_vertices(rect: Rect<i32>, radius: i32) -> Vec<Vertex> {
+    let [x, y, width, height] = rect.map(f64::from);
+    let radius = f64::from(radius).clamp(0.0, width.min(height) / 2.0);
+    let corners = [
+        (x + width - radius, y + radius, 270.0),
+        (x + width - radius, y + height - radius, 0.0),
+        (x + radius, y + height - radius, 90.0),
+        (x + radius, y + radius, 180.0),
+    ];
+    corners
+        .iter()
+        .flat_map(|&(cx, cy, start)| arc_vertices(cx, cy, radius, start, start + 90.0))
+        .collect()
+}
+
+/// Returns vertices along a Bezier curve with any number of control points, sampled `detail`
+/// times.
+pub(crate) fn bezier_vertices(points: &[Vertex], detail: i32) -> Vec<Vertex> {
+    let steps = detail.max(2) as usize;
+    let mut scratch = points.to_vec();
+    (0..=steps)
+        .map(|i| {
+            let t = i as f64 / steps as f64;
+            scratch.copy_from_slice(points);
+            for n in (1..scratch.len()).rev() {
+                for j in 0..n {
+                    let [ax, ay] = scratch[j];
+                    let [bx, by] = scratch[j + 1];
+                    scratch[j] = [ax + (bx - ax) * t, ay + (by - ay) * t];
+                }
+            }
+            scratch.first().copied().unwrap_or_default()
+        })
+        .collect()
+}
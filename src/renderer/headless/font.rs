@@ -0,0 +1,257 @@
+//! Embedded bitmap font used by the headless [Renderer](super::Renderer).
+//!
+//! Glyphs are from the public domain `font8x8_basic` set covering printable ASCII. Each glyph is
+//! scaled using nearest-neighbor sampling so every character occupies a `size` x `size` cell,
+//! which keeps text layout simple and deterministic across platforms.
+
+use crate::prelude::*;
+
+/// Width and height of a source glyph in pixels.
+const GLYPH_SIZE: u32 = 8;
+
+/// Printable ASCII glyphs from `' '` to `'~'`. Each byte is a row of pixels with the least
+/// significant bit being the left-most pixel.
+#[rustfmt::skip]
+const GLYPHS: [[u8; 8]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // space
+    [0x18, 0x3C, 0x3C, 0x18, 0x18, 0x00, 0x18, 0x00], // !
+    [0x36, 0x36, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // "
+    [0x36, 0x36, 0x7F, 0x36, 0x7F, 0x36, 0x36, 0x00], // #
+    [0x0C, 0x3E, 0x03, 0x1E, 0x30, 0x1F, 0x0C, 0x00], // $
+    [0x00, 0x63, 0x33, 0x18, 0x0C, 0x66, 0x63, 0x00], // %
+    [0x1C, 0x36, 0x1C, 0x6E, 0x3B, 0x33, 0x6E, 0x00], // &
+    [0x06, 0x06, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00], // '
+    [0x18, 0x0C, 0x06, 0x06, 0x06, 0x0C, 0x18, 0x00], // (
+    [0x06, 0x0C, 0x18, 0x18, 0x18, 0x0C, 0x06, 0x00], // )
+    [0x00, 0x66, 0x3C, 0xFF, 0x3C, 0x66, 0x00, 0x00], // *
+    [0x00, 0x0C, 0x0C, 0x3F, 0x0C, 0x0C, 0x00, 0x00], // +
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C, 0x06], // ,
+    [0x00, 0x00, 0x00, 0x3F, 0x00, 0x00, 0x00, 0x00], // -
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C, 0x00], // .
+    [0x60, 0x30, 0x18, 0x0C, 0x06, 0x03, 0x01, 0x00], // /
+    [0x3E, 0x63, 0x73, 0x7B, 0x6F, 0x67, 0x3E, 0x00], // 0
+    [0x0C, 0x0E, 0x0C, 0x0C, 0x0C, 0x0C, 0x3F, 0x00], // 1
+    [0x1E, 0x33, 0x30, 0x1C, 0x06, 0x33, 0x3F, 0x00], // 2
+    [0x1E, 0x33, 0x30, 0x1C, 0x30, 0x33, 0x1E, 0x00], // 3
+    [0x38, 0x3C, 0x36, 0x33, 0x7F, 0x30, 0x78, 0x00], // 4
+    [0x3F, 0x03, 0x1F, 0x30, 0x30, 0x33, 0x1E, 0x00], // 5
+    [0x1C, 0x06, 0x03, 0x1F, 0x33, 0x33, 0x1E, 0x00], // 6
+    [0x3F, 0x33, 0x30, 0x18, 0x0C, 0x0C, 0x0C, 0x00], // 7
+    [0x1E, 0x33, 0x33, 0x1E, 0x33, 0x33, 0x1E, 0x00], // 8
+    [0x1E, 0x33, 0x33, 0x3E, 0x30, 0x18, 0x0E, 0x00], // 9
+    [0x00, 0x0C, 0x0C, 0x00, 0x00, 0x0C, 0x0C, 0x00], // :
+    [0x00, 0x0C, 0x0C, 0x00, 0x00, 0x0C, 0x0C, 0x06], // ;
+    [0x18, 0x0C, 0x06, 0x03, 0x06, 0x0C, 0x18, 0x00], // <
+    [0x00, 0x00, 0x3F, 0x00, 0x00, 0x3F, 0x00, 0x00], // =
+    [0x06, 0x0C, 0x18, 0x30, 0x18, 0x0C, 0x06, 0x00], // >
+    [0x1E, 0x33, 0x30, 0x18, 0x0C, 0x00, 0x0C, 0x00], // ?
+    [0x3E, 0x63, 0x7B, 0x7B, 0x7B, 0x03, 0x1E, 0x00], // @
+    [0x0C, 0x1E, 0x33, 0x33, 0x3F, 0x33, 0x33, 0x00], // A
+    [0x3F, 0x66, 0x66, 0x3E, 0x66, 0x66, 0x3F, 0x00], // B
+    [0x3C, 0x66, 0x03, 0x03, 0x03, 0x66, 0x3C, 0x00], // C
+    [0x1F, 0x36, 0x66, 0x66, 0x66, 0x36, 0x1F, 0x00], // D
+    [0x7F, 0x46, 0x16, 0x1E, 0x16, 0x46, 0x7F, 0x00], // E
+    [0x7F, 0x46, 0x16, 0x1E, 0x16, 0x06, 0x0F, 0x00], // F
+    [0x3C, 0x66, 0x03, 0x03, 0x73, 0x66, 0x7C, 0x00], // G
+    [0x33, 0x33, 0x33, 0x3F, 0x33, 0x33, 0x33, 0x00], // H
+    [0x1E, 0x0C, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00], // I
+    [0x78, 0x30, 0x30, 0x30, 0x33, 0x33, 0x1E, 0x00], // J
+    [0x67, 0x66, 0x36, 0x1E, 0x36, 0x66, 0x67, 0x00], // K
+    [0x0F, 0x06, 0x06, 0x06, 0x46, 0x66, 0x7F, 0x00], // L
+    [0x63, 0x77, 0x7F, 0x7F, 0x6B, 0x63, 0x63, 0x00], // M
+    [0x63, 0x67, 0x6F, 0x7B, 0x73, 0x63, 0x63, 0x00], // N
+    [0x1C, 0x36, 0x63, 0x63, 0x63, 0x36, 0x1C, 0x00], // O
+    [0x3F, 0x66, 0x66, 0x3E, 0x06, 0x06, 0x0F, 0x00], // P
+    [0x1E, 0x33, 0x33, 0x33, 0x3B, 0x1E, 0x38, 0x00], // Q
+    [0x3F, 0x66, 0x66, 0x3E, 0x36, 0x66, 0x67, 0x00], // R
+    [0x1E, 0x33, 0x07, 0x0E, 0x38, 0x33, 0x1E, 0x00], // S
+    [0x3F, 0x2D, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00], // T
+    [0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x3F, 0x00], // U
+    [0x33, 0x33, 0x33, 0x33, 0x33, 0x1E, 0x0C, 0x00], // V
+    [0x63, 0x63, 0x63, 0x6B, 0x7F, 0x77, 0x63, 0x00], // W
+    [0x63, 0x63, 0x36, 0x1C, 0x1C, 0x36, 0x63, 0x00], // X
+    [0x33, 0x33, 0x33, 0x1E, 0x0C, 0x0C, 0x1E, 0x00], // Y
+    [0x7F, 0x63, 0x31, 0x18, 0x4C, 0x66, 0x7F, 0x00], // Z
+    [0x1E, 0x06, 0x06, 0x06, 0x06, 0x06, 0x1E, 0x00], // [
+    [0x03, 0x06, 0x0C, 0x18, 0x30, 0x60, 0x40, 0x00], // \
+    [0x1E, 0x18, 0x18, 0x18, 0x18, 0x18, 0x1E, 0x00], // ]
+    [0x08, 0x1C, 0x36, 0x63, 0x00, 0x00, 0x00, 0x00], // ^
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF], // _
+    [0x0C, 0x0C, 0x18, 0x00, 0x00, 0x00, 0x00, 0x00], // `
+    [0x00, 0x00, 0x1E, 0x30, 0x3E, 0x33, 0x6E, 0x00], // a
+    [0x07, 0x06, 0x06, 0x3E, 0x66, 0x66, 0x3B, 0x00], // b
+    [0x00, 0x00, 0x1E, 0x33, 0x03, 0x33, 0x1E, 0x00], // c
+    [0x38, 0x30, 0x30, 0x3E, 0x33, 0x33, 0x6E, 0x00], // d
+    [0x00, 0x00, 0x1E, 0x33, 0x3F, 0x03, 0x1E, 0x00], // e
+    [0x1C, 0x36, 0x06, 0x0F, 0x06, 0x06, 0x0F, 0x00], // f
+    [0x00, 0x00, 0x6E, 0x33, 0x33, 0x3E, 0x30, 0x1F], // g
+    [0x07, 0x06, 0x36, 0x6E, 0x66, 0x66, 0x67, 0x00], // h
+    [0x0C, 0x00, 0x0E, 0x0C, 0x0C, 0x0C, 0x1E, 0x00], // i
+    [0x30, 0x00, 0x30, 0x30, 0x30, 0x33, 0x33, 0x1E], // j
+    [0x07, 0x06, 0x66, 0x36, 0x1E, 0x36, 0x67, 0x00], // k
+    [0x0E, 0x0C, 0x0C, 0x0C, 0x0C, 0x0C, 0x1E, 0x00], // l
+    [0x00, 0x00, 0x33, 0x7F, 0x7F, 0x6B, 0x63, 0x00], // m
+    [0x00, 0x00, 0x1F, 0x33, 0x33, 0x33, 0x33, 0x00], // n
+    [0x00, 0x00, 0x1E, 0x33, 0x33, 0x33, 0x1E, 0x00], // o
+    [0x00, 0x00, 0x3B, 0x66, 0x66, 0x3E, 0x06, 0x0F], // p
+    [0x00, 0x00, 0x6E, 0x33, 0x33, 0x3E, 0x30, 0x78], // q
+    [0x00, 0x00, 0x3B, 0x6E, 0x66, 0x06, 0x0F, 0x00], // r
+    [0x00, 0x00, 0x3E, 0x03, 0x1E, 0x30, 0x1F, 0x00], // s
+    [0x08, 0x0C, 0x3E, 0x0C, 0x0C, 0x2C, 0x18, 0x00], // t
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x33, 0x6E, 0x00], // u
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x1E, 0x0C, 0x00], // v
+    [0x00, 0x00, 0x63, 0x6B, 0x7F, 0x7F, 0x36, 0x00], // w
+    [0x00, 0x00, 0x63, 0x36, 0x1C, 0x36, 0x63, 0x00], // x
+    [0x00, 0x00, 0x33, 0x33, 0x33, 0x3E, 0x30, 0x1F], // y
+    [0x00, 0x00, 0x3F, 0x19, 0x0C, 0x26, 0x3F, 0x00], // z
+    [0x38, 0x0C, 0x0C, 0x07, 0x0C, 0x0C, 0x38, 0x00], // {
+    [0x18, 0x18, 0x18, 0x00, 0x18, 0x18, 0x18, 0x00], // |
+    [0x07, 0x0C, 0x0C, 0x38, 0x0C, 0x0C, 0x07, 0x00], // }
+    [0x6E, 0x3B, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ~
+];
+
+/// Returns the glyph for a character, substituting `'?'` for any character outside of printable
+/// ASCII.
+fn glyph(ch: char) -> [u8; 8] {
+    let index = match ch {
+        ' '..='~' => ch as usize - 0x20,
+        _ => '?' as usize - 0x20,
+    };
+    GLYPHS.get(index).copied().unwrap_or_default()
+}
+
+/// Splits `text` into the lines that would be rendered for a given font `size`, wrapping on word
+/// boundaries when a `wrap_width` is provided.
+fn layout(text: &str, size: u32, wrap_width: Option<u32>) -> Vec<Vec<char>> {
+    let max_chars = wrap_width.map(|width| (width / size).max(1) as usize);
+    let mut lines = Vec::new();
+    for line in text.split('\n') {
+        let line = line.trim_end_matches('\r');
+        let max = match max_chars {
+            Some(max) if line.chars().count() > max => max,
+            _ => {
+                lines.push(line.chars().collect());
+                continue;
+            }
+        };
+        let mut current: Vec<char> = Vec::new();
+        for word in line.split(' ') {
+            let mut word: Vec<char> = word.chars().collect();
+            let needed = if current.is_empty() {
+                word.len()
+            } else {
+                current.len() + 1 + word.len()
+            };
+            if needed <= max {
+                if !current.is_empty() {
+                    current.push(' ');
+                }
+                current.append(&mut word);
+                continue;
+            }
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            while word.len() > max {
+                lines.push(word.drain(..max).collect());
+            }
+            current = word;
+        }
+        lines.push(current);
+    }
+    lines
+}
+
+/// Returns the rendered dimensions of `text` as `(width, height)`.
+pub(crate) fn size_of(text: &str, size: u32, wrap_width: Option<u32>, outline: u16) -> (u32, u32) {
+    let size = size.max(1);
+    let lines = layout(text, size, wrap_width);
+    let columns = lines.iter().map(Vec::len).max().unwrap_or_default() as u32;
+    let outline = 2 * u32::from(outline);
+    (
+        columns * size + outline,
+        lines.len() as u32 * size + outline,
+    )
+}
+
+/// Renders `text` into an `RGBA` pixel buffer, returning the buffer along with its `(width,
+/// height)`.
+pub(crate) fn render(
+    text: &str,
+    size: u32,
+    style: FontStyle,
+    wrap_width: Option<u32>,
+    color: Color,
+    outline: u16,
+) -> (Vec<u8>, u32, u32) {
+    let size = size.max(1);
+    let (width, height) = size_of(text, size, wrap_width, outline);
+    let (w, h) = (width as usize, height as usize);
+    let mut mask = vec![false; w * h];
+    let mut set = |x: u32, y: u32| {
+        if x < width && y < height {
+            mask[y as usize * w + x as usize] = true;
+        }
+    };
+
+    let offset = u32::from(outline);
+    let weight = (size / GLYPH_SIZE).max(1);
+    for (row, line) in layout(text, size, wrap_width).iter().enumerate() {
+        let oy = row as u32 * size + offset;
+        for (col, &ch) in line.iter().enumerate() {
+            let ox = col as u32 * size + offset;
+            let bits = glyph(ch);
+            for py in 0..size {
+                let gy = py * GLYPH_SIZE / size;
+                let row_bits = bits[gy as usize];
+                let shear = if style.contains(FontStyle::ITALIC) {
+                    (size - 1 - py) / 5
+                } else {
+                    0
+                };
+                for px in 0..size {
+                    let gx = px * GLYPH_SIZE / size;
+                    let lit = (row_bits >> gx) & 1 == 1
+                        || (style.contains(FontStyle::UNDERLINE) && gy == GLYPH_SIZE - 1)
+                        || (style.contains(FontStyle::STRIKETHROUGH) && gy == GLYPH_SIZE / 2 - 1);
+                    if lit {
+                        set(ox + px + shear, oy + py);
+                        if style.contains(FontStyle::BOLD) {
+                            for bx in 1..=weight {
+                                set(ox + px + shear + bx, oy + py);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if outline > 0 {
+        let radius = outline as usize;
+        let source = mask.clone();
+        for y in 0..h {
+            for x in 0..w {
+                if !source[y * w + x] {
+                    continue;
+                }
+                for ny in y.saturating_sub(radius)..(y + radius + 1).min(h) {
+                    for nx in x.saturating_sub(radius)..(x + radius + 1).min(w) {
+                        mask[ny * w + nx] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    let channels = color.channels();
+    let mut pixels = vec![0; w * h * 4];
+    for (pixel, _) in pixels
+        .chunks_exact_mut(4)
+        .zip(&mask)
+        .filter(|(_, &lit)| lit)
+    {
+        pixel.copy_from_slice(&channels);
+    }
+    (pixels, width, height)
+}
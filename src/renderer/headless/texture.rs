@@ -0,0 +1,126 @@
+use super::{
+    canvas::{Canvas, Source},
+    Renderer,
+};
+use crate::{
+    error::{Error, Result},
+    prelude::*,
+    renderer::TextureRenderer,
+};
+use anyhow::anyhow;
+
+impl TextureRenderer for Renderer {
+    /// Create a texture to render to.
+    #[inline]
+    fn create_texture(
+        &mut self,
+        width: u32,
+        height: u32,
+        format: Option<PixelFormat>,
+    ) -> Result<TextureId> {
+        let texture_id = TextureId(self.next_texture_id);
+        self.next_texture_id += 1;
+        let canvas = Canvas::new(width, height, format.unwrap_or_default());
+        self.textures.insert(texture_id, canvas);
+        Ok(texture_id)
+    }
+
+    /// Delete texture.
+    #[inline]
+    fn delete_texture(&mut self, texture_id: TextureId) -> Result<()> {
+        self.textures
+            .remove(&texture_id)
+            .map_or(Err(Error::InvalidTexture(texture_id).into()), |_| Ok(()))
+    }
+
+    /// Update texture with pixel data.
+    #[inline]
+    fn update_texture<P: AsRef<[u8]>>(
+        &mut self,
+        texture_id: TextureId,
+        rect: Option<Rect<i32>>,
+        pixels: P,
+        pitch: usize,
+    ) -> Result<()> {
+        self.textures
+            .get_mut(&texture_id)
+            .ok_or(Error::InvalidTexture(texture_id))?
+            .update(rect, pixels.as_ref(), pitch)
+    }
+
+    /// Draw texture canvas.
+    #[inline]
+    fn texture(
+        &mut self,
+        texture_id: TextureId,
+        src: Option<Rect<i32>>,
+        dst: Option<Rect<i32>>,
+        angle: f64,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+        tint: Option<Color>,
+    ) -> Result<()> {
+        assert_ne!(
+            Some(texture_id),
+            self.texture_target,
+            "`texture_id` must not equal the current `texture_target`"
+        );
+
+        // Temporarily take the source texture so the target canvas can be borrowed mutably.
+        let texture = self
+            .textures
+            .remove(&texture_id)
+            .ok_or(Error::InvalidTexture(texture_id))?;
+        let (width, height) = texture.dimensions();
+        let source = Source {
+            pixels: texture.as_bytes(),
+            width,
+            height,
+            format: PixelFormat::Rgba,
+        };
+        let mode = self.blend_mode;
+        let result = self
+            .canvas_mut()
+            .map(|canvas| canvas.copy(source, src, dst, angle, center, flipped, tint, mode));
+        self.textures.insert(texture_id, texture);
+        result
+    }
+
+    /// Returns texture used as the target for drawing operations, if set.
+    #[inline]
+    fn texture_target(&self) -> Option<TextureId> {
+        self.texture_target
+    }
+
+    /// Set a `Texture` as the primary target for drawing operations instead of the window
+    /// target canvas.
+    ///
+    /// # Errors
+    ///
+    /// If the texture has been dropped or is invalid, then an error is returned.
+    #[inline]
+    fn set_texture_target(&mut self, id: TextureId) -> Result<()> {
+        if self.textures.contains_key(&id) {
+            self.texture_target = Some(id);
+            Ok(())
+        } else {
+            Err(anyhow!(Error::InvalidTexture(id)))
+        }
+    }
+
+    /// Clear `Texture` target back to the window target canvas for drawing operations.
+    #[inline]
+    fn clear_texture_target(&mut self) {
+        self.texture_target = None;
+    }
+
+    /// Returns whether a texture is set as the target for drawing operations.
+    #[inline]
+    fn has_texture_target(&self) -> bool {
+        self.texture_target.is_some()
+    }
+
+    /// Clear internal texture cache. Nothing is cached when headless.
+    #[inline]
+    fn clear_texture_cache(&mut self) {}
+}
@@ -0,0 +1,252 @@
+use super::{canvas::Canvas, Renderer};
+use crate::{
+    error::{Error, Result},
+    prelude::*,
+    renderer::{RendererSettings, WindowRenderer},
+    window::{Position, WindowId},
+};
+use anyhow::Context;
+use std::fmt::Write;
+
+impl Renderer {
+    /// Returns the current window canvas.
+    #[inline]
+    pub(super) fn window_canvas(&self) -> Result<&WindowCanvas> {
+        Ok(self
+            .windows
+            .get(&self.window_target)
+            .ok_or(Error::InvalidWindow(self.window_target))?)
+    }
+
+    /// Returns the current window canvas.
+    #[inline]
+    pub(super) fn window_canvas_mut(&mut self) -> Result<&mut WindowCanvas> {
+        Ok(self
+            .windows
+            .get_mut(&self.window_target)
+            .ok_or(Error::InvalidWindow(self.window_target))?)
+    }
+}
+
+/// A simulated window backed by an in-memory framebuffer.
+#[derive(Debug)]
+pub(super) struct WindowCanvas {
+    pub(super) id: WindowId,
+    pub(super) title: String,
+    pub(super) position: (i32, i32),
+    pub(super) fullscreen: bool,
+    pub(super) hidden: bool,
+    pub(super) canvas: Canvas,
+}
+
+impl WindowCanvas {
+    pub(super) fn new(id: WindowId, s: &mut RendererSettings) -> Result<Self> {
+        let position = match (s.x, s.y) {
+            (Position::Positioned(x), Position::Positioned(y)) => (x, y),
+            _ => (0, 0),
+        };
+        let mut canvas = Canvas::new(s.width, s.height, PixelFormat::Rgba);
+        canvas.clear();
+        canvas.set_scale(s.scale_x, s.scale_y)?;
+        log::debug!("Created new headless window: {}", id);
+        Ok(Self {
+            id,
+            title: s.title.clone(),
+            position,
+            fullscreen: s.fullscreen,
+            hidden: s.hidden,
+            canvas,
+        })
+    }
+}
+
+impl WindowRenderer for Renderer {
+    /// Get the count of open windows.
+    fn window_count(&self) -> usize {
+        self.windows.len()
+    }
+
+    /// Get the primary window ID.
+    #[inline]
+    fn primary_window_id(&self) -> WindowId {
+        self.primary_window_id
+    }
+
+    /// Get the current window target ID.
+    #[inline]
+    fn window_id(&self) -> WindowId {
+        self.window_target
+    }
+
+    /// Create a new window.
+    fn create_window(&mut self, s: &mut RendererSettings) -> Result<WindowId> {
+        let window_id = WindowId(self.next_window_id);
+        self.next_window_id += 1;
+        let window_canvas = WindowCanvas::new(window_id, s)?;
+        self.windows.insert(window_id, window_canvas);
+        self.window_created(window_id);
+        Ok(window_id)
+    }
+
+    /// Close a window.
+    fn close_window(&mut self, id: WindowId) -> Result<()> {
+        if self.windows.remove(&id).is_none() {
+            return Err(Error::InvalidWindow(id).into());
+        }
+        if id == self.window_target {
+            self.reset_window_target();
+        }
+        Ok(())
+    }
+
+    /// Set the mouse cursor to a predefined symbol or image, or hides cursor if `None`. There is
+    /// no cursor when headless, so this does nothing.
+    fn cursor(&mut self, _cursor: Option<&Cursor>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Returns a single event or None if the event pump is empty. Only window events for newly
+    /// created windows are generated when headless.
+    #[inline]
+    fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    /// Get the current window title.
+    #[inline]
+    fn title(&self) -> &str {
+        &self.settings.title
+    }
+
+    /// Set the current window title.
+    #[inline]
+    fn set_title(&mut self, title: &str) -> Result<()> {
+        self.settings.title.replace_range(.., title);
+        self.window_canvas_mut()?.title.replace_range(.., title);
+        Ok(())
+    }
+
+    #[inline]
+    fn set_fps(&mut self, fps: f32) -> Result<()> {
+        self.title.clear();
+        write!(self.title, "{} - FPS: {:.02}", &self.settings.title, fps)
+            .context("invalid title")?;
+        // Can't use `self.window_canvas_mut` here due to needing split borrows
+        self.windows
+            .get_mut(&self.window_target)
+            .ok_or(Error::InvalidWindow(self.window_target))?
+            .title
+            .replace_range(.., &self.title);
+        Ok(())
+    }
+
+    /// Dimensions of the current render target as `(width, height)`.
+    #[inline]
+    fn dimensions(&self) -> Result<(u32, u32)> {
+        Ok(self.canvas()?.dimensions())
+    }
+
+    /// Dimensions of the current window target as `(width, height)`.
+    #[inline]
+    fn window_dimensions(&self) -> Result<(u32, u32)> {
+        Ok(self.window_canvas()?.canvas.dimensions())
+    }
+
+    /// Position of the current window target as `(x, y)`.
+    #[inline]
+    fn window_position(&self) -> Result<(i32, i32)> {
+        Ok(self.window_canvas()?.position)
+    }
+
+    /// Set dimensions of the current window target as `(width, height)`.
+    #[inline]
+    fn set_window_dimensions(&mut self, (width, height): (u32, u32)) -> Result<()> {
+        self.settings.width = width;
+        self.settings.height = height;
+        self.window_canvas_mut()?.canvas.resize(width, height);
+        Ok(())
+    }
+
+    /// Returns the rendering viewport of the current render target.
+    #[inline]
+    fn viewport(&self) -> Result<Rect<i32>> {
+        Ok(self.canvas()?.viewport())
+    }
+
+    /// Set the rendering viewport of the current render target.
+    #[inline]
+    fn set_viewport(&mut self, rect: Option<Rect<i32>>) -> Result<()> {
+        self.canvas_mut()?.set_viewport(rect);
+        Ok(())
+    }
+
+    /// Dimensions of the primary display as `(width, height)`. When headless, this is the size
+    /// of the primary window.
+    #[inline]
+    fn display_dimensions(&self) -> Result<(u32, u32)> {
+        Ok(self
+            .windows
+            .get(&self.primary_window_id)
+            .ok_or(Error::InvalidWindow(self.primary_window_id))?
+            .canvas
+            .dimensions())
+    }
+
+    /// Returns whether the application is fullscreen or not.
+    #[inline]
+    fn fullscreen(&self) -> Result<bool> {
+        Ok(self.window_canvas()?.fullscreen)
+    }
+
+    /// Set the application to fullscreen or not.
+    #[inline]
+    fn set_fullscreen(&mut self, val: bool) -> Result<()> {
+        self.window_canvas_mut()?.fullscreen = val;
+        Ok(())
+    }
+
+    /// Returns whether the window synchronizes frame rate to the screens refresh rate.
+    #[inline]
+    fn vsync(&self) -> bool {
+        self.settings.vsync
+    }
+
+    /// Set the window to synchronize frame rate to the screens refresh rate. Since there is no
+    /// display to synchronize with, the current window is kept and its `WindowId` returned.
+    fn set_vsync(&mut self, val: bool) -> Result<WindowId> {
+        log::debug!("Set VSync: {}", val);
+        self.settings.vsync = val;
+        Ok(self.window_target)
+    }
+
+    /// Set window as the target for drawing operations.
+    #[inline]
+    fn set_window_target(&mut self, id: WindowId) -> Result<()> {
+        if self.windows.contains_key(&id) {
+            self.window_target = id;
+            Ok(())
+        } else {
+            Err(Error::InvalidWindow(id).into())
+        }
+    }
+
+    /// Reset main window as the target for drawing operations.
+    #[inline]
+    fn reset_window_target(&mut self) {
+        self.window_target = self.primary_window_id;
+    }
+
+    /// Show the current window target.
+    #[inline]
+    fn show(&mut self) -> Result<()> {
+        self.window_canvas_mut()?.hidden = false;
+        Ok(())
+    }
+
+    /// Hide the current window target.
+    #[inline]
+    fn hide(&mut self) -> Result<()> {
+        self.window_canvas_mut()?.hidden = true;
+        Ok(())
+    }
+}
@@ -3,8 +3,10 @@
 use crate::{
     error::{Error, Result},
     gui::theme::{FontId, FontSrc},
+    image::mul,
     prelude::*,
-    renderer::{RendererSettings, Rendering},
+    renderer::{FillRule, RendererSettings, Rendering, TextureRenderer},
+    state::clip::Mask,
 };
 use anyhow::{anyhow, Context};
 use log::{debug, warn};
@@ -17,13 +19,13 @@ use sdl2::{
     mouse::{Cursor, SystemCursor},
     pixels::{Color as SdlColor, PixelFormatEnum as SdlPixelFormat},
     rect::{Point as SdlPoint, Rect as SdlRect},
-    render::{BlendMode as SdlBlendMode, Canvas, TextureQuery},
+    render::{Canvas, Texture as SdlTexture, TextureQuery},
     rwops::RWops,
     ttf::{Font as SdlFont, FontStyle as SdlFontStyle, Sdl2TtfContext},
     video::Window,
     EventPump, GameControllerSubsystem, Sdl,
 };
-use std::{collections::HashMap, fmt};
+use std::{collections::HashMap, fmt, rc::Rc};
 use texture::RendererTexture;
 use window::{TextCacheKey, WindowCanvas};
 
@@ -47,7 +49,9 @@ pub(crate) struct Renderer {
     title: String,
     settings: RendererSettings,
     cursor: Option<Cursor>,
-    blend_mode: SdlBlendMode,
+    clip: Option<Rect<i32>>,
+    mask: Option<Rc<Mask>>,
+    blend_mode: BlendMode,
     current_font: FontId,
     font_size: u16,
     font_style: SdlFontStyle,
@@ -77,10 +81,13 @@ impl Renderer {
                     .textures
                     .get(&texture_id)
                     .ok_or_else(|| anyhow!(Error::InvalidTexture(texture_id)))?;
+                let clip = self.clip.map(Into::into);
                 let mut result = Ok(());
                 window
                     .canvas
                     .with_texture_canvas(&mut texture.borrow_mut(), |canvas| {
+                        // Clip rects are reset whenever the render target changes.
+                        canvas.set_clip_rect(clip);
                         result = f(canvas);
                     })
                     .with_context(|| format!("failed to update texture target {texture_id}"))?;
@@ -93,6 +100,137 @@ impl Renderer {
         }
     }
 
+    /// Draws a shape to the current canvas using `f`. `SDL` always alpha blends shapes, so any
+    /// other [`BlendMode`] or a clip mask is composited on the CPU within `bounds`.
+    fn update_shape_canvas<F>(&mut self, bounds: Option<Rect<i32>>, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Canvas<Window>) -> Result<()>,
+    {
+        if self.mask.is_none() && matches!(self.blend_mode, BlendMode::None | BlendMode::Blend) {
+            self.update_canvas(f)
+        } else {
+            self.composite(bounds, |renderer| renderer.update_canvas(f))
+        }
+    }
+
+    /// Returns the size of the current render target in draw coordinates along with the render
+    /// scale to physical pixels.
+    fn target_viewport(&mut self) -> Result<((u32, u32), (f32, f32))> {
+        let mut viewport = ((0, 0), (1.0, 1.0));
+        self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
+            let rect = canvas.viewport();
+            viewport = ((rect.width(), rect.height()), canvas.scale());
+            Ok(())
+        })?;
+        Ok(viewport)
+    }
+
+    /// Reads `RGBA` pixels from the current render target. `rect` is in physical pixels
+    /// relative to the viewport, defaulting to the entire viewport.
+    fn read_pixels(&mut self, rect: Option<SdlRect>) -> Result<Vec<u8>> {
+        let mut pixels = vec![];
+        self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
+            pixels = canvas
+                .read_pixels(rect, SdlPixelFormat::RGBA32)
+                .map_err(Error::Renderer)?;
+            Ok(())
+        })?;
+        Ok(pixels)
+    }
+
+    /// Draws using `f` to a transparent scratch texture and composites it with the current
+    /// canvas on the CPU using the current [`BlendMode`] and clip mask. Used for blend modes
+    /// and masks `SDL` can't render. Only the area within `bounds` is read back and composited,
+    /// defaulting to the entire render target.
+    fn composite<F>(&mut self, bounds: Option<Rect<i32>>, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        let ((target_width, target_height), (sx, sy)) = self.target_viewport()?;
+        let (target_width, target_height) = (target_width as i32, target_height as i32);
+        let (x0, y0, x1, y1) = bounds.map_or((0, 0, target_width, target_height), |b| {
+            (
+                b.x().max(0),
+                b.y().max(0),
+                b.right().min(target_width),
+                b.bottom().min(target_height),
+            )
+        });
+        if x0 >= x1 || y0 >= y1 {
+            return Ok(());
+        }
+        let area = rect![x0, y0, x1 - x0, y1 - y0];
+        // Physical pixels covered by `area`, which differ on high-DPI displays
+        let px = |value: i32, scale: f32| (value as f32 * scale).round() as i32;
+        let (region_x, region_y) = (px(x0, sx), px(y0, sy));
+        let (region_width, region_height) = (
+            (px(x1, sx) - region_x).max(1) as u32,
+            (px(y1, sy) - region_y).max(1) as u32,
+        );
+        let region = SdlRect::new(region_x, region_y, region_width, region_height);
+        let mut image = Image::from_vec(
+            region_width,
+            region_height,
+            self.read_pixels(Some(region))?,
+            PixelFormat::Rgba,
+        );
+
+        let mode = self.blend_mode;
+        let mask = self.mask.take();
+        // Texture targets are drawn unscaled, so the scratch texture matches draw coordinates
+        let scratch = self.create_texture(
+            target_width as u32,
+            target_height as u32,
+            Some(PixelFormat::Rgba),
+        )?;
+        let target = self.texture_target.replace(scratch);
+        self.blend_mode = BlendMode::None;
+        let result = self
+            .update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
+                let color = canvas.draw_color();
+                canvas.set_draw_color(Color::TRANSPARENT);
+                canvas.clear();
+                canvas.set_draw_color(color);
+                Ok(())
+            })
+            .and_then(|()| f(self))
+            .and_then(|()| self.read_pixels(Some(area.into())));
+        self.texture_target = target;
+        self.delete_texture(scratch)?;
+
+        let result = result.and_then(|mut pixels| {
+            let mut mode = mode;
+            let (width, height) = (area.width() as u32, area.height() as u32);
+            if let Some(mask) = &mask {
+                for (i, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+                    let x = x0 + (i % width as usize) as i32;
+                    let y = y0 + (i / width as usize) as i32;
+                    pixel[3] = mul(pixel[3], mask.coverage(x, y));
+                }
+                if mode == BlendMode::None {
+                    mode = BlendMode::Blend;
+                }
+            }
+            let mut src = Image::from_vec(width, height, pixels, PixelFormat::Rgba);
+            if (width, height) != (region_width, region_height) {
+                src = src.resize(region_width, region_height, ResizeFilter::Nearest);
+            }
+            image.composite(&src, [0, 0], mode);
+
+            let output =
+                self.create_texture(region_width, region_height, Some(PixelFormat::Rgba))?;
+            let pitch = PixelFormat::Rgba.channels() * region_width as usize;
+            let result = self
+                .update_texture(output, None, image.as_bytes(), pitch)
+                .and_then(|()| self.texture(output, None, Some(area), 0.0, None, None, None));
+            self.delete_texture(output)?;
+            result
+        });
+        self.blend_mode = mode;
+        self.mask = mask;
+        result
+    }
+
     /// Load font if family or size has not already been loaded. Returns `true` if a font was
     /// loaded.
     fn load_font(&mut self) -> Result<bool> {
@@ -190,7 +328,9 @@ impl Rendering for Renderer {
             settings: s,
             title,
             cursor,
-            blend_mode: SdlBlendMode::None,
+            clip: None,
+            mask: None,
+            blend_mode: BlendMode::None,
             current_font,
             font_size: 14,
             font_style: SdlFontStyle::NORMAL,
@@ -228,6 +368,7 @@ impl Rendering for Renderer {
     /// Sets the clip rect used by the renderer to draw to the current canvas.
     #[inline]
     fn clip(&mut self, rect: Option<Rect<i32>>) -> Result<()> {
+        self.clip = rect;
         self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
             canvas.set_clip_rect(rect.map(Into::into));
             Ok(())
@@ -237,7 +378,14 @@ impl Rendering for Renderer {
     /// Sets the blend mode used by the renderer to drawing.
     #[inline]
     fn blend_mode(&mut self, mode: BlendMode) {
-        self.blend_mode = mode.into();
+        self.blend_mode = mode;
+    }
+
+    /// Sets the clip mask used by the renderer to draw to the current canvas. `SDL` has no
+    /// stencil support, so masked drawing is composited on the CPU.
+    #[inline]
+    fn mask(&mut self, mask: Option<Rc<Mask>>) {
+        self.mask = mask;
     }
 
     /// Updates the canvas from the current back buffer.
@@ -302,6 +450,28 @@ impl Rendering for Renderer {
         if text.is_empty() {
             return self.size_of(text, wrap_width);
         }
+        if fill.is_some() && self.mask.is_some() {
+            let mut size = (0, 0);
+            let bounds = match (angle, center, flipped) {
+                (None, None, None) => {
+                    let (width, height) = self.size_of(text, wrap_width)?;
+                    let pad = i32::from(outline);
+                    Some(rect![
+                        pos.x() - pad,
+                        pos.y() - pad,
+                        width as i32 + 2 * pad,
+                        height as i32 + 2 * pad
+                    ])
+                }
+                _ => None,
+            };
+            self.composite(bounds, |renderer| {
+                size =
+                    renderer.text(pos, text, wrap_width, angle, center, flipped, fill, outline)?;
+                Ok(())
+            })?;
+            return Ok(size);
+        }
         if let Some(fill) = fill {
             let window = self
                 .windows
@@ -362,10 +532,12 @@ impl Rendering for Renderer {
 
             if let Some(texture_id) = self.texture_target {
                 if let Some(texture) = window.textures.get(&texture_id) {
+                    let clip = self.clip.map(Into::into);
                     let mut result = Ok(());
                     window
                         .canvas
                         .with_texture_canvas(&mut texture.borrow_mut(), |canvas| {
+                            canvas.set_clip_rect(clip);
                             result = update(canvas);
                         })
                         .with_context(|| format!("failed to update texture target {texture_id}"))?;
@@ -444,34 +616,40 @@ impl Rendering for Renderer {
     /// Draw a pixel to the current canvas.
     #[inline]
     fn point(&mut self, p: Point<i32>, color: Color) -> Result<()> {
-        self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
-            let [x, y] = p.map(|v| v as i16);
-            Ok(canvas.pixel(x, y, color).map_err(Error::Renderer)?)
-        })
+        self.update_shape_canvas(
+            bounding_rect([p], 0),
+            |canvas: &mut Canvas<_>| -> Result<()> {
+                let [x, y] = p.map(|v| v as i16);
+                Ok(canvas.pixel(x, y, color).map_err(Error::Renderer)?)
+            },
+        )
     }
 
     /// Draw a line to the current canvas.
     #[inline]
     fn line(&mut self, line: Line<i32>, smooth: bool, width: u8, color: Color) -> Result<()> {
-        self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
-            let [x1, y1] = line.start().map(|v| v as i16);
-            let [x2, y2] = line.end().map(|v| v as i16);
-            if width == 1 {
-                if y1 == y2 {
-                    canvas.hline(x1, x2, y1, color)
-                } else if x1 == x2 {
-                    canvas.vline(x1, y1, y2, color)
-                } else if smooth {
-                    canvas.aa_line(x1, y1, x2, y2, color)
+        self.update_shape_canvas(
+            bounding_rect([line.start(), line.end()], i32::from(width)),
+            |canvas: &mut Canvas<_>| -> Result<()> {
+                let [x1, y1] = line.start().map(|v| v as i16);
+                let [x2, y2] = line.end().map(|v| v as i16);
+                if width == 1 {
+                    if y1 == y2 {
+                        canvas.hline(x1, x2, y1, color)
+                    } else if x1 == x2 {
+                        canvas.vline(x1, y1, y2, color)
+                    } else if smooth {
+                        canvas.aa_line(x1, y1, x2, y2, color)
+                    } else {
+                        canvas.line(x1, y1, x2, y2, color)
+                    }
                 } else {
-                    canvas.line(x1, y1, x2, y2, color)
+                    canvas.thick_line(x1, y1, x2, y2, width, color)
                 }
-            } else {
-                canvas.thick_line(x1, y1, x2, y2, width, color)
-            }
-            .map_err(Error::Renderer)?;
-            Ok(())
-        })
+                .map_err(Error::Renderer)?;
+                Ok(())
+            },
+        )
     }
 
     /// Draw a cubic Bezier curve to the current canvas.
@@ -480,20 +658,25 @@ impl Rendering for Renderer {
     where
         I: Iterator<Item = Point<i32>>,
     {
-        self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
-            let (vx, vy): (Vec<i16>, Vec<i16>) = ps
-                .map(|p| -> (i16, i16) {
-                    let [x, y] = p.map(|v| v as i16);
-                    (x, y)
-                })
-                .unzip();
-            if let Some(stroke) = stroke {
-                canvas
-                    .bezier(&vx, &vy, detail, stroke)
-                    .map_err(Error::Renderer)?;
-            }
-            Ok(())
-        })
+        let ps: Vec<Point<i32>> = ps.collect();
+        self.update_shape_canvas(
+            bounding_rect(ps.iter().copied(), 1),
+            |canvas: &mut Canvas<_>| -> Result<()> {
+                let (vx, vy): (Vec<i16>, Vec<i16>) = ps
+                    .iter()
+                    .map(|p| -> (i16, i16) {
+                        let [x, y] = p.map(|v| v as i16);
+                        (x, y)
+                    })
+                    .unzip();
+                if let Some(stroke) = stroke {
+                    canvas
+                        .bezier(&vx, &vy, detail, stroke)
+                        .map_err(Error::Renderer)?;
+                }
+                Ok(())
+            },
+        )
     }
 
     /// Draw a triangle to the current canvas.
@@ -505,25 +688,28 @@ impl Rendering for Renderer {
         fill: Option<Color>,
         stroke: Option<Color>,
     ) -> Result<()> {
-        self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
-            let [x1, y1] = tri.p1().map(|v| v as i16);
-            let [x2, y2] = tri.p2().map(|v| v as i16);
-            let [x3, y3] = tri.p3().map(|v| v as i16);
-            if let Some(fill) = fill {
-                canvas
-                    .filled_trigon(x1, y1, x2, y2, x3, y3, fill)
+        self.update_shape_canvas(
+            bounding_rect(tri.points(), 1),
+            |canvas: &mut Canvas<_>| -> Result<()> {
+                let [x1, y1] = tri.p1().map(|v| v as i16);
+                let [x2, y2] = tri.p2().map(|v| v as i16);
+                let [x3, y3] = tri.p3().map(|v| v as i16);
+                if let Some(fill) = fill {
+                    canvas
+                        .filled_trigon(x1, y1, x2, y2, x3, y3, fill)
+                        .map_err(Error::Renderer)?;
+                }
+                if let Some(stroke) = stroke {
+                    if smooth {
+                        canvas.aa_trigon(x1, y1, x2, y2, x3, y3, stroke)
+                    } else {
+                        canvas.trigon(x1, y1, x2, y2, x3, y3, stroke)
+                    }
                     .map_err(Error::Renderer)?;
-            }
-            if let Some(stroke) = stroke {
-                if smooth {
-                    canvas.aa_trigon(x1, y1, x2, y2, x3, y3, stroke)
-                } else {
-                    canvas.trigon(x1, y1, x2, y2, x3, y3, stroke)
                 }
-                .map_err(Error::Renderer)?;
-            }
-            Ok(())
-        })
+                Ok(())
+            },
+        )
     }
 
     /// Draw a rectangle to the current canvas.
@@ -535,33 +721,43 @@ impl Rendering for Renderer {
         fill: Option<Color>,
         stroke: Option<Color>,
     ) -> Result<()> {
-        self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
-            let [x, y, width, height] = rect.map(|v| v as i16);
-            if let Some(fill) = fill {
-                radius
-                    .map_or_else(
-                        || canvas.box_(x, y, x + width, y + height, fill),
-                        |radius| {
-                            let radius = radius as i16;
-                            canvas.rounded_box(x, y, x + width, y + height, radius, fill)
-                        },
-                    )
-                    .map_err(Error::Renderer)?;
-            }
-            if let Some(stroke) = stroke {
-                radius
-                    .map_or_else(
-                        // EXPL: SDL2_gfx renders this 1px smaller than it should.
-                        || canvas.rectangle(x, y, x + width + 1, y + height + 1, stroke),
-                        |radius| {
-                            let radius = radius as i16;
-                            canvas.rounded_rectangle(x, y, x + width, y + height, radius, stroke)
-                        },
-                    )
-                    .map_err(Error::Renderer)?;
-            }
-            Ok(())
-        })
+        self.update_shape_canvas(
+            bounding_rect([rect.top_left(), rect.bottom_right()], 1),
+            |canvas: &mut Canvas<_>| -> Result<()> {
+                let [x, y, width, height] = rect.map(|v| v as i16);
+                if let Some(fill) = fill {
+                    radius
+                        .map_or_else(
+                            || canvas.box_(x, y, x + width, y + height, fill),
+                            |radius| {
+                                let radius = radius as i16;
+                                canvas.rounded_box(x, y, x + width, y + height, radius, fill)
+                            },
+                        )
+                        .map_err(Error::Renderer)?;
+                }
+                if let Some(stroke) = stroke {
+                    radius
+                        .map_or_else(
+                            // EXPL: SDL2_gfx renders this 1px smaller than it should.
+                            || canvas.rectangle(x, y, x + width + 1, y + height + 1, stroke),
+                            |radius| {
+                                let radius = radius as i16;
+                                canvas.rounded_rectangle(
+                                    x,
+                                    y,
+                                    x + width,
+                                    y + height,
+                                    radius,
+                                    stroke,
+                                )
+                            },
+                        )
+                        .map_err(Error::Renderer)?;
+                }
+                Ok(())
+            },
+        )
     }
 
     /// Draw a quadrilateral to the current canvas.
@@ -573,28 +769,31 @@ impl Rendering for Renderer {
         fill: Option<Color>,
         stroke: Option<Color>,
     ) -> Result<()> {
-        self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
-            let [x1, y1] = quad.p1().map(|v| v as i16);
-            let [x2, y2] = quad.p2().map(|v| v as i16);
-            let [x3, y3] = quad.p3().map(|v| v as i16);
-            let [x4, y4] = quad.p4().map(|v| v as i16);
-            let vx = [x1, x2, x3, x4];
-            let vy = [y1, y2, y3, y4];
-            if let Some(fill) = fill {
-                canvas
-                    .filled_polygon(&vx, &vy, fill)
+        self.update_shape_canvas(
+            bounding_rect(quad.points(), 1),
+            |canvas: &mut Canvas<_>| -> Result<()> {
+                let [x1, y1] = quad.p1().map(|v| v as i16);
+                let [x2, y2] = quad.p2().map(|v| v as i16);
+                let [x3, y3] = quad.p3().map(|v| v as i16);
+                let [x4, y4] = quad.p4().map(|v| v as i16);
+                let vx = [x1, x2, x3, x4];
+                let vy = [y1, y2, y3, y4];
+                if let Some(fill) = fill {
+                    canvas
+                        .filled_polygon(&vx, &vy, fill)
+                        .map_err(Error::Renderer)?;
+                }
+                if let Some(stroke) = stroke {
+                    if smooth {
+                        canvas.aa_polygon(&vx, &vy, stroke)
+                    } else {
+                        canvas.polygon(&vx, &vy, stroke)
+                    }
                     .map_err(Error::Renderer)?;
-            }
-            if let Some(stroke) = stroke {
-                if smooth {
-                    canvas.aa_polygon(&vx, &vy, stroke)
-                } else {
-                    canvas.polygon(&vx, &vy, stroke)
                 }
-                .map_err(Error::Renderer)?;
-            }
-            Ok(())
-        })
+                Ok(())
+            },
+        )
     }
 
     /// Draw a polygon to the current canvas.
@@ -609,28 +808,115 @@ impl Rendering for Renderer {
     where
         I: Iterator<Item = Point<i32>>,
     {
-        self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
-            let (vx, vy): (Vec<i16>, Vec<i16>) = ps
-                .map(|p| -> (i16, i16) {
-                    let [x, y] = p.map(|v| v as i16);
-                    (x, y)
-                })
-                .unzip();
-            if let Some(fill) = fill {
-                canvas
-                    .filled_polygon(&vx, &vy, fill)
+        let ps: Vec<Point<i32>> = ps.collect();
+        self.update_shape_canvas(
+            bounding_rect(ps.iter().copied(), 1),
+            |canvas: &mut Canvas<_>| -> Result<()> {
+                let (vx, vy): (Vec<i16>, Vec<i16>) = ps
+                    .iter()
+                    .map(|p| -> (i16, i16) {
+                        let [x, y] = p.map(|v| v as i16);
+                        (x, y)
+                    })
+                    .unzip();
+                if let Some(fill) = fill {
+                    canvas
+                        .filled_polygon(&vx, &vy, fill)
+                        .map_err(Error::Renderer)?;
+                }
+                if let Some(stroke) = stroke {
+                    if smooth {
+                        canvas.aa_polygon(&vx, &vy, stroke)
+                    } else {
+                        canvas.polygon(&vx, &vy, stroke)
+                    }
                     .map_err(Error::Renderer)?;
-            }
-            if let Some(stroke) = stroke {
-                if smooth {
-                    canvas.aa_polygon(&vx, &vy, stroke)
-                } else {
-                    canvas.polygon(&vx, &vy, stroke)
                 }
-                .map_err(Error::Renderer)?;
-            }
-            Ok(())
-        })
+                Ok(())
+            },
+        )
+    }
+
+    /// Draw a path made up of one or more contours to the current canvas.
+    fn path(
+        &mut self,
+        contours: &[(Vec<Point<i32>>, bool)],
+        smooth: bool,
+        width: u8,
+        fill: Option<Color>,
+        stroke: Option<Color>,
+    ) -> Result<()> {
+        self.update_shape_canvas(
+            bounding_rect(
+                contours
+                    .iter()
+                    .flat_map(|(points, _)| points.iter().copied()),
+                i32::from(width),
+            ),
+            |canvas: &mut Canvas<_>| -> Result<()> {
+                if let Some(fill) = fill {
+                    // SDL_gfx polygons can't have holes, so fill using even-odd scanlines instead.
+                    let contours: Vec<Vec<[f64; 2]>> = contours
+                        .iter()
+                        .map(|(points, _)| points.iter().map(|p| p.map(f64::from)).collect())
+                        .collect();
+                    let paint = Paint::Solid(fill);
+                    fill_scanlines(
+                        canvas,
+                        &contours,
+                        FillRule::EvenOdd,
+                        &paint,
+                        &Transform::identity(),
+                    )?;
+                }
+                if let Some(stroke) = stroke {
+                    for (points, closed) in contours {
+                        let closing = points.last().zip(points.first()).filter(|_| *closed);
+                        let segments = points.windows(2).map(|w| (&w[0], &w[1]));
+                        for (p1, p2) in segments.chain(closing) {
+                            let [x1, y1] = p1.map(|v| v as i16);
+                            let [x2, y2] = p2.map(|v| v as i16);
+                            if width > 1 {
+                                canvas.thick_line(x1, y1, x2, y2, width, stroke)
+                            } else if smooth {
+                                canvas.aa_line(x1, y1, x2, y2, stroke)
+                            } else {
+                                canvas.line(x1, y1, x2, y2, stroke)
+                            }
+                            .map_err(Error::Renderer)?;
+                        }
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Fill one or more polygons to the current canvas as a single shape.
+    fn fill_polygons(
+        &mut self,
+        polygons: &[Vec<Point<f64>>],
+        rule: FillRule,
+        paint: &Paint,
+        transform: &Transform,
+    ) -> Result<()> {
+        self.update_shape_canvas(
+            bounding_rect(
+                polygons
+                    .iter()
+                    .flatten()
+                    .flat_map(|p| [p.map(|v| v.floor() as i32), p.map(|v| v.ceil() as i32)])
+                    .map(Point::from),
+                1,
+            ),
+            |canvas: &mut Canvas<_>| -> Result<()> {
+                let polygons: Vec<Vec<[f64; 2]>> = polygons
+                    .iter()
+                    .map(|polygon| polygon.iter().map(Point::coords).collect())
+                    .collect();
+                fill_scanlines(canvas, &polygons, rule, paint, transform)
+            },
+        )
     }
 
     /// Draw a ellipse to the current canvas.
@@ -642,34 +928,37 @@ impl Rendering for Renderer {
         fill: Option<Color>,
         stroke: Option<Color>,
     ) -> Result<()> {
-        self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
-            let [x, y, width, height] = ellipse.map(|v| v as i16);
-            let rw = width / 2;
-            let rh = height / 2;
-            if let Some(fill) = fill {
-                if width == height {
-                    canvas.filled_circle(x, y, rw, fill)
-                } else {
-                    canvas.filled_ellipse(x, y, rw, rh, fill)
+        self.update_shape_canvas(
+            bounding_rect([ellipse.top_left(), ellipse.bottom_right()], 1),
+            |canvas: &mut Canvas<_>| -> Result<()> {
+                let [x, y, width, height] = ellipse.map(|v| v as i16);
+                let rw = width / 2;
+                let rh = height / 2;
+                if let Some(fill) = fill {
+                    if width == height {
+                        canvas.filled_circle(x, y, rw, fill)
+                    } else {
+                        canvas.filled_ellipse(x, y, rw, rh, fill)
+                    }
+                    .map_err(Error::Renderer)?;
                 }
-                .map_err(Error::Renderer)?;
-            }
-            if let Some(stroke) = stroke {
-                if width == height {
-                    if smooth {
-                        canvas.aa_circle(x, y, rw, stroke)
+                if let Some(stroke) = stroke {
+                    if width == height {
+                        if smooth {
+                            canvas.aa_circle(x, y, rw, stroke)
+                        } else {
+                            canvas.circle(x, y, rw, stroke)
+                        }
+                    } else if smooth {
+                        canvas.aa_ellipse(x, y, rw, rh, stroke)
                     } else {
-                        canvas.circle(x, y, rw, stroke)
+                        canvas.ellipse(x, y, rw, rh, stroke)
                     }
-                } else if smooth {
-                    canvas.aa_ellipse(x, y, rw, rh, stroke)
-                } else {
-                    canvas.ellipse(x, y, rw, rh, stroke)
+                    .map_err(Error::Renderer)?;
                 }
-                .map_err(Error::Renderer)?;
-            }
-            Ok(())
-        })
+                Ok(())
+            },
+        )
     }
 
     /// Draw an arc to the current canvas.
@@ -684,34 +973,37 @@ impl Rendering for Renderer {
         fill: Option<Color>,
         stroke: Option<Color>,
     ) -> Result<()> {
-        self.update_canvas(|canvas: &mut Canvas<_>| -> Result<()> {
-            let [x, y] = p.map(|v| v as i16);
-            let radius = radius as i16;
-            let start = start as i16;
-            let end = end as i16;
-            match mode {
-                ArcMode::Default => {
-                    if let Some(stroke) = stroke {
-                        canvas
-                            .arc(x, y, radius, start, end, stroke)
-                            .map_err(Error::Renderer)?;
-                    }
-                }
-                ArcMode::Pie => {
-                    if let Some(fill) = fill {
-                        canvas
-                            .filled_pie(x, y, radius, start, end, fill)
-                            .map_err(Error::Renderer)?;
+        self.update_shape_canvas(
+            bounding_rect([p - radius, p + radius], 1),
+            |canvas: &mut Canvas<_>| -> Result<()> {
+                let [x, y] = p.map(|v| v as i16);
+                let radius = radius as i16;
+                let start = start as i16;
+                let end = end as i16;
+                match mode {
+                    ArcMode::Default => {
+                        if let Some(stroke) = stroke {
+                            canvas
+                                .arc(x, y, radius, start, end, stroke)
+                                .map_err(Error::Renderer)?;
+                        }
                     }
-                    if let Some(stroke) = stroke {
-                        canvas
-                            .pie(x, y, radius, start, end, stroke)
-                            .map_err(Error::Renderer)?;
+                    ArcMode::Pie => {
+                        if let Some(fill) = fill {
+                            canvas
+                                .filled_pie(x, y, radius, start, end, fill)
+                                .map_err(Error::Renderer)?;
+                        }
+                        if let Some(stroke) = stroke {
+                            canvas
+                                .pie(x, y, radius, start, end, stroke)
+                                .map_err(Error::Renderer)?;
+                        }
                     }
                 }
-            }
-            Ok(())
-        })
+                Ok(())
+            },
+        )
     }
 
     /// Draw an image to the current canvas, optionally rotated about a `center`, flipped or tinted
@@ -730,6 +1022,12 @@ impl Rendering for Renderer {
             .windows
             .get_mut(&self.window_target)
             .ok_or(Error::InvalidWindow(self.window_target))?;
+        let converted = match img.format() {
+            PixelFormat::Gray => Some(img.convert(PixelFormat::Rgb)),
+            PixelFormat::GrayAlpha => Some(img.convert(PixelFormat::Rgba)),
+            _ => None,
+        };
+        let pixels = converted.as_ref().unwrap_or(img);
         let texture = {
             let key: *const Image = img;
             if !window.image_cache.contains(&key) {
@@ -739,9 +1037,9 @@ impl Rendering for Renderer {
                         window
                             .canvas
                             .create_texture_static(
-                                Some(img.format().into()),
-                                img.width(),
-                                img.height(),
+                                Some(pixels.format().into()),
+                                pixels.width(),
+                                pixels.height(),
                             )
                             .context("failed to create image texture")?,
                     ),
@@ -754,13 +1052,15 @@ impl Rendering for Renderer {
         let [r, g, b, a] = tint.map_or([255; 4], |t| t.channels());
         texture.set_color_mod(r, g, b);
         texture.set_alpha_mod(a);
-        texture.set_blend_mode(self.blend_mode);
+        if self.mask.is_some() || !set_texture_blend_mode(texture, self.blend_mode) {
+            // Rotated images can extend past `dst`, so composite the whole target
+            let bounds = dst.filter(|_| angle == 0.0 && center.is_none());
+            return self.composite(bounds, |renderer| {
+                renderer.image(img, src, dst, angle, center, flipped, tint)
+            });
+        }
         texture
-            .update(
-                None,
-                img.as_bytes(),
-                img.format().channels() * img.width() as usize,
-            )
+            .update(None, pixels.as_bytes(), pixels.pitch())
             .context("failed to update image texture")?;
 
         let update = |canvas: &mut Canvas<_>| -> Result<()> {
@@ -780,10 +1080,12 @@ impl Rendering for Renderer {
 
         if let Some(texture_id) = self.texture_target {
             if let Some(texture) = window.textures.get(&texture_id) {
+                let clip = self.clip.map(Into::into);
                 let mut result = Ok(());
                 window
                     .canvas
                     .with_texture_canvas(&mut texture.borrow_mut(), |canvas| {
+                        canvas.set_clip_rect(clip);
                         result = update(canvas);
                     })
                     .with_context(|| format!("failed to update texture target {texture_id}"))?;
@@ -801,34 +1103,42 @@ impl Rendering for Renderer {
     /// Return the current rendered target pixels as an array of bytes.
     #[inline]
     fn to_bytes(&mut self) -> Result<Vec<u8>> {
-        if let Some(texture_id) = self.texture_target {
-            let window = self
-                .windows
-                .values_mut()
-                .find(|w| w.textures.contains_key(&texture_id));
-            if let Some(window) = window {
-                // We ensured there's a valid texture above
-                let texture = window
-                    .textures
-                    .get(&texture_id)
-                    .ok_or_else(|| anyhow!(Error::InvalidTexture(texture_id)))?;
-                let mut result = Ok(vec![]);
-                window
-                    .canvas
-                    .with_texture_canvas(&mut texture.borrow_mut(), |canvas| {
-                        result = canvas.read_pixels(None, SdlPixelFormat::RGBA32);
-                    })
-                    .with_context(|| format!("failed to read texture target {texture_id}"))?;
-                Ok(result.map_err(Error::Renderer)?)
-            } else {
-                Err(Error::InvalidTexture(texture_id).into())
-            }
-        } else {
-            Ok(self
-                .canvas()?
-                .read_pixels(None, SdlPixelFormat::RGBA32)
-                .map_err(Error::Renderer)?)
+        self.read_pixels(None)
+    }
+
+    /// Returns the size in pixels of the current render target as `(width, height)`.
+    fn target_size(&mut self) -> Result<(u32, u32)> {
+        let ((width, height), (sx, sy)) = self.target_viewport()?;
+        Ok((
+            (width as f32 * sx).round() as u32,
+            (height as f32 * sy).round() as u32,
+        ))
+    }
+
+    /// Replace the current rendered target pixels with an array of `RGBA` bytes.
+    fn update_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        let (width, height) = self.target_size()?;
+        let pitch = PixelFormat::Rgba.channels() * width as usize;
+        if bytes.len() != pitch * height as usize {
+            return Err(anyhow!(
+                "invalid pixel data for canvas update: expected {} bytes, got {}",
+                pitch * height as usize,
+                bytes.len()
+            ));
         }
+
+        let texture_id = self.create_texture(width, height, Some(PixelFormat::Rgba))?;
+        let (mode, clip, mask) = (self.blend_mode, self.clip, self.mask.take());
+        self.blend_mode = BlendMode::None;
+        let result = self
+            .update_texture(texture_id, None, bytes, pitch)
+            .and_then(|()| self.clip(None))
+            .and_then(|()| self.texture(texture_id, None, None, 0.0, None, None, None))
+            .and_then(|()| self.clip(clip));
+        self.blend_mode = mode;
+        self.mask = mask;
+        self.delete_texture(texture_id)?;
+        result
     }
 
     /// Connect a controller with the given joystick index to start receiving events.
@@ -852,6 +1162,97 @@ impl Rendering for Renderer {
     }
 }
 
+/// Returns the rectangle bounding `points`, padded by `pad` pixels on each side to cover strokes
+/// and anti-aliasing.
+fn bounding_rect<I>(points: I, pad: i32) -> Option<Rect<i32>>
+where
+    I: IntoIterator<Item = Point<i32>>,
+{
+    let mut points = points.into_iter();
+    let first = points.next()?;
+    let (min, max) = points.fold((first, first), |(min, max), p| {
+        (
+            point![min.x().min(p.x()), min.y().min(p.y())],
+            point![max.x().max(p.x()), max.y().max(p.y())],
+        )
+    });
+    Some(rect![
+        min.x() - pad,
+        min.y() - pad,
+        max.x() - min.x() + 1 + 2 * pad,
+        max.y() - min.y() + 1 + 2 * pad
+    ])
+}
+
+/// Fill contours using horizontal lines sampled at pixel centers, using the given [`FillRule`].
+/// Gradient paints are drawn a pixel at a time, mapped into paint space by `transform`.
+fn fill_scanlines(
+    canvas: &mut Canvas<Window>,
+    contours: &[Vec<[f64; 2]>],
+    rule: FillRule,
+    paint: &Paint,
+    transform: &Transform,
+) -> Result<()> {
+    let (min_y, max_y) = contours
+        .iter()
+        .flat_map(|contour| contour.iter())
+        .fold((f64::MAX, f64::MIN), |(min, max), &[_, y]| {
+            (min.min(y), max.max(y))
+        });
+    if min_y > max_y {
+        return Ok(());
+    }
+    // Rows are limited to the coordinate range SDL_gfx can draw
+    let y0 = (min_y.floor() as i32).max(i32::from(i16::MIN));
+    let y1 = (max_y.ceil() as i32).min(i32::from(i16::MAX));
+    let mut crossings = Vec::new();
+    for y in y0..y1 {
+        let sample_y = f64::from(y) + 0.5;
+        crossings.clear();
+        for contour in contours {
+            let edges = contour.iter().zip(contour.iter().cycle().skip(1));
+            for (&[ax, ay], &[bx, by]) in edges {
+                let winding = if ay <= sample_y && by > sample_y {
+                    1
+                } else if by <= sample_y && ay > sample_y {
+                    -1
+                } else {
+                    continue;
+                };
+                crossings.push((ax + (sample_y - ay) * (bx - ax) / (by - ay), winding));
+            }
+        }
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut winding = 0;
+        for pair in crossings.windows(2) {
+            winding += pair[0].1;
+            let inside = match rule {
+                FillRule::EvenOdd => winding % 2 != 0,
+                FillRule::NonZero => winding != 0,
+            };
+            // Pixels with centers between the crossings are filled
+            let x1 = (pair[0].0 - 0.5).ceil() as i16;
+            let x2 = ((pair[1].0 - 0.5).ceil() as i16).saturating_sub(1);
+            if !inside || x2 < x1 {
+                continue;
+            }
+            if let Paint::Solid(color) = paint {
+                canvas
+                    .hline(x1, x2, y as i16, *color)
+                    .map_err(Error::Renderer)?;
+            } else {
+                for x in x1..=x2 {
+                    let p = transform.transform_point([f64::from(x), f64::from(y)]);
+                    canvas
+                        .pixel(x, y as i16, paint.color_at(p))
+                        .map_err(Error::Renderer)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 impl fmt::Debug for Renderer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Renderer")
@@ -977,17 +1378,34 @@ impl From<&Point<i32>> for SdlPoint {
     }
 }
 
-#[doc(hidden)]
-impl From<BlendMode> for SdlBlendMode {
-    /// Convert [`BlendMode`] to [`SdlBlendMode`].
-    fn from(mode: BlendMode) -> Self {
-        match mode {
-            BlendMode::None => Self::None,
-            BlendMode::Blend => Self::Blend,
-            BlendMode::Add => Self::Add,
-            BlendMode::Mod => Self::Mod,
-        }
-    }
+/// Sets the blend mode used to draw `texture`, returning `false` if `SDL` can't render `mode` and
+/// it has to be composited on the CPU instead.
+fn set_texture_blend_mode(texture: &mut SdlTexture, mode: BlendMode) -> bool {
+    use sdl2::sys::{
+        SDL_BlendFactor as Factor, SDL_BlendMode as SdlMode, SDL_BlendOperation as Operation,
+    };
+    let mode = match mode {
+        BlendMode::None => SdlMode::SDL_BLENDMODE_NONE,
+        BlendMode::Blend => SdlMode::SDL_BLENDMODE_BLEND,
+        BlendMode::Add => SdlMode::SDL_BLENDMODE_ADD,
+        BlendMode::Mod => SdlMode::SDL_BLENDMODE_MOD,
+        // SAFETY: Composing a blend mode has no preconditions.
+        BlendMode::Subtract => unsafe {
+            sdl2::sys::SDL_ComposeCustomBlendMode(
+                Factor::SDL_BLENDFACTOR_SRC_ALPHA,
+                Factor::SDL_BLENDFACTOR_ONE,
+                Operation::SDL_BLENDOPERATION_REV_SUBTRACT,
+                Factor::SDL_BLENDFACTOR_ONE,
+                Factor::SDL_BLENDFACTOR_ONE_MINUS_SRC_ALPHA,
+                Operation::SDL_BLENDOPERATION_ADD,
+            )
+        },
+        // The remaining modes can't be expressed as blend factors of unpremultiplied colors.
+        _ => return false,
+    };
+    // SAFETY: `texture` is a valid texture. Renderers without support for custom blend modes
+    // return an error.
+    unsafe { sdl2::sys::SDL_SetTextureBlendMode(texture.raw(), mode) == 0 }
 }
 
 #[doc(hidden)]
@@ -995,8 +1413,9 @@ impl From<PixelFormat> for SdlPixelFormat {
     /// Convert [`PixelFormat`] to [`SdlPixelFormat`].
     fn from(format: PixelFormat) -> Self {
         match format {
-            PixelFormat::Rgb => Self::RGB24,
-            PixelFormat::Rgba => Self::RGBA32,
+            // SDL has no grayscale formats, so grayscale images are converted before uploading
+            PixelFormat::Rgb | PixelFormat::Gray => Self::RGB24,
+            PixelFormat::Rgba | PixelFormat::GrayAlpha => Self::RGBA32,
         }
     }
 }
@@ -189,6 +189,24 @@ impl From<SdlEvent> for Event {
                 dy,
                 pressure,
             },
+            SdlEvent::DropFile {
+                window_id,
+                filename,
+                ..
+            } => Self::DropFile {
+                window_id,
+                path: filename.into(),
+            },
+            SdlEvent::DropText {
+                window_id,
+                filename,
+                ..
+            } => Self::DropText {
+                window_id,
+                text: filename,
+            },
+            SdlEvent::DropBegin { window_id, .. } => Self::DropBegin { window_id },
+            SdlEvent::DropComplete { window_id, .. } => Self::DropComplete { window_id },
             SdlEvent::AudioDeviceAdded {
                 which, iscapture, ..
             } => Self::AudioDeviceAdded {
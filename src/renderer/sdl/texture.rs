@@ -1,4 +1,4 @@
-use super::Renderer;
+use super::{set_texture_blend_mode, Renderer};
 use crate::{
     error::{Error, Result},
     prelude::*,
@@ -149,7 +149,14 @@ impl TextureRenderer for Renderer {
                 let [r, g, b, a] = tint.map_or([255; 4], |t| t.channels());
                 texture.set_color_mod(r, g, b);
                 texture.set_alpha_mod(a);
-                texture.set_blend_mode(self.blend_mode);
+                if !set_texture_blend_mode(&mut texture, self.blend_mode) {
+                    drop(texture);
+                    // Rotated textures can extend past `dst`, so composite the whole target
+                    let bounds = dst.filter(|_| angle == 0.0 && center.is_none());
+                    return self.composite(bounds, |renderer| {
+                        renderer.texture(texture_id, src, dst, angle, center, flipped, tint)
+                    });
+                }
             }
             let src = src.map(Into::into);
             let dst = dst.map(Into::into);
@@ -172,10 +179,12 @@ impl TextureRenderer for Renderer {
 
             if let Some(texture_id) = target_texture {
                 if let Some(texture) = window.textures.get(&texture_id) {
+                    let clip = self.clip.map(Into::into);
                     let mut result = Ok(());
                     window
                         .canvas
                         .with_texture_canvas(&mut texture.borrow_mut(), |canvas| {
+                            canvas.set_clip_rect(clip);
                             result = update(canvas);
                         })
                         .with_context(|| format!("failed to update texture target {texture_id}"))?;
@@ -162,6 +162,11 @@ impl WindowCanvas {
         if let Some(ref mut icon) = s.icon {
             let surface = match icon {
                 Icon::Image(ref mut img) => {
+                    match img.format() {
+                        PixelFormat::Gray => *img = img.convert(PixelFormat::Rgb),
+                        PixelFormat::GrayAlpha => *img = img.convert(PixelFormat::Rgba),
+                        _ => (),
+                    }
                     let width = img.width();
                     let height = img.height();
                     let pitch = img.pitch() as u32;
@@ -32,6 +32,10 @@ impl Rendering for Renderer {
         todo!()
     }
 
+    fn mask(&mut self, mask: Option<std::rc::Rc<crate::state::clip::Mask>>) {
+        todo!()
+    }
+
     fn present(&mut self) {
         todo!()
     }
@@ -155,6 +159,27 @@ impl Rendering for Renderer {
         todo!()
     }
 
+    fn path(
+        &mut self,
+        contours: &[(Vec<crate::prelude::Point<i32>>, bool)],
+        smooth: bool,
+        width: u8,
+        fill: Option<crate::prelude::Color>,
+        stroke: Option<crate::prelude::Color>,
+    ) -> crate::prelude::Result<()> {
+        todo!()
+    }
+
+    fn fill_polygons(
+        &mut self,
+        polygons: &[Vec<crate::prelude::Point<f64>>],
+        rule: crate::renderer::FillRule,
+        paint: &crate::prelude::Paint,
+        transform: &crate::prelude::Transform,
+    ) -> crate::prelude::Result<()> {
+        todo!()
+    }
+
     fn ellipse(
         &mut self,
         ellipse: crate::prelude::Ellipse<i32>,
@@ -195,6 +220,14 @@ impl Rendering for Renderer {
         todo!()
     }
 
+    fn target_size(&mut self) -> crate::prelude::Result<(u32, u32)> {
+        todo!()
+    }
+
+    fn update_bytes(&mut self, bytes: &[u8]) -> crate::prelude::Result<()> {
+        todo!()
+    }
+
     fn open_controller(
         &mut self,
         controller_id: crate::event::ControllerId,
@@ -0,0 +1,848 @@
+//! [`Scene`] trait and [`SceneStack`] for managing application states such as menus, levels and
+//! pause screens.
+//!
+//! A `Scene` has the same update and event hooks as [`PixEngine`], plus [`Scene::on_enter`] and
+//! [`Scene::on_exit`] which are called as scenes are added to and removed from a `SceneStack`.
+//! Scenes request changes by returning a [`Transition`] from [`Scene::on_update`]. A `Transition`
+//! can push a new scene on top of the current one, pop back to the previous scene or replace the
+//! current scene entirely. The stack can optionally [fade](SceneStack::set_fade) out and back in
+//! between scenes.
+//!
+//! `SceneStack` implements [`PixEngine`] and is passed to [`Engine::run`] in place of an
+//! application. Only the top scene is updated each frame. Events are sent to the top scene only by
+//! default, or can be passed down through the whole stack until handled using
+//! [`Propagation::Stack`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use pix_engine::prelude::*;
+//! use std::time::Duration;
+//!
+//! struct Menu;
+//!
+//! impl Scene for Menu {
+//!     fn on_update(&mut self, s: &mut PixState) -> PixResult<Transition> {
+//!         if s.button("Play")? {
+//!             return Ok(Transition::Push(Box::new(Game { paused: false })));
+//!         }
+//!         if s.button("Quit")? {
+//!             return Ok(Transition::Quit);
+//!         }
+//!         Ok(Transition::None)
+//!     }
+//! }
+//!
+//! struct Game {
+//!     paused: bool,
+//! }
+//!
+//! impl Scene for Game {
+//!     fn on_update(&mut self, s: &mut PixState) -> PixResult<Transition> {
+//!         if self.paused {
+//!             self.paused = false;
+//!             return Ok(Transition::Pop);
+//!         }
+//!         s.background(Color::BLACK);
+//!         s.circle([100, 100, 20])?;
+//!         Ok(Transition::None)
+//!     }
+//!
+//!     fn on_key_pressed(&mut self, s: &mut PixState, event: KeyEvent) -> PixResult<bool> {
+//!         if event.key == Key::Escape {
+//!             self.paused = true;
+//!             return Ok(true);
+//!         }
+//!         Ok(false)
+//!     }
+//! }
+//!
+//! fn main() -> PixResult<()> {
+//!     let mut engine = Engine::builder().build()?;
+//!     let mut scenes = SceneStack::new(Menu);
+//!     scenes.set_fade(Duration::from_millis(500), Color::BLACK);
+//!     engine.run(&mut scenes)
+//! }
+//! ```
+
+use crate::prelude::*;
+use std::{fmt, path::Path, time::Duration};
+
+/// A change to the [`SceneStack`] requested by a [`Scene`].
+#[non_exhaustive]
+#[derive(Default)]
+pub enum Transition {
+    /// Keep the current scene.
+    #[default]
+    None,
+    /// Pause the current scene and push a new scene on top of it.
+    Push(Box<dyn Scene>),
+    /// Exit the current scene and resume the scene below it. Quits the application if there are
+    /// no scenes left.
+    Pop,
+    /// Exit the current scene and replace it with a new scene.
+    Replace(Box<dyn Scene>),
+    /// Quit the application.
+    Quit,
+}
+
+impl fmt::Debug for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::Push(_) => write!(f, "Push(..)"),
+            Self::Pop => write!(f, "Pop"),
+            Self::Replace(_) => write!(f, "Replace(..)"),
+            Self::Quit => write!(f, "Quit"),
+        }
+    }
+}
+
+/// Which scenes in a [`SceneStack`] receive events.
+#[non_exhaustive]
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Propagation {
+    /// Only the top scene receives events.
+    #[default]
+    TopOnly,
+    /// Events are passed down from the top scene through the whole stack until a scene returns
+    /// `true` to indicate the event was handled.
+    Stack,
+}
+
+/// A single application state managed by a [`SceneStack`], such as a menu, level or pause screen.
+///
+/// Methods mirror those of [`PixEngine`] and the only required method is [`Scene::on_update`].
+/// See the [module-level documentation](self) for an example.
+#[allow(unused_variables)]
+pub trait Scene {
+    /// Called when this scene is added to the [`SceneStack`], either when the stack is started or
+    /// by a [`Transition::Push`] or [`Transition::Replace`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_start`].
+    fn on_enter(&mut self, s: &mut PixState) -> PixResult<()> {
+        Ok(())
+    }
+
+    /// Called when this scene is removed from the [`SceneStack`] by a [`Transition::Pop`] or
+    /// [`Transition::Replace`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_exit(&mut self, s: &mut PixState) -> PixResult<()> {
+        Ok(())
+    }
+
+    /// Called when another scene is pushed on top of this scene.
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_pause(&mut self, s: &mut PixState) -> PixResult<()> {
+        Ok(())
+    }
+
+    /// Called when this scene becomes the top scene again after the scene above it is popped.
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_resume(&mut self, s: &mut PixState) -> PixResult<()> {
+        Ok(())
+    }
+
+    /// Called every frame while this is the top scene, returning a [`Transition`] to change
+    /// scenes. See [`PixEngine::on_update`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<Transition>;
+
+    /// Called for every fixed timestep while this is the top scene. See
+    /// [`PixEngine::on_fixed_update`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_fixed_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        Ok(())
+    }
+
+    /// Called for every scene in the stack, from top to bottom, when the engine detects a
+    /// close/exit event. See [`PixEngine::on_stop`].
+    ///
+    /// # Errors
+    ///
+    /// See [`PixEngine::on_stop`].
+    fn on_stop(&mut self, s: &mut PixState) -> PixResult<()> {
+        Ok(())
+    }
+
+    /// Called each time a [Key] is pressed. See [`PixEngine::on_key_pressed`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_key_pressed(&mut self, s: &mut PixState, event: KeyEvent) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a [Key] is released. See [`PixEngine::on_key_released`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_key_released(&mut self, s: &mut PixState, event: KeyEvent) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time text input is received. See [`PixEngine::on_key_typed`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_key_typed(&mut self, s: &mut PixState, text: &str) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time the [Mouse] is moved while a button is being held. See
+    /// [`PixEngine::on_mouse_dragged`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_mouse_dragged(
+        &mut self,
+        s: &mut PixState,
+        pos: Point<i32>,
+        rel_pos: Point<i32>,
+    ) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a [`ControllerButton`] is pressed. See
+    /// [`PixEngine::on_controller_pressed`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_controller_pressed(
+        &mut self,
+        s: &mut PixState,
+        event: ControllerEvent,
+    ) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a [`ControllerButton`] is released. See
+    /// [`PixEngine::on_controller_released`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_controller_released(
+        &mut self,
+        s: &mut PixState,
+        event: ControllerEvent,
+    ) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a controller [Axis] is moved. See
+    /// [`PixEngine::on_controller_axis_motion`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_controller_axis_motion(
+        &mut self,
+        s: &mut PixState,
+        controller_id: ControllerId,
+        axis: Axis,
+        value: i32,
+    ) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a controller is added, removed or remapped. See
+    /// [`PixEngine::on_controller_update`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_controller_update(
+        &mut self,
+        s: &mut PixState,
+        controller_id: ControllerId,
+        update: ControllerUpdate,
+    ) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a [Mouse] button is pressed. See [`PixEngine::on_mouse_pressed`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_mouse_pressed(
+        &mut self,
+        s: &mut PixState,
+        btn: Mouse,
+        pos: Point<i32>,
+    ) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a [Mouse] button is released. See [`PixEngine::on_mouse_released`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_mouse_released(
+        &mut self,
+        s: &mut PixState,
+        btn: Mouse,
+        pos: Point<i32>,
+    ) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a [Mouse] button is clicked. See [`PixEngine::on_mouse_clicked`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_mouse_clicked(
+        &mut self,
+        s: &mut PixState,
+        btn: Mouse,
+        pos: Point<i32>,
+    ) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a [Mouse] button is clicked twice within 500ms. See
+    /// [`PixEngine::on_mouse_dbl_clicked`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_mouse_dbl_clicked(
+        &mut self,
+        s: &mut PixState,
+        btn: Mouse,
+        pos: Point<i32>,
+    ) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time the [Mouse] is moved. See [`PixEngine::on_mouse_motion`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_mouse_motion(
+        &mut self,
+        s: &mut PixState,
+        pos: Point<i32>,
+        rel_pos: Point<i32>,
+    ) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time the [Mouse] wheel is scrolled. See [`PixEngine::on_mouse_wheel`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_mouse_wheel(&mut self, s: &mut PixState, pos: Point<i32>) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a finger is pressed on a touch device. See
+    /// [`PixEngine::on_touch_start`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_touch_start(&mut self, s: &mut PixState, touch: Touch) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a finger pressed on a touch device is moved. See
+    /// [`PixEngine::on_touch_move`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_touch_move(&mut self, s: &mut PixState, touch: Touch) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a finger is released from a touch device. See
+    /// [`PixEngine::on_touch_end`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_touch_end(&mut self, s: &mut PixState, touch: Touch) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a [Gesture] is recognized from touch events. See
+    /// [`PixEngine::on_gesture`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_gesture(&mut self, s: &mut PixState, gesture: Gesture) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a file is dropped onto a window. See [`PixEngine::on_file_dropped`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_file_dropped(
+        &mut self,
+        s: &mut PixState,
+        window_id: WindowId,
+        path: &Path,
+    ) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a custom user event is received. See [`PixEngine::on_user_event`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_user_event(&mut self, s: &mut PixState, event: &UserEvent) -> PixResult<bool> {
+        Ok(false)
+    }
+
+    /// Called each time a window event occurs. See [`PixEngine::on_window_event`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_window_event(
+        &mut self,
+        s: &mut PixState,
+        window_id: WindowId,
+        event: WindowEvent,
+    ) -> PixResult<()> {
+        Ok(())
+    }
+
+    /// Called for any system or user event. See [`PixEngine::on_event`].
+    ///
+    /// # Errors
+    ///
+    /// Returning an error will start exiting the application. See [`PixEngine::on_update`].
+    fn on_event(&mut self, s: &mut PixState, event: &Event) -> PixResult<bool> {
+        Ok(false)
+    }
+}
+
+/// A fade between scenes in progress.
+#[derive(Debug)]
+struct Fade {
+    elapsed: Duration,
+    pending: Option<Transition>,
+}
+
+/// A stack of [`Scene`]s that implements [`PixEngine`], updating the top scene and applying the
+/// [`Transition`]s it returns. See the [module-level documentation](self) for more details.
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+    propagation: Propagation,
+    fade_duration: Duration,
+    fade_color: Color,
+    fade: Option<Fade>,
+}
+
+impl fmt::Debug for SceneStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SceneStack")
+            .field("scenes", &self.scenes.len())
+            .field("propagation", &self.propagation)
+            .field("fade_duration", &self.fade_duration)
+            .field("fade_color", &self.fade_color)
+            .field("fade", &self.fade)
+            .finish()
+    }
+}
+
+impl SceneStack {
+    /// Constructs a `SceneStack` starting with an initial [`Scene`]. [`Scene::on_enter`] is called
+    /// for the initial scene when the [`Engine`] starts.
+    pub fn new<S>(scene: S) -> Self
+    where
+        S: Scene + 'static,
+    {
+        Self {
+            scenes: vec![Box::new(scene)],
+            propagation: Propagation::default(),
+            fade_duration: Duration::ZERO,
+            fade_color: Color::BLACK,
+            fade: None,
+        }
+    }
+
+    /// The number of scenes in the stack.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.scenes.len()
+    }
+
+    /// Whether the stack has no scenes left.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.scenes.is_empty()
+    }
+
+    /// Which scenes receive events. Defaults to [`Propagation::TopOnly`].
+    #[inline]
+    #[must_use]
+    pub const fn propagation(&self) -> Propagation {
+        self.propagation
+    }
+
+    /// Set which scenes receive events.
+    #[inline]
+    pub fn set_propagation(&mut self, propagation: Propagation) {
+        self.propagation = propagation;
+    }
+
+    /// Whether a fade between scenes is in progress.
+    #[inline]
+    #[must_use]
+    pub const fn is_fading(&self) -> bool {
+        self.fade.is_some()
+    }
+
+    /// Fade out to `color` and back in over a total `duration` when pushing, popping or replacing
+    /// scenes. The transition is applied halfway through, when the screen is fully covered. A zero
+    /// `duration` disables fading, which is the default.
+    ///
+    /// While fading out, the current scene continues to be updated and any further transitions it
+    /// returns are ignored.
+    pub fn set_fade(&mut self, duration: Duration, color: Color) {
+        self.fade_duration = duration;
+        self.fade_color = color;
+    }
+
+    /// Start a [`Transition`], either immediately or by fading out first.
+    fn transition(&mut self, s: &mut PixState, transition: Transition) -> PixResult<()> {
+        match transition {
+            Transition::None => Ok(()),
+            Transition::Quit => {
+                s.quit();
+                Ok(())
+            }
+            transition if self.fade_duration.is_zero() => self.apply(s, transition),
+            transition => {
+                match self.fade {
+                    // Still fading out, ignore the new transition
+                    Some(Fade {
+                        pending: Some(_), ..
+                    }) => (),
+                    // Fading in, reverse direction from the current fade level
+                    Some(ref mut fade) => {
+                        fade.elapsed = self.fade_duration.saturating_sub(fade.elapsed);
+                        fade.pending = Some(transition);
+                    }
+                    None => {
+                        self.fade = Some(Fade {
+                            elapsed: Duration::ZERO,
+                            pending: Some(transition),
+                        });
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Apply a [`Transition`] to the stack, calling the relevant [`Scene`] hooks.
+    fn apply(&mut self, s: &mut PixState, transition: Transition) -> PixResult<()> {
+        match transition {
+            Transition::None => (),
+            Transition::Push(mut scene) => {
+                if let Some(top) = self.scenes.last_mut() {
+                    top.on_pause(s)?;
+                }
+                scene.on_enter(s)?;
+                self.scenes.push(scene);
+            }
+            Transition::Pop => {
+                if let Some(mut scene) = self.scenes.pop() {
+                    scene.on_exit(s)?;
+                }
+                match self.scenes.last_mut() {
+                    Some(top) => top.on_resume(s)?,
+                    None => s.quit(),
+                }
+            }
+            Transition::Replace(mut scene) => {
+                if let Some(mut top) = self.scenes.pop() {
+                    top.on_exit(s)?;
+                }
+                scene.on_enter(s)?;
+                self.scenes.push(scene);
+            }
+            Transition::Quit => s.quit(),
+        }
+        Ok(())
+    }
+
+    /// Advance the current fade, applying the pending [`Transition`] once fully faded out and
+    /// drawing the fade over the canvas.
+    fn update_fade(&mut self, s: &mut PixState) -> PixResult<()> {
+        let Some(fade) = self.fade.as_mut() else {
+            return Ok(());
+        };
+        fade.elapsed += s.delta_time();
+        let elapsed = fade.elapsed;
+        let half = self.fade_duration / 2;
+        if elapsed >= half {
+            if let Some(transition) = fade.pending.take() {
+                self.apply(s, transition)?;
+            }
+        }
+        // Fades too short to split in half are applied instantly
+        if elapsed >= self.fade_duration || half.is_zero() {
+            self.fade = None;
+            return Ok(());
+        }
+
+        let distance = if elapsed > half {
+            elapsed - half
+        } else {
+            half - elapsed
+        };
+        let level = 1.0 - distance.as_secs_f64() / half.as_secs_f64();
+        let mut color = self.fade_color;
+        color.set_alpha((f64::from(color.alpha()) * level).round() as u8);
+        let (width, height) = s.dimensions()?;
+
+        s.push();
+        s.reset_transform();
+        s.blend_mode(BlendMode::Blend);
+        s.rect_mode(RectMode::Corner);
+        s.stroke(None);
+        s.fill(color);
+        let result = s.rect([0, 0, width as i32, height as i32]);
+        s.pop();
+        result
+    }
+
+    /// Send an event to the top scene, or down through the stack until handled, depending on
+    /// [`Propagation`].
+    fn dispatch<F>(&mut self, mut f: F) -> PixResult<bool>
+    where
+        F: FnMut(&mut dyn Scene) -> PixResult<bool>,
+    {
+        for scene in self.scenes.iter_mut().rev() {
+            if f(scene.as_mut())? {
+                return Ok(true);
+            }
+            if self.propagation == Propagation::TopOnly {
+                break;
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl PixEngine for SceneStack {
+    fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.on_enter(s),
+            None => Ok(()),
+        }
+    }
+
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.on_update(s)?,
+            None => Transition::None,
+        };
+        self.transition(s, transition)?;
+        self.update_fade(s)
+    }
+
+    fn on_fixed_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        match self.scenes.last_mut() {
+            Some(scene) => scene.on_fixed_update(s),
+            None => Ok(()),
+        }
+    }
+
+    fn on_stop(&mut self, s: &mut PixState) -> PixResult<()> {
+        let mut result = Ok(());
+        for scene in self.scenes.iter_mut().rev() {
+            result = result.and(scene.on_stop(s));
+        }
+        result
+    }
+
+    fn on_key_pressed(&mut self, s: &mut PixState, event: KeyEvent) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_key_pressed(s, event))
+    }
+
+    fn on_key_released(&mut self, s: &mut PixState, event: KeyEvent) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_key_released(s, event))
+    }
+
+    fn on_key_typed(&mut self, s: &mut PixState, text: &str) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_key_typed(s, text))
+    }
+
+    fn on_mouse_dragged(
+        &mut self,
+        s: &mut PixState,
+        pos: Point<i32>,
+        rel_pos: Point<i32>,
+    ) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_mouse_dragged(s, pos, rel_pos))
+    }
+
+    fn on_controller_pressed(
+        &mut self,
+        s: &mut PixState,
+        event: ControllerEvent,
+    ) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_controller_pressed(s, event))
+    }
+
+    fn on_controller_released(
+        &mut self,
+        s: &mut PixState,
+        event: ControllerEvent,
+    ) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_controller_released(s, event))
+    }
+
+    fn on_controller_axis_motion(
+        &mut self,
+        s: &mut PixState,
+        controller_id: ControllerId,
+        axis: Axis,
+        value: i32,
+    ) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_controller_axis_motion(s, controller_id, axis, value))
+    }
+
+    fn on_controller_update(
+        &mut self,
+        s: &mut PixState,
+        controller_id: ControllerId,
+        update: ControllerUpdate,
+    ) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_controller_update(s, controller_id, update))
+    }
+
+    fn on_mouse_pressed(
+        &mut self,
+        s: &mut PixState,
+        btn: Mouse,
+        pos: Point<i32>,
+    ) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_mouse_pressed(s, btn, pos))
+    }
+
+    fn on_mouse_released(
+        &mut self,
+        s: &mut PixState,
+        btn: Mouse,
+        pos: Point<i32>,
+    ) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_mouse_released(s, btn, pos))
+    }
+
+    fn on_mouse_clicked(
+        &mut self,
+        s: &mut PixState,
+        btn: Mouse,
+        pos: Point<i32>,
+    ) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_mouse_clicked(s, btn, pos))
+    }
+
+    fn on_mouse_dbl_clicked(
+        &mut self,
+        s: &mut PixState,
+        btn: Mouse,
+        pos: Point<i32>,
+    ) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_mouse_dbl_clicked(s, btn, pos))
+    }
+
+    fn on_mouse_motion(
+        &mut self,
+        s: &mut PixState,
+        pos: Point<i32>,
+        rel_pos: Point<i32>,
+    ) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_mouse_motion(s, pos, rel_pos))
+    }
+
+    fn on_mouse_wheel(&mut self, s: &mut PixState, pos: Point<i32>) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_mouse_wheel(s, pos))
+    }
+
+    fn on_touch_start(&mut self, s: &mut PixState, touch: Touch) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_touch_start(s, touch))
+    }
+
+    fn on_touch_move(&mut self, s: &mut PixState, touch: Touch) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_touch_move(s, touch))
+    }
+
+    fn on_touch_end(&mut self, s: &mut PixState, touch: Touch) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_touch_end(s, touch))
+    }
+
+    fn on_gesture(&mut self, s: &mut PixState, gesture: Gesture) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_gesture(s, gesture))
+    }
+
+    fn on_file_dropped(
+        &mut self,
+        s: &mut PixState,
+        window_id: WindowId,
+        path: &Path,
+    ) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_file_dropped(s, window_id, path))
+    }
+
+    fn on_user_event(&mut self, s: &mut PixState, event: &UserEvent) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_user_event(s, event))
+    }
+
+    fn on_window_event(
+        &mut self,
+        s: &mut PixState,
+        window_id: WindowId,
+        event: WindowEvent,
+    ) -> PixResult<()> {
+        self.dispatch(|scene| {
+            scene.on_window_event(s, window_id, event)?;
+            Ok(false)
+        })?;
+        Ok(())
+    }
+
+    fn on_event(&mut self, s: &mut PixState, event: &Event) -> PixResult<bool> {
+        self.dispatch(|scene| scene.on_event(s, event))
+    }
+}
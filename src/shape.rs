@@ -2,6 +2,14 @@
 //!
 //! Methods for drawing and interacting with shapes such as points, lines, rectangles, etc.
 //!
+//! All shapes are drawn using the current [Transform], which can be changed with methods like
+//! [`PixState::translate`], [`PixState::rotate`] and [`PixState::scale_by`] and is saved and
+//! restored by [`PixState::push`] and [`PixState::pop`].
+//!
+//! Shapes are filled and outlined using the colors set by [`PixState::fill`] and
+//! [`PixState::stroke`], or with a gradient [Paint] set by [`PixState::fill_paint`] and
+//! [`PixState::stroke_paint`].
+//!
 //! Provided traits:
 //!
 //! - [Contains]: Defines [`contains`] for shapes containing other shapes.
@@ -22,17 +30,35 @@
 //! - [`PixState::circle`]: Draw a circle [Ellipse] to the current canvas.
 //! - [`PixState::ellipse`]: Draw an [Ellipse] to the current canvas.
 //! - [`PixState::arc`]: Draw an arc to the current canvas.
+//! - [`PixState::path`]: Draw a freeform [Path] to the current canvas.
+//! - [`PixState::begin_shape`]: Begin building a freeform [Path] to draw with
+//!   [`PixState::end_shape`].
+//! - [`PixState::vertex`]: Add a vertex to the current shape.
+//! - [`PixState::quadratic_vertex`]: Add a quadratic Bezier curve to the current shape.
+//! - [`PixState::bezier_vertex`]: Add a cubic Bezier curve to the current shape.
+//! - [`PixState::curve_vertex`]: Add a Catmull-Rom curve vertex to the current shape.
+//! - [`PixState::begin_contour`]: Begin a contour cutting a hole in the current shape.
+//! - [`PixState::end_contour`]: End the current contour.
+//! - [`PixState::end_shape`]: Draw the current shape to the current canvas.
 //!
 //! [`contains`]: Contains::contains
 //! [`intersects`]: Intersects::intersects
 
-use crate::{prelude::*, renderer::Rendering};
-use std::iter::Iterator;
+use crate::{
+    prelude::*,
+    renderer::{FillRule, Rendering},
+};
+use std::{
+    f64::consts::{FRAC_PI_2, PI, TAU},
+    iter::Iterator,
+};
+use stroke::StrokeStyle;
 
 #[macro_use]
 pub mod ellipse;
 #[macro_use]
 pub mod line;
+pub mod path;
 #[macro_use]
 pub mod point;
 #[macro_use]
@@ -41,6 +67,7 @@ pub mod rect;
 pub mod quad;
 #[macro_use]
 pub mod sphere;
+mod stroke;
 #[macro_use]
 pub mod triangle;
 
@@ -49,6 +76,8 @@ pub use ellipse::*;
 #[doc(inline)]
 pub use line::*;
 #[doc(inline)]
+pub use path::*;
+#[doc(inline)]
 pub use point::*;
 #[doc(inline)]
 pub use quad::*;
@@ -100,7 +129,8 @@ impl PixState {
         P: Into<Point<i32>>,
     {
         if let Some(stroke) = self.settings.stroke {
-            self.renderer.point(p.into(), stroke)?;
+            let p = transform_point(&self.settings.transform, p.into());
+            self.renderer.point(p, stroke)?;
         }
         Ok(())
     }
@@ -130,10 +160,16 @@ impl PixState {
     where
         L: Into<Line<i32>>,
     {
+        let line = line.into();
+        if self.is_stroke_styled() {
+            let [p1, p2] = line.points().map(|p| p.as_());
+            return self.outline_stroke(&[(vec![p1, p2], false)]);
+        }
         let s = &self.settings;
         if let Some(stroke) = s.stroke {
-            self.renderer
-                .line(line.into(), s.smooth, s.stroke_weight as u8, stroke)?;
+            let line = Line(line.map(|p| transform_point(&s.transform, p)));
+            let weight = (f64::from(s.stroke_weight) * s.transform.scale_factor()).round() as u8;
+            self.renderer.line(line, s.smooth, weight, stroke)?;
         }
         Ok(())
     }
@@ -168,8 +204,15 @@ impl PixState {
         I: IntoIterator<Item = P>,
     {
         let s = &self.settings;
+        if self.is_stroke_styled() {
+            let points: Vec<Point<f64>> = points.into_iter().map(|p| p.into().as_()).collect();
+            let points = bezier_points(&points, s.bezier_detail);
+            return self.outline_stroke(&[(points, false)]);
+        }
         self.renderer.bezier(
-            points.into_iter().map(Into::into),
+            points
+                .into_iter()
+                .map(|p| transform_point(&s.transform, p.into())),
             s.bezier_detail,
             s.stroke,
         )
@@ -200,9 +243,20 @@ impl PixState {
     where
         T: Into<Tri<i32>>,
     {
+        let tri = tri.into();
+        if self.is_fill_painted() {
+            self.paint_fill(&[tri.points().map(|p| p.as_()).to_vec()])?;
+        }
         let s = &self.settings;
+        let (fill, stroke) = (self.renderer_fill(), self.renderer_stroke());
+        let transformed = Tri(tri.map(|p| transform_point(&s.transform, p)));
         self.renderer
-            .triangle(tri.into(), s.smooth, s.fill, s.stroke)
+            .triangle(transformed, s.smooth, fill, stroke)?;
+        if self.is_stroke_styled() {
+            let points = tri.points().map(|p| p.as_()).to_vec();
+            self.outline_stroke(&[(points, true)])?;
+        }
+        Ok(())
     }
 
     /// Draw a square [Rect] to the current canvas. [`PixState::fill`] and [`PixState::stroke`] control
@@ -295,9 +349,8 @@ impl PixState {
     where
         R: Into<Rect<i32>>,
     {
-        let s = &self.settings;
         let rect = self.get_rect(rect);
-        self.renderer.rect(rect, None, s.fill, s.stroke)
+        self.transformed_rect(rect, None)
     }
 
     /// Draw a rounded [Rectangle](Rect) to the current canvas. [`PixState::fill`] and
@@ -326,9 +379,8 @@ impl PixState {
     where
         R: Into<Rect<i32>>,
     {
-        let s = &self.settings;
         let rect = self.get_rect(rect);
-        self.renderer.rect(rect, Some(radius), s.fill, s.stroke)
+        self.transformed_rect(rect, Some(radius))
     }
 
     /// Draw a [Quadrilateral](Quad) to the current canvas. [`PixState::fill`] and
@@ -357,8 +409,19 @@ impl PixState {
     where
         Q: Into<Quad<i32>>,
     {
+        let quad = quad.into();
+        if self.is_fill_painted() {
+            self.paint_fill(&[quad.points().map(|p| p.as_()).to_vec()])?;
+        }
         let s = &self.settings;
-        self.renderer.quad(quad.into(), s.smooth, s.fill, s.stroke)
+        let (fill, stroke) = (self.renderer_fill(), self.renderer_stroke());
+        let transformed = Quad(quad.map(|p| transform_point(&s.transform, p)));
+        self.renderer.quad(transformed, s.smooth, fill, stroke)?;
+        if self.is_stroke_styled() {
+            let points = quad.points().map(|p| p.as_()).to_vec();
+            self.outline_stroke(&[(points, true)])?;
+        }
+        Ok(())
     }
 
     /// Draw a polygon to the current canvas. [`PixState::fill`] and [`PixState::stroke`] control
@@ -387,13 +450,23 @@ impl PixState {
         P: Into<Point<i32>>,
         I: IntoIterator<Item = P>,
     {
+        let points: Vec<Point<i32>> = points.into_iter().map(Into::into).collect();
+        if self.is_fill_painted() {
+            self.paint_fill(&[points.iter().map(Point::as_).collect()])?;
+        }
         let s = &self.settings;
+        let (fill, stroke) = (self.renderer_fill(), self.renderer_stroke());
         self.renderer.polygon(
-            points.into_iter().map(Into::into),
+            points.iter().map(|&p| transform_point(&s.transform, p)),
             s.smooth,
-            s.fill,
-            s.stroke,
-        )
+            fill,
+            stroke,
+        )?;
+        if self.is_stroke_styled() {
+            let points = points.iter().map(Point::as_).collect();
+            self.outline_stroke(&[(points, true)])?;
+        }
+        Ok(())
     }
 
     /// Draw a wireframe to the current canvas, translated to a given [Point] and optionally
@@ -516,9 +589,40 @@ impl PixState {
     where
         E: Into<Ellipse<i32>>,
     {
-        let s = &self.settings;
         let ellipse = self.get_ellipse(ellipse);
-        self.renderer.ellipse(ellipse, s.smooth, s.fill, s.stroke)
+        let s = &self.settings;
+        let t = s.transform;
+        let [x, y, width, height] = ellipse.map(f64::from);
+        if self.is_fill_painted() {
+            self.paint_fill(&[arc_points([x, y], width / 2.0, height / 2.0, 0.0, TAU, &t)])?;
+        }
+        let s = &self.settings;
+        let (fill, stroke) = (self.renderer_fill(), self.renderer_stroke());
+        if t.is_axis_aligned() {
+            let [a, _, _, d, ..] = t.to_array();
+            let center = transform_point(&t, point![ellipse.x(), ellipse.y()]);
+            let [width, height] = [
+                (f64::from(ellipse.width()) * a.abs()).round() as i32,
+                (f64::from(ellipse.height()) * d.abs()).round() as i32,
+            ];
+            let ellipse = ellipse![center.x(), center.y(), width, height];
+            self.renderer.ellipse(ellipse, s.smooth, fill, stroke)?;
+        } else {
+            let points = arc_points([x, y], width / 2.0, height / 2.0, 0.0, TAU, &t);
+            self.renderer.polygon(
+                points
+                    .into_iter()
+                    .map(|p| t.transform_point(p).round().as_()),
+                s.smooth,
+                fill,
+                stroke,
+            )?;
+        }
+        if self.is_stroke_styled() {
+            let points = arc_points([x, y], width / 2.0, height / 2.0, 0.0, TAU, &t);
+            self.outline_stroke(&[(points, true)])?;
+        }
+        Ok(())
     }
 
     /// Draw an arc of a given `radius` and length defined by `start` and `end` to the current
@@ -550,7 +654,507 @@ impl PixState {
     {
         let s = &self.settings;
         let p = p.into();
+        let t = s.transform;
+        let [x, y] = p.map(f64::from);
+        let r = f64::from(radius);
+        let start_angle = f64::from(start).to_radians();
+        let mut end_angle = f64::from(end).to_radians();
+        if end_angle <= start_angle {
+            end_angle += TAU;
+        }
+        if s.arc_mode == ArcMode::Pie && self.is_fill_painted() {
+            let mut points = arc_points([x, y], r, r, start_angle, end_angle, &t);
+            points.push(point![x, y]);
+            self.paint_fill(&[points])?;
+        }
+        let s = &self.settings;
+        let (fill, stroke) = (self.renderer_fill(), self.renderer_stroke());
+        if let Some(rotation) = t.similarity_rotation() {
+            let p = transform_point(&t, p);
+            let radius = (r * t.scale_factor()).round() as i32;
+            let rotation = rotation.to_degrees().round() as i32;
+            self.renderer.arc(
+                p,
+                radius,
+                start + rotation,
+                end + rotation,
+                s.arc_mode,
+                fill,
+                stroke,
+            )?;
+        } else {
+            let points: Vec<Point<i32>> = arc_points([x, y], r, r, start_angle, end_angle, &t)
+                .into_iter()
+                .map(|p| t.transform_point(p).round().as_())
+                .collect();
+            match s.arc_mode {
+                ArcMode::Default => {
+                    if let Some(stroke) = stroke {
+                        for line in points.windows(2) {
+                            self.renderer
+                                .line(line_![line[0], line[1]], s.smooth, 1, stroke)?;
+                        }
+                    }
+                }
+                ArcMode::Pie => {
+                    let center = transform_point(&t, p);
+                    self.renderer.polygon(
+                        points.into_iter().chain(std::iter::once(center)),
+                        s.smooth,
+                        fill,
+                        stroke,
+                    )?;
+                }
+            }
+        }
+        if self.is_stroke_styled() {
+            let closed = s.arc_mode == ArcMode::Pie;
+            let mut points = arc_points([x, y], r, r, start_angle, end_angle, &t);
+            if closed {
+                points.push(point![x, y]);
+            }
+            self.outline_stroke(&[(points, closed)])?;
+        }
+        Ok(())
+    }
+}
+
+impl PixState {
+    /// Draw a freeform [Path] to the current canvas, using the current [`PixState::fill`],
+    /// [`PixState::stroke`], [`PixState::stroke_weight`] and [`PixState::smooth`] settings. Curves
+    /// are sampled based on [`PixState::bezier_detail`].
+    ///
+    /// # Errors
+    ///
+    /// If the renderer fails to draw to the current render target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// struct App {
+    ///     arrow: Path,
+    /// }
+    ///
+    /// impl PixEngine for App {
+    ///     fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///         self.arrow
+    ///             .vertex([0, -10])
+    ///             .vertex([20, 0])
+    ///             .vertex([0, 10])
+    ///             .vertex([5, 0])
+    ///             .close();
+    ///         Ok(())
+    ///     }
+    ///
+    ///     fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///         for x in (20..200).step_by(40) {
+    ///             s.push();
+    ///             s.translate(f64::from(x), 50.0);
+    ///             s.path(&self.arrow)?;
+    ///             s.pop();
+    ///         }
+    ///         Ok(())
+    ///     }
+    /// }
+    /// ```
+    pub fn path(&mut self, path: &Path) -> PixResult<()> {
+        let outline = path.flatten(self.settings.bezier_detail);
+        if self.is_fill_painted() {
+            let contours: Vec<Vec<Point<f64>>> =
+                outline.iter().map(|(points, _)| points.clone()).collect();
+            self.paint_fill(&contours)?;
+        }
+        let s = &self.settings;
+        let t = s.transform;
+        let (fill, stroke) = (self.renderer_fill(), self.renderer_stroke());
+        let contours: Vec<(Vec<Point<i32>>, bool)> = outline
+            .iter()
+            .map(|(points, closed)| {
+                let points = points
+                    .iter()
+                    .map(|&p| t.transform_point(p).round().as_())
+                    .collect();
+                (points, *closed)
+            })
+            .collect();
+        let weight = (f64::from(s.stroke_weight) * t.scale_factor()).round() as u8;
         self.renderer
-            .arc(p, radius, start, end, s.arc_mode, s.fill, s.stroke)
+            .path(&contours, s.smooth, weight, fill, stroke)?;
+        if self.is_stroke_styled() {
+            self.outline_stroke(&outline)?;
+        }
+        Ok(())
+    }
+
+    /// Begin building a freeform shape. Add vertices with [`PixState::vertex`],
+    /// [`PixState::quadratic_vertex`], [`PixState::bezier_vertex`] and
+    /// [`PixState::curve_vertex`], and draw the shape with [`PixState::end_shape`]. Any shape
+    /// already in progress is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.fill(Color::RED);
+    ///     s.stroke(Color::BLACK);
+    ///     s.begin_shape();
+    ///     s.vertex([20, 20]);
+    ///     s.vertex([80, 20]);
+    ///     s.bezier_vertex([100, 50], [60, 90], [20, 80]);
+    ///     // Cut a triangular hole
+    ///     s.begin_contour();
+    ///     s.vertex([40, 40]);
+    ///     s.vertex([40, 60]);
+    ///     s.vertex([60, 40]);
+    ///     s.end_contour();
+    ///     s.end_shape(true)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn begin_shape(&mut self) {
+        self.shape = Some(Path::new());
+    }
+
+    /// Add a vertex to the current shape, connected to the previous vertex with a straight
+    /// line. Has no effect if [`PixState::begin_shape`] hasn't been called.
+    #[inline]
+    pub fn vertex<P>(&mut self, p: P)
+    where
+        P: Into<Point<i32>>,
+    {
+        if let Some(shape) = &mut self.shape {
+            shape.vertex(p);
+        }
+    }
+
+    /// Add a quadratic Bezier curve from the previous vertex to `end`, shaped by `control`, to
+    /// the current shape. Has no effect if [`PixState::begin_shape`] hasn't been called.
+    #[inline]
+    pub fn quadratic_vertex<P1, P2>(&mut self, control: P1, end: P2)
+    where
+        P1: Into<Point<i32>>,
+        P2: Into<Point<i32>>,
+    {
+        if let Some(shape) = &mut self.shape {
+            shape.quadratic_vertex(control, end);
+        }
     }
+
+    /// Add a cubic Bezier curve from the previous vertex to `end`, shaped by `control1` and
+    /// `control2`, to the current shape. Has no effect if [`PixState::begin_shape`] hasn't been
+    /// called.
+    #[inline]
+    pub fn bezier_vertex<P1, P2, P3>(&mut self, control1: P1, control2: P2, end: P3)
+    where
+        P1: Into<Point<i32>>,
+        P2: Into<Point<i32>>,
+        P3: Into<Point<i32>>,
+    {
+        if let Some(shape) = &mut self.shape {
+            shape.bezier_vertex(control1, control2, end);
+        }
+    }
+
+    /// Add a Catmull-Rom curve vertex to the current shape. Consecutive curve vertices form a
+    /// smooth curve passing through every point except the first and last, which only guide the
+    /// curve's direction. Has no effect if [`PixState::begin_shape`] hasn't been called.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.fill(None);
+    ///     s.stroke(Color::WHITE);
+    ///     s.begin_shape();
+    ///     s.curve_vertex([10, 80]);
+    ///     s.curve_vertex([20, 20]);
+    ///     s.curve_vertex([60, 40]);
+    ///     s.curve_vertex([100, 20]);
+    ///     s.curve_vertex([110, 80]);
+    ///     s.end_shape(false)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn curve_vertex<P>(&mut self, p: P)
+    where
+        P: Into<Point<i32>>,
+    {
+        if let Some(shape) = &mut self.shape {
+            shape.curve_vertex(p);
+        }
+    }
+
+    /// Begin a closed contour in the current shape. Contours cut holes out of the shape when
+    /// filled. Has no effect if [`PixState::begin_shape`] hasn't been called.
+    #[inline]
+    pub fn begin_contour(&mut self) {
+        if let Some(shape) = &mut self.shape {
+            shape.begin_contour();
+        }
+    }
+
+    /// End the current contour. Subsequent vertices are added to the outline of the shape.
+    #[inline]
+    pub fn end_contour(&mut self) {
+        if let Some(shape) = &mut self.shape {
+            shape.end_contour();
+        }
+    }
+
+    /// Draw the current shape started with [`PixState::begin_shape`] to the current canvas,
+    /// connecting the last vertex back to the first if `close` is `true`. Has no effect if
+    /// [`PixState::begin_shape`] hasn't been called.
+    ///
+    /// # Errors
+    ///
+    /// If the renderer fails to draw to the current render target, then an error is returned.
+    pub fn end_shape(&mut self, close: bool) -> PixResult<()> {
+        match self.shape.take() {
+            Some(mut shape) => {
+                if close {
+                    shape.close();
+                }
+                self.path(&shape)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl PixState {
+    /// Draw a [Rect] with optionally rounded corners using the current [Transform]. Rectangles
+    /// that remain axis-aligned are drawn directly, otherwise they're drawn as a polygon.
+    fn transformed_rect(&mut self, rect: Rect<i32>, radius: Option<i32>) -> PixResult<()> {
+        let t = self.settings.transform;
+        if self.is_fill_painted() {
+            self.paint_fill(&[rect_points(rect.as_(), radius.map(f64::from), &t)])?;
+        }
+        let s = &self.settings;
+        let (fill, stroke) = (self.renderer_fill(), self.renderer_stroke());
+        if t.is_axis_aligned() {
+            let [a, _, _, d, ..] = t.to_array();
+            let p1 = transform_point(&t, rect.top_left());
+            let p2 = transform_point(&t, rect.bottom_right());
+            let transformed = rect![
+                p1.x().min(p2.x()),
+                p1.y().min(p2.y()),
+                (p2.x() - p1.x()).abs(),
+                (p2.y() - p1.y()).abs(),
+            ];
+            let radius = radius.map(|r| (f64::from(r) * a.abs().min(d.abs())).round() as i32);
+            self.renderer.rect(transformed, radius, fill, stroke)?;
+        } else {
+            let points = rect_points(rect.as_(), radius.map(f64::from), &t);
+            self.renderer.polygon(
+                points
+                    .into_iter()
+                    .map(|p| t.transform_point(p).round().as_()),
+                s.smooth,
+                fill,
+                stroke,
+            )?;
+        }
+        if self.is_stroke_styled() {
+            // Outline the outermost pixels covered by the fill, matching unstyled strokes.
+            let [x, y, width, height] = rect.map(f64::from);
+            let outline = rect![x, y, width - 1.0, height - 1.0];
+            let points = rect_points(outline, radius.map(f64::from), &t);
+            self.outline_stroke(&[(points, true)])?;
+        }
+        Ok(())
+    }
+
+    /// Whether strokes are wide, dashed or painted with a gradient, and need to be drawn using
+    /// [`PixState::outline_stroke`] instead of by the renderer.
+    #[inline]
+    fn is_stroke_styled(&self) -> bool {
+        let s = &self.settings;
+        let width = f64::from(s.stroke_weight) * s.transform.scale_factor();
+        s.stroke.is_some()
+            && (width.round() > 1.0 || !s.stroke_dash.is_empty() || s.stroke_paint.is_some())
+    }
+
+    /// Whether fills are painted with a gradient, and need to be drawn using
+    /// [`PixState::paint_fill`] instead of by the renderer.
+    #[inline]
+    fn is_fill_painted(&self) -> bool {
+        let s = &self.settings;
+        s.fill.is_some() && s.fill_paint.is_some()
+    }
+
+    /// Returns the fill [Color] shapes should be drawn with by the renderer, or `None` if the
+    /// fill is painted separately.
+    #[inline]
+    fn renderer_fill(&self) -> Option<Color> {
+        if self.is_fill_painted() {
+            None
+        } else {
+            self.settings.fill
+        }
+    }
+
+    /// Fill outline contours, given in local coordinates, with the current fill [Paint] using the
+    /// current [Transform]. Contours are filled together using the even-odd rule.
+    fn paint_fill(&mut self, contours: &[Vec<Point<f64>>]) -> PixResult<()> {
+        let s = &self.settings;
+        let t = s.transform;
+        let (Some(paint), Some(inverse)) = (&s.fill_paint, t.inverse()) else {
+            return Ok(());
+        };
+        let contours: Vec<Vec<Point<f64>>> = contours
+            .iter()
+            .map(|points| points.iter().map(|&p| t.transform_point(p)).collect())
+            .collect();
+        self.renderer
+            .fill_polygons(&contours, FillRule::EvenOdd, paint, &inverse)
+    }
+
+    /// Returns the stroke [Color] shapes should be drawn with by the renderer, or `None` if the
+    /// stroke is styled and drawn separately.
+    #[inline]
+    fn renderer_stroke(&self) -> Option<Color> {
+        if self.is_stroke_styled() {
+            None
+        } else {
+            self.settings.stroke
+        }
+    }
+
+    /// Draw the stroke of outline contours, given as `(points, closed)` pairs in local
+    /// coordinates, using the current [Transform], [`PixState::stroke_weight`] and stroke style.
+    fn outline_stroke(&mut self, contours: &[(Vec<Point<f64>>, bool)]) -> PixResult<()> {
+        let s = &self.settings;
+        let t = s.transform;
+        let scale = t.scale_factor();
+        let Some(stroke) = s.stroke.filter(|_| scale > 0.0) else {
+            return Ok(());
+        };
+        let contours: Vec<(Vec<Point<f64>>, bool)> = contours
+            .iter()
+            .map(|(points, closed)| {
+                let points = points.iter().map(|&p| t.transform_point(p)).collect();
+                (points, *closed)
+            })
+            .collect();
+        let dash: Vec<f64> = s.stroke_dash.iter().map(|d| d * scale).collect();
+        let dash_offset = s.stroke_dash_offset * scale;
+        let width = f64::from(s.stroke_weight) * scale;
+        if width.round() <= 1.0 && s.stroke_paint.is_none() {
+            // Thin strokes only need to be split into dashes.
+            let dashes: Vec<(Vec<Point<i32>>, bool)> =
+                stroke::dash_contours(&contours, &dash, dash_offset)
+                    .into_iter()
+                    .map(|(points, closed)| {
+                        (points.iter().map(|p| p.round().as_()).collect(), closed)
+                    })
+                    .collect();
+            return self.renderer.path(&dashes, s.smooth, 1, None, Some(stroke));
+        }
+        let style = StrokeStyle {
+            width: width.max(1.0),
+            cap: s.stroke_cap,
+            join: s.stroke_join,
+            miter_limit: s.miter_limit,
+            dash: &dash,
+            dash_offset,
+        };
+        let polygons = stroke::stroke_polygons(&contours, &style);
+        let paint = s.stroke_paint.clone().unwrap_or(Paint::Solid(stroke));
+        let inverse = t.inverse().unwrap_or_default();
+        self.renderer
+            .fill_polygons(&polygons, FillRule::NonZero, &paint, &inverse)
+    }
+}
+
+/// Returns the corners of a [Rect], with rounded corners approximated by points along arcs that
+/// appear smooth once drawn using [Transform] `t`.
+fn rect_points(rect: Rect<f64>, radius: Option<f64>, t: &Transform) -> Vec<Point<f64>> {
+    let [x, y, width, height] = rect.coords();
+    match radius {
+        Some(radius) => {
+            let r = radius.clamp(0.0, width.min(height) / 2.0);
+            let corners = [
+                ([x + width - r, y + r], 1.5),
+                ([x + width - r, y + height - r], 0.0),
+                ([x + r, y + height - r], 0.5),
+                ([x + r, y + r], 1.0),
+            ];
+            corners
+                .iter()
+                .flat_map(|&(center, start)| {
+                    let start = start * PI;
+                    arc_points(center, r, r, start, start + FRAC_PI_2, t)
+                })
+                .collect()
+        }
+        None => vec![
+            point![x, y],
+            point![x + width, y],
+            point![x + width, y + height],
+            point![x, y + height],
+        ],
+    }
+}
+
+/// Returns points along a Bezier curve with any number of control points, sampled `detail` times.
+fn bezier_points(points: &[Point<f64>], detail: i32) -> Vec<Point<f64>> {
+    let steps = detail.max(2);
+    let mut scratch = points.to_vec();
+    (0..=steps)
+        .filter_map(|i| {
+            let t = f64::from(i) / f64::from(steps);
+            scratch.copy_from_slice(points);
+            for n in (1..scratch.len()).rev() {
+                for j in 0..n {
+                    let [ax, ay] = scratch[j].coords();
+                    let [bx, by] = scratch[j + 1].coords();
+                    scratch[j] = point![(bx - ax).mul_add(t, ax), (by - ay).mul_add(t, ay)];
+                }
+            }
+            scratch.first().copied()
+        })
+        .collect()
+}
+
+/// Transforms an integer [Point] by [Transform] `t`, rounding to the nearest pixel.
+#[inline]
+fn transform_point(t: &Transform, p: Point<i32>) -> Point<i32> {
+    if t.is_identity() {
+        p
+    } else {
+        t.transform_point(p.as_::<f64>()).round().as_()
+    }
+}
+
+/// Returns points along an elliptical arc from `start` to `end` in radians, with enough segments
+/// to appear smooth once drawn using [Transform] `t`.
+fn arc_points(
+    [x, y]: [f64; 2],
+    rx: f64,
+    ry: f64,
+    start: f64,
+    end: f64,
+    t: &Transform,
+) -> Vec<Point<f64>> {
+    let sweep = end - start;
+    let radius = rx.max(ry) * t.scale_factor();
+    let count = (radius * sweep / 4.0).ceil().clamp(4.0, 360.0) as usize;
+    (0..=count)
+        .map(|i| {
+            let angle = sweep.mul_add(i as f64 / count as f64, start);
+            let (sin, cos) = angle.sin_cos();
+            point![rx.mul_add(cos, x), ry.mul_add(sin, y)]
+        })
+        .collect()
 }
@@ -0,0 +1,291 @@
+//! A shape type representing freeform paths used for drawing.
+//!
+//! A [Path] is an outline built from a mix of straight segments, quadratic and cubic Bezier
+//! curves and Catmull-Rom curves. Additional contours can be added to cut holes out of the
+//! outline when filled.
+//!
+//! # Examples
+//!
+//! You can create a [Path] using [`Path::new`] and draw it with [`PixState::path`]:
+//!
+//! ```
+//! use pix_engine::prelude::*;
+//!
+//! let mut path = Path::new();
+//! path.vertex([10, 10])
+//!     .vertex([90, 10])
+//!     .bezier_vertex([90, 60], [60, 90], [10, 90])
+//!     .close();
+//! // Cut a square hole
+//! path.begin_contour()
+//!     .vertex([30, 30])
+//!     .vertex([30, 50])
+//!     .vertex([50, 50])
+//!     .vertex([50, 30])
+//!     .end_contour();
+//! assert_eq!(path.contour_count(), 2);
+//! ```
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+/// A segment of a [Path] contour.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+enum Segment {
+    /// A straight line to a point.
+    Vertex(Point<i32>),
+    /// A quadratic Bezier curve through a control point to an end point.
+    Quadratic(Point<i32>, Point<i32>),
+    /// A cubic Bezier curve through two control points to an end point.
+    Bezier(Point<i32>, Point<i32>, Point<i32>),
+    /// A Catmull-Rom curve point.
+    Curve(Point<i32>),
+}
+
+/// A freeform `Path` made up of one or more contours.
+///
+/// The first contour is the outline of the path and is only closed if [`Path::close`] is called.
+/// Contours added with [`Path::begin_contour`] are always closed and, when filled, cut holes out
+/// of the outline using the even-odd rule.
+///
+/// Please see the [module-level documentation] for examples.
+///
+/// [module-level documentation]: crate::shape::path
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[must_use]
+pub struct Path {
+    contours: Vec<Vec<Segment>>,
+    current: usize,
+    closed: bool,
+}
+
+impl Default for Path {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Path {
+    /// Constructs an empty `Path`.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            contours: vec![Vec::new()],
+            current: 0,
+            closed: false,
+        }
+    }
+
+    /// Add a vertex, connected to the previous vertex with a straight line.
+    #[inline]
+    pub fn vertex<P>(&mut self, p: P) -> &mut Self
+    where
+        P: Into<Point<i32>>,
+    {
+        self.push(Segment::Vertex(p.into()))
+    }
+
+    /// Add a quadratic Bezier curve from the previous vertex to `end`, shaped by `control`.
+    #[inline]
+    pub fn quadratic_vertex<P1, P2>(&mut self, control: P1, end: P2) -> &mut Self
+    where
+        P1: Into<Point<i32>>,
+        P2: Into<Point<i32>>,
+    {
+        self.push(Segment::Quadratic(control.into(), end.into()))
+    }
+
+    /// Add a cubic Bezier curve from the previous vertex to `end`, shaped by `control1` and
+    /// `control2`.
+    #[inline]
+    pub fn bezier_vertex<P1, P2, P3>(&mut self, control1: P1, control2: P2, end: P3) -> &mut Self
+    where
+        P1: Into<Point<i32>>,
+        P2: Into<Point<i32>>,
+        P3: Into<Point<i32>>,
+    {
+        self.push(Segment::Bezier(
+            control1.into(),
+            control2.into(),
+            end.into(),
+        ))
+    }
+
+    /// Add a Catmull-Rom curve vertex. Consecutive curve vertices form a smooth curve passing
+    /// through every point except the first and last, which only guide the curve's direction.
+    #[inline]
+    pub fn curve_vertex<P>(&mut self, p: P) -> &mut Self
+    where
+        P: Into<Point<i32>>,
+    {
+        self.push(Segment::Curve(p.into()))
+    }
+
+    /// Begin a new closed contour. Subsequent vertices are added to it until
+    /// [`Path::end_contour`] is called.
+    #[inline]
+    pub fn begin_contour(&mut self) -> &mut Self {
+        self.contours.push(Vec::new());
+        self.current = self.contours.len() - 1;
+        self
+    }
+
+    /// End the current contour. Subsequent vertices are added to the outline of the path.
+    #[inline]
+    pub fn end_contour(&mut self) -> &mut Self {
+        self.current = 0;
+        self
+    }
+
+    /// Close the outline of the path, connecting the last vertex back to the first.
+    #[inline]
+    pub fn close(&mut self) -> &mut Self {
+        self.closed = true;
+        self
+    }
+
+    /// Whether the outline of the path is closed.
+    #[inline]
+    #[must_use]
+    pub const fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Returns the number of non-empty contours, including the outline.
+    #[inline]
+    #[must_use]
+    pub fn contour_count(&self) -> usize {
+        self.contours.iter().filter(|c| !c.is_empty()).count()
+    }
+
+    /// Whether the path has no vertices.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.contours.iter().all(Vec::is_empty)
+    }
+
+    /// Remove all vertices and contours, re-opening the outline.
+    #[inline]
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Returns each non-empty contour as a list of points, with curves sampled `detail` times
+    /// per segment, along with whether the contour is closed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let mut path = Path::new();
+    /// path.vertex([0, 0]).quadratic_vertex([10, 0], [10, 10]);
+    /// let contours = path.flatten(4);
+    /// assert_eq!(contours.len(), 1);
+    /// let (points, closed) = &contours[0];
+    /// assert!(!closed);
+    /// assert_eq!(points.len(), 5);
+    /// assert_eq!(points[4], point!(10.0, 10.0));
+    /// ```
+    #[must_use]
+    pub fn flatten(&self, detail: i32) -> Vec<(Vec<Point<f64>>, bool)> {
+        let detail = detail.max(1);
+        self.contours
+            .iter()
+            .enumerate()
+            .filter(|(_, contour)| !contour.is_empty())
+            .map(|(i, contour)| (flatten_contour(contour, detail), i > 0 || self.closed))
+            .collect()
+    }
+
+    /// Add a segment to the current contour.
+    #[inline]
+    fn push(&mut self, segment: Segment) -> &mut Self {
+        self.contours[self.current].push(segment);
+        self
+    }
+}
+
+/// Returns the points along a contour, sampling curves `detail` times per segment.
+fn flatten_contour(segments: &[Segment], detail: i32) -> Vec<Point<f64>> {
+    let mut points: Vec<Point<f64>> = Vec::new();
+    let mut curve: Vec<Point<f64>> = Vec::new();
+    for segment in segments {
+        if let Segment::Curve(p) = segment {
+            curve.push(p.as_());
+            continue;
+        }
+        flatten_curve(&curve, detail, &mut points);
+        curve.clear();
+        match *segment {
+            Segment::Vertex(p) => points.push(p.as_()),
+            Segment::Quadratic(control, end) => {
+                let (control, end) = (control.as_(), end.as_());
+                let start = points.last().copied().unwrap_or(control);
+                sample(detail, &mut points, |t| {
+                    let u = 1.0 - t;
+                    weighted(&[(start, u * u), (control, 2.0 * u * t), (end, t * t)])
+                });
+            }
+            Segment::Bezier(control1, control2, end) => {
+                let (control1, control2, end) = (control1.as_(), control2.as_(), end.as_());
+                let start = points.last().copied().unwrap_or(control1);
+                sample(detail, &mut points, |t| {
+                    let u = 1.0 - t;
+                    weighted(&[
+                        (start, u * u * u),
+                        (control1, 3.0 * u * u * t),
+                        (control2, 3.0 * u * t * t),
+                        (end, t * t * t),
+                    ])
+                });
+            }
+            Segment::Curve(_) => unreachable!("curve vertices are collected above"),
+        }
+    }
+    flatten_curve(&curve, detail, &mut points);
+    points
+}
+
+/// Appends points along a Catmull-Rom spline through `curve`, excluding the first and last
+/// control points.
+fn flatten_curve(curve: &[Point<f64>], detail: i32, points: &mut Vec<Point<f64>>) {
+    if curve.len() < 4 {
+        // Not enough points to form a spline segment, so connect the inner points directly
+        points.extend(curve.iter().skip(1).take(curve.len().saturating_sub(2)));
+        return;
+    }
+    points.push(curve[1]);
+    for w in curve.windows(4) {
+        let [p0, p1, p2, p3] = [w[0], w[1], w[2], w[3]];
+        sample(detail, points, |t| {
+            let (t2, t3) = (t * t, t * t * t);
+            weighted(&[
+                (p0, 0.5 * (-t3 + 2.0 * t2 - t)),
+                (p1, 0.5 * (3.0 * t3 - 5.0 * t2 + 2.0)),
+                (p2, 0.5 * (-3.0 * t3 + 4.0 * t2 + t)),
+                (p3, 0.5 * (t3 - t2)),
+            ])
+        });
+    }
+}
+
+/// Appends `detail` samples of `f` for `t` in `(0.0, 1.0]`.
+fn sample<F>(detail: i32, points: &mut Vec<Point<f64>>, f: F)
+where
+    F: Fn(f64) -> Point<f64>,
+{
+    points.extend((1..=detail).map(|i| f(f64::from(i) / f64::from(detail))));
+}
+
+/// Returns the sum of `points` scaled by their weights.
+fn weighted(points: &[(Point<f64>, f64)]) -> Point<f64> {
+    let (x, y) = points.iter().fold((0.0, 0.0), |(x, y), (p, w)| {
+        (p.x().mul_add(*w, x), p.y().mul_add(*w, y))
+    });
+    point![x, y]
+}
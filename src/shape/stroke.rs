@@ -0,0 +1,292 @@
+//! Stroke geometry used to draw wide and dashed outlines.
+//!
+//! Outlines are converted into a set of polygons covering the stroke: one quad per segment plus
+//! any joins and caps. Every polygon is wound in the same direction, so filling them together
+//! using the non-zero winding rule covers the union of the stroke without gaps at the joints or
+//! overlapping blends.
+
+use crate::prelude::*;
+use std::f64::consts::TAU;
+
+/// A 2D vertex used to build stroke geometry.
+type Vertex = [f64; 2];
+
+/// Distances smaller than this are treated as zero.
+const EPSILON: f64 = 1e-9;
+
+/// Options used to build stroke geometry.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct StrokeStyle<'a> {
+    pub(crate) width: f64,
+    pub(crate) cap: StrokeCap,
+    pub(crate) join: StrokeJoin,
+    pub(crate) miter_limit: f64,
+    pub(crate) dash: &'a [f64],
+    pub(crate) dash_offset: f64,
+}
+
+/// Returns polygons covering the stroke of each contour, which are `(points, closed)` pairs.
+pub(crate) fn stroke_polygons(
+    contours: &[(Vec<Point<f64>>, bool)],
+    style: &StrokeStyle<'_>,
+) -> Vec<Vec<Point<f64>>> {
+    let mut polygons = Vec::new();
+    for (points, closed) in contours {
+        let points: Vec<Vertex> = points.iter().map(Point::coords).collect();
+        if style.dash.is_empty() {
+            outline(&points, *closed, style, &mut polygons);
+        } else {
+            for dash in dashes(&points, *closed, style.dash, style.dash_offset) {
+                outline(&dash, false, style, &mut polygons);
+            }
+        }
+    }
+    polygons
+        .into_iter()
+        .map(|mut polygon| {
+            if signed_area(&polygon) < 0.0 {
+                polygon.reverse();
+            }
+            polygon.into_iter().map(Point::new).collect()
+        })
+        .collect()
+}
+
+/// Returns the open polylines of each contour that are drawn by the dash `pattern` after
+/// skipping `offset` into it. `pattern` must have an even length, with no negative values and a
+/// positive sum.
+pub(crate) fn dash_contours(
+    contours: &[(Vec<Point<f64>>, bool)],
+    pattern: &[f64],
+    offset: f64,
+) -> Vec<(Vec<Point<f64>>, bool)> {
+    contours
+        .iter()
+        .flat_map(|(points, closed)| {
+            let points: Vec<Vertex> = points.iter().map(Point::coords).collect();
+            dashes(&points, *closed, pattern, offset)
+        })
+        .map(|dash| (dash.into_iter().map(Point::new).collect(), false))
+        .collect()
+}
+
+/// Splits a polyline into dashes.
+fn dashes(points: &[Vertex], closed: bool, pattern: &[f64], offset: f64) -> Vec<Vec<Vertex>> {
+    let mut dashes = Vec::new();
+    let Some(&first) = points.first() else {
+        return dashes;
+    };
+    let total: f64 = pattern.iter().sum();
+    let mut index = 0;
+    let mut position = offset.rem_euclid(total);
+    while position >= pattern[index] {
+        position -= pattern[index];
+        index = (index + 1) % pattern.len();
+    }
+    let mut remaining = pattern[index] - position;
+    let is_dash = |index: usize| index % 2 == 0;
+
+    let mut current = Vec::new();
+    if is_dash(index) {
+        current.push(first);
+    }
+    let closing = closed.then_some(first);
+    let ends = points.iter().skip(1).copied().chain(closing);
+    let mut start = first;
+    for end in ends {
+        let mut length = distance(start, end);
+        while length > remaining {
+            let t = remaining / length;
+            let p = [
+                (end[0] - start[0]).mul_add(t, start[0]),
+                (end[1] - start[1]).mul_add(t, start[1]),
+            ];
+            if is_dash(index) {
+                current.push(p);
+                dashes.push(std::mem::take(&mut current));
+            }
+            index = (index + 1) % pattern.len();
+            if is_dash(index) {
+                current.push(p);
+            }
+            length -= remaining;
+            start = p;
+            remaining = pattern[index];
+        }
+        remaining -= length;
+        start = end;
+        if is_dash(index) {
+            current.push(end);
+        }
+    }
+    if !current.is_empty() {
+        dashes.push(current);
+    }
+    dashes
+}
+
+/// Appends polygons covering the stroke of a single polyline.
+fn outline(
+    points: &[Vertex],
+    closed: bool,
+    style: &StrokeStyle<'_>,
+    polygons: &mut Vec<Vec<Vertex>>,
+) {
+    let mut points: Vec<Vertex> = points.to_vec();
+    points.dedup_by(|a, b| distance(*a, *b) < EPSILON);
+    if closed && points.len() > 1 && distance(points[0], points[points.len() - 1]) < EPSILON {
+        points.pop();
+    }
+    let half = style.width / 2.0;
+    let count = points.len();
+    if count == 0 || half <= 0.0 {
+        return;
+    }
+    if count == 1 {
+        let p = points[0];
+        match style.cap {
+            StrokeCap::Butt => (),
+            StrokeCap::Round => polygons.push(circle(p, half)),
+            StrokeCap::Square => polygons.push(vec![
+                [p[0] - half, p[1] - half],
+                [p[0] + half, p[1] - half],
+                [p[0] + half, p[1] + half],
+                [p[0] - half, p[1] + half],
+            ]),
+        }
+        return;
+    }
+
+    let closed = closed && count > 2;
+    let segments = if closed { count } else { count - 1 };
+    for i in 0..segments {
+        let (a, b) = (points[i], points[(i + 1) % count]);
+        let n = scale(normal(a, b), half);
+        polygons.push(vec![add(a, n), add(b, n), sub(b, n), sub(a, n)]);
+    }
+
+    let joins = if closed { 0..count } else { 1..count - 1 };
+    for i in joins {
+        let prev = points[(i + count - 1) % count];
+        let p = points[i];
+        let next = points[(i + 1) % count];
+        join(prev, p, next, style, polygons);
+    }
+
+    if !closed {
+        cap(points[1], points[0], style, polygons);
+        cap(points[count - 2], points[count - 1], style, polygons);
+    }
+}
+
+/// Appends the polygon joining the segments `prev -> p` and `p -> next`.
+fn join(
+    prev: Vertex,
+    p: Vertex,
+    next: Vertex,
+    style: &StrokeStyle<'_>,
+    polygons: &mut Vec<Vec<Vertex>>,
+) {
+    let half = style.width / 2.0;
+    let (n0, n1) = (normal(prev, p), normal(p, next));
+    let d0 = [n0[1], -n0[0]];
+    let d1 = [n1[1], -n1[0]];
+    let cross = d0[0].mul_add(d1[1], -(d0[1] * d1[0]));
+    if cross.abs() < EPSILON && dot(d0, d1) > 0.0 {
+        // Collinear segments already meet without a gap.
+        return;
+    }
+    if style.join == StrokeJoin::Round {
+        polygons.push(circle(p, half));
+        return;
+    }
+    // The outer corner is on the opposite side of the turn.
+    let side = if cross > 0.0 { -half } else { half };
+    let a = add(p, scale(n0, side));
+    let b = add(p, scale(n1, side));
+    let bisector = add(n0, n1);
+    let bisector_length = bisector[0].hypot(bisector[1]);
+    if style.join == StrokeJoin::Miter && bisector_length > EPSILON {
+        // Ratio of the miter length to the stroke width.
+        let ratio = 2.0 / bisector_length;
+        if ratio <= style.miter_limit {
+            let tip = add(p, scale(bisector, side * ratio / bisector_length));
+            polygons.push(vec![p, a, tip, b]);
+            return;
+        }
+    }
+    polygons.push(vec![p, a, b]);
+}
+
+/// Appends the cap at `end` of the segment `start -> end`.
+fn cap(start: Vertex, end: Vertex, style: &StrokeStyle<'_>, polygons: &mut Vec<Vec<Vertex>>) {
+    let half = style.width / 2.0;
+    match style.cap {
+        StrokeCap::Butt => (),
+        StrokeCap::Round => polygons.push(circle(end, half)),
+        StrokeCap::Square => {
+            let n = scale(normal(start, end), half);
+            let d = [n[1], -n[0]];
+            let extended = add(end, d);
+            polygons.push(vec![
+                add(end, n),
+                add(extended, n),
+                sub(extended, n),
+                sub(end, n),
+            ]);
+        }
+    }
+}
+
+/// Returns a polygon approximating a circle.
+fn circle(center: Vertex, radius: f64) -> Vec<Vertex> {
+    let count = (radius * TAU / 2.0).ceil().clamp(8.0, 64.0) as usize;
+    (0..count)
+        .map(|i| {
+            let (sin, cos) = (TAU * i as f64 / count as f64).sin_cos();
+            [
+                radius.mul_add(cos, center[0]),
+                radius.mul_add(sin, center[1]),
+            ]
+        })
+        .collect()
+}
+
+/// Returns the unit normal of the segment `a -> b`.
+fn normal(a: Vertex, b: Vertex) -> Vertex {
+    let length = distance(a, b);
+    if length < EPSILON {
+        [0.0, 0.0]
+    } else {
+        [-(b[1] - a[1]) / length, (b[0] - a[0]) / length]
+    }
+}
+
+/// Returns twice the signed area of a polygon.
+fn signed_area(polygon: &[Vertex]) -> f64 {
+    polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(a, b)| a[0].mul_add(b[1], -(b[0] * a[1])))
+        .sum()
+}
+
+fn distance(a: Vertex, b: Vertex) -> f64 {
+    (b[0] - a[0]).hypot(b[1] - a[1])
+}
+
+fn dot(a: Vertex, b: Vertex) -> f64 {
+    a[0].mul_add(b[0], a[1] * b[1])
+}
+
+fn add(a: Vertex, b: Vertex) -> Vertex {
+    [a[0] + b[0], a[1] + b[1]]
+}
+
+fn sub(a: Vertex, b: Vertex) -> Vertex {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn scale(a: Vertex, s: f64) -> Vertex {
+    [a[0] * s, a[1] * s]
+}
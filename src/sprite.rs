@@ -0,0 +1,1169 @@
+//! [`SpriteSheet`] and sprite [Animation] functions.
+//!
+//! A `SpriteSheet` divides an [Image] or texture into [Sprite] regions, either as a uniform grid or
+//! from a `JSON` atlas exported by tools such as [Aseprite](https://www.aseprite.org/) or
+//! [TexturePacker](https://www.codeandweb.com/texturepacker). An [Animation] plays named
+//! [`AnimationClip`]s made up of sprites, each displayed for a given [Duration].
+//!
+//! Sprites are drawn the same as [`PixState::image_transformed`], respecting the current
+//! [`ImageMode`], [`AngleMode`] and [`PixState::image_tint`].
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::sprite`]: Draw a sprite from a [`SpriteSheet`].
+//! - [`PixState::sprite_transformed`]: Draw a resized, rotated or flipped sprite from a
+//!   [`SpriteSheet`].
+//! - [`PixState::animation`]: Draw the current sprite of an [Animation].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use pix_engine::prelude::*;
+//! # use std::time::Duration;
+//! # struct App { sheet: SpriteSheet, player: Animation };
+//! # impl PixEngine for App {
+//! fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     // 4 columns and 2 rows of 16x16 sprites
+//!     let image = Image::from_file("./player.png")?;
+//!     self.sheet = SpriteSheet::from_grid(image, 16, 16, 4, 2)?;
+//!     let delay = Duration::from_millis(100);
+//!     self.player.insert_clip("idle", AnimationClip::new(0..4, delay));
+//!     self.player.insert_clip("run", AnimationClip::new(4..8, delay));
+//!     self.player.play("idle")?;
+//!     Ok(())
+//! }
+//!
+//! fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     if s.key_down(Key::Left) {
+//!         self.player.play("run")?;
+//!         self.player.set_flipped(Flipped::Horizontal);
+//!     } else if s.key_down(Key::Right) {
+//!         self.player.play("run")?;
+//!         self.player.set_flipped(None);
+//!     } else {
+//!         self.player.play("idle")?;
+//!     }
+//!     self.player.update(s.delta_time());
+//!     s.animation(&self.sheet, &self.player, [10, 10])?;
+//!     Ok(())
+//! }
+//! # }
+//! ```
+
+use crate::{prelude::*, renderer::Rendering, texture::TextureRenderer};
+use anyhow::anyhow;
+#[cfg(feature = "serde")]
+use anyhow::Context;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::Duration};
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+use std::{fs, path::Path};
+
+/// Default duration of atlas frames without one.
+#[cfg(feature = "serde")]
+const DEFAULT_FRAME_DURATION: Duration = Duration::from_millis(100);
+
+/// The [Image] or texture a [`SpriteSheet`] draws from.
+#[derive(Debug, Clone)]
+pub enum SpriteSource {
+    /// An [Image], uploaded to the renderer when first drawn.
+    Image(Image),
+    /// A texture created with [`PixState::create_texture`].
+    Texture(TextureId),
+}
+
+impl From<Image> for SpriteSource {
+    fn from(image: Image) -> Self {
+        Self::Image(image)
+    }
+}
+
+impl From<TextureId> for SpriteSource {
+    fn from(texture_id: TextureId) -> Self {
+        Self::Texture(texture_id)
+    }
+}
+
+/// A region of a [`SpriteSheet`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sprite {
+    rect: Rect<i32>,
+    offset: Point<i32>,
+    source_size: (u32, u32),
+    name: Option<String>,
+    duration: Option<Duration>,
+}
+
+impl Sprite {
+    /// Constructs an untrimmed `Sprite` covering `rect`.
+    fn new(rect: Rect<i32>) -> Self {
+        Self {
+            rect,
+            offset: point![0, 0],
+            source_size: (rect.width() as u32, rect.height() as u32),
+            name: None,
+            duration: None,
+        }
+    }
+
+    /// Returns the region of the [`SpriteSource`] this sprite covers.
+    #[inline]
+    pub const fn rect(&self) -> Rect<i32> {
+        self.rect
+    }
+
+    /// Returns the position of the sprite within its original frame, if trimmed when packed
+    /// into an atlas. Untrimmed sprites have an offset of `(0, 0)`.
+    #[inline]
+    pub const fn offset(&self) -> Point<i32> {
+        self.offset
+    }
+
+    /// Returns the original frame size of the sprite as `(width, height)`, before being trimmed
+    /// when packed into an atlas. Untrimmed sprites are the same size as their
+    /// [`Sprite::rect`].
+    #[inline]
+    #[must_use]
+    pub const fn source_size(&self) -> (u32, u32) {
+        self.source_size
+    }
+
+    /// Returns the sprite name, if loaded from an atlas.
+    #[inline]
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns how long the sprite is displayed as part of an animation, if loaded from an atlas
+    /// that specifies it.
+    #[inline]
+    #[must_use]
+    pub const fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
+    /// Whether this sprite was trimmed from a larger frame.
+    fn is_trimmed(&self) -> bool {
+        let (width, height) = self.source_size;
+        self.offset != point![0, 0]
+            || (width as i32, height as i32) != (self.rect.width(), self.rect.height())
+    }
+
+    /// Returns the region of `dst` covered by this sprite, where `dst` covers its original frame,
+    /// along with the rotation `center` relative to that region. Trimmed sprites are moved and
+    /// scaled within `dst` so they line up with untrimmed frames, mirrored when `flipped`.
+    fn trim(
+        &self,
+        dst: Rect<i32>,
+        angle: f64,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+    ) -> (Rect<i32>, Option<Point<i32>>) {
+        if !self.is_trimmed() {
+            return (dst, center);
+        }
+        let (source_width, source_height) = (self.source_size.0 as i32, self.source_size.1 as i32);
+        let (width, height) = (self.rect.width(), self.rect.height());
+        let (mut x, mut y) = (self.offset.x(), self.offset.y());
+        if matches!(flipped, Some(Flipped::Horizontal | Flipped::Both)) {
+            x = source_width - x - width;
+        }
+        if matches!(flipped, Some(Flipped::Vertical | Flipped::Both)) {
+            y = source_height - y - height;
+        }
+        let scale = |value: i32, size: i32, source_size: i32| {
+            if source_size == 0 {
+                return 0;
+            }
+            (f64::from(value) * f64::from(size) / f64::from(source_size)).round() as i32
+        };
+        let left = scale(x, dst.width(), source_width);
+        let top = scale(y, dst.height(), source_height);
+        let right = scale(x + width, dst.width(), source_width);
+        let bottom = scale(y + height, dst.height(), source_height);
+        let trimmed = rect![dst.x() + left, dst.y() + top, right - left, bottom - top];
+        // Rotate about the same point as the original frame
+        let center = (angle != 0.0 || center.is_some()).then(|| {
+            let center = center.unwrap_or_else(|| point![dst.width() / 2, dst.height() / 2]);
+            point![center.x() - left, center.y() - top]
+        });
+        (trimmed, center)
+    }
+}
+
+/// A collection of [Sprite] regions of an [Image] or texture.
+#[derive(Default, Debug, Clone)]
+#[must_use]
+pub struct SpriteSheet {
+    source: Option<SpriteSource>,
+    sprites: Vec<Sprite>,
+    names: HashMap<String, usize>,
+    clips: Vec<(String, AnimationClip)>,
+}
+
+impl SpriteSheet {
+    /// Constructs a `SpriteSheet` with a sprite for each region of `source`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::new(32, 16);
+    /// let sheet = SpriteSheet::new(image, [rect![0, 0, 16, 16], rect![16, 0, 8, 16]]);
+    /// assert_eq!(sheet.len(), 2);
+    /// ```
+    pub fn new<S, I>(source: S, rects: I) -> Self
+    where
+        S: Into<SpriteSource>,
+        I: IntoIterator<Item = Rect<i32>>,
+    {
+        let sprites = rects.into_iter().map(Sprite::new).collect();
+        Self {
+            source: Some(source.into()),
+            sprites,
+            names: HashMap::new(),
+            clips: Vec::new(),
+        }
+    }
+
+    /// Constructs a `SpriteSheet` by dividing `source` into `columns` and `rows` of equally sized
+    /// sprites, starting from the top-left corner. Sprites are numbered left to right, then top to
+    /// bottom.
+    ///
+    /// # Errors
+    ///
+    /// If any dimension is `0`, the grid is too large to address, or the grid exceeds the
+    /// dimensions of an [Image] source, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::new(64, 32);
+    /// let sheet = SpriteSheet::from_grid(image, 16, 16, 4, 2)?;
+    /// assert_eq!(sheet.len(), 8);
+    /// assert_eq!(sheet.rect(5)?, rect![16, 16, 16, 16]);
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn from_grid<S>(
+        source: S,
+        sprite_width: u32,
+        sprite_height: u32,
+        columns: u32,
+        rows: u32,
+    ) -> PixResult<Self>
+    where
+        S: Into<SpriteSource>,
+    {
+        if sprite_width == 0 || sprite_height == 0 || columns == 0 || rows == 0 {
+            return Err(anyhow!(
+                "invalid sprite grid: {columns}x{rows} of {sprite_width}x{sprite_height}"
+            ));
+        }
+        let (width, height) = sprite_width
+            .checked_mul(columns)
+            .zip(sprite_height.checked_mul(rows))
+            .filter(|&(width, height)| i32::try_from(width.max(height)).is_ok())
+            .ok_or_else(|| {
+                anyhow!(
+                    "sprite grid {columns}x{rows} of {sprite_width}x{sprite_height} is too large"
+                )
+            })?;
+        let source = source.into();
+        if let SpriteSource::Image(image) = &source {
+            if width > image.width() || height > image.height() {
+                return Err(anyhow!(
+                    "sprite grid {width}x{height} exceeds image size {}x{}",
+                    image.width(),
+                    image.height()
+                ));
+            }
+        }
+        let (width, height) = (sprite_width as i32, sprite_height as i32);
+        let rects = (0..rows as i32).flat_map(|row| {
+            (0..columns as i32)
+                .map(move |column| rect![column * width, row * height, width, height])
+        });
+        Ok(Self::new(source, rects))
+    }
+
+    /// Constructs a `SpriteSheet` from a `JSON` atlas describing the sprites in `source`, as
+    /// exported by [Aseprite](https://www.aseprite.org/) or
+    /// [TexturePacker](https://www.codeandweb.com/texturepacker) in either the `Hash` or `Array`
+    /// format.
+    ///
+    /// Sprites are named by their key or `filename` and keep their `duration`, if present. Aseprite
+    /// `frameTags` are loaded as [`AnimationClip`]s, available with [`SpriteSheet::clip`] and
+    /// [`SpriteSheet::animation`]. Trimmed sprites keep their offset and size within the original
+    /// frame from `spriteSourceSize` and `sourceSize`, so they're drawn where the untrimmed frame
+    /// would be.
+    ///
+    /// # Errors
+    ///
+    /// If `json` isn't a valid atlas, a sprite is rotated, or a tag refers to sprites that don't
+    /// exist, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let json = r#"{
+    ///     "frames": {
+    ///         "walk 0": { "frame": { "x": 0, "y": 0, "w": 16, "h": 16 }, "duration": 100 },
+    ///         "walk 1": { "frame": { "x": 16, "y": 0, "w": 16, "h": 16 }, "duration": 150 }
+    ///     },
+    ///     "meta": {
+    ///         "frameTags": [{ "name": "walk", "from": 0, "to": 1, "direction": "forward" }]
+    ///     }
+    /// }"#;
+    /// let sheet = SpriteSheet::from_atlas(Image::new(32, 16), json)?;
+    /// assert_eq!(sheet.index_of("walk 1"), Some(1));
+    /// assert!(sheet.clip("walk").is_some());
+    /// # Ok::<(), PixError>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn from_atlas<S>(source: S, json: &str) -> PixResult<Self>
+    where
+        S: Into<SpriteSource>,
+    {
+        let atlas: atlas::Atlas = serde_json::from_str(json).context("invalid sprite atlas")?;
+        let mut sheet = Self::new(source, []);
+        for (name, frame) in atlas.frames.0 {
+            if frame.rotated {
+                return Err(anyhow!("rotated sprites are not supported: {name:?}"));
+            }
+            let atlas::Rect { x, y, w, h } = frame.frame;
+            if w < 0 || h < 0 {
+                return Err(anyhow!("invalid sprite size for {name:?}: {w}x{h}"));
+            }
+            let mut sprite = Sprite::new(rect![x, y, w, h]);
+            if frame.trimmed {
+                if let Some(atlas::Rect { x, y, .. }) = frame.sprite_source_size {
+                    sprite.offset = point![x, y];
+                }
+                if let Some(atlas::Size { w, h }) = frame.source_size {
+                    sprite.source_size = (w, h);
+                }
+            }
+            sprite.duration = frame.duration.map(Duration::from_millis);
+            if let Some(name) = &name {
+                sheet.names.insert(name.clone(), sheet.sprites.len());
+            }
+            sprite.name = name;
+            sheet.sprites.push(sprite);
+        }
+        for tag in atlas.meta.frame_tags {
+            if tag.from > tag.to || tag.to >= sheet.sprites.len() {
+                return Err(anyhow!(
+                    "invalid sprite range for tag {:?}: {}..={}",
+                    tag.name,
+                    tag.from,
+                    tag.to
+                ));
+            }
+            let mut frames: Vec<_> = (tag.from..=tag.to)
+                .map(|index| {
+                    let duration = sheet.sprites[index].duration;
+                    (index, duration.unwrap_or(DEFAULT_FRAME_DURATION))
+                })
+                .collect();
+            let direction = tag.direction.as_deref().unwrap_or_default();
+            if direction.ends_with("reverse") {
+                frames.reverse();
+            }
+            let mut clip = AnimationClip::from_durations(frames);
+            if direction.starts_with("pingpong") {
+                clip.set_playback(Playback::PingPong);
+            }
+            sheet.clips.push((tag.name, clip));
+        }
+        Ok(sheet)
+    }
+
+    /// Constructs a `SpriteSheet` from a `JSON` atlas file. See [`SpriteSheet::from_atlas`].
+    ///
+    /// # Errors
+    ///
+    /// If the file fails to be read or isn't a valid atlas, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pix_engine::prelude::*;
+    /// let image = Image::from_file("./player.png")?;
+    /// let sheet = SpriteSheet::from_atlas_file(image, "./player.json")?;
+    /// # Ok::<(), PixError>(())
+    /// ```
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    pub fn from_atlas_file<S, P>(source: S, path: P) -> PixResult<Self>
+    where
+        S: Into<SpriteSource>,
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)?;
+        Self::from_atlas(source, &json).with_context(|| format!("failed to load atlas: {path:?}"))
+    }
+
+    /// Returns the [`SpriteSource`] sprites are drawn from.
+    #[inline]
+    #[must_use]
+    pub const fn source(&self) -> Option<&SpriteSource> {
+        self.source.as_ref()
+    }
+
+    /// Returns the list of [Sprite]s.
+    #[inline]
+    #[must_use]
+    pub fn sprites(&self) -> &[Sprite] {
+        &self.sprites
+    }
+
+    /// Returns the [Sprite] at `index`, if it exists.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&Sprite> {
+        self.sprites.get(index)
+    }
+
+    /// Returns the region of the [Sprite] at `index`.
+    ///
+    /// # Errors
+    ///
+    /// If there's no [Sprite] at `index`, then an error is returned.
+    #[inline]
+    pub fn rect(&self, index: usize) -> PixResult<Rect<i32>> {
+        self.sprite(index).map(Sprite::rect)
+    }
+
+    /// Returns the index of the [Sprite] with `name`, if it exists.
+    #[inline]
+    #[must_use]
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    /// Returns the number of [Sprite]s.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    /// Whether there are no [Sprite]s.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// Returns the [`AnimationClip`] loaded from an atlas with `name`, if it exists.
+    #[must_use]
+    pub fn clip(&self, name: &str) -> Option<&AnimationClip> {
+        self.clips
+            .iter()
+            .find_map(|(clip_name, clip)| (clip_name == name).then_some(clip))
+    }
+
+    /// Constructs an [Animation] with every [`AnimationClip`] loaded from an atlas, playing the
+    /// first.
+    pub fn animation(&self) -> Animation {
+        let mut animation = Animation::new();
+        for (name, clip) in &self.clips {
+            animation.insert_clip(name.clone(), clip.clone());
+        }
+        animation.current = self.clips.first().map(|(name, _)| name.clone());
+        animation
+    }
+}
+
+/// A sequence of [`SpriteSheet`] indexes, each displayed for a given [Duration].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[must_use]
+pub struct AnimationClip {
+    frames: Vec<(usize, Duration)>,
+    playback: Playback,
+}
+
+impl AnimationClip {
+    /// Constructs an `AnimationClip` displaying each sprite index for the same `duration`.
+    /// Defaults to [`Playback::Loop`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # use std::time::Duration;
+    /// let clip = AnimationClip::new([0, 1, 2, 1], Duration::from_millis(100));
+    /// assert_eq!(clip.duration(), Duration::from_millis(400));
+    /// ```
+    pub fn new<I>(sprites: I, duration: Duration) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        Self::from_durations(sprites.into_iter().map(|index| (index, duration)))
+    }
+
+    /// Constructs an `AnimationClip` from sprite indexes and how long each is displayed.
+    /// Defaults to [`Playback::Loop`].
+    pub fn from_durations<I>(frames: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, Duration)>,
+    {
+        Self {
+            frames: frames.into_iter().collect(),
+            playback: Playback::Loop,
+        }
+    }
+
+    /// Returns the sprite indexes and how long each is displayed.
+    #[inline]
+    #[must_use]
+    pub fn frames(&self) -> &[(usize, Duration)] {
+        &self.frames
+    }
+
+    /// Returns the time it takes to play through every frame once.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|&(_, duration)| duration).sum()
+    }
+
+    /// Returns the current [Playback] mode.
+    #[inline]
+    #[must_use]
+    pub const fn playback(&self) -> Playback {
+        self.playback
+    }
+
+    /// Set the [Playback] mode.
+    #[inline]
+    pub fn set_playback(&mut self, playback: Playback) {
+        self.playback = playback;
+    }
+
+    /// Returns the sprite index displayed after `elapsed` time based on the [Playback] mode.
+    #[must_use]
+    pub fn sprite_at(&self, elapsed: Duration) -> Option<usize> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        let index =
+            self.playback
+                .index_at(self.frames.len(), |index| self.frames[index].1, elapsed);
+        self.frames.get(index).map(|&(sprite, _)| sprite)
+    }
+}
+
+/// Plays named [`AnimationClip`]s of [`SpriteSheet`] sprites over time.
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[must_use]
+pub struct Animation {
+    clips: HashMap<String, AnimationClip>,
+    current: Option<String>,
+    elapsed: Duration,
+    flipped: Option<Flipped>,
+}
+
+impl Animation {
+    /// Constructs an `Animation` with no clips.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an [`AnimationClip`] with `name`, replacing any existing clip with the same name.
+    pub fn insert_clip<S: Into<String>>(&mut self, name: S, clip: AnimationClip) {
+        self.clips.insert(name.into(), clip);
+    }
+
+    /// Returns the [`AnimationClip`] with `name`, if it exists.
+    #[inline]
+    #[must_use]
+    pub fn clip(&self, name: &str) -> Option<&AnimationClip> {
+        self.clips.get(name)
+    }
+
+    /// Play the [`AnimationClip`] with `name` from the start. Playing the current clip again
+    /// continues without restarting; see [`Animation::restart`].
+    ///
+    /// # Errors
+    ///
+    /// If there's no clip with `name`, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # use std::time::Duration;
+    /// let delay = Duration::from_millis(100);
+    /// let mut animation = Animation::new();
+    /// animation.insert_clip("idle", AnimationClip::new([0, 1], delay));
+    /// animation.insert_clip("jump", AnimationClip::new([2, 3], delay));
+    /// animation.play("jump")?;
+    /// animation.update(Duration::from_millis(150));
+    /// assert_eq!(animation.sprite(), Some(3));
+    /// animation.play("jump")?;
+    /// assert_eq!(animation.sprite(), Some(3));
+    /// animation.play("idle")?;
+    /// assert_eq!(animation.sprite(), Some(0));
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn play(&mut self, name: &str) -> PixResult<()> {
+        if self.current.as_deref() != Some(name) {
+            if !self.clips.contains_key(name) {
+                return Err(anyhow!("invalid animation clip: {name:?}"));
+            }
+            self.current = Some(name.to_owned());
+            self.elapsed = Duration::ZERO;
+        }
+        Ok(())
+    }
+
+    /// Restart the current [`AnimationClip`] from the start.
+    #[inline]
+    pub fn restart(&mut self) {
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Stop playing the current [`AnimationClip`].
+    #[inline]
+    pub fn stop(&mut self) {
+        self.current = None;
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Advance the current [`AnimationClip`] by `delta` time, usually
+    /// [`PixState::delta_time`].
+    #[inline]
+    pub fn update(&mut self, delta: Duration) {
+        self.elapsed += delta;
+    }
+
+    /// Returns the name of the [`AnimationClip`] being played, if any.
+    #[inline]
+    #[must_use]
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    /// Returns the time elapsed playing the current [`AnimationClip`].
+    #[inline]
+    #[must_use]
+    pub const fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the [`SpriteSheet`] index currently displayed, if playing a clip.
+    #[must_use]
+    pub fn sprite(&self) -> Option<usize> {
+        self.current_clip()?.sprite_at(self.elapsed)
+    }
+
+    /// Whether the current [`AnimationClip`] has finished playing. Only [`Playback::Once`] and
+    /// [`Playback::Repeat`] clips finish.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.current_clip().is_some_and(|clip| {
+            clip.playback
+                .plays()
+                .is_some_and(|plays| self.elapsed >= clip.duration().saturating_mul(plays))
+        })
+    }
+
+    /// Returns how sprites are flipped when drawn.
+    #[inline]
+    #[must_use]
+    pub const fn flipped(&self) -> Option<Flipped> {
+        self.flipped
+    }
+
+    /// Set how sprites are flipped when drawn. `None` clears flipping.
+    #[inline]
+    pub fn set_flipped<F: Into<Option<Flipped>>>(&mut self, flipped: F) {
+        self.flipped = flipped.into();
+    }
+}
+
+impl Animation {
+    /// Returns the [`AnimationClip`] being played, if any.
+    fn current_clip(&self) -> Option<&AnimationClip> {
+        self.clips.get(self.current.as_deref()?)
+    }
+}
+
+impl PixState {
+    /// Draw the [Sprite] at `index` of a [`SpriteSheet`] to the current canvas at its original
+    /// size, positioned based on the current [`ImageMode`].
+    ///
+    /// # Errors
+    ///
+    /// If there's no [Sprite] at `index`, the sheet has no [`SpriteSource`], or the renderer fails
+    /// to draw to the current render target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { tiles: SpriteSheet };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     // Draw a row of the first 4 tiles
+    ///     for index in 0..4 {
+    ///         s.sprite(&self.tiles, index, [index as i32 * 16, 0])?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn sprite<P>(&mut self, sheet: &SpriteSheet, index: usize, position: P) -> PixResult<()>
+    where
+        P: Into<Point<i32>>,
+    {
+        let pos = position.into();
+        let (width, height) = sheet.sprite(index)?.source_size();
+        let dst = rect![pos, width as i32, height as i32];
+        self.sprite_transformed(sheet, index, dst, None, None, None)
+    }
+
+    /// Draw the [Sprite] at `index` of a [`SpriteSheet`] to the current canvas resized to the
+    /// target `dst`, optionally rotated by an `angle` about the `center` point or `flipped`. See
+    /// [`PixState::image_transformed`].
+    ///
+    /// # Errors
+    ///
+    /// If there's no [Sprite] at `index`, the sheet has no [`SpriteSource`], or the renderer fails
+    /// to draw to the current render target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { tiles: SpriteSheet };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     // Draw the first tile at double size, mirrored
+    ///     s.sprite_transformed(&self.tiles, 0, rect![0, 0, 32, 32], None, None, Flipped::Horizontal)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn sprite_transformed<R, A, C, F>(
+        &mut self,
+        sheet: &SpriteSheet,
+        index: usize,
+        dst: R,
+        angle: A,
+        center: C,
+        flipped: F,
+    ) -> PixResult<()>
+    where
+        R: Into<Option<Rect<i32>>>,
+        A: Into<Option<f64>>,
+        C: Into<Option<Point<i32>>>,
+        F: Into<Option<Flipped>>,
+    {
+        let s = &self.settings;
+        let mut dst = dst.into();
+        if s.image_mode == ImageMode::Center {
+            dst = dst.map(|dst| Rect::from_center(dst.top_left(), dst.width(), dst.height()));
+        };
+        let mut angle = angle.into().unwrap_or(0.0);
+        if s.angle_mode == AngleMode::Radians {
+            angle = angle.to_degrees();
+        };
+        self.draw_sprite(sheet, index, dst, angle, center.into(), flipped.into())
+    }
+
+    /// Draw the current [Sprite] of an [Animation] to the current canvas at its original size,
+    /// positioned based on the current [`ImageMode`] and flipped based on
+    /// [`Animation::flipped`]. Draws nothing if no clip is playing.
+    ///
+    /// # Errors
+    ///
+    /// If the current [Sprite] isn't in the [`SpriteSheet`], or the renderer fails to draw to the
+    /// current render target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { sheet: SpriteSheet, player: Animation, pos: Point<i32> };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     self.player.update(s.delta_time());
+    ///     s.image_mode(ImageMode::Center);
+    ///     s.animation(&self.sheet, &self.player, self.pos)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn animation<P>(
+        &mut self,
+        sheet: &SpriteSheet,
+        animation: &Animation,
+        position: P,
+    ) -> PixResult<()>
+    where
+        P: Into<Point<i32>>,
+    {
+        let Some(index) = animation.sprite() else {
+            return Ok(());
+        };
+        let pos = position.into();
+        let (width, height) = sheet.sprite(index)?.source_size();
+        let dst = rect![pos, width as i32, height as i32];
+        self.sprite_transformed(sheet, index, dst, None, None, animation.flipped)
+    }
+}
+
+impl SpriteSheet {
+    /// Returns the [Sprite] at `index`.
+    fn sprite(&self, index: usize) -> PixResult<&Sprite> {
+        self.sprites
+            .get(index)
+            .ok_or_else(|| anyhow!("invalid sprite index: {index}"))
+    }
+}
+
+impl PixState {
+    /// Draw the [Sprite] at `index` of a [`SpriteSheet`] to `dst`, rotated by `angle` in degrees,
+    /// ignoring the current [`ImageMode`] and [`AngleMode`] but not the current [Transform].
+    pub(crate) fn draw_sprite(
+        &mut self,
+        sheet: &SpriteSheet,
+        index: usize,
+        dst: Option<Rect<i32>>,
+        angle: f64,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+    ) -> PixResult<()> {
+        let sprite = sheet.sprite(index)?;
+        let src = Some(sprite.rect());
+        let source = sheet
+            .source()
+            .ok_or_else(|| anyhow!("sprite sheet has no source"))?;
+        let dst = match dst {
+            None if sprite.is_trimmed() => Some(self.target_rect()?),
+            dst => dst,
+        };
+        let (dst, center) = match dst {
+            Some(dst) => {
+                let (dst, center) = sprite.trim(dst, angle, center, flipped);
+                (Some(dst), center)
+            }
+            None => (None, center),
+        };
+        let (dst, angle, center, flipped) = self.transform_image(dst, angle, center, flipped)?;
+        let tint = self.settings.image_tint;
+        match source {
+            SpriteSource::Image(image) => self
+                .renderer
+                .image(image, src, dst, angle, center, flipped, tint),
+            SpriteSource::Texture(texture_id) => {
+                self.renderer
+                    .texture(*texture_id, src, dst, angle, center, flipped, tint)
+            }
+        }
+    }
+}
+
+/// `JSON` sprite atlas formats exported by Aseprite and TexturePacker.
+#[cfg(feature = "serde")]
+mod atlas {
+    use serde::{
+        de::{MapAccess, SeqAccess, Visitor},
+        Deserialize, Deserializer,
+    };
+    use std::fmt;
+
+    #[derive(Debug, Deserialize)]
+    pub(super) struct Atlas {
+        pub(super) frames: Frames,
+        #[serde(default)]
+        pub(super) meta: Meta,
+    }
+
+    /// Atlas frames in the order they're listed, from either a `JSON` array or object.
+    #[derive(Debug)]
+    pub(super) struct Frames(pub(super) Vec<(Option<String>, Frame)>);
+
+    impl<'de> Deserialize<'de> for Frames {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct FramesVisitor;
+
+            impl<'de> Visitor<'de> for FramesVisitor {
+                type Value = Frames;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str("an array or map of frames")
+                }
+
+                fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Frames, A::Error> {
+                    let mut frames = Vec::new();
+                    while let Some(frame) = seq.next_element::<Frame>()? {
+                        frames.push((frame.filename.clone(), frame));
+                    }
+                    Ok(Frames(frames))
+                }
+
+                fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Frames, A::Error> {
+                    let mut frames = Vec::new();
+                    while let Some((name, frame)) = map.next_entry::<String, Frame>()? {
+                        frames.push((Some(name), frame));
+                    }
+                    Ok(Frames(frames))
+                }
+            }
+
+            deserializer.deserialize_any(FramesVisitor)
+        }
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub(super) struct Frame {
+        #[serde(default)]
+        pub(super) filename: Option<String>,
+        pub(super) frame: Rect,
+        #[serde(default)]
+        pub(super) rotated: bool,
+        #[serde(default)]
+        pub(super) trimmed: bool,
+        #[serde(default, rename = "spriteSourceSize")]
+        pub(super) sprite_source_size: Option<Rect>,
+        #[serde(default, rename = "sourceSize")]
+        pub(super) source_size: Option<Size>,
+        #[serde(default)]
+        pub(super) duration: Option<u64>,
+    }
+
+    #[derive(Debug, Copy, Clone, Deserialize)]
+    pub(super) struct Rect {
+        pub(super) x: i32,
+        pub(super) y: i32,
+        pub(super) w: i32,
+        pub(super) h: i32,
+    }
+
+    #[derive(Debug, Copy, Clone, Deserialize)]
+    pub(super) struct Size {
+        pub(super) w: u32,
+        pub(super) h: u32,
+    }
+
+    #[derive(Default, Debug, Deserialize)]
+    pub(super) struct Meta {
+        #[serde(default, rename = "frameTags")]
+        pub(super) frame_tags: Vec<Tag>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub(super) struct Tag {
+        pub(super) name: String,
+        pub(super) from: usize,
+        pub(super) to: usize,
+        #[serde(default)]
+        pub(super) direction: Option<String>,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_from_grid() -> PixResult<()> {
+        let sheet = SpriteSheet::from_grid(Image::new(30, 20), 10, 10, 3, 2)?;
+        let rects: Vec<_> = sheet.sprites().iter().map(Sprite::rect).collect();
+        assert_eq!(
+            rects,
+            [
+                rect![0, 0, 10, 10],
+                rect![10, 0, 10, 10],
+                rect![20, 0, 10, 10],
+                rect![0, 10, 10, 10],
+                rect![10, 10, 10, 10],
+                rect![20, 10, 10, 10],
+            ]
+        );
+        assert!(sheet.rect(6).is_err());
+        assert!(SpriteSheet::from_grid(Image::new(30, 20), 10, 10, 4, 2).is_err());
+        assert!(SpriteSheet::from_grid(TextureId::default(), 0, 10, 4, 2).is_err());
+        assert!(SpriteSheet::from_grid(TextureId::default(), u32::MAX, 10, 2, 2).is_err());
+        assert!(SpriteSheet::from_grid(TextureId::default(), 1 << 30, 10, 4, 2).is_err());
+        // Texture dimensions are unknown
+        assert_eq!(
+            SpriteSheet::from_grid(TextureId::default(), 10, 10, 4, 2)?.len(),
+            8
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_from_atlas() -> PixResult<()> {
+        // Keys are intentionally out of alphabetical order
+        let hash = r#"{
+            "frames": {
+                "run 10": { "frame": { "x": 0, "y": 0, "w": 8, "h": 8 }, "duration": 50 },
+                "run 2": { "frame": { "x": 8, "y": 0, "w": 8, "h": 8 }, "duration": 100 },
+                "run 1": { "frame": { "x": 16, "y": 0, "w": 8, "h": 8 } }
+            },
+            "meta": {
+                "frameTags": [
+                    { "name": "forward", "from": 0, "to": 2, "direction": "forward" },
+                    { "name": "reverse", "from": 0, "to": 1, "direction": "reverse" },
+                    { "name": "pingpong", "from": 1, "to": 2, "direction": "pingpong" }
+                ]
+            }
+        }"#;
+        let sheet = SpriteSheet::from_atlas(Image::new(24, 8), hash)?;
+        assert_eq!(sheet.len(), 3);
+        assert_eq!(sheet.index_of("run 10"), Some(0));
+        assert_eq!(sheet.index_of("run 1"), Some(2));
+        assert_eq!(sheet.rect(1)?, rect![8, 0, 8, 8]);
+        assert_eq!(sheet.get(1).and_then(Sprite::name), Some("run 2"));
+
+        let clip = |name| {
+            sheet
+                .clip(name)
+                .map(|clip| (clip.frames().to_vec(), clip.playback()))
+        };
+        assert_eq!(
+            clip("forward"),
+            Some((
+                vec![(0, ms(50)), (1, ms(100)), (2, ms(100))],
+                Playback::Loop
+            ))
+        );
+        assert_eq!(
+            clip("reverse"),
+            Some((vec![(1, ms(100)), (0, ms(50))], Playback::Loop))
+        );
+        assert_eq!(
+            clip("pingpong"),
+            Some((vec![(1, ms(100)), (2, ms(100))], Playback::PingPong))
+        );
+        let animation = sheet.animation();
+        assert_eq!(animation.current(), Some("forward"));
+
+        let array = r#"{
+            "frames": [
+                { "filename": "b", "frame": { "x": 0, "y": 0, "w": 4, "h": 4 } },
+                { "filename": "a", "frame": { "x": 4, "y": 0, "w": 4, "h": 4 } }
+            ]
+        }"#;
+        let sheet = SpriteSheet::from_atlas(Image::new(8, 4), array)?;
+        assert_eq!(sheet.index_of("b"), Some(0));
+        assert_eq!(sheet.index_of("a"), Some(1));
+        assert!(sheet.animation().current().is_none());
+
+        let trimmed = r#"{
+            "frames": [{
+                "frame": { "x": 0, "y": 0, "w": 4, "h": 6 }, "trimmed": true,
+                "spriteSourceSize": { "x": 3, "y": 1, "w": 4, "h": 6 },
+                "sourceSize": { "w": 8, "h": 8 }
+            }]
+        }"#;
+        let sheet = SpriteSheet::from_atlas(Image::new(4, 6), trimmed)?;
+        let sprite = sheet.sprite(0)?;
+        assert_eq!(sprite.rect(), rect![0, 0, 4, 6]);
+        assert_eq!(sprite.offset(), point![3, 1]);
+        assert_eq!(sprite.source_size(), (8, 8));
+
+        let invalid_tag = r#"{
+            "frames": [{ "frame": { "x": 0, "y": 0, "w": 4, "h": 4 } }],
+            "meta": { "frameTags": [{ "name": "bad", "from": 0, "to": 1 }] }
+        }"#;
+        assert!(SpriteSheet::from_atlas(Image::new(8, 4), invalid_tag).is_err());
+        let rotated = r#"{
+            "frames": [{ "frame": { "x": 0, "y": 0, "w": 4, "h": 4 }, "rotated": true }]
+        }"#;
+        assert!(SpriteSheet::from_atlas(Image::new(8, 4), rotated).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_trim() {
+        let mut sprite = Sprite::new(rect![0, 0, 4, 6]);
+        let dst = rect![10, 20, 8, 8];
+        assert_eq!(sprite.trim(dst, 0.0, None, None), (dst, None));
+
+        sprite.offset = point![3, 1];
+        sprite.source_size = (8, 8);
+        assert_eq!(
+            sprite.trim(dst, 0.0, None, None),
+            (rect![13, 21, 4, 6], None)
+        );
+        assert_eq!(
+            sprite.trim(dst, 0.0, None, Some(Flipped::Both)),
+            (rect![11, 21, 4, 6], None)
+        );
+        // Scaled to twice the size, rotating about the center of the original frame
+        assert_eq!(
+            sprite.trim(rect![0, 0, 16, 16], 90.0, None, None),
+            (rect![6, 2, 8, 12], Some(point![2, 6]))
+        );
+    }
+
+    #[test]
+    fn test_animation() -> PixResult<()> {
+        let mut animation = Animation::new();
+        assert_eq!(animation.sprite(), None);
+        assert!(animation.play("walk").is_err());
+
+        animation.insert_clip("walk", AnimationClip::new([4, 5, 6], ms(100)));
+        let mut attack = AnimationClip::from_durations([(7, ms(50)), (8, ms(150))]);
+        attack.set_playback(Playback::Once);
+        animation.insert_clip("attack", attack);
+
+        animation.play("walk")?;
+        let mut sprites = vec![];
+        for _ in 0..4 {
+            sprites.push(animation.sprite());
+            animation.update(ms(100));
+        }
+        assert_eq!(sprites, [Some(4), Some(5), Some(6), Some(4)]);
+        assert!(!animation.is_finished());
+
+        animation.play("attack")?;
+        assert_eq!(animation.elapsed(), Duration::ZERO);
+        animation.update(ms(60));
+        assert_eq!(animation.sprite(), Some(8));
+        assert!(!animation.is_finished());
+        animation.update(ms(500));
+        assert_eq!(animation.sprite(), Some(8));
+        assert!(animation.is_finished());
+        animation.restart();
+        assert_eq!(animation.sprite(), Some(7));
+
+        animation.stop();
+        assert_eq!(animation.current(), None);
+        assert_eq!(animation.sprite(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_clip() -> PixResult<()> {
+        for playback in [Playback::Loop, Playback::PingPong, Playback::Once] {
+            let mut clip = AnimationClip::new([], ms(100));
+            clip.set_playback(playback);
+            assert_eq!(clip.sprite_at(Duration::ZERO), None, "{playback:?}");
+            assert_eq!(clip.sprite_at(ms(150)), None, "{playback:?}");
+        }
+
+        let mut animation = Animation::new();
+        animation.insert_clip("empty", AnimationClip::from_durations([]));
+        animation.play("empty")?;
+        animation.update(ms(100));
+        assert_eq!(animation.sprite(), None);
+        Ok(())
+    }
+}
@@ -44,15 +44,24 @@
 
 use crate::{
     gui::state::UiState,
+    input::InputState,
     prelude::*,
     renderer::{Renderer, RendererSettings, Rendering, WindowRenderer},
     texture::TextureRenderer,
+    touch::TouchState,
 };
 use environment::Environment;
+use events::EventQueue;
+#[cfg(not(target_arch = "wasm32"))]
+use recording::Recording;
 use settings::Settings;
 use std::{collections::HashSet, mem, time::Instant};
 
+pub mod clip;
 pub mod environment;
+pub mod events;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod recording;
 pub mod settings;
 
 /// Represents all state and methods for updating and interacting with the [`Engine`].
@@ -61,10 +70,17 @@ pub mod settings;
 pub struct PixState {
     pub(crate) renderer: Renderer,
     pub(crate) env: Environment,
+    pub(crate) events: EventQueue,
+    pub(crate) input: InputState,
+    pub(crate) touch: TouchState,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) recording: Recording,
+    pub(crate) shape: Option<Path>,
     pub(crate) ui: UiState,
     pub(crate) settings: Settings,
     pub(crate) setting_stack: Vec<Settings>,
     pub(crate) theme: Theme,
+    pub(crate) pixels: Option<(Option<TextureId>, Image)>,
 }
 
 impl PixState {
@@ -390,10 +406,17 @@ impl PixState {
         let mut state = Self {
             renderer,
             env: Environment::default(),
+            events: EventQueue::default(),
+            input: InputState::default(),
+            touch: TouchState::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            recording: Recording::default(),
+            shape: None,
             ui: UiState::default(),
             settings: Settings::default(),
             setting_stack: Vec::new(),
             theme: theme.clone(),
+            pixels: None,
         };
         state.background(theme.colors.background);
         state.fill(theme.colors.on_background());
@@ -411,6 +434,8 @@ impl PixState {
         // Reset mouse cursor icon to the current setting
         // Ignore any errors, as setting cursor in the first place should have succeeded.
         let _ignore_result = self.renderer.cursor(self.settings.cursor.as_ref());
+        // Transforms only apply to the frame they were set in
+        self.reset_transform();
         self.ui.pre_update(&self.theme);
     }
 
@@ -430,6 +455,15 @@ impl PixState {
         self.ui.post_update();
     }
 
+    /// Converts an `angle` to radians based on the current [`AngleMode`].
+    #[inline]
+    pub(crate) fn radians(&self, angle: f64) -> f64 {
+        match self.settings.angle_mode {
+            AngleMode::Radians => angle,
+            AngleMode::Degrees => angle.to_radians(),
+        }
+    }
+
     /// Takes a [Rect] and returns a modified [Rect] based on the current [`RectMode`].
     #[inline]
     pub(crate) fn get_rect<R>(&self, rect: R) -> Rect<i32>
@@ -500,10 +534,18 @@ impl PixState {
         self.ui.mouse.wheel(x, y);
     }
 
-    /// Polls for events from the underlying renderer.
+    /// Polls for queued synthetic or replayed events, followed by events from the underlying
+    /// renderer and then user events sent by an [`EventSender`]. Polled events are recorded if
+    /// [`PixState::record_events`] was called.
     #[inline]
     pub fn poll_event(&mut self) -> Option<Event> {
-        self.renderer.poll_event()
+        let event = self
+            .events
+            .poll()
+            .or_else(|| self.renderer.poll_event())
+            .or_else(|| self.events.poll_user())?;
+        self.events.record(&event);
+        Some(event)
     }
 
     /// Open a controller with a given ID to start handling events.
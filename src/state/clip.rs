@@ -0,0 +1,399 @@
+//! Clipping methods for drawing to a region of the current canvas.
+//!
+//! A [Clip] region limits which pixels get drawn. Regions are pushed onto a stack, each one
+//! intersecting the regions pushed before it, so that nested elements like scroll areas never
+//! draw outside of their parent. Besides rectangles, drawing can be masked by any shape or by the
+//! alpha channel of an [Image] or texture.
+//!
+//! Clip regions are given in render target coordinates and are not affected by the current
+//! [`RectMode`], [`EllipseMode`] or [Transform]. Setting a texture target starts with no clip
+//! region and clearing it restores the previous one.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::push_clip`]: Intersect the current clip region with a [Clip] region.
+//! - [`PixState::pop_clip`]: Restore the clip region from before the last
+//!   [`PixState::push_clip`].
+//!
+//! # Example
+//!
+//! ```
+//! # use pix_engine::prelude::*;
+//! # struct App;
+//! # impl PixEngine for App {
+//! fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     s.background(Color::BLACK);
+//!     // Spotlight following the mouse
+//!     s.push_clip(circle![s.mouse_pos(), 50])?;
+//!     s.fill(Color::YELLOW);
+//!     s.rect([0, 0, 400, 400])?;
+//!     s.pop_clip()?;
+//!     Ok(())
+//! }
+//! # }
+//! ```
+
+use crate::{
+    image::mul,
+    prelude::*,
+    renderer::{Rendering, TextureRenderer, WindowRenderer},
+};
+use std::{fmt, rc::Rc};
+
+/// A region to clip drawing to using [`PixState::push_clip`].
+///
+/// Shape regions are filled using the even-odd rule, so overlapping contours of a [Path] cut
+/// holes out of the region.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub enum Clip<'a> {
+    /// Clip to a [Rect].
+    Rect(Rect<i32>),
+    /// Clip to an [Ellipse].
+    Ellipse(Ellipse<i32>),
+    /// Clip to a polygon.
+    Polygon(Vec<Point<i32>>),
+    /// Clip to the interior of a [Path].
+    Path(&'a Path),
+    /// Clip to the alpha channel of an [Image] with its top-left corner at a position.
+    Image(&'a Image, Point<i32>),
+    /// Clip to the alpha channel of a texture with its top-left corner at a position.
+    Texture(TextureId, Point<i32>),
+}
+
+impl From<Rect<i32>> for Clip<'_> {
+    fn from(rect: Rect<i32>) -> Self {
+        Self::Rect(rect)
+    }
+}
+
+impl From<Ellipse<i32>> for Clip<'_> {
+    fn from(ellipse: Ellipse<i32>) -> Self {
+        Self::Ellipse(ellipse)
+    }
+}
+
+impl From<Tri<i32>> for Clip<'_> {
+    fn from(tri: Tri<i32>) -> Self {
+        Self::Polygon(tri.to_vec())
+    }
+}
+
+impl From<Quad<i32>> for Clip<'_> {
+    fn from(quad: Quad<i32>) -> Self {
+        Self::Polygon(quad.to_vec())
+    }
+}
+
+impl From<Vec<Point<i32>>> for Clip<'_> {
+    fn from(points: Vec<Point<i32>>) -> Self {
+        Self::Polygon(points)
+    }
+}
+
+impl<'a> From<&'a Path> for Clip<'a> {
+    fn from(path: &'a Path) -> Self {
+        Self::Path(path)
+    }
+}
+
+/// A clip rect and [Mask] saved by [`PixState::push_clip`].
+pub(crate) type ClipRegion = (Option<Rect<i32>>, Option<Rc<Mask>>);
+
+/// Clip coverage of each pixel within a region, from `0` for fully clipped to `255` for fully
+/// drawn. Pixels outside of the region are fully clipped.
+#[derive(Clone, PartialEq, Eq)]
+pub(crate) struct Mask {
+    rect: Rect<i32>,
+    coverage: Vec<u8>,
+}
+
+impl Mask {
+    /// Creates a mask covering the interior of `contours` within `bounds`, filled using the
+    /// even-odd rule. Pixels are covered when their center is inside.
+    pub(crate) fn polygons(contours: &[Vec<Point<f64>>], bounds: Rect<i32>) -> Self {
+        let points = contours.iter().flatten();
+        let (mut left, mut top) = (f64::INFINITY, f64::INFINITY);
+        let (mut right, mut bottom) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for p in points {
+            left = left.min(p.x());
+            top = top.min(p.y());
+            right = right.max(p.x());
+            bottom = bottom.max(p.y());
+        }
+        if left > right {
+            return Self::empty();
+        }
+        let rect = rect![
+            left.floor() as i32,
+            top.floor() as i32,
+            (right - left.floor()).ceil() as i32,
+            (bottom - top.floor()).ceil() as i32
+        ];
+        let rect = intersect(rect, bounds);
+        let mut mask = Self {
+            rect,
+            coverage: vec![0; (rect.width() * rect.height()) as usize],
+        };
+
+        let mut crossings = Vec::new();
+        for row in 0..rect.height() {
+            let y = f64::from(rect.y() + row) + 0.5;
+            crossings.clear();
+            for points in contours {
+                let edges = points.iter().zip(points.iter().cycle().skip(1));
+                for (p1, p2) in edges {
+                    if (p1.y() <= y) != (p2.y() <= y) {
+                        let t = (y - p1.y()) / (p2.y() - p1.y());
+                        crossings.push((p2.x() - p1.x()).mul_add(t, p1.x()));
+                    }
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+            for span in crossings.chunks_exact(2) {
+                let start = ((span[0] - 0.5).ceil() as i32 - rect.x()).max(0);
+                let end = ((span[1] - 0.5).ceil() as i32 - rect.x()).min(rect.width());
+                let offset = (row * rect.width()) as usize;
+                for col in start..end {
+                    mask.coverage[offset + col as usize] = 255;
+                }
+            }
+        }
+        mask
+    }
+
+    /// Creates a mask from the alpha channel of pixel data with top-left corner at `position`.
+    /// Pixel data without an alpha channel is fully covered.
+    pub(crate) fn alpha(
+        pixels: &[u8],
+        width: u32,
+        height: u32,
+        format: PixelFormat,
+        position: Point<i32>,
+    ) -> Self {
+        let channels = format.channels();
+        let coverage = pixels
+            .chunks_exact(channels)
+            .map(|pixel| {
+                if format.has_alpha() {
+                    pixel[channels - 1]
+                } else {
+                    255
+                }
+            })
+            .collect();
+        Self {
+            rect: rect![position, width as i32, height as i32],
+            coverage,
+        }
+    }
+
+    /// Returns a mask that covers nothing.
+    fn empty() -> Self {
+        Self {
+            rect: Rect::default(),
+            coverage: Vec::new(),
+        }
+    }
+
+    /// Returns the coverage of the pixel at `(x, y)`.
+    #[inline]
+    pub(crate) fn coverage(&self, x: i32, y: i32) -> u8 {
+        let (x, y) = (x - self.rect.x(), y - self.rect.y());
+        if x < 0 || y < 0 || x >= self.rect.width() || y >= self.rect.height() {
+            0
+        } else {
+            self.coverage[(y * self.rect.width() + x) as usize]
+        }
+    }
+
+    /// Returns a mask covering only the pixels covered by both masks.
+    pub(crate) fn intersect(&self, other: &Self) -> Self {
+        let rect = intersect(self.rect, other.rect);
+        let coverage = (rect.top()..rect.bottom())
+            .flat_map(|y| (rect.left()..rect.right()).map(move |x| (x, y)))
+            .map(|(x, y)| mul(self.coverage(x, y), other.coverage(x, y)))
+            .collect();
+        Self { rect, coverage }
+    }
+}
+
+impl fmt::Debug for Mask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mask").field("rect", &self.rect).finish()
+    }
+}
+
+impl PixState {
+    /// Intersect the current clip region with a [Clip] region, pushing the current region onto a
+    /// stack to be restored by [`PixState::pop_clip`]. Pixels outside of the region aren't
+    /// drawn.
+    ///
+    /// [`Clip::Rect`] regions are clipped by the renderer. Other regions mask drawing, which is
+    /// slower and always alpha blends when using [`BlendMode::None`]. Image and texture masks
+    /// scale the alpha of drawn pixels by their alpha channel.
+    ///
+    /// # Errors
+    ///
+    /// If the current render target is closed or dropped, or a texture region is invalid or is
+    /// the current texture target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.push_clip(rect![0, 0, 100, 100])?;
+    ///     // Only draws where both regions overlap
+    ///     s.push_clip(circle![100, 100, 50])?;
+    ///     s.rect([0, 0, 200, 200])?;
+    ///     s.pop_clip()?;
+    ///     s.pop_clip()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn push_clip<'a, C>(&mut self, clip: C) -> PixResult<()>
+    where
+        C: Into<Clip<'a>>,
+    {
+        let (mut rect, mut mask) = (self.settings.clip, self.settings.mask.clone());
+        match clip.into() {
+            Clip::Rect(clip) => {
+                rect = Some(rect.map_or(clip, |rect| intersect(rect, clip)));
+            }
+            clip => {
+                let clip_mask = self.clip_mask(clip)?;
+                let clip_mask = match mask {
+                    Some(mask) => mask.intersect(&clip_mask),
+                    None => clip_mask,
+                };
+                mask = Some(Rc::new(clip_mask));
+            }
+        }
+        let s = &mut self.settings;
+        let previous = (s.clip, s.mask.take());
+        s.clip_stack.push(previous);
+        s.clip = rect;
+        s.mask = mask;
+        self.renderer.mask(s.mask.clone());
+        self.renderer.clip(s.clip)
+    }
+
+    /// Restore the clip region from before the last [`PixState::push_clip`]. Does nothing if no
+    /// regions have been pushed.
+    ///
+    /// # Errors
+    ///
+    /// If the current render target is closed or dropped, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.push_clip(tri!([50, 0], [100, 100], [0, 100]))?;
+    ///     s.rect([0, 0, 100, 100])?;
+    ///     s.pop_clip()?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn pop_clip(&mut self) -> PixResult<()> {
+        let s = &mut self.settings;
+        match s.clip_stack.pop() {
+            Some((clip, mask)) => {
+                s.clip = clip;
+                s.mask = mask;
+                self.renderer.mask(s.mask.clone());
+                self.renderer.clip(s.clip)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Clears the clip region and stack, used when drawing to a new render target.
+    pub(crate) fn reset_clip(&mut self) -> PixResult<()> {
+        let s = &mut self.settings;
+        s.clip = None;
+        s.mask = None;
+        s.clip_stack.clear();
+        self.renderer.mask(None);
+        self.renderer.clip(None)
+    }
+
+    /// Returns the [Mask] for a non-rectangular [Clip] region.
+    fn clip_mask(&mut self, clip: Clip<'_>) -> PixResult<Mask> {
+        let (width, height) = self.renderer.dimensions()?;
+        let bounds = rect![
+            0,
+            0,
+            (width as f32 / self.settings.scale_x).ceil() as i32,
+            (height as f32 / self.settings.scale_y).ceil() as i32
+        ];
+        let mask = match clip {
+            Clip::Rect(rect) => Mask::polygons(&[rect.points().map(|p| p.as_()).to_vec()], bounds),
+            Clip::Ellipse(ellipse) => Mask::polygons(&[ellipse_points(ellipse)], bounds),
+            Clip::Polygon(points) => {
+                Mask::polygons(&[points.iter().map(Point::as_).collect()], bounds)
+            }
+            Clip::Path(path) => {
+                let contours: Vec<Vec<Point<f64>>> = path
+                    .flatten(self.settings.bezier_detail)
+                    .into_iter()
+                    .map(|(points, _)| points)
+                    .collect();
+                Mask::polygons(&contours, bounds)
+            }
+            Clip::Image(image, position) => Mask::alpha(
+                image.as_bytes(),
+                image.width(),
+                image.height(),
+                image.format(),
+                position,
+            ),
+            Clip::Texture(texture_id, position) => {
+                let target = self.renderer.texture_target();
+                if target == Some(texture_id) {
+                    return Err(PixError::InvalidTexture(texture_id).into());
+                }
+                self.renderer.set_texture_target(texture_id)?;
+                let pixels = self
+                    .renderer
+                    .dimensions()
+                    .and_then(|size| Ok((size, self.renderer.to_bytes()?)));
+                match target {
+                    Some(target) => self.renderer.set_texture_target(target)?,
+                    None => self.renderer.clear_texture_target(),
+                }
+                let ((width, height), pixels) = pixels?;
+                Mask::alpha(&pixels, width, height, PixelFormat::Rgba, position)
+            }
+        };
+        Ok(mask)
+    }
+}
+
+/// Returns the intersection of two [Rect]s, which is empty if they don't overlap.
+fn intersect(a: Rect<i32>, b: Rect<i32>) -> Rect<i32> {
+    let (left, top) = (a.left().max(b.left()), a.top().max(b.top()));
+    let (right, bottom) = (a.right().min(b.right()), a.bottom().min(b.bottom()));
+    rect![left, top, (right - left).max(0), (bottom - top).max(0)]
+}
+
+/// Returns points along the outline of an [Ellipse].
+fn ellipse_points(ellipse: Ellipse<i32>) -> Vec<Point<f64>> {
+    let [x, y, width, height] = ellipse.coords().map(f64::from);
+    let (rx, ry) = (width / 2.0, height / 2.0);
+    let count = rx.max(ry).ceil().clamp(16.0, 360.0) as usize;
+    (0..count)
+        .map(|i| {
+            let (sin, cos) = (std::f64::consts::TAU * i as f64 / count as f64).sin_cos();
+            point![rx.mul_add(cos, x), ry.mul_add(sin, y)]
+        })
+        .collect()
+}
@@ -7,6 +7,9 @@
 //! - [`PixState::focused`]: Whether the current window target has focus.
 //! - [`PixState::delta_time`]: [Duration] elapsed since last frame.
 //! - [`PixState::elapsed`]: [Duration] elapsed since application start.
+//! - [`PixState::fixed_timestep`]: [Duration] between calls to [`PixEngine::on_fixed_update`].
+//! - [`PixState::interpolation_alpha`]: Fraction of a fixed timestep accumulated since the last
+//!   call to [`PixEngine::on_fixed_update`].
 //! - [`PixState::frame_count`]: Total number of frames since application start.
 //! - [`PixState::redraw`]: Run render loop 1 time, calling [`PixEngine::on_update`].
 //! - [`PixState::run_times`]: Run render loop N times, calling [`PixEngine::on_update`].
@@ -29,6 +32,9 @@ use time::OffsetDateTime;
 
 const ONE_SECOND: Duration = Duration::from_secs(1);
 
+/// Default maximum number of [`PixEngine::on_fixed_update`] calls per frame.
+pub(crate) const DEFAULT_MAX_FIXED_STEPS: usize = 8;
+
 /// Environment values for [`PixState`]
 #[derive(Debug, Clone)]
 pub(crate) struct Environment {
@@ -41,6 +47,12 @@ pub(crate) struct Environment {
     quit: bool,
     last_frame_time: Instant,
     frame_timer: Duration,
+    clock_step: Option<Duration>,
+    simulated_time: Duration,
+    fixed_timestep: Option<Duration>,
+    max_fixed_steps: usize,
+    accumulator: Duration,
+    interpolation_alpha: f64,
 }
 
 impl Default for Environment {
@@ -55,6 +67,12 @@ impl Default for Environment {
             quit: false,
             last_frame_time: Instant::now(),
             frame_timer: Duration::default(),
+            clock_step: None,
+            simulated_time: Duration::default(),
+            fixed_timestep: None,
+            max_fixed_steps: DEFAULT_MAX_FIXED_STEPS,
+            accumulator: Duration::default(),
+            interpolation_alpha: 0.0,
         }
     }
 }
@@ -138,7 +156,10 @@ impl PixState {
         self.env.delta_time
     }
 
-    /// The [Duration[ elapsed since application start.
+    /// The [Duration[ elapsed since application start. When using a deterministic clock, this is
+    /// the total simulated time of all frames rendered.
+    ///
+    /// See [`EngineBuilder::deterministic_clock`](crate::engine::EngineBuilder::deterministic_clock).
     ///
     /// # Example
     ///
@@ -158,7 +179,70 @@ impl PixState {
     #[inline]
     #[must_use]
     pub fn elapsed(&self) -> Duration {
-        self.env.start.elapsed()
+        if self.env.clock_step.is_some() {
+            self.env.simulated_time
+        } else {
+            self.env.start.elapsed()
+        }
+    }
+
+    /// The fixed [Duration] simulated by each call to [`PixEngine::on_fixed_update`], if enabled.
+    ///
+    /// See [`EngineBuilder::fixed_timestep`](crate::engine::EngineBuilder::fixed_timestep).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { position: f64, velocity: f64 };
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_fixed_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     if let Some(step) = s.fixed_timestep() {
+    ///         self.position += self.velocity * step.as_secs_f64();
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn fixed_timestep(&self) -> Option<Duration> {
+        self.env.fixed_timestep
+    }
+
+    /// The fraction of a fixed timestep, between `0.0` and `1.0`, accumulated since the last call
+    /// to [`PixEngine::on_fixed_update`]. Always `0.0` when a fixed timestep is not enabled.
+    ///
+    /// Used to interpolate rendering between the previous and current fixed update states to
+    /// avoid stuttering when the frame rate doesn't match the fixed timestep.
+    ///
+    /// See [`EngineBuilder::fixed_timestep`](crate::engine::EngineBuilder::fixed_timestep).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { previous: Point<f64>, current: Point<f64> };
+    /// # impl PixEngine for App {
+    /// fn on_fixed_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     self.previous = self.current;
+    ///     self.current += point!(2.0, 0.0);
+    ///     Ok(())
+    /// }
+    ///
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let alpha = s.interpolation_alpha();
+    ///     let pos = self.previous + (self.current - self.previous) * alpha;
+    ///     s.circle([pos.x() as i32, pos.y() as i32, 10])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn interpolation_alpha(&self) -> f64 {
+        self.env.interpolation_alpha
     }
 
     /// The total number of frames rendered since application start.
@@ -368,10 +452,23 @@ impl PixState {
 }
 
 impl PixState {
-    /// Return the instant the last frame was rendered at.
+    /// Return the time since the last frame was rendered, or the fixed clock step when using a
+    /// deterministic clock.
+    #[inline]
+    pub(crate) fn time_since_last(&self, now: Instant) -> Duration {
+        self.env
+            .clock_step
+            .unwrap_or_else(|| now - self.env.last_frame_time)
+    }
+
+    /// Return the current instant, advanced by simulated time when using a deterministic clock.
     #[inline]
-    pub(crate) const fn last_frame_time(&self) -> Instant {
-        self.env.last_frame_time
+    pub(crate) fn now(&self) -> Instant {
+        if self.env.clock_step.is_some() {
+            self.env.start + self.env.simulated_time
+        } else {
+            Instant::now()
+        }
     }
 
     /// Set the delta time since last frame.
@@ -379,6 +476,53 @@ impl PixState {
     pub(crate) fn set_delta_time(&mut self, now: Instant, time_since_last: Duration) {
         self.env.delta_time = time_since_last;
         self.env.last_frame_time = now;
+        if self.env.clock_step.is_some() {
+            self.env.simulated_time += time_since_last;
+        }
+    }
+
+    /// Advance time by a fixed step each frame instead of measuring elapsed wall time.
+    #[inline]
+    pub(crate) fn set_clock_step(&mut self, step: Option<Duration>) {
+        self.env.clock_step = step;
+    }
+
+    /// Whether time is advanced by a fixed step each frame.
+    #[inline]
+    pub(crate) const fn is_deterministic(&self) -> bool {
+        self.env.clock_step.is_some()
+    }
+
+    /// Enable calling [`PixEngine::on_fixed_update`] every fixed `step`, running at most
+    /// `max_steps` times per frame. A zero `step` disables fixed updates.
+    #[inline]
+    pub(crate) fn set_fixed_timestep(&mut self, step: Option<Duration>, max_steps: usize) {
+        self.env.fixed_timestep = step.filter(|step| !step.is_zero());
+        self.env.max_fixed_steps = max_steps;
+        self.env.accumulator = Duration::default();
+        self.env.interpolation_alpha = 0.0;
+    }
+
+    /// Accumulate the time since last frame and return the number of fixed timesteps to run,
+    /// updating the interpolation alpha with the time left over.
+    ///
+    /// When more than the maximum number of steps have accumulated, such as after a long stall,
+    /// the excess time is discarded rather than carried over so that the simulation doesn't fall
+    /// further behind trying to catch up.
+    pub(crate) fn fixed_update_steps(&mut self, time_since_last: Duration) -> usize {
+        let env = &mut self.env;
+        let Some(step) = env.fixed_timestep else {
+            return 0;
+        };
+        env.accumulator += time_since_last;
+        let step_nanos = step.as_nanos();
+        let accumulated = env.accumulator.as_nanos();
+        let steps = usize::try_from(accumulated / step_nanos).unwrap_or(usize::MAX);
+        let remaining = accumulated % step_nanos;
+        // Remainder is less than `step`, which fits in a `Duration`
+        env.accumulator = Duration::from_nanos(remaining as u64);
+        env.interpolation_alpha = remaining as f64 / step_nanos as f64;
+        steps.min(env.max_fixed_steps)
     }
 
     /// Whether the current render loop should be running or not.
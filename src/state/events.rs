@@ -0,0 +1,280 @@
+//! Event queue methods for the [`Engine`].
+//!
+//! Methods for injecting synthetic [Event]s and recording or replaying events, which allows
+//! driving an application with scripted input for automated testing.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::push_event`]: Queue a synthetic [Event] to be handled.
+//! - [`PixState::record_events`]: Start recording handled events.
+//! - [`PixState::stop_recording_events`]: Stop recording events, returning the
+//!   [`EventRecording`].
+//! - [`PixState::replay_events`]: Replay an [`EventRecording`].
+//! - [`PixState::stop_replaying_events`]: Stop any in-progress replay.
+//! - [`PixState::is_replaying_events`]: Whether an [`EventRecording`] is being replayed.
+//! - [`PixState::event_sender`]: Create an [`EventSender`] for sending custom events from other
+//!   threads.
+
+use crate::prelude::*;
+use std::{
+    any::Any,
+    collections::VecDeque,
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+/// Maximum number of [`UserEvent`]s handled each frame, so a flood of events sent from other
+/// threads can't starve system events or stall the frame.
+const MAX_USER_EVENTS_PER_FRAME: usize = 128;
+
+/// A replay in progress.
+#[derive(Debug, Clone)]
+struct Replay {
+    events: VecDeque<RecordedEvent>,
+    start_frame: usize,
+}
+
+/// Queued synthetic, user and replayed events for [`PixState`].
+#[derive(Debug)]
+pub(crate) struct EventQueue {
+    frame: usize,
+    pending: VecDeque<Event>,
+    /// Number of `pending` events queued prior to the current frame.
+    pending_due: usize,
+    user_sender: Sender<UserEvent>,
+    user_receiver: Receiver<UserEvent>,
+    user_event_count: usize,
+    replay: Option<Replay>,
+    recording: Option<(usize, EventRecording)>,
+}
+
+impl Default for EventQueue {
+    fn default() -> Self {
+        let (user_sender, user_receiver) = mpsc::channel();
+        Self {
+            frame: 0,
+            pending: VecDeque::new(),
+            pending_due: 0,
+            user_sender,
+            user_receiver,
+            user_event_count: 0,
+            replay: None,
+            recording: None,
+        }
+    }
+}
+
+impl EventQueue {
+    /// Returns the next synthetic event or replayed event due this frame.
+    pub(crate) fn poll(&mut self) -> Option<Event> {
+        if self.pending_due > 0 {
+            self.pending_due -= 1;
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+        }
+        let replay = self.replay.as_mut()?;
+        let due = replay
+            .events
+            .front()
+            .is_some_and(|recorded| replay.start_frame + recorded.frame <= self.frame);
+        let event = if due {
+            replay.events.pop_front().map(|recorded| recorded.event)
+        } else {
+            None
+        };
+        if replay.events.is_empty() {
+            self.replay = None;
+        }
+        event
+    }
+
+    /// Returns the next user event sent by an [`EventSender`], if fewer than
+    /// `MAX_USER_EVENTS_PER_FRAME` have been handled this frame. Remaining events are handled on
+    /// later frames.
+    pub(crate) fn poll_user(&mut self) -> Option<Event> {
+        if self.user_event_count >= MAX_USER_EVENTS_PER_FRAME {
+            return None;
+        }
+        let event = self.user_receiver.try_recv().ok()?;
+        self.user_event_count += 1;
+        Some(Event::User(event))
+    }
+
+    /// Record an event handled this frame, if recording.
+    pub(crate) fn record(&mut self, event: &Event) {
+        // User events carry arbitrary payloads which can't be saved or replayed
+        if matches!(event, Event::User(_)) {
+            return;
+        }
+        if let Some((start_frame, recording)) = &mut self.recording {
+            recording.push(self.frame - *start_frame, event.clone());
+        }
+    }
+}
+
+impl PixState {
+    /// Queue a synthetic [Event] to be handled on the next frame, prior to any pending system
+    /// events. Events are handled exactly as if they came from the system, calling the relevant
+    /// [`PixEngine`] methods and updating input state used by the UI. Events pushed while handling
+    /// another event are not handled until the next frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     if s.button("Quit")? {
+    ///         // Other `PixEngine` methods will get a chance to react
+    ///         s.push_event(Event::Quit);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn push_event(&mut self, event: Event) {
+        self.events.pending.push_back(event);
+    }
+
+    /// Start recording all handled events along with the frame they occurred on, relative to
+    /// when recording started. Any in-progress recording is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.record_events();
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn record_events(&mut self) {
+        self.events.recording = Some((self.events.frame, EventRecording::new()));
+    }
+
+    /// Stop recording events, returning the [`EventRecording`] if recording was started with
+    /// [`PixState::record_events`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_stop(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     if let Some(recording) = s.stop_recording_events() {
+    ///         println!("recorded {} events", recording.len());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn stop_recording_events(&mut self) -> Option<EventRecording> {
+        self.events.recording.take().map(|(_, recording)| recording)
+    }
+
+    /// Replay an [`EventRecording`], with frames relative to the current frame. Replayed events
+    /// are handled along with any system events. Any in-progress replay is replaced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let mut events = EventRecording::new();
+    ///     events.push(0, Event::MouseMotion { x: 50, y: 20, xrel: 0, yrel: 0 });
+    ///     events.push(1, Event::MouseDown { button: Mouse::Left, x: 50, y: 20 });
+    ///     events.push(2, Event::MouseUp { button: Mouse::Left, x: 50, y: 20 });
+    ///     s.replay_events(events);
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn replay_events(&mut self, recording: EventRecording) {
+        self.events.replay = (!recording.is_empty()).then(|| Replay {
+            events: recording.into_iter().collect(),
+            start_frame: self.events.frame,
+        });
+    }
+
+    /// Create an [`EventSender`] that can be moved to other threads to send custom events of type
+    /// `T` to the [`Engine`]. Sent events are handled on the next frame by
+    /// [`PixEngine::on_event`] and [`PixEngine::on_user_event`] as an [`Event::User`], after any
+    /// pending system events. At most 128 user events are handled each frame, with the rest
+    /// delayed to later frames.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # use std::{thread, time::Duration};
+    /// struct Tick(u32);
+    ///
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let sender = s.event_sender();
+    ///     thread::spawn(move || {
+    ///         for i in 0.. {
+    ///             thread::sleep(Duration::from_secs(1));
+    ///             if sender.send(Tick(i)).is_err() {
+    ///                 break;
+    ///             }
+    ///         }
+    ///     });
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn event_sender<T>(&self) -> EventSender<T>
+    where
+        T: Any + Send + Sync,
+    {
+        EventSender::new(self.events.user_sender.clone())
+    }
+
+    /// Stop any in-progress replay started with [`PixState::replay_events`].
+    #[inline]
+    pub fn stop_replaying_events(&mut self) {
+        self.events.replay = None;
+    }
+
+    /// Whether an [`EventRecording`] is currently being replayed.
+    #[inline]
+    #[must_use]
+    pub const fn is_replaying_events(&self) -> bool {
+        self.events.replay.is_some()
+    }
+}
+
+impl PixState {
+    /// Start handling events for the current frame. Only synthetic events queued prior to this
+    /// call are handled this frame.
+    #[inline]
+    pub(crate) fn begin_event_frame(&mut self) {
+        self.events.pending_due = self.events.pending.len();
+    }
+
+    /// Advance the frame used to timestamp recorded events and schedule replayed events.
+    #[inline]
+    pub(crate) fn end_event_frame(&mut self) {
+        self.events.frame += 1;
+        self.events.user_event_count = 0;
+    }
+}
@@ -0,0 +1,340 @@
+//! Canvas recording methods for capturing rendered frames to an animation or image sequence.
+//!
+//! Once started, every frame presented by the [`Engine`] is read back from the window canvas and
+//! sampled at a fixed output frame rate, independent of how fast the application renders. Slow
+//! frames are repeated and fast frames are skipped so that playback matches real time, or
+//! simulated time when using a deterministic clock.
+//!
+//! Animated `PNG` and `GIF` recordings keep every distinct frame in memory until the recording is
+//! stopped, while `PNG` sequences are written to disk as frames are captured. Any recording still
+//! in progress when the application quits is stopped and saved.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::start_recording`]: Start recording frames to a file or directory.
+//! - [`PixState::stop_recording`]: Stop recording and save any pending frames.
+//! - [`PixState::is_recording`]: Whether frames are being recorded.
+//! - [`PixState::recording_frame_rate`]: Set the output frame rate of recordings.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use pix_engine::prelude::*;
+//! # struct App;
+//! # impl PixEngine for App {
+//! # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+//! fn on_key_pressed(&mut self, s: &mut PixState, event: KeyEvent) -> PixResult<bool> {
+//!     if let Key::R = event.key {
+//!         if s.is_recording() {
+//!             s.stop_recording()?;
+//!         } else {
+//!             s.start_recording("./demo.gif", RecordingFormat::Gif)?;
+//!         }
+//!     }
+//!     Ok(false)
+//! }
+//! # }
+//! ```
+
+use crate::{prelude::*, renderer::Rendering};
+use anyhow::Context;
+use log::{error, info};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Default output frame rate of recordings.
+const DEFAULT_FRAME_RATE: usize = 30;
+/// Difference in frame times considered equal when sampling output frames.
+const TIMING_TOLERANCE: Duration = Duration::from_micros(1);
+
+/// The output format of a canvas recording.
+#[non_exhaustive]
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RecordingFormat {
+    /// A looping animated `PNG` (`APNG`) file.
+    #[default]
+    Apng,
+    /// A looping animated `GIF` file, with each frame reduced to 256 colors and delays rounded to
+    /// hundredths of a second.
+    Gif,
+    /// A directory of numbered `PNG` files, starting with `frame_00001.png`.
+    PngSequence,
+}
+
+/// Where recorded frames are written.
+#[derive(Debug)]
+enum Output {
+    /// An animation file, written when recording stops.
+    Animation {
+        file: BufWriter<File>,
+        format: ImageFormat,
+        frames: Vec<(Image, Duration)>,
+    },
+    /// A directory of images, written as frames are captured.
+    Sequence(PathBuf),
+}
+
+/// A recording in progress.
+#[derive(Debug)]
+struct Recorder {
+    output: Output,
+    path: PathBuf,
+    frame_delay: Duration,
+    start: Duration,
+    frame_count: u64,
+    dimensions: (u32, u32),
+}
+
+/// Canvas recording state for [`PixState`].
+#[derive(Debug)]
+pub(crate) struct Recording {
+    frame_rate: usize,
+    recorder: Option<Recorder>,
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self {
+            frame_rate: DEFAULT_FRAME_RATE,
+            recorder: None,
+        }
+    }
+}
+
+impl Recorder {
+    /// Returns the number of output frames with timestamps before `elapsed`.
+    fn frames_due(&self, elapsed: Duration) -> u64 {
+        // Allow for rounding in frame times, e.g. 60 frames per second recorded at 30
+        let elapsed = elapsed
+            .saturating_sub(self.start)
+            .saturating_sub(TIMING_TOLERANCE)
+            .as_nanos();
+        let frame_delay = self.frame_delay.as_nanos().max(1);
+        ((elapsed + frame_delay - 1) / frame_delay) as u64
+    }
+
+    /// Add `image` to the output, displayed for `count` output frames.
+    fn push(&mut self, image: Image, count: u64) -> PixResult<()> {
+        match &mut self.output {
+            Output::Animation { frames, .. } => {
+                let delay = self.frame_delay * count as u32;
+                match frames.last_mut() {
+                    // Extend identical frames instead of storing duplicates
+                    Some((last, last_delay)) if last.as_bytes() == image.as_bytes() => {
+                        *last_delay += delay;
+                    }
+                    _ => frames.push((image, delay)),
+                }
+            }
+            Output::Sequence(dir) => {
+                let mut bytes = Vec::new();
+                image.write_to(&mut bytes, ImageFormat::Png)?;
+                for frame in self.frame_count + 1..=self.frame_count + count {
+                    let path = dir.join(format!("frame_{frame:05}.png"));
+                    fs::write(&path, &bytes)
+                        .with_context(|| format!("failed to write recording frame: {path:?}"))?;
+                }
+            }
+        }
+        self.frame_count += count;
+        Ok(())
+    }
+
+    /// Write any pending frames to the output.
+    fn finish(self) -> PixResult<()> {
+        match self.output {
+            Output::Animation {
+                file,
+                format,
+                frames,
+            } => {
+                let animation = AnimatedImage::from_frames(frames)
+                    .context("no frames were recorded before stopping")?;
+                animation
+                    .write_to(file, format)
+                    .with_context(|| format!("failed to save recording: {:?}", self.path))
+            }
+            Output::Sequence(_) => Ok(()),
+        }
+    }
+}
+
+impl PixState {
+    /// Start recording presented frames to `path` in the given [`RecordingFormat`]. For
+    /// [`RecordingFormat::PngSequence`], `path` is a directory that's created if it doesn't
+    /// exist. Any recording already in progress is stopped and saved first.
+    ///
+    /// Frames are captured after each call to [`PixEngine::on_update`] at the
+    /// [recording frame rate](PixState::recording_frame_rate). If the window is resized while
+    /// recording, frames are scaled to the size the recording started with.
+    ///
+    /// # Errors
+    ///
+    /// If an in-progress recording fails to save, the current window target is closed or
+    /// invalid, or an [`io::Error`](std::io::Error) occurs creating the output file or
+    /// directory, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.recording_frame_rate(60);
+    ///     s.start_recording("./frames", RecordingFormat::PngSequence)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn start_recording<P>(&mut self, path: P, format: RecordingFormat) -> PixResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        self.stop_recording()?;
+        let path = path.as_ref().to_path_buf();
+        info!("Recording canvas to {}", path.display());
+        let output = match format {
+            RecordingFormat::Apng | RecordingFormat::Gif => Output::Animation {
+                file: BufWriter::new(File::create(&path)?),
+                format: if format == RecordingFormat::Gif {
+                    ImageFormat::Gif
+                } else {
+                    ImageFormat::Png
+                },
+                frames: Vec::new(),
+            },
+            RecordingFormat::PngSequence => {
+                fs::create_dir_all(&path)?;
+                Output::Sequence(path.clone())
+            }
+        };
+        self.recording.recorder = Some(Recorder {
+            output,
+            path,
+            frame_delay: Duration::from_secs(1) / self.recording.frame_rate.max(1) as u32,
+            start: self.elapsed(),
+            frame_count: 0,
+            dimensions: self.dimensions()?,
+        });
+        Ok(())
+    }
+
+    /// Stop recording started with [`PixState::start_recording`], saving any pending frames.
+    /// Does nothing if not recording.
+    ///
+    /// # Errors
+    ///
+    /// If no frames were captured for an animation, the animation fails to encode, or an
+    /// [`io::Error`](std::io::Error) occurs writing to the output file, then an error is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     if s.frame_count() == 300 {
+    ///         s.stop_recording()?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn stop_recording(&mut self) -> PixResult<()> {
+        match self.recording.recorder.take() {
+            Some(recorder) => {
+                info!("Saving recording to {}", recorder.path.display());
+                recorder.finish()
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Whether frames are being recorded with [`PixState::start_recording`].
+    #[inline]
+    #[must_use]
+    pub const fn is_recording(&self) -> bool {
+        self.recording.recorder.is_some()
+    }
+
+    /// Set the number of frames per second written by recordings started with
+    /// [`PixState::start_recording`]. Defaults to `30`. Takes effect on the next recording.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.recording_frame_rate(24);
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn recording_frame_rate(&mut self, frame_rate: usize) {
+        self.recording.frame_rate = frame_rate;
+    }
+}
+
+impl PixState {
+    /// Capture the frame about to be presented if any output frames are due before the next
+    /// frame, based on the elapsed time after the frame is complete. Must be called prior to
+    /// presenting, as the canvas contents are undefined afterwards.
+    ///
+    /// Recording errors don't interrupt the application. Instead, the error is logged and the
+    /// recording is stopped, saving any frames captured so far.
+    pub(crate) fn record_frame(&mut self, time_since_last: Duration) {
+        if let Err(err) = self.capture_frame(time_since_last) {
+            error!("Failed to record frame, stopping recording: {err:?}");
+            if let Err(err) = self.stop_recording() {
+                error!("Failed to save recording: {err:?}");
+            }
+        }
+    }
+
+    /// Read back the current canvas and push it to the recorder as many times as output frames
+    /// are due.
+    fn capture_frame(&mut self, time_since_last: Duration) -> PixResult<()> {
+        // Simulated time isn't advanced until after the frame is presented
+        let elapsed = if self.is_deterministic() {
+            self.elapsed() + time_since_last
+        } else {
+            self.elapsed()
+        };
+        let Some(recorder) = &self.recording.recorder else {
+            return Ok(());
+        };
+        let count = recorder
+            .frames_due(elapsed)
+            .saturating_sub(recorder.frame_count);
+        if count == 0 {
+            return Ok(());
+        }
+
+        let (width, height) = recorder.dimensions;
+        let (current_width, current_height) = self.renderer.target_size()?;
+        let bytes = self.renderer.to_bytes()?;
+        let mut image = Image::from_bytes(current_width, current_height, bytes, PixelFormat::Rgba)?;
+        if (current_width, current_height) != (width, height) {
+            image = image.resize(width, height, ResizeFilter::Bilinear);
+        }
+        if let Some(recorder) = &mut self.recording.recorder {
+            recorder.push(image, count)?;
+        }
+        Ok(())
+    }
+}
@@ -9,7 +9,9 @@
 //! - [`EllipseMode`]: Alias for `DrawMode`.
 //! - [`ImageMode`]: Alias for `DrawMode`.
 //! - [`ArcMode`]: Determines how arcs are rendered.
-//! - [`BlendMode`]: Determines how images and textures are blended.
+//! - [`StrokeCap`]: Determines how the ends of open strokes are rendered.
+//! - [`StrokeJoin`]: Determines how the corners between stroke segments are rendered.
+//! - [`BlendMode`]: Determines how shapes, images and textures are blended.
 //! - [`AngleMode`]: Determines how angles are interpreted.
 //! - [`FontStyle`]: Determines how text is rendered.
 //!
@@ -17,8 +19,15 @@
 //!
 //! - [`PixState::background`]: Sets the [Color] used by [`PixState::clear`] to clear the canvas.
 //! - [`PixState::fill`]: Sets the [Color] used to fill shapes.
+//! - [`PixState::fill_paint`]: Sets the [Paint] used to fill shapes, such as a [Gradient].
 //! - [`PixState::stroke`]: Sets the [Color] used to stroke shapes and text.
-//! - [`PixState::stroke_weight`]: Sets the stroke line thickness for lines and text.
+//! - [`PixState::stroke_paint`]: Sets the [Paint] used to stroke shapes, such as a [Gradient].
+//! - [`PixState::stroke_weight`]: Sets the stroke line thickness for shapes and text.
+//! - [`PixState::stroke_cap`]: Sets the [`StrokeCap`] used to draw the ends of open strokes.
+//! - [`PixState::stroke_join`]: Sets the [`StrokeJoin`] used to draw stroke corners.
+//! - [`PixState::miter_limit`]: Sets the limit at which mitered corners are beveled instead.
+//! - [`PixState::stroke_dash`]: Sets a dash pattern used to draw strokes.
+//! - [`PixState::stroke_dash_offset`]: Sets the distance into the dash pattern strokes start at.
 //! - [`PixState::text_shadow`]: Sets the shadow distance for drawing text.
 //! - [`PixState::smooth`]: Enables the anti-alias smoothing option for drawing shapes.
 //! - [`PixState::bezier_detail`]: Set the resolution at which Bezier curves are dispalyed.
@@ -36,16 +45,26 @@
 //! - [`PixState::target_frame_rate`]: Return the current targeted frame rate.
 //! - [`PixState::frame_rate`]: Set or clear a targeted frame rate.
 //! - [`PixState::scale`]: Set the rendering scale of the current canvas.
+//! - [`PixState::translate`]: Translate the current [Transform] used to draw shapes and images.
+//! - [`PixState::rotate`]: Rotate the current [Transform] used to draw shapes and images.
+//! - [`PixState::scale_by`]: Scale the current [Transform] used to draw shapes and images.
+//! - [`PixState::shear_x`]: Shear the current [Transform] along the x-axis.
+//! - [`PixState::shear_y`]: Shear the current [Transform] along the y-axis.
+//! - [`PixState::apply_transform`]: Multiply the current [Transform] by another.
+//! - [`PixState::set_transform`]: Replace the current [Transform].
+//! - [`PixState::reset_transform`]: Reset the current [Transform] to the identity.
+//! - [`PixState::transform`]: Return the current [Transform].
 //! - [`PixState::rect_mode`]: Change the [`RectMode`] for rendering rectangles.
 //! - [`PixState::ellipse_mode`]: Change the [`EllipseMode`] for rendering ellipses.
 //! - [`PixState::image_mode`]: Change the [`ImageMode`] for rendering images.
 //! - [`PixState::image_tint`]: Set or clear a [Color] used to tint [Image]s.
 //! - [`PixState::arc_mode`]: Change the [`ArcMode`] for rendering arcs.
 //! - [`PixState::angle_mode`]: Change the [`AngleMode`] for angle interpretation.
-//! - [`PixState::blend_mode`]: Change the [`BlendMode`] for rendering images and textures.
+//! - [`PixState::blend_mode`]: Change the [`BlendMode`] used to draw shapes, images and textures.
 //! - [`PixState::push`]: Push a copy of all the current settings to a stack.
 //! - [`PixState::pop`]: Pop the previously pushed settings off the stack, restoring them.
 
+use super::clip::{ClipRegion, Mask};
 use crate::{
     prelude::*,
     renderer::{Rendering, WindowRenderer},
@@ -53,7 +72,7 @@ use crate::{
 use bitflags::bitflags;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{rc::Rc, time::Duration};
 
 /// Drawing mode which changes how `(x, y)` coordinates are interpreted.
 #[non_exhaustive]
@@ -86,7 +105,42 @@ pub enum ArcMode {
     Pie,
 }
 
-/// Drawing mode which determines how textures are blended together.
+/// Determines how the ends of open strokes are drawn when [`PixState::stroke_weight`] is greater
+/// than `1`.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StrokeCap {
+    /// Stroke ends exactly at the end points. Default.
+    Butt,
+    /// Stroke ends with a semicircle centered on the end points.
+    Round,
+    /// Stroke extends past the end points by half the stroke width.
+    Square,
+}
+
+/// Determines how the corners between stroke segments are drawn when
+/// [`PixState::stroke_weight`] is greater than `1`.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StrokeJoin {
+    /// Extend the outer edges of segments until they meet, falling back to [`StrokeJoin::Bevel`]
+    /// when the corner is longer than [`PixState::miter_limit`]. Default.
+    Miter,
+    /// Round off the corner with a circular arc.
+    Round,
+    /// Cut off the corner with a straight edge.
+    Bevel,
+}
+
+/// Drawing mode which determines how shapes, images and textures are blended with what's already
+/// drawn.
+///
+/// The extended modes past [`BlendMode::Mod`] combine each source color channel `s` with the
+/// destination channel `d`, then mix the result over the destination using the source alpha.
+/// Backends that can't blend a mode natively fall back to compositing on the CPU, which is much
+/// slower.
 #[non_exhaustive]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -99,6 +153,22 @@ pub enum BlendMode {
     Add,
     /// Color modulate.
     Mod,
+    /// Multiply colors: `s * d`. Always darkens.
+    Multiply,
+    /// Inverse multiply colors: `s + d - s * d`. Always lightens.
+    Screen,
+    /// Multiply dark destination colors and screen light ones, increasing contrast.
+    Overlay,
+    /// Keep the darker color: `min(s, d)`.
+    Darken,
+    /// Keep the lighter color: `max(s, d)`.
+    Lighten,
+    /// Absolute difference of colors: `|s - d|`.
+    Difference,
+    /// Like [`BlendMode::Difference`] with lower contrast: `s + d - 2 * s * d`.
+    Exclusion,
+    /// Subtract the source color from the destination: `d - s`.
+    Subtract,
 }
 
 /// Determines how angles are interpreted.
@@ -136,8 +206,15 @@ bitflags! {
 pub(crate) struct Settings {
     pub(crate) background: Color,
     pub(crate) fill: Option<Color>,
+    pub(crate) fill_paint: Option<Paint>,
     pub(crate) stroke: Option<Color>,
+    pub(crate) stroke_paint: Option<Paint>,
     pub(crate) stroke_weight: u16,
+    pub(crate) stroke_cap: StrokeCap,
+    pub(crate) stroke_join: StrokeJoin,
+    pub(crate) miter_limit: f64,
+    pub(crate) stroke_dash: Vec<f64>,
+    pub(crate) stroke_dash_offset: f64,
     pub(crate) font_size: u32,
     pub(crate) font_style: FontStyle,
     #[cfg_attr(feature = "serde", serde(skip))]
@@ -147,6 +224,10 @@ pub(crate) struct Settings {
     pub(crate) bezier_detail: i32,
     pub(crate) wrap_width: Option<u32>,
     pub(crate) clip: Option<Rect<i32>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) mask: Option<Rc<Mask>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) clip_stack: Vec<ClipRegion>,
     pub(crate) running: bool,
     pub(crate) show_frame_rate: bool,
     pub(crate) target_frame_rate: Option<usize>,
@@ -159,6 +240,7 @@ pub(crate) struct Settings {
     pub(crate) image_tint: Option<Color>,
     pub(crate) arc_mode: ArcMode,
     pub(crate) angle_mode: AngleMode,
+    pub(crate) transform: Transform,
     pub(crate) blend_mode: BlendMode,
     pub(crate) cursor: Option<Cursor>,
     pub(crate) disabled: bool,
@@ -169,8 +251,15 @@ impl Default for Settings {
         Self {
             background: Color::BLACK,
             fill: Some(Color::WHITE),
+            fill_paint: None,
             stroke: None,
+            stroke_paint: None,
             stroke_weight: 1,
+            stroke_cap: StrokeCap::Butt,
+            stroke_join: StrokeJoin::Miter,
+            miter_limit: 10.0,
+            stroke_dash: Vec::new(),
+            stroke_dash_offset: 0.0,
             font_size: 14,
             font_style: FontStyle::NORMAL,
             font_family: Font::default(),
@@ -179,6 +268,8 @@ impl Default for Settings {
             bezier_detail: 20,
             wrap_width: None,
             clip: None,
+            mask: None,
+            clip_stack: Vec::new(),
             running: true,
             show_frame_rate: false,
             target_frame_rate: None,
@@ -191,6 +282,7 @@ impl Default for Settings {
             image_tint: None,
             arc_mode: ArcMode::Default,
             angle_mode: AngleMode::Radians,
+            transform: Transform::identity(),
             blend_mode: BlendMode::None,
             cursor: Some(Cursor::default()),
             disabled: false,
@@ -247,6 +339,35 @@ impl PixState {
         C: Into<Option<Color>>,
     {
         self.settings.fill = color.into();
+        self.settings.fill_paint = None;
+    }
+
+    /// Sets the [Paint] used to fill shapes drawn on the canvas, such as a [Gradient]. Drawing
+    /// methods that don't support gradients, like [`PixState::text`], use the color of the first
+    /// gradient stop. Calling [`PixState::fill`] replaces the paint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let sky = Gradient::new(ColorMode::Rgb, [(0.0, Color::NAVY), (1.0, Color::SKY_BLUE)]);
+    ///     s.fill_paint(Paint::linear([0.0, 0.0], [0.0, 100.0], sky));
+    ///     s.rect([0, 0, 100, 100])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn fill_paint<P>(&mut self, paint: P)
+    where
+        P: Into<Paint>,
+    {
+        let paint = paint.into();
+        self.settings.fill = Some(paint.base_color());
+        self.settings.fill_paint = (!paint.is_solid()).then_some(paint);
     }
 
     /// Sets the [Color] value used to outline shapes drawn on the canvas. `None` disables stroke
@@ -273,9 +394,42 @@ impl PixState {
         C: Into<Option<Color>>,
     {
         self.settings.stroke = color.into();
+        self.settings.stroke_paint = None;
+    }
+
+    /// Sets the [Paint] used to outline shapes drawn on the canvas, such as a [Gradient].
+    /// Drawing methods that don't support gradients, like [`PixState::text`], use the color of
+    /// the first gradient stop. Calling [`PixState::stroke`] replaces the paint.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let rainbow = Gradient::new(
+    ///         ColorMode::Hsb,
+    ///         [(0.0, hsb!(0.0, 100.0, 100.0)), (1.0, hsb!(359.0, 100.0, 100.0))],
+    ///     );
+    ///     s.stroke_paint(Paint::conic([50.0, 50.0], 0.0, rainbow));
+    ///     s.stroke_weight(4);
+    ///     s.circle([50, 50, 40])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn stroke_paint<P>(&mut self, paint: P)
+    where
+        P: Into<Paint>,
+    {
+        let paint = paint.into();
+        self.settings.stroke = Some(paint.base_color());
+        self.settings.stroke_paint = (!paint.is_solid()).then_some(paint);
     }
 
-    /// Sets the width used to draw lines on the canvas.
+    /// Sets the width used to draw lines and shape outlines on the canvas.
     ///
     /// # Example
     ///
@@ -297,6 +451,99 @@ impl PixState {
         self.settings.stroke_weight = weight;
     }
 
+    /// Sets the [`StrokeCap`] used to draw the ends of lines, arcs and other open strokes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.stroke(Color::BLUE);
+    ///     s.stroke_weight(8);
+    ///     s.stroke_cap(StrokeCap::Round);
+    ///     s.line(line_![20, 20, 100, 20])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn stroke_cap(&mut self, cap: StrokeCap) {
+        self.settings.stroke_cap = cap;
+    }
+
+    /// Sets the [`StrokeJoin`] used to draw the corners of rectangles, polygons and other
+    /// multi-segment strokes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.stroke(Color::BLUE);
+    ///     s.stroke_weight(8);
+    ///     s.stroke_join(StrokeJoin::Round);
+    ///     s.polygon([[20, 80], [60, 20], [100, 80]])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn stroke_join(&mut self, join: StrokeJoin) {
+        self.settings.stroke_join = join;
+    }
+
+    /// Sets the limit on the ratio of a mitered corner's length to the stroke width. Sharper
+    /// corners exceeding the limit are drawn using [`StrokeJoin::Bevel`]. Defaults to `10.0`.
+    #[inline]
+    pub fn miter_limit(&mut self, limit: f64) {
+        self.settings.miter_limit = limit.max(1.0);
+    }
+
+    /// Sets a dash pattern of alternating dash and gap lengths used to draw strokes. An odd
+    /// number of lengths is repeated to make it even, and an empty pattern draws solid strokes.
+    /// Patterns containing negative or non-finite lengths, or that are entirely zero, are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.stroke(Color::WHITE);
+    ///     // 6 pixel dashes with 3 pixel gaps
+    ///     s.stroke_dash(&[6.0, 3.0]);
+    ///     s.rect([20, 20, 80, 40])?;
+    ///     // Back to solid strokes
+    ///     s.stroke_dash(&[]);
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn stroke_dash(&mut self, dash: &[f64]) {
+        let valid = dash.iter().all(|d| d.is_finite() && *d >= 0.0);
+        let s = &mut self.settings;
+        s.stroke_dash.clear();
+        if valid && dash.iter().any(|&d| d > 0.0) {
+            s.stroke_dash.extend_from_slice(dash);
+            if dash.len() % 2 == 1 {
+                s.stroke_dash.extend_from_slice(dash);
+            }
+        }
+    }
+
+    /// Sets the distance into the dash pattern set by [`PixState::stroke_dash`] at which
+    /// strokes start. Animating the offset makes dashes appear to move along the stroke.
+    #[inline]
+    pub fn stroke_dash_offset(&mut self, offset: f64) {
+        self.settings.stroke_dash_offset = offset;
+    }
+
     /// Set the font size for drawing to the current canvas.
     ///
     /// # Errors
@@ -481,8 +728,9 @@ impl PixState {
         self.settings.wrap_width = width.into();
     }
 
-    /// Sets the clip [Rect] used by the renderer to draw to the current canvas. `None` disables
-    /// clipping.
+    /// Sets the clip [Rect] used by the renderer to draw to the current canvas, replacing the
+    /// current clip rect. `None` disables clipping. Use [`PixState::push_clip`] to intersect with
+    /// the current clip region instead.
     ///
     /// # Errors
     ///
@@ -860,6 +1108,191 @@ impl PixState {
         self.renderer.scale(s.scale_x, s.scale_y)
     }
 
+    /// Translate the current [Transform] by `(x, y)`. All subsequent shapes, images, textures and
+    /// sprites are drawn relative to the new origin. Transforms accumulate until reset with
+    /// [`PixState::reset_transform`], restored with [`PixState::pop`] or reset at the start of the
+    /// next frame. Text is not transformed.
+    ///
+    /// Images are moved, rotated, scaled and mirrored along their own axes, but not sheared.
+    /// Images drawn without a destination fill the transformed render target.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.translate(50.0, 50.0);
+    ///     // Drawn at (60, 60)
+    ///     s.rect([10, 10, 20, 20])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn translate(&mut self, x: f64, y: f64) {
+        let s = &mut self.settings;
+        s.transform = s.transform.translate(x, y);
+    }
+
+    /// Rotate the current [Transform] clockwise by `angle` about the current origin. `angle` is
+    /// interpreted based on the current [`AngleMode`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.angle_mode(AngleMode::Degrees);
+    ///     s.push();
+    ///     s.translate(50.0, 50.0);
+    ///     s.rotate(45.0);
+    ///     s.rect_mode(RectMode::Center);
+    ///     // A diamond centered at (50, 50)
+    ///     s.square([0, 0, 20])?;
+    ///     s.pop();
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn rotate(&mut self, angle: f64) {
+        let angle = self.radians(angle);
+        let s = &mut self.settings;
+        s.transform = s.transform.rotate(angle);
+    }
+
+    /// Scale the current [Transform] by `(x, y)` about the current origin. Unlike
+    /// [`PixState::scale`], which scales the entire canvas, this only affects subsequent shapes and
+    /// images.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.scale_by(2.0, 2.0);
+    ///     // Drawn as a 40x40 rect at (20, 20)
+    ///     s.rect([10, 10, 20, 20])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn scale_by(&mut self, x: f64, y: f64) {
+        let s = &mut self.settings;
+        s.transform = s.transform.scale(x, y);
+    }
+
+    /// Shear the current [Transform] along the x-axis by `angle`. `angle` is interpreted based on
+    /// the current [`AngleMode`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.angle_mode(AngleMode::Degrees);
+    ///     s.shear_x(30.0);
+    ///     s.rect([10, 10, 20, 20])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn shear_x(&mut self, angle: f64) {
+        let angle = self.radians(angle);
+        let s = &mut self.settings;
+        s.transform = s.transform.shear_x(angle);
+    }
+
+    /// Shear the current [Transform] along the y-axis by `angle`. `angle` is interpreted based on
+    /// the current [`AngleMode`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.angle_mode(AngleMode::Degrees);
+    ///     s.shear_y(30.0);
+    ///     s.rect([10, 10, 20, 20])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn shear_y(&mut self, angle: f64) {
+        let angle = self.radians(angle);
+        let s = &mut self.settings;
+        s.transform = s.transform.shear_y(angle);
+    }
+
+    /// Multiply the current [Transform] by `transform`, applying it in local coordinates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     // Mirror horizontally about x = 100
+    ///     s.apply_transform(Transform::new(-1.0, 0.0, 0.0, 1.0, 200.0, 0.0));
+    ///     s.rect([10, 10, 20, 20])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn apply_transform(&mut self, transform: Transform) {
+        self.settings.transform *= transform;
+    }
+
+    /// Replace the current [Transform].
+    #[inline]
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.settings.transform = transform;
+    }
+
+    /// Reset the current [Transform] to the identity, drawing shapes without any translation,
+    /// rotation, scaling or shearing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     s.translate(50.0, 50.0);
+    ///     s.rect([0, 0, 20, 20])?;
+    ///     s.reset_transform();
+    ///     s.rect([0, 0, 20, 20])?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn reset_transform(&mut self) {
+        self.settings.transform = Transform::identity();
+    }
+
+    /// Return the current [Transform] used to draw shapes and images.
+    #[inline]
+    pub const fn transform(&self) -> Transform {
+        self.settings.transform
+    }
+
     /// Change the way parameters are interpreted for drawing [Square](Rect)s and
     /// [Rectangle](Rect)s.
     ///
@@ -994,7 +1427,8 @@ impl PixState {
         self.settings.angle_mode = mode;
     }
 
-    /// Change the way textures are blended together.
+    /// Change the way shapes, images and textures are blended with what's already drawn. Shapes
+    /// are always alpha blended with [`BlendMode::None`]. Text is always alpha blended.
     ///
     /// # Example
     ///
@@ -1006,6 +1440,10 @@ impl PixState {
     ///     s.blend_mode(BlendMode::Blend);
     ///     // Draw image with alpha blended with background
     ///     s.image(&Image::from_file("./some_image.png")?, [0, 0])?;
+    ///     // Darken the background with a red tint
+    ///     s.blend_mode(BlendMode::Multiply);
+    ///     s.fill(Color::RED);
+    ///     s.rect([0, 0, 100, 100])?;
     ///     Ok(())
     /// }
     /// # }
@@ -1082,6 +1520,7 @@ impl PixState {
         // All of these settings should be valid since they were set prior to `pop()` being
         // called.
         let _ = self.renderer.clip(s.clip);
+        self.renderer.mask(s.mask.clone());
         // Excluding restoring cursor - as it's used for mouse hover.
         let _ = self.renderer.font_size(s.font_size);
         self.renderer.font_style(s.font_style);
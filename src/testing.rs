@@ -0,0 +1,348 @@
+//! Visual regression testing utilities.
+//!
+//! [`Harness`] runs a [`PixEngine`] application using a [headless](EngineBuilder::headless)
+//! [`Engine`] for a fixed number of frames on a [deterministic
+//! clock](EngineBuilder::deterministic_clock) and captures the rendered canvas as an [Image].
+//! Input can be scripted with [`Harness::event`] or by replaying an [`EventRecording`] with
+//! [`Harness::replay`]. Captured frames can be compared against a stored golden [png] image with a per-channel
+//! tolerance using [`Harness::assert_golden`] or [`compare`].
+//!
+//! When a golden image doesn't match, the captured frame and an image highlighting mismatched
+//! pixels in red are written next to the golden image as `<name>.actual.png` and
+//! `<name>.diff.png` respectively. Missing golden images result in an error unless the
+//! `PIX_ENGINE_UPDATE_GOLDEN` environment variable is set, in which case golden images are
+//! created or overwritten with the captured frame.
+//!
+//! # Example
+//!
+//! ```
+//! use pix_engine::{prelude::*, testing::Harness};
+//!
+//! struct MyApp;
+//!
+//! impl PixEngine for MyApp {
+//!     fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!         s.background(Color::BLACK);
+//!         s.fill(Color::RED);
+//!         s.rect([4, 4, 8, 8])?;
+//!         Ok(())
+//!     }
+//! }
+//!
+//! # fn main() -> PixResult<()> {
+//! let frame = Harness::new(16, 16).frames(2).capture(&mut MyApp)?;
+//! assert_eq!(frame.get_pixel(8, 8), Color::RED);
+//! // Compare with a stored golden image:
+//! // Harness::new(16, 16).assert_golden(&mut MyApp, "tests/golden/my_app.png")?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [png]: https://en.wikipedia.org/wiki/Portable_Network_Graphics
+
+use crate::{engine::EngineBuilder, prelude::*};
+use anyhow::{anyhow, Context};
+use std::{env, fs, path::Path, time::Duration};
+
+/// Environment variable which, when set, creates or overwrites golden images with captured frames.
+pub const UPDATE_GOLDEN_ENV: &str = "PIX_ENGINE_UPDATE_GOLDEN";
+
+/// Default simulated time between frames, equivalent to `60` frames per second.
+const DEFAULT_DELTA_TIME: Duration = Duration::from_nanos(16_666_667);
+
+/// Color used for mismatched pixels in a diff image.
+const DIFF_COLOR: [u8; 4] = [255, 0, 0, 255];
+
+/// Runs an application headless for a fixed number of frames and captures the result.
+///
+/// See the [module-level documentation](crate::testing) for an example.
+#[must_use]
+#[derive(Debug)]
+pub struct Harness {
+    engine: EngineBuilder,
+    frames: usize,
+    tolerance: u8,
+    events: EventRecording,
+}
+
+impl Harness {
+    /// Constructs a `Harness` rendering to a canvas with the given dimensions. Defaults to running
+    /// `1` frame at a simulated `60` frames per second with no tolerance for pixel differences.
+    pub fn new(width: u32, height: u32) -> Self {
+        let mut engine = Engine::builder();
+        engine
+            .title("pix-engine test harness")
+            .dimensions(width, height)
+            .headless()
+            .deterministic_clock(DEFAULT_DELTA_TIME);
+        Self {
+            engine,
+            frames: 1,
+            tolerance: 0,
+            events: EventRecording::new(),
+        }
+    }
+
+    /// Set the number of frames to render before capturing the canvas. Setting `0` captures
+    /// the canvas after [`PixEngine::on_start`].
+    pub fn frames(&mut self, frames: usize) -> &mut Self {
+        self.frames = frames;
+        self
+    }
+
+    /// Set the simulated time elapsed between each frame.
+    pub fn delta_time(&mut self, delta_time: Duration) -> &mut Self {
+        self.engine.deterministic_clock(delta_time);
+        self
+    }
+
+    /// Set the maximum difference allowed per color channel before a pixel is considered
+    /// mismatched.
+    pub fn tolerance(&mut self, tolerance: u8) -> &mut Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Queue an [Event] to be handled on a given `frame`. Events for frame `0` are handled before
+    /// the first call to [`PixEngine::on_update`], matching the frames of an [`EventRecording`]
+    /// started in [`PixEngine::on_start`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::{prelude::*, testing::Harness};
+    /// # #[derive(Default)] struct App { clicked: bool }
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     if s.button("Click")? {
+    ///         self.clicked = true;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// # fn main() -> PixResult<()> {
+    /// let mut app = App::default();
+    /// Harness::new(100, 50)
+    ///     .event(0, Event::MouseMotion { x: 20, y: 20, xrel: 0, yrel: 0 })
+    ///     .event(1, Event::MouseDown { button: Mouse::Left, x: 20, y: 20 })
+    ///     .event(2, Event::MouseUp { button: Mouse::Left, x: 20, y: 20 })
+    ///     .frames(3)
+    ///     .capture(&mut app)?;
+    /// assert!(app.clicked);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn event(&mut self, frame: usize, event: Event) -> &mut Self {
+        self.events.push(frame, event);
+        self
+    }
+
+    /// Replay all events from an [`EventRecording`], in addition to any events added with
+    /// [`Harness::event`]. Frames are numbered the same as [`Harness::event`].
+    pub fn replay(&mut self, recording: EventRecording) -> &mut Self {
+        for recorded in recording {
+            self.events.push(recorded.frame, recorded.event);
+        }
+        self
+    }
+
+    /// Returns the [`EngineBuilder`] used to build the [`Engine`], allowing customization of
+    /// settings such as theme, font or scale.
+    pub fn engine(&mut self) -> &mut EngineBuilder {
+        &mut self.engine
+    }
+
+    /// Run `app` for the configured number of frames and return the rendered canvas as an
+    /// `RGBA` [Image].
+    ///
+    /// # Errors
+    ///
+    /// If the engine fails to build, the application returns an error, or the canvas fails to be
+    /// read, then an error is returned.
+    pub fn capture<A>(&self, app: &mut A) -> PixResult<Image>
+    where
+        A: PixEngine,
+    {
+        let mut engine = self.engine.build()?;
+        // Replay starts after the events polled prior to `PixEngine::on_start`
+        let mut events = EventRecording::new();
+        for recorded in self.events.events() {
+            events.push(recorded.frame + 1, recorded.event.clone());
+        }
+        engine.state.replay_events(events);
+        engine.run_frames(app, self.frames)
+    }
+
+    /// Run `app` for the configured number of frames and compare the rendered canvas against the
+    /// golden [png] image at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if capturing the canvas fails, if the golden image is missing or can't be
+    /// read, or if any pixels differ by more than the configured tolerance. See the
+    /// [module-level documentation](crate::testing) for details on updating golden images.
+    ///
+    /// [png]: https://en.wikipedia.org/wiki/Portable_Network_Graphics
+    pub fn assert_golden<A, P>(&self, app: &mut A, path: P) -> PixResult<()>
+    where
+        A: PixEngine,
+        P: AsRef<Path>,
+    {
+        let actual = self.capture(app)?;
+        assert_golden(&actual, path, self.tolerance)
+    }
+}
+
+/// The result of comparing two images with [`compare`].
+#[must_use]
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    mismatched: usize,
+    max_difference: u8,
+    diff: Image,
+}
+
+impl Comparison {
+    /// Whether all pixels are within tolerance.
+    #[inline]
+    #[must_use]
+    pub const fn is_match(&self) -> bool {
+        self.mismatched == 0
+    }
+
+    /// The number of pixels with a channel difference greater than the tolerance.
+    #[inline]
+    #[must_use]
+    pub const fn mismatched(&self) -> usize {
+        self.mismatched
+    }
+
+    /// The largest difference found in any color channel.
+    #[inline]
+    #[must_use]
+    pub const fn max_difference(&self) -> u8 {
+        self.max_difference
+    }
+
+    /// An `RGBA` [Image] showing mismatched pixels in red over a faded copy of the expected image.
+    #[inline]
+    pub const fn diff(&self) -> &Image {
+        &self.diff
+    }
+}
+
+/// Compare two images, counting pixels where any color channel differs by more than `tolerance`.
+///
+/// Images with different [`PixelFormat`]s are compared as `RGBA`, with `RGB` pixels treated as
+/// opaque.
+///
+/// # Errors
+///
+/// If the image dimensions differ, then an error is returned.
+///
+/// # Example
+///
+/// ```
+/// # use pix_engine::{prelude::*, testing::compare};
+/// # fn main() -> PixResult<()> {
+/// let expected = Image::from_pixels(1, 1, [Color::rgb(100, 100, 100)], PixelFormat::Rgb)?;
+/// let actual = Image::from_pixels(1, 1, [Color::rgb(102, 100, 100)], PixelFormat::Rgba)?;
+/// assert!(!compare(&actual, &expected, 0)?.is_match());
+/// assert!(compare(&actual, &expected, 2)?.is_match());
+/// # Ok(())
+/// # }
+/// ```
+pub fn compare(actual: &Image, expected: &Image, tolerance: u8) -> PixResult<Comparison> {
+    if actual.dimensions() != expected.dimensions() {
+        return Err(anyhow!(
+            "image dimensions differ: actual {:?}, expected {:?}",
+            actual.dimensions(),
+            expected.dimensions()
+        ));
+    }
+
+    let (width, height) = expected.dimensions();
+    let mut mismatched = 0;
+    let mut max_difference = 0;
+    let mut diff = Vec::with_capacity(4 * width as usize * height as usize);
+    for (a, e) in actual.pixels().zip(expected.pixels()) {
+        let difference = a
+            .channels()
+            .iter()
+            .zip(e.channels())
+            .map(|(a, e)| a.abs_diff(e))
+            .max()
+            .unwrap_or_default();
+        max_difference = max_difference.max(difference);
+        if difference > tolerance {
+            mismatched += 1;
+            diff.extend(DIFF_COLOR);
+        } else {
+            let [r, g, b, _] = e.channels();
+            let luma = (u16::from(r) * 77 + u16::from(g) * 150 + u16::from(b) * 29) >> 8;
+            // Fade matching pixels so mismatches stand out
+            let faded = (luma / 4) as u8;
+            diff.extend([faded, faded, faded, 255]);
+        }
+    }
+
+    Ok(Comparison {
+        mismatched,
+        max_difference,
+        diff: Image::from_vec(width, height, diff, PixelFormat::Rgba),
+    })
+}
+
+/// Compare `actual` against the golden [png] image at `path`, writing the actual and diff images
+/// next to the golden image on mismatch.
+///
+/// # Errors
+///
+/// Returns an error if the golden image is missing or can't be read, or if any pixels differ by
+/// more than `tolerance`. See the [module-level documentation](crate::testing) for details on
+/// updating golden images.
+///
+/// [png]: https://en.wikipedia.org/wiki/Portable_Network_Graphics
+pub fn assert_golden<P>(actual: &Image, path: P, tolerance: u8) -> PixResult<()>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    if env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        return actual.save(path);
+    }
+    if !path.exists() {
+        return Err(anyhow!(
+            "golden image {path:?} not found, set `{UPDATE_GOLDEN_ENV}` to create it"
+        ));
+    }
+
+    let expected =
+        Image::from_file(path).with_context(|| format!("failed to read golden image {path:?}"))?;
+    let actual_path = path.with_extension("actual.png");
+    let diff_path = path.with_extension("diff.png");
+    let comparison = match compare(actual, &expected, tolerance) {
+        Ok(comparison) => comparison,
+        Err(err) => {
+            actual.save(&actual_path)?;
+            return Err(err.context(format!("golden image {path:?} mismatch")));
+        }
+    };
+    if comparison.is_match() {
+        // Clean up output from any previous mismatches
+        let _ignore_result = fs::remove_file(&actual_path);
+        let _ignore_result = fs::remove_file(&diff_path);
+        return Ok(());
+    }
+
+    actual.save(&actual_path)?;
+    comparison.diff().save(&diff_path)?;
+    Err(anyhow!(
+        "golden image {path:?} mismatch: {} pixels differ by more than {tolerance} (max difference: {}), see {actual_path:?} and {diff_path:?}",
+        comparison.mismatched(),
+        comparison.max_difference(),
+    ))
+}
@@ -13,6 +13,8 @@
 //! - [`PixState::set_texture_target`]: Target a texture for rendering.
 //! - [`PixState::clear_texture_target`]: Clear texture target back to primary canvas for rendering.
 //!
+//! See the [atlas] module for packing many images into shared textures.
+//!
 //! # Example
 //!
 //! ```
@@ -48,11 +50,14 @@
 //! ```
 
 use crate::prelude::*;
+use anyhow::anyhow;
 use std::{
     fmt,
     ops::{Deref, DerefMut},
 };
 
+pub mod atlas;
+
 /// `Texture` identifier used to reference and target an internally managed texture.
 #[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[must_use]
@@ -124,8 +129,9 @@ impl PixState {
         R1: Into<Option<Rect<i32>>>,
         R2: Into<Option<Rect<i32>>>,
     {
+        let (dst, angle, center, flipped) = self.transform_image(dst.into(), 0.0, None, None)?;
         self.renderer
-            .texture(texture_id, src.into(), dst.into(), 0.0, None, None, None)
+            .texture(texture_id, src.into(), dst, angle, center, flipped, None)
     }
 
     /// Draw a transformed portion `src` of a texture to the current render target translated and
@@ -196,18 +202,19 @@ impl PixState {
         C: Into<Option<Point<i32>>>,
         F: Into<Option<Flipped>>,
     {
-        let s = &self.settings;
-        if s.angle_mode == AngleMode::Radians {
+        if self.settings.angle_mode == AngleMode::Radians {
             angle = angle.to_degrees();
         };
+        let (dst, angle, center, flipped) =
+            self.transform_image(dst.into(), angle, center.into(), flipped.into())?;
         self.renderer.texture(
             texture_id,
             src.into(),
-            dst.into(),
+            dst,
             angle,
-            center.into(),
-            flipped.into(),
-            s.image_tint,
+            center,
+            flipped,
+            self.settings.image_tint,
         )
     }
 
@@ -218,8 +225,8 @@ impl PixState {
     ///
     /// # Errors
     ///
-    /// If the current window target is closed or invalid, or the texture dimensions are invalid,
-    /// then an error is returned.
+    /// If the current window target is closed or invalid, the texture dimensions are invalid, or
+    /// `format` is a grayscale [`PixelFormat`], then an error is returned.
     ///
     /// # Note
     ///
@@ -255,7 +262,11 @@ impl PixState {
     where
         F: Into<Option<PixelFormat>>,
     {
-        self.renderer.create_texture(width, height, format.into())
+        let format = format.into();
+        if let Some(format) = format.filter(PixelFormat::is_gray) {
+            return Err(anyhow!("unsupported texture format: {format:?}"));
+        }
+        self.renderer.create_texture(width, height, format)
     }
 
     /// Delete a `Texture`.
@@ -336,7 +347,8 @@ impl PixState {
 
     /// Set a `Texture` as the priamry target for drawing operations. Pushes current settings and UI
     /// cursor to the stack, so any changes made while a texture target is set will be in effect
-    /// until [`PixState::clear_texture_target`] is called.
+    /// until [`PixState::clear_texture_target`] is called. Drawing to the texture starts with no
+    /// clip region.
     ///
     /// # Errors
     ///
@@ -364,7 +376,8 @@ impl PixState {
             self.push();
             self.ui.push_cursor();
             self.set_cursor_pos(self.theme.spacing.frame_pad);
-            self.renderer.set_texture_target(id)
+            self.renderer.set_texture_target(id)?;
+            self.reset_clip()
         } else {
             Ok(())
         }
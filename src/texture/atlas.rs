@@ -0,0 +1,407 @@
+//! [`TextureAtlas`] methods for packing many [Image]s into a few large textures.
+//!
+//! Drawing many small images with [`PixState::image`] creates a separate texture for each one,
+//! requiring the renderer to switch textures between draws. A `TextureAtlas` instead packs images
+//! into shared texture pages using a skyline packer, returning an [`AtlasRegion`] for each image
+//! that can be drawn with [`PixState::texture`] or [`PixState::texture_transformed`]. New pages
+//! are created as needed when an image doesn't fit in existing ones.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::pack_image`]: Pack an [Image] into a [`TextureAtlas`].
+//! - [`PixState::pack_images`]: Pack a list of [Image]s into a [`TextureAtlas`].
+//! - [`PixState::delete_texture_atlas`]: Delete the textures of a [`TextureAtlas`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use pix_engine::prelude::*;
+//! # struct App { atlas: TextureAtlas, tiles: Vec<AtlasRegion> };
+//! # impl PixEngine for App {
+//! fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     let images = ["grass.png", "water.png", "sand.png"]
+//!         .into_iter()
+//!         .map(Image::from_file)
+//!         .collect::<PixResult<Vec<_>>>()?;
+//!     self.atlas = TextureAtlas::new(1024, 1024);
+//!     self.tiles = s.pack_images(&mut self.atlas, &images)?;
+//!     Ok(())
+//! }
+//!
+//! fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     for (i, tile) in self.tiles.iter().enumerate() {
+//!         let dst = rect![i as i32 * 32, 0, 32, 32];
+//!         s.texture(tile.texture_id(), tile.rect(), dst)?;
+//!     }
+//!     Ok(())
+//! }
+//! # }
+//! ```
+
+use crate::{prelude::*, texture::TextureRenderer};
+use anyhow::anyhow;
+
+/// Default space between packed images, preventing neighboring pixels from bleeding into each
+/// other when drawn scaled.
+const DEFAULT_PADDING: u32 = 1;
+
+/// The location of an [Image] packed into a [`TextureAtlas`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[must_use]
+pub struct AtlasRegion {
+    texture_id: TextureId,
+    rect: Rect<i32>,
+}
+
+impl AtlasRegion {
+    /// Returns the texture page the [Image] was packed into.
+    #[inline]
+    pub const fn texture_id(&self) -> TextureId {
+        self.texture_id
+    }
+
+    /// Returns the area of the texture page containing the [Image].
+    #[inline]
+    pub const fn rect(&self) -> Rect<i32> {
+        self.rect
+    }
+}
+
+/// A horizontal segment of the top edge of packed images.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Segment {
+    x: u32,
+    y: u32,
+    width: u32,
+}
+
+/// A bottom-left skyline rectangle packer.
+#[derive(Debug, Clone)]
+struct Skyline {
+    width: u32,
+    height: u32,
+    segments: Vec<Segment>,
+}
+
+impl Skyline {
+    /// Constructs an empty `Skyline` of the given dimensions.
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            segments: vec![Segment { x: 0, y: 0, width }],
+        }
+    }
+
+    /// Returns the top edge of a `width` by `height` rectangle placed at the start of the segment
+    /// at `index`, if it fits.
+    fn fit(&self, index: usize, width: u32, height: u32) -> Option<u32> {
+        let x = self.segments[index].x;
+        if x + width > self.width {
+            return None;
+        }
+        let mut y = 0;
+        let mut remaining = width;
+        for segment in &self.segments[index..] {
+            if remaining == 0 {
+                break;
+            }
+            y = y.max(segment.y);
+            remaining = remaining.saturating_sub(segment.width);
+        }
+        (y + height <= self.height).then_some(y)
+    }
+
+    /// Place a `width` by `height` rectangle, returning its top-left position, or `None` if it
+    /// doesn't fit. Prefers the lowest position, breaking ties by the narrowest segment.
+    fn pack(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let (index, y) = (0..self.segments.len())
+            .filter_map(|index| Some((index, self.fit(index, width, height)?)))
+            .min_by_key(|&(index, y)| (y + height, self.segments[index].width))?;
+        let x = self.segments[index].x;
+        self.segments.insert(
+            index,
+            Segment {
+                x,
+                y: y + height,
+                width,
+            },
+        );
+
+        // Trim segments now covered by the new one
+        let end = x + width;
+        let next = index + 1;
+        while let Some(segment) = self.segments.get_mut(next) {
+            if segment.x >= end {
+                break;
+            }
+            let overlap = end - segment.x;
+            if segment.width <= overlap {
+                self.segments.remove(next);
+            } else {
+                segment.x += overlap;
+                segment.width -= overlap;
+                break;
+            }
+        }
+        self.segments.dedup_by(|segment, previous| {
+            let merge = segment.y == previous.y;
+            if merge {
+                previous.width += segment.width;
+            }
+            merge
+        });
+        Some((x, y))
+    }
+}
+
+/// A texture page and the space left in it.
+#[derive(Debug, Clone)]
+struct Page {
+    texture_id: TextureId,
+    skyline: Skyline,
+}
+
+/// Packs [Image]s into shared texture pages. See the [module-level documentation](self) for more
+/// details.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct TextureAtlas {
+    width: u32,
+    height: u32,
+    padding: u32,
+    pages: Vec<Page>,
+}
+
+impl Default for TextureAtlas {
+    fn default() -> Self {
+        Self::new(1024, 1024)
+    }
+}
+
+impl TextureAtlas {
+    /// Constructs an empty `TextureAtlas` with pages of the given dimensions. Textures aren't
+    /// created until an [Image] is packed.
+    #[inline]
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            padding: DEFAULT_PADDING,
+            pages: Vec::new(),
+        }
+    }
+
+    /// Returns the dimensions of each texture page as `(width, height)`.
+    #[inline]
+    #[must_use]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the space in pixels left between packed images. Defaults to `1`.
+    #[inline]
+    #[must_use]
+    pub const fn padding(&self) -> u32 {
+        self.padding
+    }
+
+    /// Set the space in pixels left between images packed from now on. Padding prevents pixels of
+    /// neighboring images from bleeding in when regions are drawn scaled or rotated.
+    #[inline]
+    pub fn set_padding(&mut self, padding: u32) {
+        self.padding = padding;
+    }
+
+    /// Returns the texture pages created so far.
+    pub fn textures(&self) -> impl Iterator<Item = TextureId> + '_ {
+        self.pages.iter().map(|page| page.texture_id)
+    }
+}
+
+impl PixState {
+    /// Pack an [Image] into a [`TextureAtlas`], creating a new texture page if it doesn't fit in
+    /// any existing page. Returns the [`AtlasRegion`] to draw it with.
+    ///
+    /// # Errors
+    ///
+    /// If the image is larger than the atlas page size, the current window target is closed or
+    /// invalid, or the renderer fails to create or update a texture, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { atlas: TextureAtlas, player: AtlasRegion };
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let image = Image::rgba(16, 16);
+    ///     self.player = s.pack_image(&mut self.atlas, &image)?;
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn pack_image(
+        &mut self,
+        atlas: &mut TextureAtlas,
+        image: &Image,
+    ) -> PixResult<AtlasRegion> {
+        let (width, height) = image.dimensions();
+        if width > atlas.width || height > atlas.height {
+            return Err(anyhow!(
+                "image size {width}x{height} exceeds texture atlas size {}x{}",
+                atlas.width,
+                atlas.height
+            ));
+        }
+        // Padding is only needed between images, so it may be dropped at the page edges
+        let padded = |size: u32, max: u32| (size + atlas.padding).min(max);
+        let (packed_width, packed_height) =
+            (padded(width, atlas.width), padded(height, atlas.height));
+
+        let placed = atlas.pages.iter_mut().find_map(|page| {
+            let (x, y) = page.skyline.pack(packed_width, packed_height)?;
+            Some((page.texture_id, x, y))
+        });
+        let (texture_id, x, y) = match placed {
+            Some(placed) => placed,
+            None => {
+                let texture_id =
+                    self.create_texture(atlas.width, atlas.height, PixelFormat::Rgba)?;
+                // Textures may start with undefined contents
+                let blank = vec![0x00; 4 * atlas.width as usize * atlas.height as usize];
+                self.renderer
+                    .update_texture(texture_id, None, blank, 4 * atlas.width as usize)?;
+                let mut skyline = Skyline::new(atlas.width, atlas.height);
+                let (x, y) = skyline
+                    .pack(packed_width, packed_height)
+                    .ok_or_else(|| anyhow!("failed to pack image into empty texture atlas page"))?;
+                atlas.pages.push(Page {
+                    texture_id,
+                    skyline,
+                });
+                (texture_id, x, y)
+            }
+        };
+
+        let rect = rect![x as i32, y as i32, width as i32, height as i32];
+        let converted;
+        let image = if image.format() == PixelFormat::Rgba {
+            image
+        } else {
+            converted = image.convert(PixelFormat::Rgba);
+            &converted
+        };
+        self.renderer
+            .update_texture(texture_id, Some(rect), image.as_bytes(), image.pitch())?;
+        Ok(AtlasRegion { texture_id, rect })
+    }
+
+    /// Pack a list of [Image]s into a [`TextureAtlas`], returning an [`AtlasRegion`] for each in
+    /// the same order. Images are packed tallest first, which fills pages more tightly than
+    /// packing them one at a time with [`PixState::pack_image`].
+    ///
+    /// # Errors
+    ///
+    /// If any image is larger than the atlas page size, the current window target is closed or
+    /// invalid, or the renderer fails to create or update a texture, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { atlas: TextureAtlas, regions: Vec<AtlasRegion> };
+    /// # impl PixEngine for App {
+    /// # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+    /// fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let images = [Image::rgba(8, 8), Image::rgba(16, 32)];
+    ///     self.regions = s.pack_images(&mut self.atlas, &images)?;
+    ///     assert_eq!(self.regions[1].rect().height(), 32);
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn pack_images(
+        &mut self,
+        atlas: &mut TextureAtlas,
+        images: &[Image],
+    ) -> PixResult<Vec<AtlasRegion>> {
+        let mut order: Vec<_> = (0..images.len()).collect();
+        order.sort_by_key(|&index| {
+            let (width, height) = images[index].dimensions();
+            (std::cmp::Reverse(height), std::cmp::Reverse(width))
+        });
+        let mut regions = vec![AtlasRegion::default(); images.len()];
+        for index in order {
+            regions[index] = self.pack_image(atlas, &images[index])?;
+        }
+        Ok(regions)
+    }
+
+    /// Delete the texture pages of a [`TextureAtlas`], invalidating every [`AtlasRegion`] packed
+    /// into it.
+    ///
+    /// # Errors
+    ///
+    /// If the current window target is closed or invalid, or a texture page has already been
+    /// deleted, then an error is returned.
+    pub fn delete_texture_atlas(&mut self, atlas: TextureAtlas) -> PixResult<()> {
+        for page in atlas.pages {
+            self.delete_texture(page.texture_id)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(a: (u32, u32, u32, u32), b: (u32, u32, u32, u32)) -> bool {
+        a.0 < b.0 + b.2 && b.0 < a.0 + a.2 && a.1 < b.1 + b.3 && b.1 < a.1 + a.3
+    }
+
+    #[test]
+    fn test_skyline_pack() {
+        let mut skyline = Skyline::new(10, 10);
+        assert_eq!(skyline.pack(4, 5), Some((0, 0)));
+        assert_eq!(skyline.pack(6, 3), Some((4, 0)));
+        // Lowest position is on top of the shorter rectangle
+        assert_eq!(skyline.pack(6, 2), Some((4, 3)));
+        assert_eq!(skyline.pack(10, 5), Some((0, 5)));
+        assert_eq!(skyline.pack(1, 1), None);
+        assert_eq!(
+            skyline.segments,
+            [Segment {
+                x: 0,
+                y: 10,
+                width: 10
+            }]
+        );
+        assert_eq!(Skyline::new(10, 10).pack(11, 1), None);
+    }
+
+    #[test]
+    fn test_skyline_no_overlap() {
+        let mut skyline = Skyline::new(64, 64);
+        let mut placed: Vec<(u32, u32, u32, u32)> = Vec::new();
+        for i in 0..200 {
+            let (width, height) = (1 + i * 7 % 13, 1 + i * 5 % 11);
+            if let Some((x, y)) = skyline.pack(width, height) {
+                let rect = (x, y, width, height);
+                assert!(
+                    x + width <= 64 && y + height <= 64,
+                    "{rect:?} out of bounds"
+                );
+                assert!(
+                    placed.iter().all(|&other| !overlaps(rect, other)),
+                    "{rect:?} overlaps"
+                );
+                placed.push(rect);
+            }
+        }
+        let area: u32 = placed.iter().map(|&(_, _, w, h)| w * h).sum();
+        assert!(area > 64 * 64 / 2, "packed area {area} is too sparse");
+    }
+}
@@ -0,0 +1,1099 @@
+//! [`Tilemap`] functions for drawing and querying layered grids of tiles.
+//!
+//! A `Tilemap` is a grid of equally sized cells with one or more [`TileLayer`]s drawn in order.
+//! Each [Tile] refers to a sprite of a [`Tileset`], which is a [`SpriteSheet`] backed by an
+//! [Image] or texture along with optional per-tile flags and collision masks. Only tiles
+//! overlapping the given view are drawn, so maps can be much larger than the canvas.
+//!
+//! Maps exported from the [Tiled](https://www.mapeditor.org/) editor in its `JSON` format can be
+//! loaded with `Tilemap::from_tiled` when the `serde` feature is enabled.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::tilemap`]: Draw every visible layer of a [`Tilemap`].
+//! - [`PixState::tilemap_layer`]: Draw a single layer of a [`Tilemap`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use pix_engine::prelude::*;
+//! # struct App { map: Tilemap, camera: Camera2D };
+//! # impl PixEngine for App {
+//! fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     let image = Image::from_file("./tiles.png")?;
+//!     let tileset = Tileset::new(SpriteSheet::from_grid(image, 16, 16, 8, 8)?);
+//!     self.map = Tilemap::new(100, 100, 16, 16)?;
+//!     self.map.add_tileset(tileset);
+//!     let ground = self.map.add_layer("ground");
+//!     for x in 0..100 {
+//!         self.map.layer_mut(ground)?.set_tile(x, 99, Tile::new(0, 1))?;
+//!     }
+//!     let (width, height) = s.dimensions()?;
+//!     self.camera = Camera2D::new(width, height);
+//!     self.camera.set_bounds(self.map.bounds().as_());
+//!     Ok(())
+//! }
+//!
+//! fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+//!     s.background(Color::BLACK);
+//!     s.begin_camera(&self.camera);
+//!     s.tilemap(&self.map, self.camera.view())?;
+//!     s.end_camera();
+//!     Ok(())
+//! }
+//! # }
+//! ```
+
+use crate::prelude::*;
+use anyhow::anyhow;
+#[cfg(feature = "serde")]
+use anyhow::Context;
+use std::collections::HashMap;
+#[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+use std::{fs, path::Path};
+
+/// A cell of a [`TileLayer`], referring to a sprite of a [`Tileset`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Tile {
+    /// Index of the [`Tileset`] in the [`Tilemap`].
+    pub tileset: usize,
+    /// Index of the sprite in the [`Tileset`].
+    pub id: usize,
+    /// How the tile is flipped when drawn.
+    pub flipped: Option<Flipped>,
+    /// Whether the tile is flipped diagonally, swapping its `x` and `y` axes, before applying
+    /// `flipped`. Only square tiles are drawn correctly when transposed.
+    pub transposed: bool,
+}
+
+impl Tile {
+    /// Constructs an unflipped `Tile` for the sprite `id` in `tileset`.
+    #[inline]
+    #[must_use]
+    pub const fn new(tileset: usize, id: usize) -> Self {
+        Self {
+            tileset,
+            id,
+            flipped: None,
+            transposed: false,
+        }
+    }
+}
+
+/// A [`SpriteSheet`] of tiles with optional per-tile flags and collision masks.
+#[derive(Default, Debug, Clone)]
+#[must_use]
+pub struct Tileset {
+    sheet: SpriteSheet,
+    tile_size: (u32, u32),
+    flags: HashMap<usize, u32>,
+    collision_masks: HashMap<usize, u32>,
+}
+
+impl Tileset {
+    /// Constructs a `Tileset` from the sprites of a [`SpriteSheet`].
+    pub fn new(sheet: SpriteSheet) -> Self {
+        let tile_size = sheet
+            .sprites()
+            .iter()
+            .fold((0, 0), |(width, height), sprite| {
+                let rect = sprite.rect();
+                (
+                    width.max(rect.width() as u32),
+                    height.max(rect.height() as u32),
+                )
+            });
+        Self {
+            sheet,
+            tile_size,
+            flags: HashMap::new(),
+            collision_masks: HashMap::new(),
+        }
+    }
+
+    /// Returns the [`SpriteSheet`] tiles are drawn from.
+    #[inline]
+    pub const fn sheet(&self) -> &SpriteSheet {
+        &self.sheet
+    }
+
+    /// Returns the user-defined flags of tile `id`. Defaults to `0`.
+    #[inline]
+    #[must_use]
+    pub fn flags(&self, id: usize) -> u32 {
+        self.flags.get(&id).copied().unwrap_or_default()
+    }
+
+    /// Set user-defined flags for tile `id`, e.g. to mark tiles as water or ladders.
+    #[inline]
+    pub fn set_flags(&mut self, id: usize, flags: u32) {
+        self.flags.insert(id, flags);
+    }
+
+    /// Returns the collision mask of tile `id`. Defaults to `0`, which never collides.
+    #[inline]
+    #[must_use]
+    pub fn collision_mask(&self, id: usize) -> u32 {
+        self.collision_masks.get(&id).copied().unwrap_or_default()
+    }
+
+    /// Set the collision mask of tile `id`. Each bit is a separate collision layer checked by
+    /// [`Tilemap::collides`].
+    #[inline]
+    pub fn set_collision_mask(&mut self, id: usize, mask: u32) {
+        self.collision_masks.insert(id, mask);
+    }
+}
+
+/// A named grid of [Tile]s the size of its [`Tilemap`].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct TileLayer {
+    name: String,
+    width: u32,
+    height: u32,
+    tiles: Vec<Option<Tile>>,
+    visible: bool,
+}
+
+impl TileLayer {
+    /// Constructs an empty, visible `TileLayer`.
+    fn new(name: String, width: u32, height: u32) -> Self {
+        Self {
+            name,
+            width,
+            height,
+            tiles: vec![None; width as usize * height as usize],
+            visible: true,
+        }
+    }
+
+    /// Returns the layer name.
+    #[inline]
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether the layer is drawn by [`PixState::tilemap`]. Hidden layers still count for
+    /// collisions.
+    #[inline]
+    #[must_use]
+    pub const fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Set whether the layer is drawn by [`PixState::tilemap`].
+    #[inline]
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Returns the [Tile] at column `x` and row `y`, if any.
+    #[inline]
+    #[must_use]
+    pub fn tile(&self, x: u32, y: u32) -> Option<Tile> {
+        if x < self.width && y < self.height {
+            self.tiles[(y * self.width + x) as usize]
+        } else {
+            None
+        }
+    }
+
+    /// Set or clear the [Tile] at column `x` and row `y`.
+    ///
+    /// # Errors
+    ///
+    /// If `x` or `y` are outside the layer, then an error is returned.
+    pub fn set_tile<T>(&mut self, x: u32, y: u32, tile: T) -> PixResult<()>
+    where
+        T: Into<Option<Tile>>,
+    {
+        if x >= self.width || y >= self.height {
+            return Err(anyhow!(
+                "tile ({x}, {y}) is outside of {}x{} layer {:?}",
+                self.width,
+                self.height,
+                self.name
+            ));
+        }
+        self.tiles[(y * self.width + x) as usize] = tile.into();
+        Ok(())
+    }
+
+    /// Returns an iterator of every [Tile] along with its column and row.
+    pub fn tiles(&self) -> impl Iterator<Item = (u32, u32, Tile)> + '_ {
+        let width = self.width as usize;
+        self.tiles.iter().enumerate().filter_map(move |(i, tile)| {
+            tile.map(|tile| ((i % width) as u32, (i / width) as u32, tile))
+        })
+    }
+}
+
+/// A grid of [Tile]s drawn in layers from one or more [`Tileset`]s. See the
+/// [module-level documentation](self) for more details.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct Tilemap {
+    width: u32,
+    height: u32,
+    tile_width: u32,
+    tile_height: u32,
+    tilesets: Vec<Tileset>,
+    layers: Vec<TileLayer>,
+}
+
+impl Default for Tilemap {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            tile_width: 1,
+            tile_height: 1,
+            tilesets: Vec::new(),
+            layers: Vec::new(),
+        }
+    }
+}
+
+impl Tilemap {
+    /// Constructs an empty `Tilemap` of `width` by `height` tiles, each `tile_width` by
+    /// `tile_height` pixels.
+    ///
+    /// # Errors
+    ///
+    /// If the tile size is `0`, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let map = Tilemap::new(40, 30, 16, 16)?;
+    /// assert_eq!(map.bounds(), rect![0, 0, 640, 480]);
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn new(width: u32, height: u32, tile_width: u32, tile_height: u32) -> PixResult<Self> {
+        if tile_width == 0 || tile_height == 0 {
+            return Err(anyhow!("invalid tile size: {tile_width}x{tile_height}"));
+        }
+        Ok(Self {
+            width,
+            height,
+            tile_width,
+            tile_height,
+            tilesets: Vec::new(),
+            layers: Vec::new(),
+        })
+    }
+
+    /// Constructs a `Tilemap` from a map exported by the [Tiled](https://www.mapeditor.org/)
+    /// editor in its `JSON` format. `load_image` is called with the `image` path of each tileset,
+    /// as written in the map, to load the [`SpriteSource`] its tiles are drawn from.
+    ///
+    /// Every tile layer is loaded, including those nested in groups, while object and image
+    /// layers are skipped. Flipped and rotated tiles are supported. Integer tile properties named
+    /// `flags` and `collision` are loaded as [`Tileset::flags`] and [`Tileset::collision_mask`],
+    /// with a `true` boolean `collision` property setting a mask of `1`.
+    ///
+    /// # Errors
+    ///
+    /// If `json` isn't a valid map, or the map uses features that aren't supported, then an error
+    /// is returned. Maps must be orthogonal and finite, with uncompressed layer data in the `CSV`
+    /// format and embedded single-image tilesets. The `.tmx` `XML` format isn't supported.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let json = r#"{
+    ///     "orientation": "orthogonal",
+    ///     "width": 2, "height": 1, "tilewidth": 8, "tileheight": 8,
+    ///     "layers": [{ "type": "tilelayer", "name": "ground", "data": [1, 2], "visible": true }],
+    ///     "tilesets": [{
+    ///         "firstgid": 1, "image": "tiles.png", "columns": 2, "tilecount": 2,
+    ///         "tilewidth": 8, "tileheight": 8, "margin": 0, "spacing": 0,
+    ///         "tiles": [{ "id": 1, "properties": [{ "name": "collision", "type": "bool", "value": true }] }]
+    ///     }]
+    /// }"#;
+    /// let map = Tilemap::from_tiled(json, |path| {
+    ///     assert_eq!(path, "tiles.png");
+    ///     Ok(Image::new(16, 8).into())
+    /// })?;
+    /// assert_eq!(map.layer(0)?.tile(1, 0), Some(Tile::new(0, 1)));
+    /// assert!(map.collides(rect![8, 0, 1, 1], 1));
+    /// # Ok::<(), PixError>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn from_tiled<F>(json: &str, load_image: F) -> PixResult<Self>
+    where
+        F: FnMut(&str) -> PixResult<SpriteSource>,
+    {
+        let map: tiled::Map = serde_json::from_str(json).context("invalid tiled map")?;
+        map.into_tilemap(load_image)
+    }
+
+    /// Constructs a `Tilemap` from a [Tiled](https://www.mapeditor.org/) `JSON` map file, loading
+    /// tileset images relative to the map. See [`Tilemap::from_tiled`].
+    ///
+    /// # Errors
+    ///
+    /// If the map or a tileset image fails to be read or is invalid, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pix_engine::prelude::*;
+    /// let map = Tilemap::from_tiled_file("./maps/level1.tmj")?;
+    /// # Ok::<(), PixError>(())
+    /// ```
+    #[cfg(all(feature = "serde", not(target_arch = "wasm32")))]
+    pub fn from_tiled_file<P: AsRef<Path>>(path: P) -> PixResult<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)?;
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        Self::from_tiled(&json, |image| Ok(Image::from_file(dir.join(image))?.into()))
+            .with_context(|| format!("failed to load tiled map: {path:?}"))
+    }
+
+    /// Returns the size of the map in tiles as `(width, height)`.
+    #[inline]
+    #[must_use]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the size of each tile in pixels as `(width, height)`.
+    #[inline]
+    #[must_use]
+    pub const fn tile_size(&self) -> (u32, u32) {
+        (self.tile_width, self.tile_height)
+    }
+
+    /// Returns the area covered by the map in pixels.
+    #[inline]
+    pub fn bounds(&self) -> Rect<i32> {
+        rect![
+            0,
+            0,
+            (self.width * self.tile_width) as i32,
+            (self.height * self.tile_height) as i32
+        ]
+    }
+
+    /// Add a [`Tileset`], returning its index for use in [Tile]s.
+    pub fn add_tileset(&mut self, tileset: Tileset) -> usize {
+        self.tilesets.push(tileset);
+        self.tilesets.len() - 1
+    }
+
+    /// Returns the list of [`Tileset`]s.
+    #[inline]
+    pub fn tilesets(&self) -> &[Tileset] {
+        &self.tilesets
+    }
+
+    /// Returns a mutable reference to the [`Tileset`] at `index`.
+    ///
+    /// # Errors
+    ///
+    /// If there's no tileset at `index`, then an error is returned.
+    pub fn tileset_mut(&mut self, index: usize) -> PixResult<&mut Tileset> {
+        self.tilesets
+            .get_mut(index)
+            .ok_or_else(|| anyhow!("invalid tileset: {index}"))
+    }
+
+    /// Add an empty [`TileLayer`] drawn above existing layers, returning its index.
+    pub fn add_layer<S: Into<String>>(&mut self, name: S) -> usize {
+        self.layers
+            .push(TileLayer::new(name.into(), self.width, self.height));
+        self.layers.len() - 1
+    }
+
+    /// Returns the list of [`TileLayer`]s, from bottom to top.
+    #[inline]
+    pub fn layers(&self) -> &[TileLayer] {
+        &self.layers
+    }
+
+    /// Returns the [`TileLayer`] at `index`.
+    ///
+    /// # Errors
+    ///
+    /// If there's no layer at `index`, then an error is returned.
+    pub fn layer(&self, index: usize) -> PixResult<&TileLayer> {
+        self.layers
+            .get(index)
+            .ok_or_else(|| anyhow!("invalid tilemap layer: {index}"))
+    }
+
+    /// Returns a mutable reference to the [`TileLayer`] at `index`.
+    ///
+    /// # Errors
+    ///
+    /// If there's no layer at `index`, then an error is returned.
+    pub fn layer_mut(&mut self, index: usize) -> PixResult<&mut TileLayer> {
+        self.layers
+            .get_mut(index)
+            .ok_or_else(|| anyhow!("invalid tilemap layer: {index}"))
+    }
+
+    /// Returns the index of the first [`TileLayer`] with `name`, if any.
+    #[must_use]
+    pub fn layer_index(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name == name)
+    }
+
+    /// Returns the area in pixels of the tile at column `x` and row `y`.
+    #[inline]
+    pub fn tile_rect(&self, x: u32, y: u32) -> Rect<i32> {
+        let (width, height) = (self.tile_width as i32, self.tile_height as i32);
+        rect![x as i32 * width, y as i32 * height, width, height]
+    }
+
+    /// Returns the column and row of the tile containing the pixel `point`, if it's within the
+    /// map.
+    #[must_use]
+    pub fn tile_at<P: Into<Point<i32>>>(&self, point: P) -> Option<(u32, u32)> {
+        let point = point.into();
+        if point.x() < 0 || point.y() < 0 {
+            return None;
+        }
+        let x = point.x() as u32 / self.tile_width;
+        let y = point.y() as u32 / self.tile_height;
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    /// Returns the column and row of every tile overlapping the pixel area `rect`, row by row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let map = Tilemap::new(10, 10, 16, 16)?;
+    /// let tiles: Vec<_> = map.tiles_in(rect![8, -8, 16, 16]).collect();
+    /// assert_eq!(tiles, [(0, 0), (1, 0)]);
+    /// # Ok::<(), PixError>(())
+    /// ```
+    pub fn tiles_in<R: Into<Rect<i32>>>(&self, rect: R) -> impl Iterator<Item = (u32, u32)> {
+        let rect = rect.into();
+        let (width, height) = (self.tile_width as i32, self.tile_height as i32);
+        let clamp = |value: i32, max: u32| value.clamp(0, max as i32) as u32;
+        let (left, top) = (
+            clamp(rect.left().div_euclid(width), self.width),
+            clamp(rect.top().div_euclid(height), self.height),
+        );
+        let (right, bottom) = (
+            clamp((rect.right() + width - 1).div_euclid(width), self.width),
+            clamp((rect.bottom() + height - 1).div_euclid(height), self.height),
+        );
+        (top..bottom).flat_map(move |y| (left..right).map(move |x| (x, y)))
+    }
+
+    /// Returns the combined [`Tileset::flags`] of every layer at column `x` and row `y`.
+    #[must_use]
+    pub fn flags_at(&self, x: u32, y: u32) -> u32 {
+        self.tile_data_at(x, y, Tileset::flags)
+    }
+
+    /// Returns the combined [`Tileset::collision_mask`] of every layer at column `x` and row `y`.
+    #[must_use]
+    pub fn collision_mask_at(&self, x: u32, y: u32) -> u32 {
+        self.tile_data_at(x, y, Tileset::collision_mask)
+    }
+
+    /// Whether any tile overlapping the pixel area `rect` has a collision mask sharing bits with
+    /// `mask`, including tiles in hidden layers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// const SOLID: u32 = 1;
+    /// let mut tileset = Tileset::new(SpriteSheet::from_grid(Image::new(32, 16), 16, 16, 2, 1)?);
+    /// tileset.set_collision_mask(1, SOLID);
+    /// let mut map = Tilemap::new(10, 10, 16, 16)?;
+    /// map.add_tileset(tileset);
+    /// let walls = map.add_layer("walls");
+    /// map.layer_mut(walls)?.set_tile(2, 0, Tile::new(0, 1))?;
+    ///
+    /// assert!(!map.collides(rect![16, 0, 16, 16], SOLID));
+    /// assert!(map.collides(rect![20, 0, 16, 16], SOLID));
+    /// # Ok::<(), PixError>(())
+    /// ```
+    #[must_use]
+    pub fn collides<R: Into<Rect<i32>>>(&self, rect: R, mask: u32) -> bool {
+        self.tiles_in(rect)
+            .any(|(x, y)| self.collision_mask_at(x, y) & mask != 0)
+    }
+}
+
+impl Tilemap {
+    /// Combines tile data from every layer at column `x` and row `y`.
+    fn tile_data_at<F>(&self, x: u32, y: u32, data: F) -> u32
+    where
+        F: Fn(&Tileset, usize) -> u32,
+    {
+        self.layers
+            .iter()
+            .filter_map(|layer| layer.tile(x, y))
+            .filter_map(|tile| Some(data(self.tilesets.get(tile.tileset)?, tile.id)))
+            .fold(0, |combined, value| combined | value)
+    }
+
+    /// Returns the largest amount tileset tiles extend past map tiles as `(width, height)`.
+    fn overdraw(&self) -> (u32, u32) {
+        self.tilesets
+            .iter()
+            .fold((0, 0), |(width, height), tileset| {
+                let (tile_width, tile_height) = tileset.tile_size;
+                (
+                    width.max(tile_width.saturating_sub(self.tile_width)),
+                    height.max(tile_height.saturating_sub(self.tile_height)),
+                )
+            })
+    }
+}
+
+/// Returns the horizontal and vertical flip of `flipped`.
+const fn flip_axes(flipped: Option<Flipped>) -> (bool, bool) {
+    match flipped {
+        Some(Flipped::Horizontal) => (true, false),
+        Some(Flipped::Vertical) => (false, true),
+        Some(Flipped::Both) => (true, true),
+        Some(Flipped::None) | None => (false, false),
+    }
+}
+
+/// Returns the [Flipped] direction for a horizontal and vertical flip.
+const fn flipped(horizontal: bool, vertical: bool) -> Option<Flipped> {
+    match (horizontal, vertical) {
+        (true, false) => Some(Flipped::Horizontal),
+        (false, true) => Some(Flipped::Vertical),
+        (true, true) => Some(Flipped::Both),
+        (false, false) => None,
+    }
+}
+
+impl PixState {
+    /// Draw every visible [`TileLayer`] of a [`Tilemap`] from bottom to top, with the top-left of
+    /// the map at the current origin. Only tiles overlapping `view`, the area of the map in pixels
+    /// that's on screen, are drawn. Tiles are tinted by [`PixState::image_tint`] and follow the
+    /// current [Transform], but are not affected by [`ImageMode`]. Use a [`Camera2D`] and
+    /// [`Camera2D::view`] to scroll and zoom.
+    ///
+    /// Tileset tiles larger than the map tile size are aligned to the bottom-left of their cell,
+    /// matching the Tiled editor.
+    ///
+    /// # Errors
+    ///
+    /// If a tile refers to a [`Tileset`] or sprite that doesn't exist, or the renderer fails to
+    /// draw to the current render target, then an error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { map: Tilemap, player: Point<i32> };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     // Keep the player centered on screen
+    ///     let (width, height) = s.dimensions()?;
+    ///     let view = Rect::from_center(self.player, width as i32, height as i32);
+    ///     s.push();
+    ///     s.translate(-f64::from(view.x()), -f64::from(view.y()));
+    ///     s.tilemap(&self.map, view)?;
+    ///     s.pop();
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn tilemap<R>(&mut self, map: &Tilemap, view: R) -> PixResult<()>
+    where
+        R: Into<Rect<i32>>,
+    {
+        let view = view.into();
+        for layer in map.layers.iter().filter(|layer| layer.visible) {
+            self.draw_tile_layer(map, layer, view)?;
+        }
+        Ok(())
+    }
+
+    /// Draw the [`TileLayer`] at index `layer` of a [`Tilemap`], even if hidden. See
+    /// [`PixState::tilemap`].
+    ///
+    /// # Errors
+    ///
+    /// If there's no layer at `layer`, a tile refers to a [`Tileset`] or sprite that doesn't
+    /// exist, or the renderer fails to draw to the current render target, then an error is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App { map: Tilemap, camera: Camera2D, player: Point<i32> };
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     // Draw the player between the background and foreground layers
+    ///     let view = self.camera.view();
+    ///     s.begin_camera(&self.camera);
+    ///     s.tilemap_layer(&self.map, 0, view)?;
+    ///     s.circle([self.player.x(), self.player.y(), 8])?;
+    ///     s.tilemap_layer(&self.map, 1, view)?;
+    ///     s.end_camera();
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    pub fn tilemap_layer<R>(&mut self, map: &Tilemap, layer: usize, view: R) -> PixResult<()>
+    where
+        R: Into<Rect<i32>>,
+    {
+        self.draw_tile_layer(map, map.layer(layer)?, view.into())
+    }
+}
+
+impl PixState {
+    /// Draw the tiles of `layer` overlapping `view`.
+    fn draw_tile_layer(
+        &mut self,
+        map: &Tilemap,
+        layer: &TileLayer,
+        view: Rect<i32>,
+    ) -> PixResult<()> {
+        // Include tiles outside of the view that extend into it
+        let (overdraw_width, overdraw_height) = map.overdraw();
+        let area = rect![
+            view.x() - overdraw_width as i32,
+            view.y(),
+            view.width() + overdraw_width as i32,
+            view.height() + overdraw_height as i32
+        ];
+        for (x, y) in map.tiles_in(area) {
+            let Some(tile) = layer.tile(x, y) else {
+                continue;
+            };
+            let tileset = map
+                .tilesets
+                .get(tile.tileset)
+                .ok_or_else(|| anyhow!("invalid tileset: {}", tile.tileset))?;
+            let sprite = tileset.sheet.rect(tile.id)?;
+            let cell = map.tile_rect(x, y);
+            let dst = rect![
+                cell.left(),
+                cell.bottom() - sprite.height(),
+                sprite.width(),
+                sprite.height()
+            ];
+            let (horizontal, vertical) = flip_axes(tile.flipped);
+            // A diagonal flip is a quarter turn clockwise of the vertically flipped tile
+            let (angle, flipped) = if tile.transposed {
+                (90.0, flipped(vertical, !horizontal))
+            } else {
+                (0.0, flipped(horizontal, vertical))
+            };
+            self.draw_sprite(&tileset.sheet, tile.id, Some(dst), angle, None, flipped)?;
+        }
+        Ok(())
+    }
+}
+
+/// The [Tiled](https://www.mapeditor.org/) `JSON` map format.
+#[cfg(feature = "serde")]
+mod tiled {
+    use super::{Tile, TileLayer, Tilemap, Tileset};
+    use crate::prelude::*;
+    use anyhow::anyhow;
+    use serde::{de::IgnoredAny, Deserialize};
+
+    /// Global tile id bit set when a tile is flipped horizontally.
+    const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+    /// Global tile id bit set when a tile is flipped vertically.
+    const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+    /// Global tile id bit set when a tile is flipped diagonally.
+    const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+    /// Global tile id bits not part of the tile id.
+    const FLAG_BITS: u32 = 0xF000_0000;
+
+    #[derive(Debug, Deserialize)]
+    pub(super) struct Map {
+        #[serde(default)]
+        orientation: Option<String>,
+        #[serde(default)]
+        infinite: bool,
+        width: u32,
+        height: u32,
+        tilewidth: u32,
+        tileheight: u32,
+        #[serde(default)]
+        layers: Vec<Layer>,
+        #[serde(default)]
+        tilesets: Vec<TilesetDef>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Layer {
+        #[serde(rename = "type")]
+        kind: String,
+        #[serde(default)]
+        name: String,
+        #[serde(default = "visible")]
+        visible: bool,
+        #[serde(default)]
+        encoding: Option<String>,
+        #[serde(default)]
+        data: Option<Data>,
+        #[serde(default)]
+        layers: Vec<Layer>,
+    }
+
+    /// Layers are visible unless specified.
+    const fn visible() -> bool {
+        true
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(untagged)]
+    enum Data {
+        Csv(Vec<u32>),
+        Encoded(IgnoredAny),
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct TilesetDef {
+        firstgid: u32,
+        #[serde(default)]
+        name: String,
+        #[serde(default)]
+        source: Option<String>,
+        #[serde(default)]
+        image: Option<String>,
+        #[serde(default)]
+        columns: u32,
+        #[serde(default)]
+        tilecount: u32,
+        #[serde(default)]
+        tilewidth: u32,
+        #[serde(default)]
+        tileheight: u32,
+        #[serde(default)]
+        margin: u32,
+        #[serde(default)]
+        spacing: u32,
+        #[serde(default)]
+        tiles: Vec<TileDef>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct TileDef {
+        id: usize,
+        #[serde(default)]
+        properties: Vec<Property>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Property {
+        name: String,
+        value: serde_json::Value,
+    }
+
+    impl Property {
+        /// Returns the property as an integer, treating booleans as `0` or `1`.
+        fn as_u32(&self) -> Option<u32> {
+            match &self.value {
+                serde_json::Value::Bool(value) => Some(u32::from(*value)),
+                value => value.as_u64().map(|value| value as u32),
+            }
+        }
+    }
+
+    impl Map {
+        /// Convert to a [`Tilemap`], loading tileset images with `load_image`.
+        pub(super) fn into_tilemap<F>(self, mut load_image: F) -> PixResult<Tilemap>
+        where
+            F: FnMut(&str) -> PixResult<SpriteSource>,
+        {
+            if let Some(orientation) = self.orientation.filter(|o| o != "orthogonal") {
+                return Err(anyhow!(
+                    "unsupported tiled map orientation: {orientation:?}"
+                ));
+            }
+            if self.infinite {
+                return Err(anyhow!("infinite tiled maps are not supported"));
+            }
+            let mut tilemap =
+                Tilemap::new(self.width, self.height, self.tilewidth, self.tileheight)?;
+            let mut first_gids = Vec::with_capacity(self.tilesets.len());
+            for def in self.tilesets {
+                first_gids.push(def.firstgid);
+                tilemap.add_tileset(def.into_tileset(&mut load_image)?);
+            }
+            for layer in self.layers {
+                layer.flatten(&mut tilemap, &first_gids, true)?;
+            }
+            Ok(tilemap)
+        }
+    }
+
+    impl Layer {
+        /// Add this layer, or the layers of a group, to `tilemap`.
+        fn flatten(
+            self,
+            tilemap: &mut Tilemap,
+            first_gids: &[u32],
+            visible: bool,
+        ) -> PixResult<()> {
+            let visible = visible && self.visible;
+            match self.kind.as_str() {
+                "group" => {
+                    for layer in self.layers {
+                        layer.flatten(tilemap, first_gids, visible)?;
+                    }
+                }
+                "tilelayer" => {
+                    let data = match (self.encoding.as_deref(), self.data) {
+                        (None | Some("csv"), Some(Data::Csv(data))) => data,
+                        (_, None) => return Err(anyhow!("tile layer {:?} has no data", self.name)),
+                        (encoding, _) => {
+                            return Err(anyhow!(
+                                "unsupported tile layer encoding {encoding:?} for {:?}, use CSV",
+                                self.name
+                            ))
+                        }
+                    };
+                    let (width, height) = tilemap.dimensions();
+                    if data.len() != width as usize * height as usize {
+                        return Err(anyhow!(
+                            "tile layer {:?} has {} tiles, expected {}",
+                            self.name,
+                            data.len(),
+                            width as usize * height as usize
+                        ));
+                    }
+                    let mut layer = TileLayer::new(self.name, width, height);
+                    layer.visible = visible;
+                    for (cell, &gid) in layer.tiles.iter_mut().zip(&data) {
+                        *cell = tile(gid, first_gids)?;
+                    }
+                    tilemap.layers.push(layer);
+                }
+                _ => (),
+            }
+            Ok(())
+        }
+    }
+
+    /// Decode a global tile id into a [Tile].
+    fn tile(gid: u32, first_gids: &[u32]) -> PixResult<Option<Tile>> {
+        let id = gid & !FLAG_BITS;
+        if id == 0 {
+            return Ok(None);
+        }
+        let tileset = first_gids
+            .iter()
+            .rposition(|&first_gid| first_gid <= id)
+            .ok_or_else(|| anyhow!("tile id {id} has no tileset"))?;
+        Ok(Some(Tile {
+            tileset,
+            id: (id - first_gids[tileset]) as usize,
+            flipped: super::flipped(
+                gid & FLIPPED_HORIZONTALLY != 0,
+                gid & FLIPPED_VERTICALLY != 0,
+            ),
+            transposed: gid & FLIPPED_DIAGONALLY != 0,
+        }))
+    }
+
+    impl TilesetDef {
+        /// Convert to a [`Tileset`], loading the tileset image with `load_image`.
+        fn into_tileset<F>(self, load_image: F) -> PixResult<Tileset>
+        where
+            F: FnOnce(&str) -> PixResult<SpriteSource>,
+        {
+            if let Some(source) = self.source {
+                return Err(anyhow!(
+                    "external tileset {source:?} is not supported, embed it in the map"
+                ));
+            }
+            let image = self.image.ok_or_else(|| {
+                anyhow!("image collection tileset {:?} is not supported", self.name)
+            })?;
+            if self.columns == 0 || self.tilewidth == 0 || self.tileheight == 0 {
+                return Err(anyhow!("invalid tileset {:?}", self.name));
+            }
+            let (width, height) = (self.tilewidth as i32, self.tileheight as i32);
+            let (margin, spacing) = (self.margin as i32, self.spacing as i32);
+            let columns = self.columns;
+            let rects = (0..self.tilecount).map(|id| {
+                let (column, row) = ((id % columns) as i32, (id / columns) as i32);
+                rect![
+                    margin + column * (width + spacing),
+                    margin + row * (height + spacing),
+                    width,
+                    height
+                ]
+            });
+            let mut tileset = Tileset::new(SpriteSheet::new(load_image(&image)?, rects));
+            for def in self.tiles {
+                for property in &def.properties {
+                    match (property.name.as_str(), property.as_u32()) {
+                        ("flags", Some(flags)) => tileset.set_flags(def.id, flags),
+                        ("collision", Some(mask)) => tileset.set_collision_mask(def.id, mask),
+                        _ => (),
+                    }
+                }
+            }
+            Ok(tileset)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tilemap() -> PixResult<Tilemap> {
+        let mut tileset = Tileset::new(SpriteSheet::from_grid(Image::new(16, 8), 8, 8, 2, 1)?);
+        tileset.set_collision_mask(1, 0b01);
+        tileset.set_flags(1, 0b100);
+        let mut map = Tilemap::new(4, 3, 8, 8)?;
+        map.add_tileset(tileset);
+        let ground = map.add_layer("ground");
+        let walls = map.add_layer("walls");
+        map.layer_mut(walls)?.set_visible(false);
+        for x in 0..4 {
+            map.layer_mut(ground)?.set_tile(x, 2, Tile::new(0, 0))?;
+        }
+        map.layer_mut(walls)?.set_tile(3, 1, Tile::new(0, 1))?;
+        Ok(map)
+    }
+
+    #[test]
+    fn test_tiles_in() -> PixResult<()> {
+        let map = tilemap()?;
+        let tiles: Vec<_> = map.tiles_in(rect![4, 4, 8, 8]).collect();
+        assert_eq!(tiles, [(0, 0), (1, 0), (0, 1), (1, 1)]);
+        let tiles: Vec<_> = map.tiles_in(rect![8, 8, 8, 8]).collect();
+        assert_eq!(tiles, [(1, 1)]);
+        // Clamped to the map
+        let tiles: Vec<_> = map.tiles_in(rect![-100, 20, 200, 100]).collect();
+        assert_eq!(tiles, [(0, 2), (1, 2), (2, 2), (3, 2)]);
+        assert_eq!(map.tiles_in(rect![32, 0, 8, 8]).count(), 0);
+        assert_eq!(map.tile_at([31, 23]), Some((3, 2)));
+        assert_eq!(map.tile_at([32, 0]), None);
+        assert_eq!(map.tile_at([-1, 0]), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_collides() -> PixResult<()> {
+        let mut map = tilemap()?;
+        assert_eq!(map.collision_mask_at(3, 1), 0b01);
+        assert_eq!(map.flags_at(3, 1), 0b100);
+        assert_eq!(map.flags_at(3, 2), 0);
+        // Hidden layers still collide
+        assert!(map.collides(rect![20, 4, 8, 8], 0b01));
+        assert!(!map.collides(rect![20, 4, 8, 8], 0b10));
+        assert!(!map.collides(rect![16, 8, 8, 8], 0b01));
+        assert!(map.layer_mut(0)?.set_tile(4, 0, Tile::new(0, 0)).is_err());
+        assert!(map.layer(2).is_err());
+        assert_eq!(map.layer_index("walls"), Some(1));
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_from_tiled() -> PixResult<()> {
+        let json = r#"{
+            "orientation": "orthogonal",
+            "width": 3, "height": 1, "tilewidth": 8, "tileheight": 8,
+            "layers": [
+                { "type": "tilelayer", "name": "ground", "data": [1, 0, 3221225476] },
+                { "type": "objectgroup", "name": "spawns", "objects": [] },
+                {
+                    "type": "group", "name": "decor", "visible": false,
+                    "layers": [{ "type": "tilelayer", "name": "plants", "data": [2147483650, 536870916, 0] }]
+                }
+            ],
+            "tilesets": [
+                {
+                    "firstgid": 1, "name": "a", "image": "a.png", "columns": 2, "tilecount": 2,
+                    "tilewidth": 8, "tileheight": 8, "margin": 1, "spacing": 2,
+                    "tiles": [{ "id": 1, "properties": [
+                        { "name": "collision", "type": "int", "value": 6 },
+                        { "name": "flags", "type": "int", "value": 1 }
+                    ] }]
+                },
+                {
+                    "firstgid": 3, "name": "b", "image": "b.png", "columns": 1, "tilecount": 2,
+                    "tilewidth": 8, "tileheight": 16
+                }
+            ]
+        }"#;
+        let mut images = vec![];
+        let map = Tilemap::from_tiled(json, |path| {
+            images.push(path.to_owned());
+            Ok(Image::new(32, 32).into())
+        })?;
+        assert_eq!(images, ["a.png", "b.png"]);
+        assert_eq!(map.dimensions(), (3, 1));
+        assert_eq!(map.tilesets()[0].sheet().rect(1)?, rect![11, 1, 8, 8]);
+        assert_eq!(map.tilesets()[0].collision_mask(1), 6);
+        assert_eq!(map.tilesets()[0].flags(1), 1);
+        assert_eq!(map.tilesets()[1].sheet().rect(1)?, rect![0, 16, 8, 16]);
+
+        let names: Vec<_> = map.layers().iter().map(TileLayer::name).collect();
+        assert_eq!(names, ["ground", "plants"]);
+        let ground = map.layer(0)?;
+        assert_eq!(ground.tile(0, 0), Some(Tile::new(0, 0)));
+        assert_eq!(ground.tile(1, 0), None);
+        assert_eq!(
+            ground.tile(2, 0),
+            Some(Tile {
+                flipped: Some(Flipped::Both),
+                ..Tile::new(1, 1)
+            })
+        );
+        let plants = map.layer(1)?;
+        assert!(!plants.is_visible());
+        assert_eq!(
+            plants.tile(0, 0),
+            Some(Tile {
+                flipped: Some(Flipped::Horizontal),
+                ..Tile::new(0, 1)
+            })
+        );
+        assert_eq!(
+            plants.tile(1, 0),
+            Some(Tile {
+                transposed: true,
+                ..Tile::new(1, 1)
+            })
+        );
+
+        let load = |_: &str| Ok(Image::new(8, 8).into());
+        let encoded = r#"{
+            "width": 1, "height": 1, "tilewidth": 8, "tileheight": 8,
+            "layers": [{ "type": "tilelayer", "encoding": "base64", "data": "AQAAAA==" }],
+            "tilesets": []
+        }"#;
+        assert!(Tilemap::from_tiled(encoded, load).is_err());
+        let external = r#"{
+            "width": 1, "height": 1, "tilewidth": 8, "tileheight": 8,
+            "layers": [], "tilesets": [{ "firstgid": 1, "source": "tiles.tsj" }]
+        }"#;
+        assert!(Tilemap::from_tiled(external, load).is_err());
+        let isometric = r#"{
+            "orientation": "isometric",
+            "width": 1, "height": 1, "tilewidth": 8, "tileheight": 8
+        }"#;
+        assert!(Tilemap::from_tiled(isometric, load).is_err());
+        let oversized = r#"{
+            "width": 100000, "height": 100000, "tilewidth": 8, "tileheight": 8,
+            "layers": [{ "type": "tilelayer", "data": [1] }], "tilesets": []
+        }"#;
+        assert!(Tilemap::from_tiled(oversized, load).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,537 @@
+//! [Touch] tracking and [Gesture] recognition for touchscreens.
+//!
+//! Each finger pressed on a touch device is tracked as a [Touch] from the time it's pressed until
+//! it's released. [`PixEngine::on_touch_start`], [`PixEngine::on_touch_move`] and
+//! [`PixEngine::on_touch_end`] are called as fingers are pressed, moved and released, and all
+//! current touches can be queried with [`PixState::touches`]. Touch positions are converted to
+//! window coordinates.
+//!
+//! Touches are also used to recognize common [Gesture]s which are sent to
+//! [`PixEngine::on_gesture`]:
+//!
+//! - [`Gesture::Tap`]: A single finger pressed and released quickly without moving.
+//! - [`Gesture::DoubleTap`]: A second tap shortly after and near a previous tap.
+//! - [`Gesture::LongPress`]: A single finger held without moving.
+//! - [`Gesture::Pan`]: A single finger moved.
+//! - [`Gesture::Pinch`]: Two fingers moved closer together or further apart.
+//! - [`Gesture::Rotate`]: Two fingers rotated around each other.
+//!
+//! Provided [`PixState`] methods:
+//!
+//! - [`PixState::touches`]: Returns a list of fingers currently pressed.
+//!
+//! # Example
+//!
+//! ```
+//! # use pix_engine::prelude::*;
+//! # struct App { camera: Camera2D };
+//! # impl PixEngine for App {
+//! # fn on_update(&mut self, s: &mut PixState) -> PixResult<()> { Ok(()) }
+//! fn on_gesture(&mut self, s: &mut PixState, gesture: Gesture) -> PixResult<bool> {
+//!     match gesture {
+//!         Gesture::Pan { delta, .. } => {
+//!             let zoom = self.camera.zoom();
+//!             self.camera.translate(-delta.x() / zoom, -delta.y() / zoom);
+//!         }
+//!         Gesture::Pinch { scale, .. } => {
+//!             self.camera.set_zoom(self.camera.zoom() * scale);
+//!         }
+//!         Gesture::Rotate { angle, .. } => {
+//!             self.camera.set_rotation(self.camera.rotation() + angle);
+//!         }
+//!         Gesture::DoubleTap { .. } => self.camera.set_zoom(1.0),
+//!         _ => return Ok(false),
+//!     }
+//!     Ok(true)
+//! }
+//! # }
+//! ```
+
+use crate::prelude::*;
+use std::{f64::consts::PI, time::Duration, time::Instant};
+
+/// Distance a finger can move, in pixels, before it's no longer considered a tap or long press.
+const TAP_SLOP: f64 = 10.0;
+/// Maximum time a finger can be held to be considered a tap.
+const TAP_TIMEOUT: Duration = Duration::from_millis(300);
+/// Maximum time between taps to be considered a double tap.
+const DOUBLE_TAP_TIMEOUT: Duration = Duration::from_millis(300);
+/// Time a finger must be held without moving to be considered a long press.
+const LONG_PRESS_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// A finger pressed on a touch device.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Touch {
+    id: i64,
+    device_id: i64,
+    pos: Point<f64>,
+    start_pos: Point<f64>,
+    delta: Vector<f64>,
+    pressure: f32,
+    started: Instant,
+    moved: bool,
+    long_pressed: bool,
+    consumed: bool,
+}
+
+impl Touch {
+    /// The finger identifier, unique per [device](Touch::device_id) while the finger is pressed.
+    #[inline]
+    #[must_use]
+    pub const fn id(&self) -> i64 {
+        self.id
+    }
+
+    /// The touch device identifier.
+    #[inline]
+    #[must_use]
+    pub const fn device_id(&self) -> i64 {
+        self.device_id
+    }
+
+    /// The current finger position in window coordinates.
+    #[inline]
+    pub const fn pos(&self) -> Point<f64> {
+        self.pos
+    }
+
+    /// The position where the finger was first pressed in window coordinates.
+    #[inline]
+    pub const fn start_pos(&self) -> Point<f64> {
+        self.start_pos
+    }
+
+    /// The distance moved since the last touch event.
+    #[inline]
+    pub const fn delta(&self) -> Vector<f64> {
+        self.delta
+    }
+
+    /// The amount of pressure applied, normalized between `0.0` and `1.0`.
+    #[inline]
+    #[must_use]
+    pub const fn pressure(&self) -> f32 {
+        self.pressure
+    }
+
+    /// Whether `other` is the same finger on the same touch device.
+    #[inline]
+    const fn is_finger(&self, other: &Touch) -> bool {
+        self.device_id == other.device_id && self.id == other.id
+    }
+}
+
+/// A gesture recognized from one or more [Touch]es.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Gesture {
+    /// A single finger pressed and released quickly without moving.
+    Tap {
+        /// Position of the tap in window coordinates.
+        pos: Point<f64>,
+    },
+    /// A second tap shortly after and near a previous tap. Sent after the [`Gesture::Tap`] for the
+    /// second tap.
+    DoubleTap {
+        /// Position of the second tap in window coordinates.
+        pos: Point<f64>,
+    },
+    /// A single finger held without moving. Releasing the finger after a long press doesn't result
+    /// in a [`Gesture::Tap`].
+    LongPress {
+        /// Position of the finger in window coordinates.
+        pos: Point<f64>,
+    },
+    /// A single finger moved.
+    Pan {
+        /// Current position of the finger in window coordinates.
+        pos: Point<f64>,
+        /// Distance moved since the last pan.
+        delta: Vector<f64>,
+    },
+    /// Two fingers moved closer together or further apart.
+    Pinch {
+        /// Point halfway between both fingers in window coordinates.
+        center: Point<f64>,
+        /// Change in distance between fingers since the last pinch. Greater than `1.0` when
+        /// fingers move apart and less than `1.0` when they move together.
+        scale: f64,
+    },
+    /// Two fingers rotated around each other.
+    Rotate {
+        /// Point halfway between both fingers in window coordinates.
+        center: Point<f64>,
+        /// Change in angle between fingers since the last rotation, in radians. Positive values are
+        /// clockwise.
+        angle: f64,
+    },
+}
+
+/// Keep track of current touches and recognize gestures between frames.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub(crate) struct TouchState {
+    touches: Vec<Touch>,
+    multi_touch: bool,
+    last_tap: Option<(Instant, Point<f64>)>,
+}
+
+impl TouchState {
+    /// Track a newly pressed finger.
+    fn start(&mut self, mut touch: Touch) -> Touch {
+        touch.start_pos = touch.pos;
+        self.touches.retain(|t| !t.is_finger(&touch));
+        self.touches.push(touch);
+        if self.touches.len() > 1 {
+            self.multi_touch = true;
+        }
+        touch
+    }
+
+    /// Stop recognizing gestures for a pressed finger until it's released.
+    pub(crate) fn consume(&mut self, touch: &Touch) {
+        if let Some(touch) = self.touches.iter_mut().find(|t| t.is_finger(touch)) {
+            touch.consumed = true;
+        }
+    }
+
+    /// Update a moved finger, returning any recognized gestures.
+    fn motion(&mut self, touch: Touch) -> Option<(Touch, Vec<Gesture>)> {
+        let index = self.touches.iter().position(|t| t.is_finger(&touch))?;
+        let previous = self.touches[index];
+        let current = &mut self.touches[index];
+        current.pos = touch.pos;
+        current.delta = touch.delta;
+        current.pressure = touch.pressure;
+        if current.pos.dist(current.start_pos) > TAP_SLOP {
+            current.moved = true;
+        }
+        let current = *current;
+
+        let mut gestures = vec![];
+        match self.touches[..] {
+            [only] if !self.multi_touch && !only.consumed && only.moved && !only.long_pressed => {
+                gestures.push(Gesture::Pan {
+                    pos: only.pos,
+                    delta: only.pos - previous.pos,
+                });
+            }
+            [a, b] if !a.consumed && !b.consumed => {
+                let other = if a.is_finger(&current) { b } else { a };
+                let old = previous.pos - other.pos;
+                let new = current.pos - other.pos;
+                let center = Point::from((current.pos + other.pos) / 2.0);
+                let (old_len, new_len) = (old.mag(), new.mag());
+                if old_len > 0.0 && new_len > 0.0 {
+                    let scale = new_len / old_len;
+                    if scale != 1.0 {
+                        gestures.push(Gesture::Pinch { center, scale });
+                    }
+                    let mut angle = new.y().atan2(new.x()) - old.y().atan2(old.x());
+                    if angle > PI {
+                        angle -= 2.0 * PI;
+                    } else if angle <= -PI {
+                        angle += 2.0 * PI;
+                    }
+                    if angle != 0.0 {
+                        gestures.push(Gesture::Rotate { center, angle });
+                    }
+                }
+            }
+            _ => (),
+        }
+        Some((current, gestures))
+    }
+
+    /// Stop tracking a released finger, returning any recognized gestures.
+    fn end(&mut self, touch: Touch, now: Instant) -> Option<(Touch, Vec<Gesture>)> {
+        let index = self.touches.iter().position(|t| t.is_finger(&touch))?;
+        let mut released = self.touches.remove(index);
+        released.pos = touch.pos;
+        released.delta = touch.delta;
+        released.pressure = touch.pressure;
+
+        let mut gestures = vec![];
+        let is_tap = !self.multi_touch
+            && !released.consumed
+            && !released.long_pressed
+            && released.pos.dist(released.start_pos) <= TAP_SLOP
+            && now - released.started <= TAP_TIMEOUT;
+        if is_tap {
+            let pos = released.pos;
+            gestures.push(Gesture::Tap { pos });
+            match self.last_tap.take() {
+                Some((time, last_pos))
+                    if now - time <= DOUBLE_TAP_TIMEOUT && pos.dist(last_pos) <= 2.0 * TAP_SLOP =>
+                {
+                    gestures.push(Gesture::DoubleTap { pos });
+                }
+                _ => self.last_tap = Some((now, pos)),
+            }
+        }
+        if self.touches.is_empty() {
+            self.multi_touch = false;
+        }
+        Some((released, gestures))
+    }
+
+    /// Check for fingers held long enough to be a long press.
+    pub(crate) fn update(&mut self, now: Instant) -> Option<Gesture> {
+        match self.touches.as_mut_slice() {
+            [touch]
+                if !self.multi_touch
+                    && !touch.consumed
+                    && !touch.moved
+                    && !touch.long_pressed
+                    && now - touch.started >= LONG_PRESS_TIMEOUT =>
+            {
+                touch.long_pressed = true;
+                Some(Gesture::LongPress { pos: touch.pos })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl PixState {
+    /// Returns a list of fingers currently pressed on any touch device.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # struct App;
+    /// # impl PixEngine for App {
+    /// fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+    ///     let touches = s.touches().to_vec();
+    ///     for touch in touches {
+    ///         let pos = touch.pos().round().as_::<i32>();
+    ///         s.circle([pos.x(), pos.y(), 20])?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn touches(&self) -> &[Touch] {
+        &self.touch.touches
+    }
+
+    /// Update touches from a `FingerDown`, `FingerMotion` or `FingerUp` [Event], returning the
+    /// updated [Touch] and any recognized [Gesture]s. Returns `None` for other events or fingers
+    /// that aren't being tracked.
+    pub(crate) fn on_touch(&mut self, event: &Event) -> PixResult<Option<(Touch, Vec<Gesture>)>> {
+        let (Event::FingerDown {
+            touch_id,
+            finger_id,
+            x,
+            y,
+            dx,
+            dy,
+            pressure,
+        }
+        | Event::FingerMotion {
+            touch_id,
+            finger_id,
+            x,
+            y,
+            dx,
+            dy,
+            pressure,
+        }
+        | Event::FingerUp {
+            touch_id,
+            finger_id,
+            x,
+            y,
+            dx,
+            dy,
+            pressure,
+        }) = *event
+        else {
+            return Ok(None);
+        };
+
+        // Touch coordinates are normalized to the window size
+        let (width, height) = self.window_dimensions()?;
+        let width = f64::from(width) / f64::from(self.settings.scale_x);
+        let height = f64::from(height) / f64::from(self.settings.scale_y);
+        let now = self.now();
+        let touch = Touch {
+            id: finger_id,
+            device_id: touch_id,
+            pos: point!(f64::from(x) * width, f64::from(y) * height),
+            start_pos: Point::default(),
+            delta: vector!(f64::from(dx) * width, f64::from(dy) * height),
+            pressure,
+            started: now,
+            moved: false,
+            long_pressed: false,
+            consumed: false,
+        };
+        Ok(match event {
+            Event::FingerDown { .. } => Some((self.touch.start(touch), vec![])),
+            Event::FingerMotion { .. } => self.touch.motion(touch),
+            _ => self.touch.end(touch, now),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(id: i64, x: f64, y: f64, started: Instant) -> Touch {
+        Touch {
+            id,
+            device_id: 0,
+            pos: point!(x, y),
+            start_pos: Point::default(),
+            delta: Vector::default(),
+            pressure: 1.0,
+            started,
+            moved: false,
+            long_pressed: false,
+            consumed: false,
+        }
+    }
+
+    fn recognized(result: Option<(Touch, Vec<Gesture>)>) -> Vec<Gesture> {
+        result.map(|(_, gestures)| gestures).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_tap() {
+        let mut state = TouchState::default();
+        let now = Instant::now();
+        let later = now + Duration::from_millis(100);
+
+        state.start(touch(0, 10.0, 10.0, now));
+        let gestures = recognized(state.end(touch(0, 12.0, 10.0, now), later));
+        assert_eq!(
+            gestures,
+            [Gesture::Tap {
+                pos: point!(12.0, 10.0)
+            }]
+        );
+
+        state.start(touch(1, 14.0, 10.0, later));
+        let gestures = recognized(state.end(touch(1, 14.0, 10.0, later), later));
+        let pos = point!(14.0, 10.0);
+        assert_eq!(gestures, [Gesture::Tap { pos }, Gesture::DoubleTap { pos }]);
+
+        // Held too long
+        let much_later = later + TAP_TIMEOUT * 2;
+        state.start(touch(2, 14.0, 10.0, later));
+        let gestures = recognized(state.end(touch(2, 14.0, 10.0, later), much_later));
+        assert!(gestures.is_empty());
+    }
+
+    #[test]
+    fn test_long_press_and_pan() {
+        let mut state = TouchState::default();
+        let now = Instant::now();
+
+        state.start(touch(0, 10.0, 10.0, now));
+        assert_eq!(state.update(now + Duration::from_millis(100)), None);
+        let long_press = now + LONG_PRESS_TIMEOUT;
+        assert_eq!(
+            state.update(long_press),
+            Some(Gesture::LongPress {
+                pos: point!(10.0, 10.0)
+            })
+        );
+        assert_eq!(state.update(long_press), None, "only sent once");
+        state.end(touch(0, 10.0, 10.0, now), long_press);
+
+        state.start(touch(1, 10.0, 10.0, now));
+        // Within tap slop
+        let gestures = recognized(state.motion(touch(1, 15.0, 10.0, now)));
+        assert!(gestures.is_empty());
+        let gestures = recognized(state.motion(touch(1, 30.0, 10.0, now)));
+        assert_eq!(
+            gestures,
+            [Gesture::Pan {
+                pos: point!(30.0, 10.0),
+                delta: vector!(15.0, 0.0)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_consumed_touch() {
+        let mut state = TouchState::default();
+        let now = Instant::now();
+
+        let pressed = state.start(touch(0, 10.0, 10.0, now));
+        state.consume(&pressed);
+        let gestures = recognized(state.motion(touch(0, 30.0, 10.0, now)));
+        assert!(gestures.is_empty(), "no pan");
+        assert_eq!(
+            state.update(now + LONG_PRESS_TIMEOUT),
+            None,
+            "no long press"
+        );
+        let gestures = recognized(state.end(touch(0, 10.0, 10.0, now), now));
+        assert!(gestures.is_empty(), "no tap");
+
+        let pressed = state.start(touch(1, 0.0, 0.0, now));
+        state.start(touch(2, 10.0, 0.0, now));
+        state.consume(&pressed);
+        let gestures = recognized(state.motion(touch(2, 20.0, 0.0, now)));
+        assert!(gestures.is_empty(), "no pinch");
+    }
+
+    #[test]
+    fn test_touch_devices() {
+        let mut state = TouchState::default();
+        let now = Instant::now();
+        let on_device = |device_id, x| Touch {
+            device_id,
+            ..touch(0, x, 0.0, now)
+        };
+
+        // The same finger id on different devices are separate touches
+        state.start(on_device(0, 0.0));
+        let second = state.start(on_device(1, 10.0));
+        assert_eq!(state.touches.len(), 2);
+        state.consume(&second);
+        assert!(!state.touches[0].consumed && state.touches[1].consumed);
+
+        let released = state
+            .end(on_device(1, 10.0), now)
+            .map(|(touch, _)| touch.device_id());
+        assert_eq!(released, Some(1));
+        assert_eq!(state.touches.len(), 1);
+        assert_eq!(state.touches[0].device_id(), 0);
+    }
+
+    #[test]
+    fn test_pinch_and_rotate() {
+        let mut state = TouchState::default();
+        let now = Instant::now();
+
+        state.start(touch(0, 0.0, 0.0, now));
+        state.start(touch(1, 10.0, 0.0, now));
+        let gestures = recognized(state.motion(touch(1, 20.0, 0.0, now)));
+        assert_eq!(
+            gestures,
+            [Gesture::Pinch {
+                center: point!(10.0, 0.0),
+                scale: 2.0
+            }]
+        );
+        let gestures = recognized(state.motion(touch(1, 0.0, 20.0, now)));
+        assert_eq!(
+            gestures,
+            [Gesture::Rotate {
+                center: point!(0.0, 10.0),
+                angle: PI / 2.0
+            }]
+        );
+
+        // No taps after multi-touch
+        let gestures = recognized(state.end(touch(0, 0.0, 0.0, now), now));
+        assert!(gestures.is_empty());
+    }
+}
@@ -1,7 +1,12 @@
 //! Transformation functions and types.
 
+use crate::prelude::*;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::{
+    f64::consts::FRAC_PI_2,
+    ops::{Mul, MulAssign},
+};
 
 /// Enum representing which direction to flip during drawing.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -16,3 +21,363 @@ pub enum Flipped {
     /// Flip in both the horizontal and vertical directions.
     Both,
 }
+
+/// The destination, angle, center and flip of a drawn image.
+pub(crate) type ImagePlacement = (Option<Rect<i32>>, f64, Option<Point<i32>>, Option<Flipped>);
+
+/// A 2D affine transformation matrix used to translate, rotate, scale and shear drawing
+/// coordinates.
+///
+/// The matrix is stored as `[a, b, c, d, e, f]`, representing:
+///
+/// ```text
+/// | a c e |
+/// | b d f |
+/// | 0 0 1 |
+/// ```
+///
+/// Transforms are composed right-to-left, so `t1 * t2` applies `t2` first and then `t1`. The
+/// chaining methods such as [`Transform::translate`] and [`Transform::rotate`] follow the same
+/// convention as `p5.js` and Processing: each call transforms the local coordinate space, so later
+/// calls are applied to points first.
+///
+/// # Example
+///
+/// ```
+/// # use pix_engine::prelude::*;
+/// let t = Transform::identity().translate(10.0, 20.0).scale(2.0, 2.0);
+/// assert_eq!(t.transform_point([5.0, 5.0]), point!(20.0, 30.0));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[must_use]
+pub struct Transform([f64; 6]);
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform {
+    /// Constructs a `Transform` from the matrix components `[a, b, c, d, e, f]`.
+    #[inline]
+    pub const fn new(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> Self {
+        Self([a, b, c, d, e, f])
+    }
+
+    /// Constructs an identity `Transform` which leaves coordinates unchanged.
+    #[inline]
+    pub const fn identity() -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0)
+    }
+
+    /// Constructs a `Transform` that translates by `(x, y)`.
+    #[inline]
+    pub const fn translation(x: f64, y: f64) -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, x, y)
+    }
+
+    /// Constructs a `Transform` that rotates clockwise by `angle` in radians.
+    #[inline]
+    pub fn rotation(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(cos, sin, -sin, cos, 0.0, 0.0)
+    }
+
+    /// Constructs a `Transform` that scales by `(x, y)`.
+    #[inline]
+    pub const fn scaling(x: f64, y: f64) -> Self {
+        Self::new(x, 0.0, 0.0, y, 0.0, 0.0)
+    }
+
+    /// Constructs a `Transform` that shears along the x-axis by `angle` in radians.
+    #[inline]
+    pub fn shearing_x(angle: f64) -> Self {
+        Self::new(1.0, 0.0, angle.tan(), 1.0, 0.0, 0.0)
+    }
+
+    /// Constructs a `Transform` that shears along the y-axis by `angle` in radians.
+    #[inline]
+    pub fn shearing_y(angle: f64) -> Self {
+        Self::new(1.0, angle.tan(), 0.0, 1.0, 0.0, 0.0)
+    }
+
+    /// Returns the matrix components `[a, b, c, d, e, f]`.
+    #[inline]
+    #[must_use]
+    pub const fn to_array(self) -> [f64; 6] {
+        self.0
+    }
+
+    /// Returns this `Transform` translated by `(x, y)` in local coordinates.
+    #[inline]
+    pub fn translate(self, x: f64, y: f64) -> Self {
+        self * Self::translation(x, y)
+    }
+
+    /// Returns this `Transform` rotated clockwise by `angle` in radians in local coordinates.
+    #[inline]
+    pub fn rotate(self, angle: f64) -> Self {
+        self * Self::rotation(angle)
+    }
+
+    /// Returns this `Transform` scaled by `(x, y)` in local coordinates.
+    #[inline]
+    pub fn scale(self, x: f64, y: f64) -> Self {
+        self * Self::scaling(x, y)
+    }
+
+    /// Returns this `Transform` sheared along the x-axis by `angle` in radians in local
+    /// coordinates.
+    #[inline]
+    pub fn shear_x(self, angle: f64) -> Self {
+        self * Self::shearing_x(angle)
+    }
+
+    /// Returns this `Transform` sheared along the y-axis by `angle` in radians in local
+    /// coordinates.
+    #[inline]
+    pub fn shear_y(self, angle: f64) -> Self {
+        self * Self::shearing_y(angle)
+    }
+
+    /// Returns the determinant of the linear portion of the matrix.
+    #[inline]
+    #[must_use]
+    pub fn determinant(&self) -> f64 {
+        let [a, b, c, d, ..] = self.0;
+        a.mul_add(d, -(b * c))
+    }
+
+    /// Returns the inverse `Transform`, or `None` if the matrix is not invertible.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// let t = Transform::translation(10.0, 5.0).scale(2.0, 4.0);
+    /// let inverse = t.inverse().expect("invertible transform");
+    /// assert_eq!(inverse.transform_point(t.transform_point([3.0, 2.0])), point!(3.0, 2.0));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let [a, b, c, d, e, f] = self.0;
+        Some(Self::new(
+            d / det,
+            -b / det,
+            -c / det,
+            a / det,
+            c.mul_add(f, -(d * e)) / det,
+            b.mul_add(e, -(a * f)) / det,
+        ))
+    }
+
+    /// Whether this `Transform` leaves coordinates unchanged.
+    #[inline]
+    #[must_use]
+    pub fn is_identity(&self) -> bool {
+        *self == Self::identity()
+    }
+
+    /// Whether this `Transform` keeps horizontal and vertical lines axis-aligned, meaning it only
+    /// translates and scales.
+    #[inline]
+    #[must_use]
+    pub fn is_axis_aligned(&self) -> bool {
+        let [_, b, c, ..] = self.0;
+        b == 0.0 && c == 0.0
+    }
+
+    /// Returns the average scaling factor applied to lengths, which is the square root of the
+    /// absolute determinant.
+    #[inline]
+    #[must_use]
+    pub fn scale_factor(&self) -> f64 {
+        self.determinant().abs().sqrt()
+    }
+
+    /// Returns the rotation in radians if this `Transform` only translates, rotates and uniformly
+    /// scales coordinates, preserving the shape of circles and arcs.
+    #[inline]
+    #[must_use]
+    pub fn similarity_rotation(&self) -> Option<f64> {
+        const EPSILON: f64 = 1e-9;
+        let [a, b, c, d, ..] = self.0;
+        ((a - d).abs() < EPSILON && (b + c).abs() < EPSILON && self.determinant() > 0.0)
+            .then(|| b.atan2(a))
+    }
+
+    /// Transforms a [Point] by this matrix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pix_engine::prelude::*;
+    /// # use std::f64::consts::FRAC_PI_2;
+    /// let t = Transform::rotation(FRAC_PI_2);
+    /// let p = t.transform_point([10.0, 0.0]);
+    /// assert!((p.x() - 0.0).abs() < 1e-9 && (p.y() - 10.0).abs() < 1e-9);
+    /// ```
+    #[inline]
+    pub fn transform_point<P>(&self, p: P) -> Point<f64>
+    where
+        P: Into<Point<f64>>,
+    {
+        let p = p.into();
+        let [a, b, c, d, e, f] = self.0;
+        point!(
+            a.mul_add(p.x(), c.mul_add(p.y(), e)),
+            b.mul_add(p.x(), d.mul_add(p.y(), f))
+        )
+    }
+}
+
+impl Flipped {
+    /// Returns this flip with an additional flip in the `horizontal` and/or `vertical`
+    /// directions applied.
+    const fn toggle(self, horizontal: bool, vertical: bool) -> Self {
+        let (h, v) = match self {
+            Self::None => (false, false),
+            Self::Horizontal => (true, false),
+            Self::Vertical => (false, true),
+            Self::Both => (true, true),
+        };
+        match (h ^ horizontal, v ^ vertical) {
+            (false, false) => Self::None,
+            (true, false) => Self::Horizontal,
+            (false, true) => Self::Vertical,
+            (true, true) => Self::Both,
+        }
+    }
+}
+
+impl Transform {
+    /// Maps the destination of an image drawn rotated by `angle` degrees about `center` and
+    /// `flipped` through this matrix, returning the new destination, angle, center and flip.
+    /// Scaling is applied along each axis of the image and shearing is ignored. Reflections are
+    /// drawn as a horizontal or vertical flip, whichever requires the least rotation.
+    pub(crate) fn transform_image(
+        &self,
+        dst: Rect<i32>,
+        angle: f64,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+    ) -> (Rect<i32>, f64, Option<Point<i32>>, Option<Flipped>) {
+        let [a, b, c, d, ..] = self.0;
+        let (scale_x, scale_y) = (a.hypot(b), c.hypot(d));
+        let mut rotation = b.atan2(a);
+        let (mut angle, mut flip_horizontal, mut flip_vertical) = (angle, false, false);
+        if self.determinant() < 0.0 {
+            // Mirroring reverses the direction of rotation within the image
+            angle = -angle;
+            if rotation.abs() <= FRAC_PI_2 {
+                flip_vertical = true;
+            } else {
+                flip_horizontal = true;
+                rotation = (-b).atan2(-a);
+            }
+        }
+
+        let (width, height) = (f64::from(dst.width()), f64::from(dst.height()));
+        let pivot = center.map_or_else(|| point!(width / 2.0, height / 2.0), |center| center.as_());
+        let mut scaled_pivot = point!(pivot.x() * scale_x, pivot.y() * scale_y);
+        if flip_horizontal {
+            scaled_pivot.set_x(width.mul_add(scale_x, -scaled_pivot.x()));
+        }
+        if flip_vertical {
+            scaled_pivot.set_y(height.mul_add(scale_y, -scaled_pivot.y()));
+        }
+        let top_left = self.transform_point(dst.top_left().as_::<f64>() + pivot) - scaled_pivot;
+        let dst = rect![
+            top_left.x().round() as i32,
+            top_left.y().round() as i32,
+            (width * scale_x).round() as i32,
+            (height * scale_y).round() as i32
+        ];
+        let center = center.map(|_| scaled_pivot.round().as_());
+        let flipped = if flip_horizontal || flip_vertical {
+            Some(
+                flipped
+                    .unwrap_or(Flipped::None)
+                    .toggle(flip_horizontal, flip_vertical),
+            )
+        } else {
+            flipped
+        };
+        (dst, angle + rotation.to_degrees(), center, flipped)
+    }
+}
+
+impl PixState {
+    /// Maps the destination, angle, center and flip of an image through the current
+    /// [Transform]. A `None` destination, which fills the current render target, is transformed
+    /// as a rect covering the render target.
+    ///
+    /// # Errors
+    ///
+    /// If the current render target is invalid, then an error is returned.
+    pub(crate) fn transform_image(
+        &self,
+        dst: Option<Rect<i32>>,
+        angle: f64,
+        center: Option<Point<i32>>,
+        flipped: Option<Flipped>,
+    ) -> PixResult<ImagePlacement> {
+        let s = &self.settings;
+        if s.transform.is_identity() {
+            return Ok((dst, angle, center, flipped));
+        }
+        let dst = match dst {
+            Some(dst) => dst,
+            None => self.target_rect()?,
+        };
+        let (dst, angle, center, flipped) =
+            s.transform.transform_image(dst, angle, center, flipped);
+        Ok((Some(dst), angle, center, flipped))
+    }
+
+    /// Returns a rect covering the current render target in drawing coordinates, which is the
+    /// destination of images drawn without one.
+    ///
+    /// # Errors
+    ///
+    /// If the current render target is invalid, then an error is returned.
+    pub(crate) fn target_rect(&self) -> PixResult<Rect<i32>> {
+        let s = &self.settings;
+        let (width, height) = self.dimensions()?;
+        Ok(rect![
+            0,
+            0,
+            (width as f32 / s.scale_x).round() as i32,
+            (height as f32 / s.scale_y).round() as i32
+        ])
+    }
+}
+
+impl Mul for Transform {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let [a1, b1, c1, d1, e1, f1] = self.0;
+        let [a2, b2, c2, d2, e2, f2] = rhs.0;
+        Self::new(
+            a1.mul_add(a2, c1 * b2),
+            b1.mul_add(a2, d1 * b2),
+            a1.mul_add(c2, c1 * d2),
+            b1.mul_add(c2, d1 * d2),
+            a1.mul_add(e2, c1.mul_add(f2, e1)),
+            b1.mul_add(e2, d1.mul_add(f2, f1)),
+        )
+    }
+}
+
+impl MulAssign for Transform {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
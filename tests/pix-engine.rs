@@ -4,9 +4,11 @@
 //! cargo test engine -- --test-threads=1 --ignored
 //! ```
 //!
-//! This is due to `SDL2` context needing to be on the main thread.
+//! This is due to `SDL2` context needing to be on the main thread. Tests using a headless
+//! [Engine] have no such restriction.
 
-use pix_engine::prelude::*;
+use pix_engine::{prelude::*, scene::Propagation, testing::Harness};
+use std::{cell::RefCell, path::PathBuf, rc::Rc, thread, time::Duration};
 
 #[derive(Default, Debug)]
 struct App {
@@ -58,6 +60,631 @@ fn create_engine() -> PixResult<Engine> {
         .build()
 }
 
+fn create_headless_engine() -> PixResult<Engine> {
+    Engine::builder()
+        .title("pix-engine headless integration test")
+        .dimensions(32, 24)
+        .headless()
+        .build()
+}
+
+#[derive(Default, Debug)]
+struct Sketch {
+    frame: Vec<u8>,
+}
+
+impl PixEngine for Sketch {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::BLACK);
+        s.clear()?;
+        s.stroke(None);
+        s.fill(Color::RED);
+        s.rect([4, 4, 8, 8])?;
+        s.fill(Color::BLUE);
+        s.circle([24, 12, 8])?;
+        s.stroke(Color::WHITE);
+        s.line([0, 23, 31, 23])?;
+        self.frame = s.to_bytes()?;
+        s.quit();
+        Ok(())
+    }
+}
+
+#[derive(Default, Debug)]
+struct Shapes;
+
+impl PixEngine for Shapes {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::GRAY);
+        s.clear()?;
+        s.stroke(Color::WHITE);
+        s.fill(Color::RED);
+        s.rect([4, 4, 20, 12])?;
+        s.fill(Color::GREEN);
+        s.rounded_rect([28, 4, 20, 12], 4)?;
+        s.fill(Color::BLUE);
+        s.circle([60, 10, 8])?;
+        s.fill(Color::YELLOW);
+        s.ellipse([14, 32, 20, 10])?;
+        s.fill(Color::MAGENTA);
+        s.triangle([[30, 40], [48, 40], [39, 24]])?;
+        s.fill(Color::CYAN);
+        s.arc([60, 32], 8, 0, 270)?;
+        s.stroke(Color::BLACK);
+        s.stroke_weight(3);
+        s.line([4, 52, 68, 60])?;
+        Ok(())
+    }
+}
+
+struct Transforms;
+
+impl PixEngine for Transforms {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::GRAY);
+        s.clear()?;
+        s.angle_mode(AngleMode::Degrees);
+        s.rect_mode(RectMode::Center);
+        s.stroke(Color::WHITE);
+        s.push();
+        s.translate(16.0, 16.0);
+        s.rotate(45.0);
+        s.fill(Color::RED);
+        s.square([0, 0, 14])?;
+        s.pop();
+        s.push();
+        s.translate(48.0, 16.0);
+        s.scale_by(2.0, 1.0);
+        s.fill(Color::BLUE);
+        s.circle([0, 0, 6])?;
+        s.pop();
+        s.push();
+        s.translate(16.0, 48.0);
+        s.shear_x(30.0);
+        s.fill(Color::GREEN);
+        s.rect([0, 0, 16, 12])?;
+        s.pop();
+        s.translate(48.0, 48.0);
+        s.rotate(30.0);
+        s.fill(Color::YELLOW);
+        s.ellipse([0, 0, 20, 10])?;
+        s.stroke(Color::BLACK);
+        s.line([-10, 0, 10, 0])?;
+        Ok(())
+    }
+}
+
+struct MirroredImages {
+    image: Image,
+}
+
+impl MirroredImages {
+    /// A 3x2 image with a distinct color for each pixel.
+    fn new() -> PixResult<Self> {
+        let colors = [
+            Color::RED,
+            Color::LIME,
+            Color::BLUE,
+            Color::WHITE,
+            Color::BLACK,
+            Color::YELLOW,
+        ];
+        Ok(Self {
+            image: Image::from_pixels(3, 2, colors, PixelFormat::Rgba)?,
+        })
+    }
+}
+
+impl PixEngine for MirroredImages {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::GRAY);
+        s.clear()?;
+        s.angle_mode(AngleMode::Degrees);
+        s.push();
+        s.translate(8.0, 1.0);
+        s.scale_by(-1.0, 1.0);
+        s.image(&self.image, [0, 0])?;
+        s.pop();
+        s.push();
+        s.translate(10.0, 3.0);
+        s.scale_by(1.0, -1.0);
+        s.image(&self.image, [0, 0])?;
+        s.pop();
+        s.push();
+        s.translate(20.0, 5.0);
+        s.rotate(90.0);
+        s.scale_by(-1.0, 1.0);
+        let square = rect![0, 0, 2, 2];
+        s.image_transformed(&self.image, square, square, None, None, None)?;
+        s.pop();
+        Ok(())
+    }
+}
+
+struct Paths;
+
+impl PixEngine for Paths {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::GRAY);
+        s.clear()?;
+        s.stroke(Color::WHITE);
+        s.fill(Color::RED);
+        s.begin_shape();
+        s.vertex([4, 4]);
+        s.vertex([36, 4]);
+        s.bezier_vertex([44, 16], [36, 36], [4, 36]);
+        s.begin_contour();
+        s.vertex([12, 12]);
+        s.vertex([12, 26]);
+        s.vertex([26, 26]);
+        s.vertex([26, 12]);
+        s.end_contour();
+        s.end_shape(true)?;
+        s.fill(None);
+        s.stroke(Color::YELLOW);
+        s.stroke_weight(2);
+        s.begin_shape();
+        s.curve_vertex([40, 60]);
+        s.curve_vertex([44, 44]);
+        s.curve_vertex([52, 56]);
+        s.curve_vertex([60, 44]);
+        s.curve_vertex([64, 60]);
+        s.end_shape(false)?;
+        let mut path = Path::new();
+        path.vertex([0, 0])
+            .quadratic_vertex([10, -12], [20, 0])
+            .vertex([10, 8])
+            .close();
+        s.stroke_weight(1);
+        s.stroke(Color::BLACK);
+        s.fill(Color::CYAN);
+        s.translate(44.0, 20.0);
+        s.path(&path)?;
+        s.translate(-36.0, 28.0);
+        s.path(&path)?;
+        Ok(())
+    }
+}
+
+struct StrokeStyles;
+
+impl PixEngine for StrokeStyles {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::GRAY);
+        s.clear()?;
+        s.stroke(Color::WHITE);
+        s.stroke_weight(5);
+        for (y, cap) in [
+            (6, StrokeCap::Butt),
+            (16, StrokeCap::Round),
+            (26, StrokeCap::Square),
+        ] {
+            s.stroke_cap(cap);
+            s.line([8, y, 28, y])?;
+        }
+        s.fill(None);
+        s.stroke(Color::YELLOW);
+        s.stroke_weight(4);
+        for (x, join) in [
+            (40, StrokeJoin::Miter),
+            (56, StrokeJoin::Round),
+            (72, StrokeJoin::Bevel),
+        ] {
+            s.stroke_join(join);
+            s.polygon([[x, 28], [x + 6, 6], [x + 12, 28]])?;
+        }
+        s.stroke(Color::BLACK);
+        s.stroke_weight(1);
+        s.stroke_dash(&[4.0, 2.0]);
+        s.fill(Color::BLUE);
+        s.rect([6, 38, 28, 18])?;
+        s.stroke(Color::RED);
+        s.stroke_weight(3);
+        s.stroke_cap(StrokeCap::Round);
+        s.stroke_dash(&[5.0, 5.0]);
+        s.stroke_dash_offset(2.0);
+        s.fill(None);
+        s.circle([62, 48, 11])?;
+        Ok(())
+    }
+}
+
+struct Gradients;
+
+impl PixEngine for Gradients {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::BLACK);
+        s.clear()?;
+        let stops = [(0.0, Color::RED), (0.5, Color::YELLOW), (1.0, Color::BLUE)];
+        s.fill_paint(Paint::linear(
+            [4.0, 0.0],
+            [36.0, 0.0],
+            Gradient::new(ColorMode::Rgb, stops),
+        ));
+        s.rect([4, 4, 32, 24])?;
+        let hue = Gradient::new(
+            ColorMode::Hsb,
+            [
+                (0.0, hsb!(0.0, 100.0, 100.0)),
+                (1.0, hsb!(300.0, 100.0, 100.0)),
+            ],
+        );
+        s.fill_paint(Paint::linear([4.0, 0.0], [36.0, 0.0], hue.clone()));
+        s.rect([4, 32, 32, 8])?;
+        s.fill_paint(Paint::radial(
+            [60.0, 16.0],
+            12.0,
+            Gradient::new(
+                ColorMode::Rgb,
+                [(0.0, Color::WHITE), (1.0, Color::DARK_GREEN)],
+            ),
+        ));
+        s.circle([60, 16, 12])?;
+        s.fill_paint(Paint::conic([60.0, 48.0], 0.0, hue.clone()));
+        s.circle([60, 48, 12])?;
+        s.fill(None);
+        s.stroke_paint(Paint::linear([-14.0, 0.0], [14.0, 0.0], hue));
+        s.stroke_weight(3);
+        s.stroke_join(StrokeJoin::Round);
+        s.push();
+        s.translate(22.0, 52.0);
+        s.rotate(0.3);
+        s.rect_mode(RectMode::Center);
+        s.rect([0, 0, 28, 12])?;
+        s.pop();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct BlendModes {
+    image: Image,
+}
+
+impl PixEngine for BlendModes {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::BLACK);
+        s.clear()?;
+        s.stroke(None);
+        s.fill_paint(Paint::linear(
+            [0.0, 0.0],
+            [80.0, 0.0],
+            Gradient::new(ColorMode::Rgb, [(0.0, Color::BLACK), (1.0, Color::WHITE)]),
+        ));
+        s.rect([0, 0, 80, 56])?;
+        let modes = [
+            BlendMode::Multiply,
+            BlendMode::Screen,
+            BlendMode::Overlay,
+            BlendMode::Darken,
+            BlendMode::Lighten,
+            BlendMode::Difference,
+            BlendMode::Exclusion,
+            BlendMode::Subtract,
+        ];
+        for (i, mode) in modes.into_iter().enumerate() {
+            let x = 4 + 20 * (i as i32 % 4);
+            let y = 4 + 20 * (i as i32 / 4);
+            s.blend_mode(mode);
+            s.fill(rgb!(255, 128, 0));
+            s.rect([x, y, 16, 8])?;
+            s.fill(rgb!(0, 128, 255, 128));
+            s.rect([x, y + 8, 16, 8])?;
+        }
+        s.blend_mode(BlendMode::Multiply);
+        s.image(&self.image, [4, 44])?;
+        s.blend_mode(BlendMode::Screen);
+        s.image(&self.image, [44, 44])?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct Clipping {
+    image: Image,
+    texture_id: Option<TextureId>,
+}
+
+impl PixEngine for Clipping {
+    fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+        let texture_id = s.create_texture(24, 24, PixelFormat::Rgba)?;
+        s.set_texture_target(texture_id)?;
+        s.background(Color::TRANSPARENT);
+        s.clear()?;
+        s.fill(Color::WHITE);
+        s.triangle([[12, 0], [23, 23], [0, 23]])?;
+        s.clear_texture_target();
+        self.texture_id = Some(texture_id);
+        Ok(())
+    }
+
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::DARK_GRAY);
+        s.clear()?;
+        s.stroke(None);
+
+        // Nested rects intersect
+        s.push_clip(rect![4, 4, 28, 20])?;
+        s.push_clip(rect![16, 12, 28, 20])?;
+        s.fill(Color::RED);
+        s.rect([0, 0, 80, 64])?;
+        s.pop_clip()?;
+        s.fill(Color::BLUE);
+        s.rect([0, 0, 12, 12])?;
+        s.pop_clip()?;
+
+        // Shapes intersect with rects
+        s.push_clip(rect![48, 4, 20, 28])?;
+        s.push_clip(circle![64, 16, 12])?;
+        s.fill(Color::YELLOW);
+        s.rect([0, 0, 80, 64])?;
+        s.pop_clip()?;
+        s.pop_clip()?;
+
+        // Paths cut holes
+        let mut path = Path::new();
+        path.vertex([4, 36])
+            .vertex([28, 36])
+            .vertex([28, 60])
+            .vertex([4, 60]);
+        path.begin_contour()
+            .vertex([10, 42])
+            .vertex([22, 42])
+            .vertex([22, 54])
+            .vertex([10, 54])
+            .end_contour();
+        s.push_clip(&path)?;
+        s.fill(Color::GREEN);
+        s.rect([0, 0, 80, 64])?;
+        s.pop_clip()?;
+
+        // Image and texture alpha
+        s.push_clip(Clip::Image(&self.image, point![32, 36]))?;
+        s.fill(Color::WHITE);
+        s.rect([0, 0, 80, 64])?;
+        s.pop_clip()?;
+        if let Some(texture_id) = self.texture_id {
+            s.push_clip(Clip::Texture(texture_id, point![52, 36]))?;
+            s.fill(Color::MAGENTA);
+            s.rect([0, 0, 80, 64])?;
+            s.pop_clip()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default, Debug)]
+struct Pixels {
+    texture_id: Option<TextureId>,
+}
+
+impl PixEngine for Pixels {
+    fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+        let texture_id = s.create_texture(16, 16, PixelFormat::Rgba)?;
+        s.set_texture_target(texture_id)?;
+        s.background(Color::TRANSPARENT);
+        s.clear()?;
+        let pixels = s.load_pixels()?;
+        for (i, pixel) in pixels.as_mut_bytes().chunks_exact_mut(4).enumerate() {
+            let (x, y) = (i % 16, i / 16);
+            if (x / 4 + y / 4) % 2 == 0 {
+                pixel.copy_from_slice(&[255, 255, 0, 255]);
+            }
+        }
+        s.update_pixels()?;
+        s.clear_texture_target();
+        self.texture_id = Some(texture_id);
+        Ok(())
+    }
+
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::BLACK);
+        s.clear()?;
+        s.stroke(None);
+        s.fill(Color::RED);
+        s.rect([4, 4, 24, 24])?;
+        s.fill(Color::BLUE);
+        s.circle([44, 16, 12])?;
+
+        // Clipping and blending are ignored when writing pixels back
+        s.push();
+        s.clip(rect![0, 0, 8, 8])?;
+        s.blend_mode(BlendMode::Add);
+        let width = s.width()? as usize;
+        let pixels = s.load_pixels()?;
+        for (i, pixel) in pixels.as_mut_bytes().chunks_exact_mut(4).enumerate() {
+            let (x, y) = (i % width, i / width);
+            if y < 16 {
+                pixel[0] = 255 - pixel[0];
+                pixel[1] = 255 - pixel[1];
+                pixel[2] = 255 - pixel[2];
+            } else if y >= 32 {
+                pixel.copy_from_slice(&[(x * 4) as u8, 0, (y * 4) as u8, 255]);
+            }
+        }
+        s.update_pixels()?;
+        s.pop();
+
+        if let Some(texture_id) = self.texture_id {
+            s.blend_mode(BlendMode::Blend);
+            s.texture(texture_id, None, rect![44, 36, 16, 16])?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default, Debug)]
+struct Widgets {
+    checked: bool,
+    selected: usize,
+}
+
+impl PixEngine for Widgets {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.text("Widgets")?;
+        s.button("Button")?;
+        s.checkbox("Checkbox", &mut self.checked)?;
+        s.radio("Radio", &mut self.selected, 0)?;
+        Ok(())
+    }
+}
+
+#[derive(Default, Debug)]
+struct Animation {
+    elapsed: Duration,
+}
+
+impl PixEngine for Animation {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::BLACK);
+        s.clear()?;
+        s.fill(Color::WHITE);
+        let x = (s.elapsed().as_secs_f64() * 60.0).round() as i32;
+        s.square([x * 4, 4, 4])?;
+        self.elapsed = s.elapsed();
+        Ok(())
+    }
+}
+
+#[derive(Default, Debug)]
+struct FixedUpdate {
+    steps: Vec<usize>,
+    alphas: Vec<f64>,
+    fixed_updates: usize,
+}
+
+impl PixEngine for FixedUpdate {
+    fn on_fixed_update(&mut self, _s: &mut PixState) -> PixResult<()> {
+        self.fixed_updates += 1;
+        Ok(())
+    }
+
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        self.steps.push(self.fixed_updates);
+        self.alphas.push(s.interpolation_alpha());
+        self.fixed_updates = 0;
+        Ok(())
+    }
+}
+
+#[derive(Default, Debug)]
+struct Controls {
+    record: bool,
+    recording: Option<EventRecording>,
+    keys: Vec<Key>,
+    dragged: Point<i32>,
+    clicked: bool,
+    text: String,
+    value: i32,
+}
+
+impl PixEngine for Controls {
+    fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+        if self.record {
+            s.record_events();
+        }
+        Ok(())
+    }
+
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        if s.button("Click")? {
+            self.clicked = true;
+        }
+        s.text_field("Text", &mut self.text)?;
+        s.slider("Slider", &mut self.value, 0, 100)?;
+        Ok(())
+    }
+
+    fn on_stop(&mut self, s: &mut PixState) -> PixResult<()> {
+        self.recording = s.stop_recording_events();
+        Ok(())
+    }
+
+    fn on_key_pressed(&mut self, _s: &mut PixState, event: KeyEvent) -> PixResult<bool> {
+        self.keys.push(event.key);
+        Ok(false)
+    }
+
+    fn on_mouse_dragged(
+        &mut self,
+        _s: &mut PixState,
+        _pos: Point<i32>,
+        rel_pos: Point<i32>,
+    ) -> PixResult<bool> {
+        self.dragged += rel_pos;
+        Ok(false)
+    }
+}
+
+fn mouse_move(x: i32, y: i32, xrel: i32) -> Event {
+    Event::MouseMotion {
+        x,
+        y,
+        xrel,
+        yrel: 0,
+    }
+}
+
+fn mouse_down(x: i32, y: i32) -> Event {
+    Event::MouseDown {
+        button: Mouse::Left,
+        x,
+        y,
+    }
+}
+
+fn mouse_up(x: i32, y: i32) -> Event {
+    Event::MouseUp {
+        button: Mouse::Left,
+        x,
+        y,
+    }
+}
+
+fn controls_harness() -> Harness {
+    let mut harness = Harness::new(240, 120);
+    harness
+        .frames(12)
+        // Click button
+        .event(0, mouse_move(40, 20, 0))
+        .event(1, mouse_down(40, 20))
+        .event(2, mouse_up(40, 20))
+        // Focus text field and type
+        .event(3, mouse_move(120, 58, 0))
+        .event(4, mouse_down(120, 58))
+        .event(5, mouse_up(120, 58))
+        .event(
+            6,
+            Event::KeyDown {
+                key: Some(Key::H),
+                keymod: KeyMod::NONE,
+                repeat: false,
+                scan: Some(Scan::H),
+            },
+        )
+        .event(
+            6,
+            Event::TextInput {
+                text: "hi".to_string(),
+            },
+        )
+        // Drag slider
+        .event(7, mouse_move(150, 90, 0))
+        .event(8, mouse_down(150, 90))
+        .event(9, mouse_move(200, 90, 50))
+        .event(10, mouse_up(200, 90));
+    harness
+}
+
+fn golden_path(name: &str) -> String {
+    format!("{}/tests/golden/{name}.png", env!("CARGO_MANIFEST_DIR"))
+}
+
+fn pixel(frame: &[u8], x: usize, y: usize) -> [u8; 4] {
+    let i = 4 * (y * 32 + x);
+    [frame[i], frame[i + 1], frame[i + 2], frame[i + 3]]
+}
+
 #[test]
 #[ignore = "engine can only be tested in the main thread. --test-threads=1"]
 fn single_thread_engine_start() -> PixResult<()> {
@@ -101,3 +728,1406 @@ fn single_thread_engine_stop() -> PixResult<()> {
     assert_eq!(app.stop_count, 2, "on_stop was called");
     Ok(())
 }
+
+#[test]
+fn headless_engine_start() -> PixResult<()> {
+    let mut eng = create_headless_engine()?;
+    let mut app = App::new();
+    app.quit_on_start = true;
+    eng.run(&mut app)?;
+    assert_eq!(app.start_count, 1, "on_start was called");
+    assert_eq!(app.update_count, 0, "on_update was not called");
+    assert_eq!(app.stop_count, 1, "on_stop was called");
+    Ok(())
+}
+
+#[test]
+fn headless_engine_stop() -> PixResult<()> {
+    let mut eng = create_headless_engine()?;
+    let mut app = App::new();
+    app.quit_on_update = true;
+    app.abort_quit_on_stop = true;
+    eng.run(&mut app)?;
+    assert_eq!(app.start_count, 1, "on_start was called");
+    assert_eq!(app.update_count, 2, "on_update was called");
+    assert_eq!(app.stop_count, 2, "on_stop was called");
+    Ok(())
+}
+
+#[test]
+fn headless_engine_draw() -> PixResult<()> {
+    let mut eng = create_headless_engine()?;
+    let mut app = Sketch::default();
+    eng.run(&mut app)?;
+    assert_eq!(app.frame.len(), 32 * 24 * 4, "frame is RGBA");
+    assert_eq!(pixel(&app.frame, 0, 0), [0, 0, 0, 255], "background");
+    assert_eq!(pixel(&app.frame, 4, 4), [255, 0, 0, 255], "rect top-left");
+    assert_eq!(
+        pixel(&app.frame, 11, 11),
+        [255, 0, 0, 255],
+        "rect bottom-right"
+    );
+    assert_eq!(pixel(&app.frame, 12, 12), [0, 0, 0, 255], "outside rect");
+    assert_eq!(pixel(&app.frame, 24, 12), [0, 0, 255, 255], "circle center");
+    assert_eq!(pixel(&app.frame, 16, 23), [255, 255, 255, 255], "line");
+    Ok(())
+}
+
+#[test]
+fn golden_shapes() -> PixResult<()> {
+    Harness::new(72, 64).assert_golden(&mut Shapes, golden_path("shapes"))
+}
+
+struct FillOnly(fn(&mut PixState) -> PixResult<()>);
+
+impl PixEngine for FillOnly {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::BLACK);
+        s.clear()?;
+        s.stroke(None);
+        s.fill(Color::WHITE);
+        (self.0)(s)
+    }
+}
+
+#[test]
+fn rect_fill_edges() -> PixResult<()> {
+    let capture = |draw| Harness::new(16, 16).capture(&mut FillOnly(draw));
+    let rect = capture(|s| s.rect([3, 2, 9, 11]))?;
+    let quad = capture(|s| s.quad([[3, 2], [12, 2], [12, 13], [3, 13]]))?;
+    assert_eq!(
+        rect.as_bytes(),
+        quad.as_bytes(),
+        "rect matches polygon fill"
+    );
+    let filled = |x, y| rect.get_pixel(x, y) == Color::WHITE;
+    assert!(filled(3, 2) && filled(11, 12), "corners are filled");
+    assert!(
+        !filled(2, 2) && !filled(12, 12) && !filled(3, 13),
+        "edges are exclusive"
+    );
+
+    let rounded = capture(|s| s.rounded_rect([3, 2, 9, 11], 4))?;
+    for y in 0..=14 {
+        for x in 0..=14 {
+            let pixel = rounded.get_pixel(x, y);
+            assert_eq!(
+                pixel,
+                rounded.get_pixel(14 - x, y),
+                "symmetric at ({x}, {y})"
+            );
+            assert_eq!(
+                pixel,
+                rounded.get_pixel(x, 14 - y),
+                "symmetric at ({x}, {y})"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn golden_transforms() -> PixResult<()> {
+    Harness::new(64, 64).assert_golden(&mut Transforms, golden_path("transforms"))
+}
+
+#[test]
+fn golden_mirrored_images() -> PixResult<()> {
+    let mut app = MirroredImages::new()?;
+    Harness::new(24, 6).assert_golden(&mut app, golden_path("mirrored_images"))
+}
+
+#[test]
+fn draw_mirrored_images() -> PixResult<()> {
+    let mut app = MirroredImages::new()?;
+    let canvas = Harness::new(24, 6).capture(&mut app)?;
+    let row = |y, xs: std::ops::Range<u32>| xs.map(|x| canvas.get_pixel(x, y)).collect::<Vec<_>>();
+
+    // Flipped horizontally
+    assert_eq!(row(1, 5..8), [Color::BLUE, Color::LIME, Color::RED]);
+    assert_eq!(row(2, 5..8), [Color::YELLOW, Color::BLACK, Color::WHITE]);
+    // Flipped vertically
+    assert_eq!(row(1, 10..13), [Color::WHITE, Color::BLACK, Color::YELLOW]);
+    assert_eq!(row(2, 10..13), [Color::RED, Color::LIME, Color::BLUE]);
+    // Rotated and mirrored, transposing the image
+    assert_eq!(row(2, 18..20), [Color::GRAY, Color::GRAY]);
+    assert_eq!(row(3, 18..20), [Color::BLACK, Color::LIME]);
+    assert_eq!(row(4, 18..20), [Color::WHITE, Color::RED]);
+    Ok(())
+}
+
+struct FullTarget {
+    image: Image,
+}
+
+impl PixEngine for FullTarget {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::BLACK);
+        s.clear()?;
+        s.translate(2.0, 0.0);
+        s.image_transformed(&self.image, None, None, None, None, None)
+    }
+}
+
+#[test]
+fn draw_image_without_destination_transformed() -> PixResult<()> {
+    let image = Image::from_pixels(2, 1, [Color::RED, Color::BLUE], PixelFormat::Rgba)?;
+    let canvas = Harness::new(4, 2).capture(&mut FullTarget { image })?;
+    for y in 0..2 {
+        let row: Vec<_> = (0..4).map(|x| canvas.get_pixel(x, y)).collect();
+        assert_eq!(row, [Color::BLACK, Color::BLACK, Color::RED, Color::RED]);
+    }
+    Ok(())
+}
+
+#[derive(Default, Debug)]
+struct TranslatedRect {
+    frames: Vec<Image>,
+}
+
+impl PixEngine for TranslatedRect {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::BLACK);
+        s.clear()?;
+        s.stroke(None);
+        s.fill(Color::WHITE);
+        s.translate(2.0, 2.0);
+        s.rect([1, 1, 2, 2])?;
+        let (width, height) = s.dimensions()?;
+        self.frames.push(Image::from_bytes(
+            width,
+            height,
+            s.to_bytes()?,
+            PixelFormat::Rgba,
+        )?);
+        Ok(())
+    }
+}
+
+#[test]
+fn transform_resets_each_frame() -> PixResult<()> {
+    let mut app = TranslatedRect::default();
+    let _canvas = Harness::new(8, 8).frames(3).capture(&mut app)?;
+    assert_eq!(app.frames.len(), 3, "frames drawn");
+    for (i, frame) in app.frames.iter().enumerate() {
+        assert_eq!(frame.get_pixel(3, 3), Color::WHITE, "frame {i} rect");
+        assert_eq!(frame.get_pixel(4, 4), Color::WHITE, "frame {i} rect");
+        assert_eq!(frame.get_pixel(5, 5), Color::BLACK, "frame {i} outside");
+    }
+    Ok(())
+}
+
+#[test]
+fn golden_paths() -> PixResult<()> {
+    Harness::new(68, 64).assert_golden(&mut Paths, golden_path("paths"))
+}
+
+#[test]
+fn golden_stroke_styles() -> PixResult<()> {
+    Harness::new(88, 64).assert_golden(&mut StrokeStyles, golden_path("stroke_styles"))
+}
+
+#[test]
+fn golden_gradients() -> PixResult<()> {
+    Harness::new(80, 64).assert_golden(&mut Gradients, golden_path("gradients"))
+}
+
+#[test]
+fn golden_blend_modes() -> PixResult<()> {
+    let mut image = Image::rgba(32, 8);
+    for (i, pixel) in image.as_mut_bytes().chunks_exact_mut(4).enumerate() {
+        let level = (i % 32 * 8) as u8;
+        pixel.copy_from_slice(&[level, 255 - level, 128, 255]);
+    }
+    Harness::new(80, 56).assert_golden(&mut BlendModes { image }, golden_path("blend_modes"))
+}
+
+#[test]
+fn golden_clipping() -> PixResult<()> {
+    let mut image = Image::rgba(16, 24);
+    for (i, pixel) in image.as_mut_bytes().chunks_exact_mut(4).enumerate() {
+        let alpha = (i % 16 * 17) as u8;
+        pixel.copy_from_slice(&[0, 0, 0, alpha]);
+    }
+    let mut app = Clipping {
+        image,
+        texture_id: None,
+    };
+    Harness::new(80, 64).assert_golden(&mut app, golden_path("clipping"))
+}
+
+struct GrayAlphaClip {
+    mask: Image,
+}
+
+impl PixEngine for GrayAlphaClip {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::BLACK);
+        s.clear()?;
+        s.stroke(None);
+        s.fill(Color::WHITE);
+        s.push_clip(Clip::Image(&self.mask, point![1, 1]))?;
+        s.rect([0, 0, 4, 4])?;
+        s.pop_clip()
+    }
+}
+
+#[test]
+fn gray_alpha_image_clip() -> PixResult<()> {
+    let mask = Image::from_bytes(2, 1, [200, 0, 200, 255], PixelFormat::GrayAlpha)?;
+    let canvas = Harness::new(4, 4).capture(&mut GrayAlphaClip { mask })?;
+    let row: Vec<_> = (0..4).map(|x| canvas.get_pixel(x, 1)).collect();
+    assert_eq!(
+        row,
+        [Color::BLACK, Color::BLACK, Color::WHITE, Color::BLACK],
+        "clipped by the gray alpha channel"
+    );
+    assert_eq!(canvas.get_pixel(2, 0), Color::BLACK, "outside mask");
+    Ok(())
+}
+
+#[test]
+fn golden_pixels() -> PixResult<()> {
+    Harness::new(64, 56).assert_golden(&mut Pixels::default(), golden_path("pixels"))
+}
+
+#[test]
+fn golden_widgets() -> PixResult<()> {
+    Harness::new(160, 128).assert_golden(&mut Widgets::default(), golden_path("widgets"))
+}
+
+#[test]
+fn golden_deterministic_clock() -> PixResult<()> {
+    let mut app = Animation::default();
+    let mut harness = Harness::new(32, 12);
+    harness.frames(4);
+    harness.assert_golden(&mut app, golden_path("deterministic_clock"))?;
+    assert_eq!(
+        app.elapsed,
+        Duration::from_nanos(3 * 16_666_667),
+        "simulated elapsed time"
+    );
+    Ok(())
+}
+
+#[test]
+fn fixed_timestep() -> PixResult<()> {
+    let mut app = FixedUpdate::default();
+    let mut harness = Harness::new(4, 4);
+    harness.frames(3).delta_time(Duration::from_millis(25));
+    harness.engine().fixed_timestep(Duration::from_millis(10));
+    let _canvas = harness.capture(&mut app)?;
+    assert_eq!(app.steps, [2, 3, 2], "fixed updates per frame");
+    assert_eq!(app.alphas, [0.5, 0.0, 0.5], "interpolation alpha");
+
+    // Excess time beyond the maximum steps per frame is discarded
+    let mut app = FixedUpdate::default();
+    let mut harness = Harness::new(4, 4);
+    harness.frames(2).delta_time(Duration::from_millis(105));
+    harness
+        .engine()
+        .fixed_timestep(Duration::from_millis(10))
+        .max_fixed_steps(4);
+    let _canvas = harness.capture(&mut app)?;
+    assert_eq!(app.steps, [4, 4], "capped fixed updates per frame");
+    assert_eq!(app.alphas, [0.5, 0.0], "interpolation alpha");
+    Ok(())
+}
+
+#[derive(Default, Debug)]
+struct Repushed {
+    pressed: usize,
+    pressed_per_frame: Vec<usize>,
+}
+
+impl PixEngine for Repushed {
+    fn on_update(&mut self, _s: &mut PixState) -> PixResult<()> {
+        self.pressed_per_frame.push(self.pressed);
+        self.pressed = 0;
+        Ok(())
+    }
+
+    fn on_key_pressed(&mut self, s: &mut PixState, event: KeyEvent) -> PixResult<bool> {
+        self.pressed += 1;
+        // Pushed events are handled on the next frame instead of looping forever
+        s.push_event(Event::KeyDown {
+            key: Some(event.key),
+            keymod: event.keymod,
+            repeat: event.repeat,
+            scan: Some(event.scan),
+        });
+        Ok(true)
+    }
+}
+
+#[test]
+fn events_pushed_while_handling_wait_a_frame() -> PixResult<()> {
+    let mut app = Repushed::default();
+    let _canvas = Harness::new(4, 4)
+        .event(
+            0,
+            Event::KeyDown {
+                key: Some(Key::A),
+                keymod: KeyMod::NONE,
+                repeat: false,
+                scan: Some(Scan::A),
+            },
+        )
+        .frames(3)
+        .capture(&mut app)?;
+    assert_eq!(app.pressed_per_frame, [1, 1, 1], "key presses per frame");
+    Ok(())
+}
+
+#[test]
+fn scripted_events() -> PixResult<()> {
+    let mut app = Controls::default();
+    controls_harness().assert_golden(&mut app, golden_path("controls"))?;
+    assert!(app.clicked, "button clicked");
+    assert_eq!(app.text, "hi", "text field typed");
+    assert_eq!(app.keys, [Key::H], "key pressed");
+    assert_eq!(app.dragged, point!(50, 0), "mouse dragged");
+    assert!(app.value > 0, "slider dragged");
+    Ok(())
+}
+
+#[test]
+fn record_and_replay_events() -> PixResult<()> {
+    let mut recorded = Controls {
+        record: true,
+        ..Controls::default()
+    };
+    let expected = controls_harness().capture(&mut recorded)?;
+    let recording = recorded.recording.take().expect("events were recorded");
+    assert_eq!(recording.len(), 12, "all events recorded");
+
+    let mut replayed = Controls::default();
+    let actual = Harness::new(240, 120)
+        .frames(12)
+        .replay(recording)
+        .capture(&mut replayed)?;
+    assert!(replayed.clicked, "button clicked");
+    assert_eq!(replayed.text, recorded.text, "text field typed");
+    assert_eq!(replayed.keys, recorded.keys, "key pressed");
+    assert_eq!(replayed.dragged, recorded.dragged, "mouse dragged");
+    assert_eq!(replayed.value, recorded.value, "slider dragged");
+    assert_eq!(actual.as_bytes(), expected.as_bytes(), "frames match");
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn save_and_load_event_recording() -> PixResult<()> {
+    let mut recording = EventRecording::new();
+    recording.push(0, mouse_move(10, 20, 0));
+    recording.push(2, Event::Quit);
+    recording.push(1, mouse_down(10, 20));
+    let path = std::env::temp_dir().join("pix_engine_event_recording.json");
+    recording.save(&path)?;
+    let loaded = EventRecording::from_file(&path)?;
+    std::fs::remove_file(&path)?;
+    assert_eq!(loaded, recording);
+    assert_eq!(loaded.last_frame(), Some(2));
+    Ok(())
+}
+
+#[derive(Debug)]
+struct Recorded {
+    path: std::path::PathBuf,
+    format: RecordingFormat,
+}
+
+impl PixEngine for Recorded {
+    fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.start_recording(&self.path, self.format)
+    }
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        // Change color every frame, only every other frame is recorded at 30 frames per second
+        s.background([Color::RED, Color::LIME, Color::BLUE][s.frame_count() % 3]);
+        s.clear()
+    }
+}
+
+#[test]
+fn record_canvas_animation() -> PixResult<()> {
+    for (format, extension) in [
+        (RecordingFormat::Apng, "png"),
+        (RecordingFormat::Gif, "gif"),
+    ] {
+        let path = std::env::temp_dir().join(format!("pix_engine_recording.{extension}"));
+        let mut app = Recorded {
+            path: path.clone(),
+            format,
+        };
+        let _canvas = Harness::new(4, 4).frames(6).capture(&mut app)?;
+        let recording = AnimatedImage::from_file(&path)?;
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(recording.dimensions(), (4, 4), "{format:?} dimensions");
+        assert_eq!(recording.playback(), Playback::Loop, "{format:?} playback");
+        let colors: Vec<_> = recording
+            .frames()
+            .iter()
+            .map(|frame| frame.image().get_pixel(0, 0))
+            .collect();
+        assert_eq!(
+            colors,
+            [Color::RED, Color::BLUE, Color::LIME],
+            "{format:?} frames"
+        );
+        let delay = recording.duration().as_millis();
+        assert!(
+            (99..=101).contains(&delay),
+            "{format:?} duration: {delay}ms"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn record_canvas_sequence() -> PixResult<()> {
+    let path = std::env::temp_dir().join("pix_engine_recording_sequence");
+    let mut app = Recorded {
+        path: path.clone(),
+        format: RecordingFormat::PngSequence,
+    };
+    // 15 frames per second renders each frame twice at 30 frames per second
+    let _canvas = Harness::new(4, 4)
+        .frames(3)
+        .delta_time(Duration::from_secs(1) / 15)
+        .capture(&mut app)?;
+    let colors = (1..=6)
+        .map(|frame| {
+            let image = Image::from_file(path.join(format!("frame_{frame:05}.png")))?;
+            Ok(image.get_pixel(0, 0))
+        })
+        .collect::<PixResult<Vec<_>>>()?;
+    let extra = path.join("frame_00007.png").exists();
+    std::fs::remove_dir_all(&path)?;
+
+    assert_eq!(
+        colors,
+        [
+            Color::RED,
+            Color::RED,
+            Color::LIME,
+            Color::LIME,
+            Color::BLUE,
+            Color::BLUE
+        ]
+    );
+    assert!(!extra, "no extra frames");
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct FailedRecording {
+    path: std::path::PathBuf,
+    updates: usize,
+    recording: Vec<bool>,
+    stopped: bool,
+}
+
+impl PixEngine for FailedRecording {
+    fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.start_recording(&self.path, RecordingFormat::PngSequence)
+    }
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        // Writing subsequent frames fails once the output directory is removed
+        if self.updates == 1 {
+            std::fs::remove_dir_all(&self.path)?;
+        }
+        self.updates += 1;
+        self.recording.push(s.is_recording());
+        Ok(())
+    }
+    fn on_stop(&mut self, _s: &mut PixState) -> PixResult<()> {
+        self.stopped = true;
+        Ok(())
+    }
+}
+
+#[test]
+fn record_canvas_error_stops_recording() -> PixResult<()> {
+    let mut app = FailedRecording {
+        path: std::env::temp_dir().join("pix_engine_failed_recording"),
+        ..FailedRecording::default()
+    };
+    let _canvas = Harness::new(4, 4)
+        .frames(3)
+        .delta_time(Duration::from_secs(1) / 15)
+        .capture(&mut app)?;
+
+    assert_eq!(app.updates, 3, "updates continue after a recording error");
+    assert_eq!(app.recording, [true, true, false]);
+    assert!(app.stopped, "on_stop called");
+    Ok(())
+}
+
+struct Sprites {
+    sheet: SpriteSheet,
+    animation: pix_engine::sprite::Animation,
+}
+
+impl PixEngine for Sprites {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::BLACK);
+        s.clear()?;
+        s.image_mode(ImageMode::Center);
+        s.image_tint(None);
+        s.sprite(&self.sheet, 0, [2, 2])?;
+        // Advances to sprite 0 after the first frame
+        self.animation.update(s.delta_time());
+        s.animation(&self.sheet, &self.animation, [6, 2])?;
+        s.image_tint(Color::LIME);
+        s.sprite_transformed(&self.sheet, 1, rect![10, 2, 2, 2], None, None, None)
+    }
+}
+
+#[test]
+fn draw_sprites() -> PixResult<()> {
+    // Sprite 0 is red on the left and blue on the right, sprite 1 is white
+    let mut image = Image::rgba(4, 2);
+    for y in 0..2 {
+        image.set_pixel(0, y, Color::RED);
+        image.set_pixel(1, y, Color::BLUE);
+        image.set_pixel(2, y, Color::WHITE);
+        image.set_pixel(3, y, Color::WHITE);
+    }
+    let sheet = SpriteSheet::from_grid(image, 2, 2, 2, 1)?;
+    let mut animation = pix_engine::sprite::Animation::new();
+    animation.insert_clip(
+        "idle",
+        AnimationClip::new([1, 0], Duration::from_millis(10)),
+    );
+    animation.play("idle")?;
+    animation.set_flipped(Flipped::Horizontal);
+    let mut app = Sprites { sheet, animation };
+    let canvas = Harness::new(12, 4).frames(2).capture(&mut app)?;
+
+    let row: Vec<_> = (0..12).map(|x| canvas.get_pixel(x, 1)).collect();
+    assert_eq!(
+        row,
+        [
+            Color::BLACK,
+            Color::RED,
+            Color::BLUE,
+            Color::BLACK,
+            Color::BLACK,
+            Color::BLUE,
+            Color::RED,
+            Color::BLACK,
+            Color::BLACK,
+            Color::LIME,
+            Color::LIME,
+            Color::BLACK,
+        ]
+    );
+    Ok(())
+}
+
+#[derive(Default)]
+struct Atlas {
+    images: Vec<Image>,
+    atlas: TextureAtlas,
+    regions: Vec<AtlasRegion>,
+    pages: usize,
+}
+
+impl PixEngine for Atlas {
+    fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+        self.regions = s.pack_images(&mut self.atlas, &self.images)?;
+        self.pages = self.atlas.textures().count();
+        Ok(())
+    }
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::BLACK);
+        s.clear()?;
+        for (i, region) in self.regions.iter().enumerate() {
+            let dst = rect![i as i32 * 4, 0, 4, 4];
+            s.texture(region.texture_id(), region.rect(), dst)?;
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn pack_texture_atlas() -> PixResult<()> {
+    let colors = [Color::RED, Color::LIME, Color::BLUE, Color::WHITE];
+    let images = colors
+        .iter()
+        .map(|&color| Image::from_pixels(4, 4, [color; 16], PixelFormat::Rgb))
+        .collect::<PixResult<Vec<_>>>()?;
+    // Only 2 padded 4x4 images fit in each 10x5 page
+    let mut app = Atlas {
+        images,
+        atlas: TextureAtlas::new(10, 5),
+        ..Atlas::default()
+    };
+    let canvas = Harness::new(16, 4).capture(&mut app)?;
+
+    assert_eq!(app.pages, 2);
+    let regions: Vec<_> = app.regions.iter().map(AtlasRegion::rect).collect();
+    assert_eq!(
+        regions,
+        [
+            rect![0, 0, 4, 4],
+            rect![5, 0, 4, 4],
+            rect![0, 0, 4, 4],
+            rect![5, 0, 4, 4]
+        ]
+    );
+    for (i, color) in colors.into_iter().enumerate() {
+        let x = i as u32 * 4;
+        assert_eq!(canvas.get_pixel(x, 0), color, "region {i}");
+        assert_eq!(canvas.get_pixel(x + 3, 3), color, "region {i}");
+    }
+
+    let mut app = Atlas {
+        images: vec![Image::new(11, 1)],
+        atlas: TextureAtlas::new(10, 5),
+        ..Atlas::default()
+    };
+    assert!(Harness::new(16, 4).capture(&mut app).is_err());
+    Ok(())
+}
+
+struct Tiles {
+    map: Tilemap,
+}
+
+impl PixEngine for Tiles {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::BLACK);
+        s.clear()?;
+        // Draw the map 2 pixels to the right, culling the last tile
+        let camera = Camera2D::new(6, 2);
+        s.translate(2.0, 0.0);
+        s.begin_camera(&camera);
+        s.tilemap(&self.map, camera.view())?;
+        s.end_camera();
+        Ok(())
+    }
+}
+
+#[test]
+fn draw_tilemap() -> PixResult<()> {
+    // A 2x2 tile with red, lime, blue and white pixels, and a hidden 1x1 tile
+    let image = Image::from_pixels(
+        3,
+        2,
+        [
+            Color::RED,
+            Color::LIME,
+            Color::YELLOW,
+            Color::BLUE,
+            Color::WHITE,
+            Color::YELLOW,
+        ],
+        PixelFormat::Rgb,
+    )?;
+    let sheet = SpriteSheet::new(image, [rect![0, 0, 2, 2], rect![2, 0, 1, 1]]);
+    let mut map = Tilemap::new(4, 1, 2, 2)?;
+    map.add_tileset(Tileset::new(sheet));
+    let layer = map.add_layer("tiles");
+    let tiles = [
+        Tile::new(0, 0),
+        Tile {
+            transposed: true,
+            ..Tile::new(0, 0)
+        },
+        // Rotated a quarter turn clockwise
+        Tile {
+            flipped: Some(Flipped::Horizontal),
+            transposed: true,
+            ..Tile::new(0, 0)
+        },
+        // Culled
+        Tile {
+            flipped: Some(Flipped::Horizontal),
+            ..Tile::new(0, 0)
+        },
+    ];
+    for (x, tile) in tiles.into_iter().enumerate() {
+        map.layer_mut(layer)?.set_tile(x as u32, 0, tile)?;
+    }
+    let hidden = map.add_layer("hidden");
+    map.layer_mut(hidden)?.set_tile(0, 0, Tile::new(0, 1))?;
+    map.layer_mut(hidden)?.set_visible(false);
+
+    let canvas = Harness::new(10, 2).capture(&mut Tiles { map })?;
+    let (black, red, lime, blue, white) = (
+        Color::BLACK,
+        Color::RED,
+        Color::LIME,
+        Color::BLUE,
+        Color::WHITE,
+    );
+    let rows: Vec<Vec<_>> = (0..2)
+        .map(|y| (0..10).map(|x| canvas.get_pixel(x, y)).collect())
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            [black, black, red, lime, red, blue, blue, red, black, black],
+            [black, black, blue, white, lime, white, white, lime, black, black],
+        ]
+    );
+    Ok(())
+}
+
+struct Zoomed {
+    camera: Camera2D,
+    image: Image,
+}
+
+impl PixEngine for Zoomed {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        s.background(Color::BLACK);
+        s.clear()?;
+        s.stroke(None);
+        s.fill(Color::BLUE);
+        s.begin_camera(&self.camera);
+        s.image(&self.image, [0, 0])?;
+        s.rect([2, 2, 2, 2])?;
+        s.end_camera();
+        // Drawn in screen coordinates
+        s.fill(Color::WHITE);
+        s.rect([7, 0, 1, 1])
+    }
+}
+
+#[test]
+fn draw_through_camera() -> PixResult<()> {
+    // Shows the world area from (0, 0) to (4, 4) at twice the size
+    let mut camera = Camera2D::new(8, 8);
+    camera.set_zoom(2.0);
+    camera.set_position([2.0, 2.0]);
+    let image = Image::from_pixels(2, 2, [Color::RED; 4], PixelFormat::Rgb)?;
+    let canvas = Harness::new(8, 8).capture(&mut Zoomed { camera, image })?;
+
+    assert_eq!(canvas.get_pixel(0, 0), Color::RED);
+    assert_eq!(canvas.get_pixel(3, 3), Color::RED);
+    assert_eq!(canvas.get_pixel(4, 4), Color::BLUE);
+    assert_eq!(canvas.get_pixel(7, 7), Color::BLUE);
+    assert_eq!(canvas.get_pixel(5, 2), Color::BLACK);
+    assert_eq!(canvas.get_pixel(7, 0), Color::WHITE);
+    assert_eq!(camera.screen_to_world([6, 6]), point!(3.0, 3.0));
+    Ok(())
+}
+
+struct Logged {
+    name: &'static str,
+    color: Color,
+    transitions: Vec<Transition>,
+    handles_keys: bool,
+    log: Rc<RefCell<Vec<String>>>,
+}
+
+impl Logged {
+    fn new(name: &'static str, color: Color, log: &Rc<RefCell<Vec<String>>>) -> Self {
+        Self {
+            name,
+            color,
+            transitions: vec![],
+            handles_keys: false,
+            log: Rc::clone(log),
+        }
+    }
+
+    fn then(mut self, transition: Transition) -> Self {
+        self.transitions.insert(0, transition);
+        self
+    }
+
+    fn log(&self, hook: &str) {
+        self.log.borrow_mut().push(format!("{}:{hook}", self.name));
+    }
+}
+
+impl Scene for Logged {
+    fn on_enter(&mut self, _s: &mut PixState) -> PixResult<()> {
+        self.log("enter");
+        Ok(())
+    }
+
+    fn on_exit(&mut self, _s: &mut PixState) -> PixResult<()> {
+        self.log("exit");
+        Ok(())
+    }
+
+    fn on_pause(&mut self, _s: &mut PixState) -> PixResult<()> {
+        self.log("pause");
+        Ok(())
+    }
+
+    fn on_resume(&mut self, _s: &mut PixState) -> PixResult<()> {
+        self.log("resume");
+        Ok(())
+    }
+
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<Transition> {
+        self.log("update");
+        s.background(self.color);
+        s.clear()?;
+        Ok(self.transitions.pop().unwrap_or_default())
+    }
+
+    fn on_key_pressed(&mut self, _s: &mut PixState, _event: KeyEvent) -> PixResult<bool> {
+        self.log("key");
+        Ok(self.handles_keys)
+    }
+}
+
+#[test]
+fn scene_transitions() -> PixResult<()> {
+    let log = Rc::new(RefCell::new(vec![]));
+    let c = Logged::new("c", Color::BLUE, &log).then(Transition::Pop);
+    let b = Logged::new("b", Color::LIME, &log).then(Transition::Replace(Box::new(c)));
+    let a = Logged::new("a", Color::RED, &log)
+        .then(Transition::Push(Box::new(b)))
+        .then(Transition::None)
+        .then(Transition::Pop);
+    let mut scenes = SceneStack::new(a);
+    let _canvas = Harness::new(4, 4).frames(8).capture(&mut scenes)?;
+
+    assert!(scenes.is_empty(), "all scenes popped");
+    assert_eq!(
+        *log.borrow(),
+        [
+            "a:enter", "a:update", "a:pause", "b:enter", "b:update", "b:exit", "c:enter",
+            "c:update", "c:exit", "a:resume", "a:update", "a:update", "a:exit"
+        ],
+        "scene hooks"
+    );
+    Ok(())
+}
+
+#[test]
+fn scene_event_propagation() -> PixResult<()> {
+    for (propagation, expected) in [
+        (Propagation::TopOnly, &["b:key"][..]),
+        (Propagation::Stack, &["b:key", "a:key"][..]),
+    ] {
+        let log = Rc::new(RefCell::new(vec![]));
+        let b = Logged::new("b", Color::LIME, &log);
+        let a = Logged::new("a", Color::RED, &log).then(Transition::Push(Box::new(b)));
+        let mut scenes = SceneStack::new(a);
+        scenes.set_propagation(propagation);
+        let _canvas = Harness::new(4, 4)
+            .event(
+                1,
+                Event::KeyDown {
+                    key: Some(Key::Space),
+                    keymod: KeyMod::NONE,
+                    repeat: false,
+                    scan: Some(Scan::Space),
+                },
+            )
+            .frames(2)
+            .capture(&mut scenes)?;
+        let keys = log
+            .borrow()
+            .iter()
+            .filter(|hook| hook.ends_with(":key"))
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(keys, expected, "{propagation:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn scene_fade() -> PixResult<()> {
+    let capture = |frames| -> PixResult<Color> {
+        let log = Rc::new(RefCell::new(vec![]));
+        let b = Logged::new("b", Color::BLUE, &log);
+        let a = Logged::new("a", Color::RED, &log).then(Transition::Push(Box::new(b)));
+        let mut scenes = SceneStack::new(a);
+        scenes.set_fade(Duration::from_millis(40), Color::WHITE);
+        let canvas = Harness::new(4, 4)
+            .delta_time(Duration::from_millis(10))
+            .frames(frames)
+            .capture(&mut scenes)?;
+        Ok(canvas.get_pixel(0, 0))
+    };
+
+    // Fading out over `a`
+    let [r, g, b, _] = capture(2)?.channels();
+    assert_eq!(r, 255);
+    assert!((120..=135).contains(&g) && g == b, "half faded: {g}");
+    // Fully faded, `b` is pushed
+    assert_eq!(capture(3)?, Color::WHITE);
+    // Fading in over `b`
+    let [r, g, b, _] = capture(4)?.channels();
+    assert_eq!(b, 255);
+    assert!((120..=135).contains(&r) && r == g, "half faded: {r}");
+    assert_eq!(capture(5)?, Color::BLUE);
+    Ok(())
+}
+
+#[test]
+fn scene_fade_instant() -> PixResult<()> {
+    for duration in [Duration::ZERO, Duration::from_nanos(1)] {
+        let log = Rc::new(RefCell::new(vec![]));
+        let b = Logged::new("b", Color::BLUE, &log);
+        let a = Logged::new("a", Color::RED, &log).then(Transition::Push(Box::new(b)));
+        let mut scenes = SceneStack::new(a);
+        scenes.set_fade(duration, Color::WHITE);
+        let canvas = Harness::new(4, 4)
+            .delta_time(Duration::ZERO)
+            .frames(2)
+            .capture(&mut scenes)?;
+        assert!(!scenes.is_fading(), "{duration:?} fade finished");
+        assert_eq!(canvas.get_pixel(0, 0), Color::BLUE, "{duration:?} fade");
+    }
+    Ok(())
+}
+
+#[derive(Default, Debug)]
+struct Actions {
+    pressed: Vec<bool>,
+    down: Vec<bool>,
+    axis: Vec<f64>,
+    rebound: bool,
+}
+
+impl PixEngine for Actions {
+    fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+        let mut input = InputMap::new();
+        input
+            .bind_action("jump", Input::Key(Key::Space))
+            .bind_axis(
+                "move_x",
+                AxisBinding::Digital {
+                    negative: Input::Key(Key::A),
+                    positive: Input::Key(Key::D),
+                },
+            )
+            .bind_axis("move_x", AxisBinding::Analog(Axis::LeftX));
+        s.set_input_map(input);
+        Ok(())
+    }
+
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        self.pressed.push(s.action_pressed("jump"));
+        self.down.push(s.action_down("jump"));
+        self.axis.push(s.axis_value("move_x"));
+        if s.frame_count() == 2 {
+            s.listen_for_input();
+        } else if let Some(input) = s.take_listened_input() {
+            s.input_map_mut().rebind_action("jump", input);
+            self.rebound = true;
+        }
+        Ok(())
+    }
+
+    fn on_key_pressed(&mut self, _s: &mut PixState, _event: KeyEvent) -> PixResult<bool> {
+        // Input state is tracked even for handled events
+        Ok(true)
+    }
+}
+
+fn key_event(key: Key, scan: Scan, down: bool) -> Event {
+    if down {
+        Event::KeyDown {
+            key: Some(key),
+            keymod: KeyMod::NONE,
+            repeat: false,
+            scan: Some(scan),
+        }
+    } else {
+        Event::KeyUp {
+            key: Some(key),
+            keymod: KeyMod::NONE,
+            repeat: false,
+            scan: Some(scan),
+        }
+    }
+}
+
+#[test]
+fn input_actions() -> PixResult<()> {
+    let mut app = Actions::default();
+    let _canvas = Harness::new(4, 4)
+        .event(0, key_event(Key::Space, Scan::Space, true))
+        .event(0, key_event(Key::D, Scan::D, true))
+        .event(1, key_event(Key::D, Scan::D, false))
+        .event(
+            1,
+            Event::ControllerAxisMotion {
+                controller_id: 0,
+                axis: Axis::LeftX,
+                value: i16::MIN,
+            },
+        )
+        .event(2, key_event(Key::Space, Scan::Space, false))
+        .event(3, key_event(Key::J, Scan::J, true))
+        .frames(5)
+        .capture(&mut app)?;
+
+    assert_eq!(app.pressed, [true, false, false, false, false], "pressed");
+    // Held `J` is captured while listening and rebound to jump
+    assert_eq!(app.down, [true, true, false, false, true], "down");
+    assert_eq!(app.axis, [1.0, -1.0, -1.0, -1.0, -1.0], "axis");
+    assert!(app.rebound, "jump rebound");
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn input_map_save_and_load() -> PixResult<()> {
+    let path = std::env::temp_dir().join("pix_engine_input_map.json");
+    let mut input = InputMap::new();
+    input
+        .bind_action("fire", Input::Mouse(Mouse::Left))
+        .bind_action(
+            "fire",
+            Input::Axis(Axis::TriggerRight, AxisDirection::Positive),
+        )
+        .bind_axis("look_y", AxisBinding::Analog(Axis::RightY));
+    input.save(&path)?;
+    let loaded = InputMap::from_file(&path);
+    std::fs::remove_file(&path)?;
+    assert_eq!(loaded?, input);
+    Ok(())
+}
+
+#[derive(Default, Debug)]
+struct Touches {
+    hooks: Vec<String>,
+    gestures: Vec<Gesture>,
+    touches: Vec<usize>,
+    consume: bool,
+}
+
+impl PixEngine for Touches {
+    fn on_update(&mut self, s: &mut PixState) -> PixResult<()> {
+        self.touches.push(s.touches().len());
+        Ok(())
+    }
+
+    fn on_touch_start(&mut self, _s: &mut PixState, touch: Touch) -> PixResult<bool> {
+        self.hooks.push(format!("start {:?}", touch.pos()));
+        Ok(self.consume)
+    }
+
+    fn on_touch_move(&mut self, _s: &mut PixState, touch: Touch) -> PixResult<bool> {
+        self.hooks.push(format!("move {:?}", touch.pos()));
+        Ok(false)
+    }
+
+    fn on_touch_end(&mut self, _s: &mut PixState, touch: Touch) -> PixResult<bool> {
+        self.hooks.push(format!("end {:?}", touch.pos()));
+        Ok(false)
+    }
+
+    fn on_gesture(&mut self, _s: &mut PixState, gesture: Gesture) -> PixResult<bool> {
+        self.gestures.push(gesture);
+        Ok(true)
+    }
+}
+
+fn finger_down(finger_id: i64, x: f32, y: f32) -> Event {
+    Event::FingerDown {
+        touch_id: 1,
+        finger_id,
+        x,
+        y,
+        dx: 0.0,
+        dy: 0.0,
+        pressure: 1.0,
+    }
+}
+
+fn finger_motion(finger_id: i64, x: f32, y: f32) -> Event {
+    Event::FingerMotion {
+        touch_id: 1,
+        finger_id,
+        x,
+        y,
+        dx: 0.0,
+        dy: 0.0,
+        pressure: 1.0,
+    }
+}
+
+fn finger_up(finger_id: i64, x: f32, y: f32) -> Event {
+    Event::FingerUp {
+        touch_id: 1,
+        finger_id,
+        x,
+        y,
+        dx: 0.0,
+        dy: 0.0,
+        pressure: 1.0,
+    }
+}
+
+#[test]
+fn touch_gestures() -> PixResult<()> {
+    let mut app = Touches::default();
+    let _canvas = Harness::new(100, 100)
+        // Tap
+        .event(0, finger_down(0, 0.5, 0.5))
+        .event(1, finger_up(0, 0.5, 0.5))
+        // Pan
+        .event(2, finger_down(1, 0.25, 0.25))
+        .event(3, finger_motion(1, 0.5, 0.25))
+        .event(4, finger_up(1, 0.5, 0.25))
+        // Pinch
+        .event(5, finger_down(2, 0.25, 0.5))
+        .event(5, finger_down(3, 0.75, 0.5))
+        .event(6, finger_motion(3, 0.5, 0.5))
+        .event(7, finger_up(2, 0.25, 0.5))
+        .event(7, finger_up(3, 0.5, 0.5))
+        // Long press after 500ms
+        .event(8, finger_down(4, 0.125, 0.125))
+        .frames(40)
+        .capture(&mut app)?;
+
+    assert_eq!(
+        app.hooks[..3],
+        [
+            "start Point([50.0, 50.0])",
+            "end Point([50.0, 50.0])",
+            "start Point([25.0, 25.0])"
+        ]
+    );
+    assert_eq!(
+        app.gestures,
+        [
+            Gesture::Tap {
+                pos: point!(50.0, 50.0)
+            },
+            Gesture::Pan {
+                pos: point!(50.0, 25.0),
+                delta: vector!(25.0, 0.0)
+            },
+            Gesture::Pinch {
+                center: point!(37.5, 50.0),
+                scale: 0.5
+            },
+            Gesture::LongPress {
+                pos: point!(12.5, 12.5)
+            },
+        ]
+    );
+    assert_eq!(
+        app.touches[..9],
+        [1, 0, 1, 1, 0, 2, 2, 0, 1],
+        "active touches"
+    );
+    Ok(())
+}
+
+#[test]
+fn consumed_touch() -> PixResult<()> {
+    let mut app = Touches {
+        consume: true,
+        ..Touches::default()
+    };
+    let _canvas = Harness::new(100, 100)
+        // Tap
+        .event(0, finger_down(0, 0.5, 0.5))
+        .event(1, finger_up(0, 0.5, 0.5))
+        // Pan
+        .event(2, finger_down(1, 0.25, 0.25))
+        .event(3, finger_motion(1, 0.5, 0.25))
+        .event(4, finger_up(1, 0.5, 0.25))
+        // Long press after 500ms
+        .event(5, finger_down(2, 0.125, 0.125))
+        .frames(40)
+        .capture(&mut app)?;
+
+    assert_eq!(app.hooks.len(), 6, "touch hooks called");
+    assert!(app.gestures.is_empty(), "no gestures: {:?}", app.gestures);
+    assert_eq!(app.touches[..6], [1, 0, 1, 1, 0, 1], "active touches");
+    Ok(())
+}
+
+#[derive(Default)]
+struct Drops {
+    files: Vec<(WindowId, PathBuf)>,
+    events: Vec<Event>,
+}
+
+impl PixEngine for Drops {
+    fn on_update(&mut self, _s: &mut PixState) -> PixResult<()> {
+        Ok(())
+    }
+
+    fn on_file_dropped(
+        &mut self,
+        _s: &mut PixState,
+        window_id: WindowId,
+        path: &std::path::Path,
+    ) -> PixResult<bool> {
+        self.files.push((window_id, path.to_path_buf()));
+        Ok(true)
+    }
+
+    fn on_event(&mut self, _s: &mut PixState, event: &Event) -> PixResult<bool> {
+        if matches!(
+            event,
+            Event::DropBegin { .. } | Event::DropText { .. } | Event::DropComplete { .. }
+        ) {
+            self.events.push(event.clone());
+        }
+        Ok(false)
+    }
+}
+
+#[test]
+fn file_dropped() -> PixResult<()> {
+    let mut app = Drops::default();
+    let _canvas = Harness::new(10, 10)
+        .event(0, Event::DropBegin { window_id: 0 })
+        .event(
+            0,
+            Event::DropFile {
+                window_id: 0,
+                path: PathBuf::from("assets/a.png"),
+            },
+        )
+        .event(
+            0,
+            Event::DropFile {
+                window_id: 0,
+                path: PathBuf::from("assets/b.png"),
+            },
+        )
+        .event(
+            0,
+            Event::DropText {
+                window_id: 0,
+                text: "hello".into(),
+            },
+        )
+        .event(0, Event::DropComplete { window_id: 0 })
+        .frames(1)
+        .capture(&mut app)?;
+
+    assert_eq!(
+        app.files,
+        [
+            (WindowId::default(), PathBuf::from("assets/a.png")),
+            (WindowId::default(), PathBuf::from("assets/b.png")),
+        ]
+    );
+    assert_eq!(
+        app.events,
+        [
+            Event::DropBegin { window_id: 0 },
+            Event::DropText {
+                window_id: 0,
+                text: "hello".into()
+            },
+            Event::DropComplete { window_id: 0 },
+        ]
+    );
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum Loaded {
+    Level(usize),
+    Done,
+}
+
+#[derive(Default)]
+struct UserEvents {
+    sender: Option<EventSender<Loaded>>,
+    received: Vec<(usize, String)>,
+}
+
+impl PixEngine for UserEvents {
+    fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+        let sender = s.event_sender::<Loaded>();
+        let worker = sender.clone();
+        thread::spawn(move || -> PixResult<()> {
+            for level in 1..=2 {
+                worker.send(Loaded::Level(level))?;
+            }
+            worker.send(Loaded::Done)
+        })
+        .join()
+        .map_err(|_| anyhow::anyhow!("worker thread panicked"))??;
+        self.sender = Some(sender);
+        Ok(())
+    }
+
+    fn on_update(&mut self, _s: &mut PixState) -> PixResult<()> {
+        Ok(())
+    }
+
+    fn on_event(&mut self, _s: &mut PixState, event: &Event) -> PixResult<bool> {
+        // Consume `Done` before it reaches `on_user_event`
+        Ok(matches!(event, Event::User(event)
+            if event.downcast_ref::<Loaded>() == Some(&Loaded::Done)))
+    }
+
+    fn on_user_event(&mut self, s: &mut PixState, event: &UserEvent) -> PixResult<bool> {
+        if let Some(loaded) = event.downcast_ref::<Loaded>() {
+            self.received.push((s.frame_count(), format!("{loaded:?}")));
+        }
+        Ok(true)
+    }
+}
+
+#[test]
+fn user_events() -> PixResult<()> {
+    let mut app = UserEvents::default();
+    let _canvas = Harness::new(10, 10).frames(2).capture(&mut app)?;
+
+    assert_eq!(
+        app.received,
+        [(0, "Level(1)".to_owned()), (0, "Level(2)".to_owned())]
+    );
+    let sender = app
+        .sender
+        .take()
+        .ok_or_else(|| anyhow::anyhow!("no sender"))?;
+    assert!(
+        sender.send(Loaded::Done).is_err(),
+        "sending after the engine is dropped"
+    );
+    Ok(())
+}
+
+#[derive(Default)]
+struct FloodedUserEvents {
+    handled: Vec<usize>,
+}
+
+impl PixEngine for FloodedUserEvents {
+    fn on_start(&mut self, s: &mut PixState) -> PixResult<()> {
+        let sender = s.event_sender::<usize>();
+        for i in 0..300 {
+            sender.send(i)?;
+        }
+        Ok(())
+    }
+
+    fn on_update(&mut self, _s: &mut PixState) -> PixResult<()> {
+        Ok(())
+    }
+
+    fn on_user_event(&mut self, s: &mut PixState, _event: &UserEvent) -> PixResult<bool> {
+        let frame = s.frame_count();
+        if self.handled.len() <= frame {
+            self.handled.resize(frame + 1, 0);
+        }
+        self.handled[frame] += 1;
+        Ok(true)
+    }
+}
+
+#[test]
+fn user_events_limited_per_frame() -> PixResult<()> {
+    let mut app = FloodedUserEvents::default();
+    let _canvas = Harness::new(10, 10).frames(4).capture(&mut app)?;
+
+    assert_eq!(app.handled, [128, 128, 44]);
+    Ok(())
+}